@@ -0,0 +1,73 @@
+//! Workspace preview downscaling: the windows of a 4K workspace, each scaled
+//! to its size in the 640px-wide popup before upload.
+//!
+//!     cargo bench --bench preview_downscale
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use jb_shell::core::preview::{downscale_bilinear, Frame};
+
+const MONITOR_WIDTH: u32 = 3840;
+const PREVIEW_WIDTH: u32 = 640;
+
+struct Capture {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl Capture {
+    fn new(width: u32, height: u32) -> Self {
+        let data = (0..width * height * 4).map(|i| (i % 251) as u8).collect();
+        Capture {
+            data,
+            width,
+            height,
+        }
+    }
+
+    fn frame(&self) -> Frame<'_> {
+        Frame {
+            data: &self.data,
+            width: self.width,
+            height: self.height,
+            stride: self.width * 4,
+        }
+    }
+}
+
+fn workspace() -> Vec<Capture> {
+    vec![
+        Capture::new(1920, 2160),
+        Capture::new(1920, 1080),
+        Capture::new(1920, 1080),
+        // Floating
+        Capture::new(1200, 900),
+    ]
+}
+
+fn downscale_all(captures: &[Capture]) -> Vec<Vec<u8>> {
+    let scale = PREVIEW_WIDTH as f64 / MONITOR_WIDTH as f64;
+    captures
+        .iter()
+        .map(|capture| {
+            let w = ((capture.width as f64 * scale) as u32).max(1);
+            let h = ((capture.height as f64 * scale) as u32).max(1);
+            downscale_bilinear(&capture.frame(), w, h)
+        })
+        .collect()
+}
+
+fn bench_preview(c: &mut Criterion) {
+    let captures = workspace();
+    c.bench_function("downscale 4k workspace", |b| {
+        b.iter(|| downscale_all(black_box(&captures)))
+    });
+
+    let window = Capture::new(1920, 1080);
+    c.bench_function("downscale 1080p window to 320px", |b| {
+        b.iter(|| downscale_bilinear(black_box(&window.frame()), 320, 180))
+    });
+}
+
+criterion_group!(benches, bench_preview);
+criterion_main!(benches);
@@ -0,0 +1,178 @@
+use gtk4::prelude::*;
+use gtk4::{EventControllerScroll, EventControllerScrollFlags, GestureClick, Widget};
+use hyprland::dispatch::{Dispatch, DispatchType, WorkspaceIdentifierWithSpecial};
+use std::sync::OnceLock;
+
+use crate::config::WidgetActions;
+use crate::widgets::color_picker::ColorPickerInput;
+use crate::widgets::keybinds::KeybindsInput;
+use crate::widgets::launcher::LauncherInput;
+use crate::widgets::notifications::NotificationInput;
+
+/// Something a configured click or scroll does: a `@builtin` or a shell command.
+#[derive(Debug, Clone)]
+pub enum Action {
+    Builtin(Builtin),
+    Command(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Builtin {
+    Launcher,
+    Keybinds,
+    NotificationCenter,
+    ColorPicker,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    WorkspaceNext,
+    WorkspacePrev,
+    ScreenOff,
+}
+
+impl Action {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let Some(name) = spec.strip_prefix('@') else {
+            return Some(Action::Command(spec.to_string()));
+        };
+        let builtin = match name {
+            "launcher" => Builtin::Launcher,
+            "keybinds" => Builtin::Keybinds,
+            "notification-center" => Builtin::NotificationCenter,
+            "color-picker" => Builtin::ColorPicker,
+            "volume-up" => Builtin::VolumeUp,
+            "volume-down" => Builtin::VolumeDown,
+            "volume-mute" => Builtin::VolumeMute,
+            "workspace-next" => Builtin::WorkspaceNext,
+            "workspace-prev" => Builtin::WorkspacePrev,
+            "screen-off" => Builtin::ScreenOff,
+            _ => {
+                eprintln!("jb-shell: [actions] unknown builtin action: {spec}");
+                return None;
+            }
+        };
+        Some(Action::Builtin(builtin))
+    }
+}
+
+/// Senders for the shell singletons that builtins drive, registered once by `Shell`.
+pub struct ActionTargets {
+    pub launcher: relm4::Sender<LauncherInput>,
+    pub keybinds: relm4::Sender<KeybindsInput>,
+    pub color_picker: relm4::Sender<ColorPickerInput>,
+    pub notifications: relm4::Sender<NotificationInput>,
+}
+
+static TARGETS: OnceLock<ActionTargets> = OnceLock::new();
+
+pub fn set_targets(targets: ActionTargets) {
+    let _ = TARGETS.set(targets);
+}
+
+/// Common executor for configured widget actions.
+pub fn run(action: &Action) {
+    match action {
+        Action::Command(cmd) => spawn_command(cmd),
+        Action::Builtin(builtin) => run_builtin(*builtin),
+    }
+}
+
+fn run_builtin(builtin: Builtin) {
+    let targets = TARGETS.get();
+    match builtin {
+        Builtin::Launcher => {
+            if let Some(t) = targets {
+                t.launcher.emit(LauncherInput::Toggle);
+            }
+        }
+        Builtin::Keybinds => {
+            if let Some(t) = targets {
+                t.keybinds.emit(KeybindsInput::Show);
+            }
+        }
+        Builtin::NotificationCenter => {
+            if let Some(t) = targets {
+                t.notifications.emit(NotificationInput::OpenCenter);
+            }
+        }
+        Builtin::ColorPicker => {
+            if let Some(t) = targets {
+                t.color_picker.emit(ColorPickerInput::Start);
+            }
+        }
+        Builtin::VolumeUp | Builtin::VolumeDown => {
+            let up = matches!(builtin, Builtin::VolumeUp);
+            let step = crate::config::get().volume.step;
+            std::thread::spawn(move || crate::widgets::volume::step_volume(step, up));
+        }
+        Builtin::VolumeMute => spawn_command("wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle"),
+        Builtin::WorkspaceNext => {
+            let _ = Dispatch::call(DispatchType::Workspace(
+                WorkspaceIdentifierWithSpecial::Relative(1),
+            ));
+        }
+        Builtin::WorkspacePrev => {
+            let _ = Dispatch::call(DispatchType::Workspace(
+                WorkspaceIdentifierWithSpecial::Relative(-1),
+            ));
+        }
+        // Give the click's pointer events time to settle, or they wake the
+        // outputs straight back up
+        Builtin::ScreenOff => spawn_command("sleep 0.5 && hyprctl dispatch dpms off"),
+    }
+}
+
+fn spawn_command(cmd: &str) {
+    let cmd = cmd.to_string();
+    std::thread::spawn(move || {
+        match std::process::Command::new("sh").args(["-c", &cmd]).status() {
+            Ok(status) if !status.success() => {
+                eprintln!("jb-shell: [actions] `{cmd}` exited with {status}");
+            }
+            Err(e) => eprintln!("jb-shell: [actions] failed to run `{cmd}`: {e}"),
+            _ => {}
+        }
+    });
+}
+
+/// Wire the configured click/scroll actions onto a bar widget. Configured
+/// buttons are claimed in the capture phase, so they override the widget's own
+/// handling; unconfigured buttons keep working as before.
+pub fn attach(widget: &Widget, actions: &WidgetActions) {
+    let buttons = [
+        (gdk4::BUTTON_PRIMARY, &actions.left_click),
+        (gdk4::BUTTON_MIDDLE, &actions.middle_click),
+        (gdk4::BUTTON_SECONDARY, &actions.right_click),
+    ];
+    for (button, spec) in buttons {
+        let Some(action) = spec.as_deref().and_then(Action::parse) else {
+            continue;
+        };
+        let click = GestureClick::new();
+        click.set_button(button);
+        click.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        click.connect_pressed(move |gesture, _, _, _| {
+            gesture.set_state(gtk4::EventSequenceState::Claimed);
+            run(&action);
+        });
+        widget.add_controller(click);
+    }
+
+    let scroll_up = actions.scroll_up.as_deref().and_then(Action::parse);
+    let scroll_down = actions.scroll_down.as_deref().and_then(Action::parse);
+    if scroll_up.is_some() || scroll_down.is_some() {
+        let scroll = EventControllerScroll::new(EventControllerScrollFlags::VERTICAL);
+        scroll.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        scroll.connect_scroll(move |_, _, dy| {
+            let action = if dy < 0.0 { &scroll_up } else { &scroll_down };
+            match action {
+                Some(action) => {
+                    run(action);
+                    gtk4::glib::Propagation::Stop
+                }
+                None => gtk4::glib::Propagation::Proceed,
+            }
+        });
+        widget.add_controller(scroll);
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use gdk4::prelude::*;
+use hyprland::data::Clients;
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
+use hyprland::shared::{HyprData, HyprDataVec};
+
+use crate::core::window_focus::FocusQuery;
+
+/// How long after launching something an urgent window is taken to be it.
+const HANDOFF_WINDOW: Duration = Duration::from_secs(10);
+
+/// The app the last launch handed focus off to, and until when, until one of
+/// its windows claims it.
+static EXPECTING: Mutex<Option<(Instant, FocusQuery)>> = Mutex::new(None);
+
+/// Open `uri` in its default app with an xdg-activation token from GDK, so
+/// the app comes up focused instead of behind a popup or toast. Falls back to
+/// `xdg-open`. Main thread only.
+pub fn open_uri(uri: &str) {
+    if let Some(app) = default_app(uri) {
+        expect(FocusQuery {
+            desktop_entry: app.id().map(|id| id.to_string()),
+            class_hints: vec![app.name().to_string()],
+            ..FocusQuery::default()
+        });
+    }
+    let context = gdk4::Display::default().map(|d| d.app_launch_context());
+    if let Err(e) = gio::AppInfo::launch_default_for_uri(uri, context.as_ref()) {
+        eprintln!("jb-shell: [activation] {e}, falling back to xdg-open");
+        let _ = std::process::Command::new("xdg-open").arg(uri).spawn();
+    }
+}
+
+/// The app `launch_default_for_uri` will pick for `uri`.
+fn default_app(uri: &str) -> Option<gio::AppInfo> {
+    let scheme = glib::Uri::parse_scheme(uri)?;
+    if scheme == "file" {
+        gio::File::for_uri(uri)
+            .query_default_handler(None::<&gio::Cancellable>)
+            .ok()
+    } else {
+        gio::AppInfo::default_for_uri_scheme(&scheme)
+    }
+}
+
+/// A fresh xdg-activation token for `app`, which will raise its own window,
+/// like one told about a notification action. Main thread only.
+pub fn token(app: FocusQuery) -> Option<String> {
+    expect(app);
+    let context = gdk4::Display::default()?.app_launch_context();
+    context
+        .startup_notify_id(None::<&gio::AppInfo>, &[])
+        .map(|id| id.to_string())
+}
+
+/// Hand focus to the next window of `app` to ask for it within a few seconds.
+pub fn expect(app: FocusQuery) {
+    if let Ok(mut expecting) = EXPECTING.lock() {
+        *expecting = Some((Instant::now() + HANDOFF_WINDOW, app));
+    }
+}
+
+/// A window went urgent. Hyprland only marks a window urgent when it asks
+/// for activation (unless `misc:focus_on_activate` is on), so one of the app
+/// just launched doing that gets focused. Other apps going urgent meanwhile
+/// are left alone.
+pub fn urgent(address: hyprland::shared::Address) {
+    let Some(app) = EXPECTING.lock().ok().and_then(|expecting| {
+        expecting
+            .as_ref()
+            .filter(|(until, _)| Instant::now() < *until)
+            .map(|(_, app)| app.clone())
+    }) else {
+        return;
+    };
+    let Some(client) = Clients::get()
+        .ok()
+        .and_then(|clients| clients.to_vec().into_iter().find(|c| c.address == address))
+    else {
+        return;
+    };
+    if !crate::window_focus::is_match(app, &client) {
+        return;
+    }
+    if let Ok(mut expecting) = EXPECTING.lock() {
+        *expecting = None;
+    }
+    let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
+        address,
+    )));
+}
@@ -0,0 +1,86 @@
+use gtk4::prelude::*;
+use gtk4::{IconTheme, Image};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::widgets::launcher::xdg_app_dirs;
+
+thread_local! {
+    /// (desktop_entry, app_name) -> icon name or absolute path. Misses are
+    /// cached too so chatty apps without an icon don't rescan the disk.
+    static CACHE: RefCell<HashMap<(String, String), Option<String>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Application icon for a notification sender: the `Icon=` of its
+/// `desktop-entry` hint, falling back to guesses from the app name. GTK main
+/// thread only.
+pub fn resolve(desktop_entry: Option<&str>, app_name: &str) -> Option<String> {
+    let key = (
+        desktop_entry.unwrap_or_default().to_string(),
+        app_name.to_string(),
+    );
+    if let Some(hit) = CACHE.with_borrow(|cache| cache.get(&key).cloned()) {
+        return hit;
+    }
+
+    let icon = desktop_entry
+        .and_then(icon_from_desktop_entry)
+        .or_else(|| guess_from_app_name(app_name))
+        .filter(|icon| icon_exists(icon));
+    CACHE.with_borrow_mut(|cache| cache.insert(key, icon.clone()));
+    icon
+}
+
+/// `resolve` as an `Image` at the given pixel size.
+pub fn image(desktop_entry: Option<&str>, app_name: &str, size: i32) -> Option<Image> {
+    let icon = resolve(desktop_entry, app_name)?;
+    let image = if icon.starts_with('/') {
+        Image::from_file(&icon)
+    } else {
+        Image::from_icon_name(&icon)
+    };
+    image.set_pixel_size(size);
+    image.add_css_class("app-icon");
+    Some(image)
+}
+
+fn icon_from_desktop_entry(id: &str) -> Option<String> {
+    let file_name = format!("{}.desktop", id.trim_end_matches(".desktop"));
+    xdg_app_dirs().into_iter().find_map(|dir| {
+        let content = std::fs::read_to_string(dir.join(&file_name)).ok()?;
+        let mut in_desktop_entry = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_desktop_entry = line == "[Desktop Entry]";
+            } else if in_desktop_entry {
+                if let Some(icon) = line.strip_prefix("Icon=") {
+                    return Some(icon.trim().to_string());
+                }
+            }
+        }
+        None
+    })
+}
+
+/// "Google Chrome" -> google-chrome.desktop / google-chrome icon, etc.
+fn guess_from_app_name(app_name: &str) -> Option<String> {
+    if app_name.is_empty() {
+        return None;
+    }
+    let lower = app_name.to_lowercase();
+    let candidates = [lower.replace(' ', "-"), lower.replace(' ', "")];
+    candidates.iter().find_map(|name| {
+        icon_from_desktop_entry(name).or_else(|| icon_exists(name).then(|| name.clone()))
+    })
+}
+
+fn icon_exists(icon: &str) -> bool {
+    if icon.starts_with('/') {
+        return std::path::Path::new(icon).exists();
+    }
+    gdk4::Display::default()
+        .map(|display| IconTheme::for_display(&display).has_icon(icon))
+        .unwrap_or(false)
+}
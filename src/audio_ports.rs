@@ -0,0 +1,107 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crate::core::audio::{active_output, parse_sinks, ActiveOutput};
+use crate::widgets::notifications::{
+    ActionCallback, NotificationAction, NotificationId, NotificationInput, NotificationKind,
+    NotificationRequest, NotificationSource,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const SWITCH_NOTIFICATION_ID: NotificationId = u64::MAX - 0xe0;
+
+/// Watches which sink and port PipeWire plays through, publishing it for the
+/// volume tooltips and toasting each switch with an undo button. The first
+/// reading only publishes.
+pub fn spawn_audio_port_thread(
+    notif_sender: relm4::Sender<NotificationInput>,
+    publish: impl Fn(Option<ActiveOutput>) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let mut current = read_active_output();
+        publish(current.clone());
+        loop {
+            crate::idle::sleep(POLL_INTERVAL);
+            let now = read_active_output();
+            if now == current {
+                continue;
+            }
+            if let (Some(old), Some(new)) = (&current, &now) {
+                eprintln!(
+                    "jb-shell: [audio] output switched to {} ({})",
+                    new.sink,
+                    new.port.as_ref().map_or("no port", |p| p.name.as_str())
+                );
+                notif_sender.emit(NotificationInput::Show(switch_toast(old, new)));
+            }
+            publish(now.clone());
+            current = now;
+        }
+    });
+}
+
+fn read_active_output() -> Option<ActiveOutput> {
+    let default_sink = pactl(&["get-default-sink"])?;
+    let sinks = parse_sinks(&pactl(&["list", "sinks"])?);
+    active_output(&sinks, default_sink.trim())
+}
+
+fn pactl(args: &[&str]) -> Option<String> {
+    let out = Command::new("pactl")
+        .env("LC_ALL", "C")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())?;
+    Some(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// "Headphones" toast; undo re-selects the old port, or the old default sink
+/// when the switch was to another device.
+fn switch_toast(old: &ActiveOutput, new: &ActiveOutput) -> NotificationRequest {
+    let undo: Vec<String> = match &old.port {
+        Some(port) if old.sink == new.sink => {
+            vec![
+                "pactl".into(),
+                "set-sink-port".into(),
+                old.sink.clone(),
+                port.name.clone(),
+            ]
+        }
+        _ => vec!["pactl".into(), "set-default-sink".into(), old.sink.clone()],
+    };
+    NotificationRequest {
+        id: SWITCH_NOTIFICATION_ID,
+        kind: NotificationKind::Toast,
+        icon: Some(crate::widgets::volume::output_icon(new.kind).to_string()),
+        title: output_name(new),
+        body: Some(format!(
+            "{} \u{b7} was {}",
+            new.sink_description,
+            output_name(old)
+        )),
+        subtitle: None,
+        countdown_target: None,
+        actions: vec![NotificationAction {
+            label: "Undo".to_string(),
+            css_class: "notif-default-action".to_string(),
+            callback: ActionCallback::RunCommand(undo),
+        }],
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: None,
+        timeout_ms: Some(6000),
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    }
+}
+
+/// The port's own name ("Headphones", "HDMI / DisplayPort"), or the kind's.
+pub fn output_name(output: &ActiveOutput) -> String {
+    match &output.port {
+        Some(port) if !port.description.is_empty() => port.description.clone(),
+        _ => output.kind.label().to_string(),
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+
+use crate::core::exec::exec_command_line;
+use crate::widgets::launcher::{binary_exists, current_desktops, split_list};
+
+/// Launch XDG autostart entries when `[autostart] enabled` is set. Runs once per
+/// login session: a marker in `$XDG_RUNTIME_DIR` keeps a shell restart from
+/// starting everything a second time.
+pub fn run() {
+    if !crate::config::get().autostart.enabled {
+        return;
+    }
+    let marker = std::env::var("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("jb-shell-autostart-done"))
+        .ok();
+    if marker.as_ref().is_some_and(|m| m.exists()) {
+        eprintln!("jb-shell: [autostart] already ran this session, skipping");
+        return;
+    }
+    if let Some(marker) = &marker {
+        let _ = std::fs::write(marker, "");
+    }
+
+    std::thread::spawn(|| {
+        for path in autostart_entries() {
+            if let Some(command) = entry_command(&path) {
+                spawn(&path, &command);
+            }
+        }
+    });
+}
+
+fn autostart_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(config_home) = std::env::var("XDG_CONFIG_HOME") {
+        dirs.push(PathBuf::from(config_home).join("autostart"));
+    } else if let Ok(home) = std::env::var("HOME") {
+        dirs.push(PathBuf::from(home).join(".config/autostart"));
+    }
+    let config_dirs = std::env::var("XDG_CONFIG_DIRS").unwrap_or_else(|_| "/etc/xdg".to_string());
+    for dir in config_dirs.split(':') {
+        if !dir.is_empty() {
+            dirs.push(PathBuf::from(dir).join("autostart"));
+        }
+    }
+    dirs
+}
+
+/// One path per file name; earlier (user) dirs shadow later (system) ones, so
+/// a user file with `Hidden=true` disables the system entry.
+fn autostart_entries() -> Vec<PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    let mut entries = Vec::new();
+    for dir in autostart_dirs() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = read_dir
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("desktop"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            if seen.insert(path.file_name().map(|n| n.to_os_string())) {
+                entries.push(path);
+            }
+        }
+    }
+    entries
+}
+
+/// The command to run for an entry, or `None` if the spec says to skip it.
+fn entry_command(path: &Path) -> Option<String> {
+    let keys = read_desktop_entry(path)?;
+    let get = |key: &str| keys.get(key).map(String::as_str);
+    let is_true = |key: &str| get(key).is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    let is_false = |key: &str| get(key).is_some_and(|v| v.eq_ignore_ascii_case("false"));
+
+    if get("Type").is_some_and(|t| t != "Application") || is_true("Hidden") {
+        return None;
+    }
+    if is_false("X-GNOME-Autostart-enabled") {
+        return None;
+    }
+    if get("TryExec").is_some_and(|bin| !binary_exists(bin)) {
+        return None;
+    }
+    let desktops = current_desktops();
+    let only_show_in = get("OnlyShowIn").map(split_list).unwrap_or_default();
+    if !only_show_in.is_empty() && !only_show_in.iter().any(|d| desktops.contains(d)) {
+        return None;
+    }
+    let not_show_in = get("NotShowIn").map(split_list).unwrap_or_default();
+    if not_show_in.iter().any(|d| desktops.contains(d)) {
+        return None;
+    }
+
+    let command = exec_command_line(
+        path,
+        get("Name").unwrap_or_default(),
+        get("Exec")?,
+        get("Icon"),
+    );
+    (!command.is_empty()).then_some(command)
+}
+
+/// Unlocalized keys of the `[Desktop Entry]` group.
+fn read_desktop_entry(path: &Path) -> Option<HashMap<String, String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut in_desktop_entry = false;
+    let mut keys = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_desktop_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_desktop_entry {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            if !key.contains('[') {
+                keys.insert(key.to_string(), value.trim().to_string());
+            }
+        }
+    }
+    Some(keys)
+}
+
+fn spawn(path: &Path, command: &str) {
+    match std::process::Command::new("sh")
+        .args(["-c", command])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()
+    {
+        Ok(_) => eprintln!("jb-shell: [autostart] started {}", path.display()),
+        Err(e) => eprintln!(
+            "jb-shell: [autostart] failed to start {}: {e}",
+            path.display()
+        ),
+    }
+}
@@ -1,20 +1,37 @@
 use gdk4::Monitor;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, CenterBox, Orientation, Window};
+use gtk4::{Box as GtkBox, Button, CenterBox, Orientation, Separator, Widget, Window};
 use gtk4_layer_shell::{Edge, Layer, LayerShell};
 use relm4::{Component, ComponentController, Controller};
+use std::collections::HashMap;
 
+use crate::config::{BarConfig, BarStyle};
 use crate::hyprland_listener::HyprlandMsg;
+use crate::shell::Shell;
 use crate::widgets::active_window::ActiveWindowWidget;
-use crate::widgets::battery::BatteryModel;
+use crate::widgets::airplane::AirplaneModel;
+use crate::widgets::battery::{BatteryInit, BatteryModel};
+use crate::widgets::caffeine::CaffeineModel;
 use crate::widgets::calendar::{CalendarInit, CalendarModel};
+use crate::widgets::ci::{CiInit, CiModel};
 use crate::widgets::clock::ClockModel;
+use crate::widgets::custom::{CustomInit, CustomModel};
+use crate::widgets::display_profiles::{DisplayInit, DisplayModel};
+use crate::widgets::focus_mode::FocusModel;
 use crate::widgets::gcloud_config::GcloudModel;
+use crate::widgets::git::{GitInit, GitModel};
+use crate::widgets::i3bar::I3barModel;
+use crate::widgets::join_meeting::JoinMeetingModel;
 use crate::widgets::kube_context::KubeModel;
+use crate::widgets::metrics::{MetricsInit, MetricsModel};
 use crate::widgets::mpris::MprisModel;
-use crate::widgets::network::NetworkModel;
+use crate::widgets::network::{NetworkInit, NetworkModel};
+use crate::widgets::night_light::{NightLightInit, NightLightModel};
 use crate::widgets::notification_center::{NotificationCenterInit, NotificationCenterModel};
-use crate::widgets::notifications::NotificationModel;
+use crate::widgets::notifications::NotificationInput;
+use crate::widgets::oncall::{OnCallInit, OnCallModel};
+use crate::widgets::switcher::SwitcherInit;
+use crate::widgets::toolchains::{NodeModel, RustupModel, TerraformWorkspaceModel, TfenvModel};
 use crate::widgets::volume::VolumeModel;
 use crate::widgets::workspaces::WorkspacesWidget;
 
@@ -28,17 +45,36 @@ pub struct StatusBar {
     _battery: Controller<BatteryModel>,
     _volume: Controller<VolumeModel>,
     _network: Controller<NetworkModel>,
+    _airplane: Controller<AirplaneModel>,
     _kube: Controller<KubeModel>,
     _gcloud: Controller<GcloudModel>,
+    _terraform: Controller<TfenvModel>,
+    _terraform_workspace: Controller<TerraformWorkspaceModel>,
+    _node: Controller<NodeModel>,
+    _rustup: Controller<RustupModel>,
+    _git: Controller<GitModel>,
+    _ci: Controller<CiModel>,
+    _oncall: Controller<OnCallModel>,
+    _metrics: Controller<MetricsModel>,
     _mpris: Controller<MprisModel>,
-    _notifications: Controller<NotificationModel>,
+    _night_light: Controller<NightLightModel>,
+    _caffeine: Controller<CaffeineModel>,
+    _focus: Controller<FocusModel>,
+    _display: Controller<DisplayModel>,
     _notification_center: Controller<NotificationCenterModel>,
     _calendar: Controller<CalendarModel>,
+    _join_meeting: Controller<JoinMeetingModel>,
+    _custom: Vec<Controller<CustomModel>>,
+    _i3bar: Controller<I3barModel>,
+    /// `[privacy] widgets` — blurred while a screencast is running.
+    private_widgets: Vec<Widget>,
     monitor_name: String,
 }
 
 impl StatusBar {
-    pub fn new(monitor: &Monitor, hyprland_monitor_name: &str) -> Self {
+    pub fn new(monitor: &Monitor, hyprland_monitor_name: &str, shell: &Shell) -> Self {
+        let notif_sender = shell.notification_sender();
+
         let window = Window::new();
         window.set_title(Some("jb-shell"));
 
@@ -48,66 +84,224 @@ impl StatusBar {
         window.set_anchor(Edge::Left, true);
         window.set_anchor(Edge::Top, true);
         window.set_anchor(Edge::Right, true);
-        window.auto_exclusive_zone_enable();
+        let bar_config = &crate::config::get().bar;
+        if bar_config.style == BarStyle::Floating {
+            for edge in [Edge::Top, Edge::Left, Edge::Right] {
+                window.set_margin(edge, bar_config.margin);
+            }
+            window.set_exclusive_zone(0);
+        } else {
+            window.auto_exclusive_zone_enable();
+        }
         window.set_monitor(Some(monitor));
 
         // Build widgets
         let workspaces = WorkspacesWidget::new(hyprland_monitor_name, monitor);
-        let active_window = ActiveWindowWidget::new();
+        let active_window = ActiveWindowWidget::new(hyprland_monitor_name);
 
         // Create relm4 components
-        let clock = ClockModel::builder().launch(()).detach();
-        let battery = BatteryModel::builder().launch(()).detach();
-        let volume = VolumeModel::builder().launch(()).detach();
-        let network = NetworkModel::builder().launch(()).detach();
-        let kube = KubeModel::builder().launch(monitor.clone()).detach();
-        let gcloud = GcloudModel::builder().launch(monitor.clone()).detach();
-        let mpris = MprisModel::builder().launch(()).detach();
-        let notifications = NotificationModel::builder()
-            .launch(monitor.clone())
-            .detach();
-        let notif_sender = notifications.sender().clone();
-
+        let clock = ClockModel::builder()
+            .launch(shell.timezone.clone())
+            .detach();
+        let battery = BatteryModel::builder()
+            .launch(BatteryInit {
+                monitor: monitor.clone(),
+            })
+            .detach();
+        let volume = VolumeModel::builder()
+            .launch(shell.audio_output.clone())
+            .detach();
+        let network = NetworkModel::builder()
+            .launch(NetworkInit {
+                monitor: monitor.clone(),
+                usage: shell.net_usage.clone(),
+                latency: shell.net_latency.clone(),
+            })
+            .detach();
+        let airplane = AirplaneModel::builder().launch(()).detach();
+        let kube = KubeModel::builder()
+            .launch(SwitcherInit {
+                monitor: monitor.clone(),
+                handle: shell.kube.clone(),
+            })
+            .detach();
+        let gcloud = GcloudModel::builder()
+            .launch(SwitcherInit {
+                monitor: monitor.clone(),
+                handle: shell.gcloud.clone(),
+            })
+            .detach();
+        let terraform = TfenvModel::builder()
+            .launch(SwitcherInit {
+                monitor: monitor.clone(),
+                handle: shell.terraform.clone(),
+            })
+            .detach();
+        let terraform_workspace = TerraformWorkspaceModel::builder()
+            .launch(SwitcherInit {
+                monitor: monitor.clone(),
+                handle: shell.terraform_workspace.clone(),
+            })
+            .detach();
+        let node = NodeModel::builder()
+            .launch(SwitcherInit {
+                monitor: monitor.clone(),
+                handle: shell.node.clone(),
+            })
+            .detach();
+        let rustup = RustupModel::builder()
+            .launch(SwitcherInit {
+                monitor: monitor.clone(),
+                handle: shell.rustup.clone(),
+            })
+            .detach();
+        let git = GitModel::builder()
+            .launch(GitInit {
+                monitor: monitor.clone(),
+                feed: shell.git.clone(),
+            })
+            .detach();
+        let ci = CiModel::builder()
+            .launch(CiInit {
+                monitor: monitor.clone(),
+                feed: shell.ci.clone(),
+            })
+            .detach();
+        let oncall = OnCallModel::builder()
+            .launch(OnCallInit {
+                monitor: monitor.clone(),
+                handle: shell.oncall.clone(),
+            })
+            .detach();
+        let metrics = MetricsModel::builder()
+            .launch(MetricsInit {
+                monitor: monitor.clone(),
+                feed: shell.metrics.clone(),
+            })
+            .detach();
+        let mpris = MprisModel::builder().launch(shell.mpris.clone()).detach();
+        let caffeine = CaffeineModel::builder()
+            .launch(shell.caffeine.clone())
+            .detach();
+        let focus = FocusModel::builder().launch(shell.focus.clone()).detach();
+        let night_light = NightLightModel::builder()
+            .launch(NightLightInit {
+                monitor: monitor.clone(),
+                handle: shell.night_light.clone(),
+            })
+            .detach();
+        let display = DisplayModel::builder()
+            .launch(DisplayInit {
+                monitor: monitor.clone(),
+                handle: shell.display.clone(),
+            })
+            .detach();
         let notification_center = NotificationCenterModel::builder()
             .launch(NotificationCenterInit {
                 monitor: monitor.clone(),
                 notif_sender: notif_sender.clone(),
+                summary: shell.summary.clone(),
             })
             .detach();
 
-        // Wire center sender into notification model for suppression
-        notifications.sender().emit(
-            crate::widgets::notifications::NotificationInput::SetCenterSender(
-                notification_center.sender().clone(),
-            ),
-        );
+        // Register this bar's center with the shared notification model for suppression
+        notif_sender.emit(NotificationInput::AddCenter(
+            monitor.clone(),
+            notification_center.sender().clone(),
+        ));
+
+        let join_meeting = JoinMeetingModel::builder()
+            .launch(shell.calendar.clone())
+            .detach();
 
         let calendar = CalendarModel::builder()
             .launch(CalendarInit {
                 monitor: monitor.clone(),
-                notif_sender,
+                notif_sender: notif_sender.clone(),
+                calendar: shell.calendar.clone(),
             })
             .detach();
 
-        // Start box (left)
-        let start_box = GtkBox::new(Orientation::Horizontal, 12);
-        start_box.append(&workspaces.container);
-        start_box.append(kube.widget());
-        start_box.append(gcloud.widget());
-        start_box.append(mpris.widget());
+        let i3bar = I3barModel::builder().launch(shell.i3bar.clone()).detach();
 
-        // Center box
-        let center_box = GtkBox::new(Orientation::Horizontal, 0);
-        center_box.append(&active_window.container);
+        // `[custom.<name>]` scripts, placed as `custom/<name>`
+        let mut custom_slots = Vec::new();
+        let mut custom = Vec::new();
+        for (name, feed) in &shell.custom {
+            let config = &crate::config::get().custom[name];
+            custom_slots.push((format!("custom/{name}"), config));
+            custom.push(
+                CustomModel::builder()
+                    .launch(CustomInit {
+                        name: name.clone(),
+                        config,
+                        feed: feed.clone(),
+                    })
+                    .detach(),
+            );
+        }
 
-        // End box (right)
-        let end_box = GtkBox::new(Orientation::Horizontal, 8);
-        end_box.append(notification_center.widget());
-        end_box.append(calendar.widget());
-        end_box.append(volume.widget());
-        end_box.append(network.widget());
-        end_box.append(battery.widget());
-        end_box.append(clock.widget());
+        // Lay out sections from config; each widget is placed at most once
+        let mut slots: HashMap<&str, Widget> = HashMap::from([
+            ("workspaces", workspaces.container.clone().upcast()),
+            ("kube", kube.widget().clone().upcast()),
+            ("gcloud", gcloud.widget().clone().upcast()),
+            ("terraform", terraform.widget().clone().upcast()),
+            (
+                "terraform-workspace",
+                terraform_workspace.widget().clone().upcast(),
+            ),
+            ("node", node.widget().clone().upcast()),
+            ("rustup", rustup.widget().clone().upcast()),
+            ("git", git.widget().clone().upcast()),
+            ("ci", ci.widget().clone().upcast()),
+            ("oncall", oncall.widget().clone().upcast()),
+            ("metrics", metrics.widget().clone().upcast()),
+            ("mpris", mpris.widget().clone().upcast()),
+            ("active-window", active_window.container.clone().upcast()),
+            (
+                "notifications",
+                notification_center.widget().clone().upcast(),
+            ),
+            ("calendar", calendar.widget().clone().upcast()),
+            ("join-meeting", join_meeting.widget().clone().upcast()),
+            ("volume", volume.widget().clone().upcast()),
+            ("network", network.widget().clone().upcast()),
+            ("airplane", airplane.widget().clone().upcast()),
+            ("night-light", night_light.widget().clone().upcast()),
+            ("caffeine", caffeine.widget().clone().upcast()),
+            ("focus", focus.widget().clone().upcast()),
+            ("display", display.widget().clone().upcast()),
+            ("battery", battery.widget().clone().upcast()),
+            ("clock", clock.widget().clone().upcast()),
+            ("color-picker", color_picker_button().upcast()),
+            ("screen-off", screen_off_button().upcast()),
+            ("taskbar", crate::widgets::taskbar::build().upcast()),
+            ("i3bar", i3bar.widget().clone().upcast()),
+        ]);
+        for ((key, config), controller) in custom_slots.iter().zip(&custom) {
+            let widget: Widget = controller.widget().clone().upcast();
+            // The module's own on-click keys, unless `[actions]` overrides them
+            if !crate::config::get().actions.contains_key(key) {
+                crate::actions::attach(&widget, &config.actions);
+            }
+            slots.insert(key, widget);
+        }
+        for (name, widget) in &slots {
+            if let Some(actions) = crate::config::get().actions.get(*name) {
+                crate::actions::attach(widget, actions);
+            }
+        }
+        let private_widgets = crate::config::get()
+            .privacy
+            .widgets
+            .iter()
+            .filter_map(|name| slots.get(name.as_str()).cloned())
+            .collect();
+        let layout = &bar_config.layout;
+        let start_box = build_section(&layout.left, layout.spacing.left, &mut slots);
+        let center_box = build_section(&layout.center, layout.spacing.center, &mut slots);
+        let end_box = build_section(&layout.right, layout.spacing.right, &mut slots);
 
         let center = CenterBox::new();
         center.set_widget_name("bar-inner");
@@ -141,12 +335,28 @@ impl StatusBar {
             _battery: battery,
             _volume: volume,
             _network: network,
+            _airplane: airplane,
             _kube: kube,
             _gcloud: gcloud,
+            _terraform: terraform,
+            _terraform_workspace: terraform_workspace,
+            _node: node,
+            _rustup: rustup,
+            _git: git,
+            _ci: ci,
+            _oncall: oncall,
+            _metrics: metrics,
             _mpris: mpris,
-            _notifications: notifications,
+            _night_light: night_light,
+            _caffeine: caffeine,
+            _focus: focus,
+            _display: display,
             _notification_center: notification_center,
             _calendar: calendar,
+            _join_meeting: join_meeting,
+            _custom: custom,
+            _i3bar: i3bar,
+            private_widgets,
             monitor_name: hyprland_monitor_name.to_string(),
         }
     }
@@ -164,9 +374,11 @@ impl StatusBar {
             HyprlandMsg::WorkspaceCreated {
                 workspace_id,
                 monitor_name,
+                name,
             } => {
                 if *monitor_name == self.monitor_name {
                     self.workspaces.add_workspace(*workspace_id);
+                    self.workspaces.set_name(*workspace_id, name);
                 }
             }
             HyprlandMsg::WorkspaceDestroyed { workspace_id } => {
@@ -175,33 +387,55 @@ impl StatusBar {
             HyprlandMsg::WorkspaceMoved {
                 workspace_id,
                 monitor_name,
+                name,
             } => {
                 if *monitor_name == self.monitor_name {
                     self.workspaces.add_workspace(*workspace_id);
+                    self.workspaces.set_name(*workspace_id, name);
                 } else {
                     self.workspaces.remove_workspace(*workspace_id);
                 }
             }
-            HyprlandMsg::ActiveWindowChanged { title } => {
-                self.active_window.set_title(title);
+            HyprlandMsg::WorkspaceRenamed { workspace_id, name } => {
+                self.workspaces.set_name(*workspace_id, name);
+            }
+            HyprlandMsg::ActiveWindowChanged {
+                title,
+                monitor_name,
+                ..
+            } => {
+                // Other monitors keep showing their own last focused window
+                if *monitor_name == self.monitor_name {
+                    self.active_window.set_title(title);
+                    self.active_window.set_focused(true);
+                }
             }
             HyprlandMsg::MonitorFocusChanged {
                 monitor_name,
                 workspace_id,
             } => {
-                if *monitor_name == self.monitor_name {
+                let focused = *monitor_name == self.monitor_name;
+                if focused {
                     self.workspaces.set_active(*workspace_id);
                 }
+                self.active_window.set_focused(focused);
+            }
+            HyprlandMsg::ScreencastChanged { active } => {
+                if crate::config::get().privacy.enabled {
+                    for widget in &self.private_widgets {
+                        if *active {
+                            widget.add_css_class("screencast-private");
+                        } else {
+                            widget.remove_css_class("screencast-private");
+                        }
+                    }
+                }
             }
+            // Display profiles are the shell's business, not a bar's
+            HyprlandMsg::MonitorsChanged => {}
         }
     }
 
-    pub fn notification_sender(
-        &self,
-    ) -> &relm4::Sender<crate::widgets::notifications::NotificationInput> {
-        self._notifications.sender()
-    }
-
     pub fn destroy(&self) {
         eprintln!(
             "jb-shell: [lifecycle] StatusBar::destroy called for monitor: {}",
@@ -215,3 +449,88 @@ impl StatusBar {
         &self.monitor_name
     }
 }
+
+/// Eyedropper button for the `color-picker` layout slot.
+fn color_picker_button() -> Button {
+    let button = Button::from_icon_name("color-select-symbolic");
+    crate::widgets::a11y::name(&button, "Pick a color");
+    button.set_widget_name("bar-color-picker");
+    button.set_tooltip_text(Some("Pick a color"));
+    button.connect_clicked(|_| {
+        crate::actions::run(&crate::actions::Action::Builtin(
+            crate::actions::Builtin::ColorPicker,
+        ));
+    });
+    button
+}
+
+fn screen_off_button() -> Button {
+    let button = Button::from_icon_name("preferences-desktop-screensaver-symbolic");
+    crate::widgets::a11y::name(&button, "Turn displays off");
+    button.set_widget_name("bar-screen-off");
+    button.set_tooltip_text(Some("Turn displays off"));
+    button.connect_clicked(|_| {
+        crate::actions::run(&crate::actions::Action::Builtin(
+            crate::actions::Builtin::ScreenOff,
+        ));
+    });
+    button
+}
+
+/// Build one bar section from layout entries. Group names expand into a pill
+/// container; `"|"` inserts a separator.
+fn build_section(entries: &[String], spacing: i32, slots: &mut HashMap<&str, Widget>) -> GtkBox {
+    let bar_config = &crate::config::get().bar;
+    let section = GtkBox::new(Orientation::Horizontal, spacing);
+    for entry in entries {
+        if let Some(members) = bar_config.groups.get(entry) {
+            let group = GtkBox::new(Orientation::Horizontal, bar_config.layout.spacing.group);
+            group.add_css_class("bar-group");
+            group.add_css_class(&format!("group-{entry}"));
+            for member in members {
+                append_entry(&group, member, slots);
+            }
+            section.append(&group);
+        } else {
+            append_entry(&section, entry, slots);
+        }
+    }
+    section
+}
+
+fn append_entry(container: &GtkBox, name: &str, slots: &mut HashMap<&str, Widget>) {
+    if name == "|" {
+        let separator = Separator::new(Orientation::Vertical);
+        separator.add_css_class("bar-separator");
+        container.append(&separator);
+    } else if let Some(widget) = slots.remove(name) {
+        container.append(&widget);
+    } else {
+        eprintln!("jb-shell: [bar] unknown or duplicate widget in layout: {name}");
+    }
+}
+
+/// Popup windows that hang off the bar and share its corner radius.
+const POPUP_SELECTORS: &str = "#ws-popup, #kube-popup, #gcloud-popup, #terraform-popup, \
+     #terraform-workspace-popup, #node-popup, #rust-popup, #git-popup, #ci-popup, #oncall-popup, \
+     #metrics-popup, #calendar-popup, #notif-center-popup, #bar-tooltip";
+
+/// CSS generated from `[bar]` config, layered above style.css. Empty when the
+/// config leaves the theme alone.
+pub fn style_overrides(config: &BarConfig) -> String {
+    let mut css = String::new();
+    if let Some(opacity) = config.opacity {
+        css.push_str(&format!(
+            "#bar-inner {{ background-color: alpha(@bg, {:.2}); }}\n",
+            opacity.clamp(0.0, 1.0)
+        ));
+    }
+    if config.style == BarStyle::Floating {
+        let radius = config.radius;
+        css.push_str(&format!(
+            "#bar-inner {{ border-radius: {radius}px; border: 1px solid @border_color; }}\n\
+             {POPUP_SELECTORS} {{ border-radius: {radius}px; }}\n"
+        ));
+    }
+    css
+}
@@ -0,0 +1,130 @@
+use std::sync::mpsc;
+
+/// Why the inhibitor is held; the bar tooltip shows it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaffeineReason {
+    Manual,
+    /// The focused window's class matched this `[caffeine] apps` entry.
+    App(String),
+    /// This MPRIS player is playing video.
+    Video(String),
+}
+
+impl CaffeineReason {
+    pub fn describe(&self) -> String {
+        match self {
+            CaffeineReason::Manual => "Turned on manually".to_string(),
+            CaffeineReason::App(rule) => format!("Rule: {rule} is focused"),
+            CaffeineReason::Video(player) => format!("Rule: {player} is playing video"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CaffeineCommand {
+    Toggle,
+    /// Hyprland focus changed; the window class, empty for none.
+    Focused(String),
+    /// MPRIS playback: the playing video player's name, or `None`.
+    Video(Option<String>),
+}
+
+/// Holds the `org.freedesktop.ScreenSaver` idle inhibitor (honored by hypridle)
+/// while any rule applies. Publishes the reason, or `None` when released.
+pub fn spawn_caffeine_thread(
+    publish: impl Fn(Option<CaffeineReason>) + Send + 'static,
+) -> mpsc::Sender<CaffeineCommand> {
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let config = &crate::config::get().caffeine;
+        let mut manual = false;
+        let mut focused = String::new();
+        let mut video: Option<String> = None;
+        let mut cookie: Option<u32> = None;
+        let mut held: Option<CaffeineReason> = None;
+        publish(None);
+
+        while let Ok(command) = rx.recv() {
+            crate::metrics::wakeup();
+            match command {
+                CaffeineCommand::Toggle => manual = !manual,
+                CaffeineCommand::Focused(class) => focused = class,
+                CaffeineCommand::Video(player) => video = player,
+            }
+
+            let reason = if manual {
+                Some(CaffeineReason::Manual)
+            } else if let Some(rule) = config
+                .apps
+                .iter()
+                .find(|app| !focused.is_empty() && app.eq_ignore_ascii_case(&focused))
+            {
+                Some(CaffeineReason::App(rule.clone()))
+            } else if config.video {
+                video.clone().map(CaffeineReason::Video)
+            } else {
+                None
+            };
+            if reason == held {
+                continue;
+            }
+
+            match (&reason, cookie) {
+                (Some(reason), None) => {
+                    cookie = inhibit(&reason.describe());
+                    if cookie.is_none() {
+                        // Not held: the next command retries
+                        if held.take().is_some() {
+                            publish(None);
+                        }
+                        continue;
+                    }
+                }
+                (None, Some(c)) => {
+                    uninhibit(c);
+                    cookie = None;
+                }
+                _ => {}
+            }
+            if let Some(reason) = &reason {
+                eprintln!("jb-shell: [caffeine] holding: {}", reason.describe());
+            } else {
+                eprintln!("jb-shell: [caffeine] released");
+            }
+            held = reason;
+            publish(held.clone());
+        }
+    });
+
+    tx
+}
+
+fn screensaver_call<B>(method: &str, body: &B) -> zbus::Result<zbus::Message>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+{
+    crate::dbus::session_blocking()?.call_method(
+        Some("org.freedesktop.ScreenSaver"),
+        "/org/freedesktop/ScreenSaver",
+        Some("org.freedesktop.ScreenSaver"),
+        method,
+        body,
+    )
+}
+
+fn inhibit(reason: &str) -> Option<u32> {
+    match screensaver_call("Inhibit", &("jb-shell", reason)).and_then(|r| r.body().deserialize()) {
+        Ok(cookie) => Some(cookie),
+        Err(e) => {
+            eprintln!("jb-shell: [caffeine] Inhibit failed (is hypridle running?): {e}");
+            None
+        }
+    }
+}
+
+fn uninhibit(cookie: u32) {
+    if let Err(e) = screensaver_call("UnInhibit", &(cookie,)) {
+        eprintln!("jb-shell: [caffeine] UnInhibit failed: {e}");
+    }
+}
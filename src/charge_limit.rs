@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+
+use crate::core::charge::{target_thresholds, threshold_writes, ChargeLimit, Threshold};
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+const IDEAPAD: &str = "/sys/bus/platform/drivers/ideapad_acpi";
+
+/// Where this laptop's charge limit lives in sysfs.
+enum Control {
+    /// Battery directories with `charge_control_end_threshold`; ThinkPads
+    /// with two batteries have both set.
+    Thresholds(Vec<PathBuf>),
+    /// The IdeaPad `conservation_mode` file.
+    Conservation(PathBuf),
+}
+
+/// The current charge limit, `None` on machines without one.
+pub fn read() -> Option<ChargeLimit> {
+    match find()? {
+        Control::Thresholds(batteries) => {
+            let battery = batteries.first()?;
+            Some(ChargeLimit::Thresholds {
+                start: read_percent(&battery.join("charge_control_start_threshold")),
+                end: read_percent(&battery.join("charge_control_end_threshold"))?,
+            })
+        }
+        Control::Conservation(file) => Some(ChargeLimit::Conservation(read_percent(&file)? == 1)),
+    }
+}
+
+/// Turn the limit on (`[battery] charge_start`/`charge_end`, or
+/// conservation mode) or off, returning what the kernel reports after.
+/// Blocking; sysfs writes need the permissions the README sets up.
+pub fn set_limited(on: bool) -> Result<ChargeLimit, String> {
+    match find().ok_or("no charge limit on this machine")? {
+        Control::Thresholds(batteries) => {
+            let config = &crate::config::get().battery;
+            let (start, end) = target_thresholds(on, config.charge_start, config.charge_end);
+            for battery in &batteries {
+                let start_file = battery.join("charge_control_start_threshold");
+                let end_file = battery.join("charge_control_end_threshold");
+                let current_end = read_percent(&end_file).unwrap_or(100);
+                let start = start_file.exists().then_some(start);
+                for (threshold, value) in threshold_writes(current_end, start, end) {
+                    let file = match threshold {
+                        Threshold::Start => &start_file,
+                        Threshold::End => &end_file,
+                    };
+                    write(file, value)?;
+                }
+            }
+        }
+        Control::Conservation(file) => write(&file, u32::from(on))?,
+    }
+    eprintln!(
+        "jb-shell: [battery] charge limit {}",
+        if on { "on" } else { "off" }
+    );
+    read().ok_or_else(|| "charge limit disappeared".to_string())
+}
+
+fn find() -> Option<Control> {
+    let mut batteries: Vec<PathBuf> = std::fs::read_dir(POWER_SUPPLY)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| {
+            std::fs::read_to_string(dir.join("type")).is_ok_and(|t| t.trim() == "Battery")
+                && dir.join("charge_control_end_threshold").exists()
+        })
+        .collect();
+    if !batteries.is_empty() {
+        batteries.sort();
+        return Some(Control::Thresholds(batteries));
+    }
+    std::fs::read_dir(IDEAPAD)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join("conservation_mode"))
+        .find(|file| file.exists())
+        .map(Control::Conservation)
+}
+
+fn read_percent(file: &Path) -> Option<u32> {
+    std::fs::read_to_string(file).ok()?.trim().parse().ok()
+}
+
+fn write(file: &Path, value: u32) -> Result<(), String> {
+    std::fs::write(file, value.to_string()).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => {
+            format!("no write access to {}", file.display())
+        }
+        _ => format!("{}: {e}", file.display()),
+    })
+}
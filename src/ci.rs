@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use crate::config::{CiPipeline, CiProvider};
+use crate::core::ci::{
+    gitlab_project_id, newly_failed, parse_github_runs, parse_gitlab_pipelines, PipelineRun,
+};
+use crate::http::fetch;
+use crate::widgets::notifications::{
+    ActionCallback, NotificationAction, NotificationId, NotificationInput, NotificationKind,
+    NotificationRequest, NotificationSource,
+};
+
+/// Failure toasts count down from here, one id per pipeline so a second
+/// failure replaces the first.
+const FAILURE_NOTIFICATION_ID: NotificationId = u64::MAX - 0x100;
+
+/// One `[[ci.pipelines]]` entry as last polled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiPipelineState {
+    /// "owner/repo@branch", shown in the tooltip and popup.
+    pub name: String,
+    /// None until the first poll, or when the branch has no runs.
+    pub run: Option<PipelineRun>,
+    /// Why the last poll failed; the previous `run` is kept.
+    pub error: Option<String>,
+}
+
+/// Polls the latest run of every `[[ci.pipelines]]` entry, publishes all of
+/// them whenever any changed and toasts each one that turns red. Nothing runs
+/// unless the bar layout places "ci".
+pub fn spawn_ci_thread(
+    rt: &tokio::runtime::Handle,
+    notif_sender: relm4::Sender<NotificationInput>,
+    publish: impl Fn(Vec<CiPipelineState>) + Send + 'static,
+) {
+    let config = &crate::config::get().ci;
+    if config.pipelines.is_empty() || !crate::config::get().bar.places("ci") {
+        return;
+    }
+    let interval = Duration::from_secs(config.poll_interval_secs.max(30));
+
+    rt.spawn(async move {
+        let config = &crate::config::get().ci;
+        // An explicit `[ci] github_token` wins over the keyring, which wins
+        // over `$GITHUB_TOKEN`
+        let github_token = match config.github_token.clone().filter(|t| !t.is_empty()) {
+            Some(configured) => Some(configured),
+            None => match crate::secrets::get_async(crate::secrets::GITHUB_TOKEN).await {
+                Some(stored) => Some(stored),
+                None => std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
+            },
+        };
+        let gitlab_token = token(&config.gitlab_token, "GITLAB_TOKEN");
+        let client = reqwest::Client::new();
+        let mut states: Vec<CiPipelineState> = config
+            .pipelines
+            .iter()
+            .map(|p| CiPipelineState {
+                name: format!("{}@{}", p.repo, p.branch),
+                run: None,
+                error: None,
+            })
+            .collect();
+        let mut polled = false;
+
+        loop {
+            let mut changed = false;
+            for (i, (pipeline, state)) in config.pipelines.iter().zip(&mut states).enumerate() {
+                let result = match pipeline.provider {
+                    CiProvider::Github => {
+                        latest_github_run(&client, pipeline, github_token.as_deref()).await
+                    }
+                    CiProvider::Gitlab => {
+                        latest_gitlab_run(&client, pipeline, gitlab_token.as_deref()).await
+                    }
+                };
+                let (run, error) = match result {
+                    Ok(run) => (run, None),
+                    Err(e) => {
+                        eprintln!("jb-shell: [ci] {}: {e}", state.name);
+                        (state.run.clone(), Some(e))
+                    }
+                };
+                if let Some(current) = &run {
+                    if newly_failed(state.run.as_ref(), current) {
+                        notif_sender.emit(NotificationInput::Show(failure_toast(
+                            FAILURE_NOTIFICATION_ID - i as NotificationId,
+                            &state.name,
+                            current,
+                        )));
+                    }
+                }
+                if state.run != run || state.error != error {
+                    state.run = run;
+                    state.error = error;
+                    changed = true;
+                }
+            }
+            if changed || !polled {
+                publish(states.clone());
+            }
+            polled = true;
+            crate::idle::sleep_async(interval).await;
+        }
+    });
+}
+
+fn token(configured: &Option<String>, env: &str) -> Option<String> {
+    configured
+        .clone()
+        .or_else(|| std::env::var(env).ok())
+        .filter(|t| !t.is_empty())
+}
+
+async fn latest_github_run(
+    client: &reqwest::Client,
+    pipeline: &CiPipeline,
+    token: Option<&str>,
+) -> Result<Option<PipelineRun>, String> {
+    let mut request = client
+        .get(format!(
+            "https://api.github.com/repos/{}/actions/runs",
+            pipeline.repo
+        ))
+        .query(&[("branch", pipeline.branch.as_str()), ("per_page", "1")])
+        .header(reqwest::header::USER_AGENT, "jb-shell")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let body = fetch(request).await?;
+    parse_github_runs(&body).map_err(|e| e.to_string())
+}
+
+async fn latest_gitlab_run(
+    client: &reqwest::Client,
+    pipeline: &CiPipeline,
+    token: Option<&str>,
+) -> Result<Option<PipelineRun>, String> {
+    let host = pipeline.host.as_deref().unwrap_or("gitlab.com");
+    let mut request = client
+        .get(format!(
+            "https://{host}/api/v4/projects/{}/pipelines",
+            gitlab_project_id(&pipeline.repo)
+        ))
+        .query(&[("ref", pipeline.branch.as_str()), ("per_page", "1")]);
+    if let Some(token) = token {
+        request = request.header("PRIVATE-TOKEN", token);
+    }
+    let body = fetch(request).await?;
+    parse_gitlab_pipelines(&body).map_err(|e| e.to_string())
+}
+
+fn failure_toast(id: NotificationId, name: &str, run: &PipelineRun) -> NotificationRequest {
+    NotificationRequest {
+        id,
+        kind: NotificationKind::Toast,
+        icon: Some(run.status.icon().to_string()),
+        title: format!("{name} failed"),
+        body: Some(run.title.clone()),
+        subtitle: None,
+        countdown_target: None,
+        actions: vec![NotificationAction {
+            label: "Open run".to_string(),
+            css_class: "notif-default-action".to_string(),
+            callback: ActionCallback::OpenUrl(run.url.clone()),
+        }],
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: Some("urgency-critical".to_string()),
+        timeout_ms: Some(15000),
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    }
+}
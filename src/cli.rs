@@ -0,0 +1,138 @@
+use chrono::Local;
+use std::io::{BufRead, IsTerminal};
+
+use crate::core::cli::{
+    parse_key_args, parse_notify_args, parse_secret_args, SecretArgs, KEY_USAGE, NOTIFY_USAGE,
+    SECRET_USAGE,
+};
+
+/// Run a `jb-shell <subcommand>` against the running shell, returning the
+/// exit code. `None` when `args` name no subcommand and the shell should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first()?.as_str() {
+        "notify" => Some(notify(&args[1..])),
+        "secret" => Some(secret(&args[1..])),
+        "key" => Some(key(&args[1..])),
+        _ => None,
+    }
+}
+
+/// Post a toast through `dev.jb.shell.Notify`, printing its ID.
+fn notify(args: &[String]) -> i32 {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{NOTIFY_USAGE}");
+        return 0;
+    }
+    // Checked here too so mistakes don't depend on the shell running
+    if let Err(e) = parse_notify_args(args, Local::now()) {
+        eprintln!("jb-shell notify: {e}\n{NOTIFY_USAGE}");
+        return 2;
+    }
+
+    let reply = crate::dbus::session_blocking().and_then(|conn| {
+        conn.call_method(
+            Some("dev.jb.shell.Notify"),
+            "/dev/jb/shell/Notify",
+            Some("dev.jb.shell.Notify"),
+            "Post",
+            &(args,),
+        )?
+        .body()
+        .deserialize::<u64>()
+    });
+    match reply {
+        Ok(id) => {
+            println!("{id}");
+            0
+        }
+        Err(e) => {
+            eprintln!("jb-shell notify: is jb-shell running? {e}");
+            1
+        }
+    }
+}
+
+/// Store or remove a secret in the Secret Service. The value is read from
+/// stdin so it stays out of the shell history and `ps`.
+fn secret(args: &[String]) -> i32 {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{SECRET_USAGE}");
+        return 0;
+    }
+    let parsed = match parse_secret_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("jb-shell secret: {e}\n{SECRET_USAGE}");
+            return 2;
+        }
+    };
+    let result = match &parsed {
+        SecretArgs::Set(name) => {
+            let stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                eprint!("{name}: ");
+            }
+            let mut value = String::new();
+            if let Err(e) = stdin.lock().read_line(&mut value) {
+                eprintln!("jb-shell secret: can't read stdin: {e}");
+                return 1;
+            }
+            let value = value.trim();
+            if value.is_empty() {
+                eprintln!("jb-shell secret: empty value; use `secret clear {name}` to remove it");
+                return 2;
+            }
+            crate::secrets::set(name, value, true).map(|()| format!("stored {name}"))
+        }
+        SecretArgs::Clear(name) => crate::secrets::delete(name).map(|found| {
+            if found {
+                format!("removed {name}")
+            } else {
+                format!("{name} wasn't stored")
+            }
+        }),
+    };
+    match result {
+        Ok(message) => {
+            println!("{message}");
+            0
+        }
+        Err(e) => {
+            eprintln!("jb-shell secret: no usable Secret Service (gnome-keyring, KeePassXC): {e}");
+            1
+        }
+    }
+}
+
+/// Hand a volume, brightness or media key to `dev.jb.shell.Media`, for
+/// Hyprland binds: `bindel = , XF86AudioRaiseVolume, exec, jb-shell key
+/// XF86AudioRaiseVolume`.
+fn key(args: &[String]) -> i32 {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{KEY_USAGE}");
+        return 0;
+    }
+    let key = match parse_key_args(args) {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("jb-shell key: {e}\n{KEY_USAGE}");
+            return 2;
+        }
+    };
+    let reply = crate::dbus::session_blocking().and_then(|conn| {
+        conn.call_method(
+            Some("dev.jb.shell.Media"),
+            "/dev/jb/shell/Media",
+            Some("dev.jb.shell.Media"),
+            "Key",
+            &(key.keysym(),),
+        )
+    });
+    match reply {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("jb-shell key: is jb-shell running? {e}");
+            1
+        }
+    }
+}
@@ -0,0 +1,1203 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// User settings from `~/.config/jb-shell/config.toml`. Every section and key is
+/// optional; missing values fall back to the built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub bar: BarConfig,
+    pub toasts: ToastConfig,
+    pub tooltips: TooltipConfig,
+    pub clock: ClockConfig,
+    pub active_window: ActiveWindowConfig,
+    /// Click/scroll actions keyed by bar widget name (as used in `[bar.layout]`).
+    pub actions: HashMap<String, WidgetActions>,
+    pub context_menu: ContextMenuConfig,
+    pub volume: VolumeConfig,
+    pub media_keys: MediaKeysConfig,
+    pub meetings: MeetingConfig,
+    pub commute: CommuteConfig,
+    pub idle: IdleConfig,
+    pub power: PowerConfig,
+    pub battery: BatteryConfig,
+    pub launcher: LauncherConfig,
+    pub autostart: AutostartConfig,
+    pub wallpaper: WallpaperConfig,
+    pub night_light: NightLightConfig,
+    pub location: LocationConfig,
+    pub caffeine: CaffeineConfig,
+    pub focus: FocusConfig,
+    pub privacy: PrivacyConfig,
+    pub workspace_preview: WorkspacePreviewConfig,
+    pub display: DisplayConfig,
+    pub network: NetworkConfig,
+    /// Waybar-style script modules, placed in the layout as `custom/<name>`.
+    pub custom: HashMap<String, CustomModuleConfig>,
+    pub i3bar: I3barConfig,
+    pub window_focus: WindowFocusConfig,
+    pub taskbar: TaskbarConfig,
+    pub summary: SummaryConfig,
+    pub switchers: SwitchersConfig,
+    pub git: GitConfig,
+    pub ci: CiConfig,
+    pub oncall: OnCallConfig,
+    pub metrics: MetricsConfig,
+    /// `[[reminders]]`: notifications posted on a cron-style schedule.
+    pub reminders: Vec<ReminderConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BarStyle {
+    /// Full-width strip along the top edge that reserves screen space.
+    Docked,
+    /// Rounded bar inset from the screen edges that windows can slide under.
+    Floating,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct BarConfig {
+    pub style: BarStyle,
+    /// Background opacity multiplier (0.0–1.0) for the theme's `@bg`; unset
+    /// leaves the stylesheet alone.
+    pub opacity: Option<f64>,
+    /// Floating style: gap between the bar and the screen edges.
+    pub margin: i32,
+    /// Floating style: corner radius shared by the bar and its popups.
+    pub radius: i32,
+    pub layout: BarLayout,
+    /// Named widget groups, rendered as pill containers with a
+    /// `group-<name>` CSS class and referenced by name from the layout.
+    pub groups: HashMap<String, Vec<String>>,
+}
+
+/// Widget order per bar section. Entries are widget names, group names, or
+/// `"|"` for a separator.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct BarLayout {
+    pub left: Vec<String>,
+    pub center: Vec<String>,
+    pub right: Vec<String>,
+    pub spacing: SectionSpacing,
+}
+
+impl Default for BarLayout {
+    fn default() -> Self {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
+        BarLayout {
+            left: names(&["workspaces", "kube", "gcloud", "mpris"]),
+            center: names(&["active-window"]),
+            right: names(&[
+                "join-meeting",
+                "notifications",
+                "calendar",
+                "volume",
+                "network",
+                "battery",
+                "clock",
+            ]),
+            spacing: SectionSpacing::default(),
+        }
+    }
+}
+
+/// Gap between items, per section and inside groups.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SectionSpacing {
+    pub left: i32,
+    pub center: i32,
+    pub right: i32,
+    pub group: i32,
+}
+
+impl Default for SectionSpacing {
+    fn default() -> Self {
+        SectionSpacing {
+            left: 12,
+            center: 0,
+            right: 8,
+            group: 6,
+        }
+    }
+}
+
+impl Default for BarConfig {
+    fn default() -> Self {
+        BarConfig {
+            style: BarStyle::Docked,
+            opacity: None,
+            margin: 8,
+            radius: 12,
+            layout: BarLayout::default(),
+            groups: HashMap::new(),
+        }
+    }
+}
+
+impl BarConfig {
+    /// Whether the layout places `name`, directly or inside a group.
+    pub fn places(&self, name: &str) -> bool {
+        let layout = &self.layout;
+        [&layout.left, &layout.center, &layout.right]
+            .into_iter()
+            .flatten()
+            .any(|entry| {
+                entry == name
+                    || self
+                        .groups
+                        .get(entry)
+                        .is_some_and(|group| group.iter().any(|member| member == name))
+            })
+    }
+
+    /// Distance from the screen edges to the bar window; 0 when docked.
+    pub fn edge_margin(&self) -> i32 {
+        match self.style {
+            BarStyle::Docked => 0,
+            BarStyle::Floating => self.margin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToastAnchor {
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
+}
+
+impl ToastAnchor {
+    pub fn is_top(self) -> bool {
+        matches!(self, ToastAnchor::TopRight | ToastAnchor::TopLeft)
+    }
+
+    pub fn is_right(self) -> bool {
+        matches!(self, ToastAnchor::TopRight | ToastAnchor::BottomRight)
+    }
+}
+
+/// What to do with toasts beyond `max_visible`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToastOverflow {
+    /// Queue them behind a "+N more" pill that opens the notification center.
+    Collapse,
+    /// Queue them silently until a slot frees up.
+    Hide,
+}
+
+/// How urgency=0 ("low") notifications are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LowUrgency {
+    /// A regular toast, just styled quieter.
+    Toast,
+    /// Straight to history: unread badge only, no toast.
+    Silent,
+}
+
+/// How markup in app notification bodies is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BodyMarkup {
+    /// Plain text: every tag dropped, and entities other than the standard
+    /// ones too.
+    Strict,
+    /// Bold, italic, underline and links kept; other tags dropped and
+    /// unknown entities shown as written.
+    Lenient,
+}
+
+/// Which monitor(s) new toasts appear on.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToastMonitor {
+    /// The monitor Hyprland currently has focused.
+    Focused,
+    /// Duplicate every toast onto every monitor.
+    All,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ToastConfig {
+    pub anchor: ToastAnchor,
+    pub monitor: ToastMonitor,
+    /// Distance from the anchored vertical screen edge. Defaults to clearing the
+    /// bar for top anchors and 8px for bottom anchors.
+    pub margin_y: Option<i32>,
+    /// Distance from the anchored horizontal screen edge.
+    pub margin_x: i32,
+    /// Gap between stacked toasts.
+    pub spacing: i32,
+    /// Maximum toasts on screen at once; 0 means unlimited.
+    pub max_visible: usize,
+    pub overflow: ToastOverflow,
+    /// On startup, re-show unread notifications from the last N minutes whose
+    /// toast was cut short by a restart; 0 disables.
+    pub restore_missed_minutes: u32,
+    /// Identical notifications (same app, summary and body) within this many
+    /// seconds collapse into one "×N" toast and history row; 0 disables.
+    pub collapse_repeats_secs: u32,
+    pub low_urgency: LowUrgency,
+    /// Keep a small capture of the sender's window with each notification,
+    /// shown in the notification center. Off by default; never taken for
+    /// muted apps, during DND or while `[privacy]` sees a screencast.
+    pub thumbnails: bool,
+    /// "Open link" and "Copy code" buttons for URLs and 6-digit one-time
+    /// codes in notification bodies.
+    pub quick_actions: bool,
+    /// Longer bodies are cut to this many lines behind a "show more" chevron.
+    pub body_lines: i32,
+    /// Height an expanded body may grow to before it scrolls, in pixels.
+    pub expanded_max_height: i32,
+    /// Body markup in toasts, notification center rows and the AI summary
+    /// prompt (which always gets plain text).
+    pub markup: BodyMarkup,
+}
+
+const BAR_HEIGHT_OFFSET: i32 = 40; // ~31px bar + 8px gap + 1px breathing room
+
+impl Default for ToastConfig {
+    fn default() -> Self {
+        ToastConfig {
+            anchor: ToastAnchor::TopRight,
+            monitor: ToastMonitor::Focused,
+            margin_y: None,
+            margin_x: 8,
+            spacing: 8,
+            max_visible: 0,
+            overflow: ToastOverflow::Collapse,
+            restore_missed_minutes: 30,
+            collapse_repeats_secs: 60,
+            low_urgency: LowUrgency::Toast,
+            thumbnails: false,
+            quick_actions: true,
+            body_lines: 4,
+            expanded_max_height: 320,
+            markup: BodyMarkup::Lenient,
+        }
+    }
+}
+
+impl ToastConfig {
+    pub fn margin_y(&self) -> i32 {
+        self.margin_y.unwrap_or(if self.anchor.is_top() {
+            BAR_HEIGHT_OFFSET + get().bar.edge_margin()
+        } else {
+            8
+        })
+    }
+}
+
+/// The bar clock's labels, as strftime formats. Month and day names and
+/// AM/PM follow the locale (`LC_TIME`).
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Empty hides the date label.
+    pub date_format: String,
+    /// Overrides `24h` and `seconds`.
+    pub time_format: Option<String>,
+    #[serde(rename = "24h")]
+    pub hour24: bool,
+    pub seconds: bool,
+    /// Shown instead after a left click on the clock, until the next one.
+    pub alt_date_format: String,
+    pub alt_time_format: String,
+}
+
+impl ClockConfig {
+    /// `time_format`, or the 12- or 24-hour format `24h` and `seconds` ask for.
+    pub fn time_format(&self) -> &str {
+        if let Some(format) = &self.time_format {
+            return format;
+        }
+        match (self.hour24, self.seconds) {
+            (false, false) => "%-I:%M %p",
+            (false, true) => "%-I:%M:%S %p",
+            (true, false) => "%H:%M",
+            (true, true) => "%H:%M:%S",
+        }
+    }
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            date_format: "%a, %b %-d".into(),
+            time_format: None,
+            hour24: false,
+            seconds: false,
+            alt_date_format: "%A, %B %-d, %Y".into(),
+            alt_time_format: "%H:%M:%S".into(),
+        }
+    }
+}
+
+/// Per-widget overrides. Values are `@builtin` names (e.g. `@launcher`,
+/// `@volume-mute`) or shell commands run via `sh -c`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct WidgetActions {
+    #[serde(alias = "on-click")]
+    pub left_click: Option<String>,
+    #[serde(alias = "on-click-middle")]
+    pub middle_click: Option<String>,
+    #[serde(alias = "on-click-right")]
+    pub right_click: Option<String>,
+    #[serde(alias = "on-scroll-up")]
+    pub scroll_up: Option<String>,
+    #[serde(alias = "on-scroll-down")]
+    pub scroll_down: Option<String>,
+}
+
+/// Apps the bar widgets' right-click menus open.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ContextMenuConfig {
+    /// "Connection settings" in the network widget's menu.
+    pub network_editor: String,
+    /// "Volume mixer" in the volume widget's menu.
+    pub mixer: String,
+    /// Snippets file the active window's menu saves window rules to, for
+    /// `source =` in hyprland.conf; `windowrules.conf` in the config
+    /// directory if unset.
+    pub window_rules: Option<String>,
+}
+
+impl Default for ContextMenuConfig {
+    fn default() -> Self {
+        Self {
+            network_editor: "nm-connection-editor".into(),
+            mixer: "pavucontrol".into(),
+            window_rules: None,
+        }
+    }
+}
+
+/// How far the volume widget, `@volume-up` and the volume keys may go.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct VolumeConfig {
+    /// Highest volume any of them sets, e.g. 150 to boost quiet videos;
+    /// the widget turns a warning color above 100.
+    pub max_percent: u32,
+    /// Percent per scroll notch on the widget, per `@volume-up`/`-down` and
+    /// per volume key press.
+    pub step: u32,
+}
+
+impl Default for VolumeConfig {
+    fn default() -> Self {
+        Self {
+            max_percent: 100,
+            step: 5,
+        }
+    }
+}
+
+/// Volume, brightness and media keys the shell handles itself, handed over
+/// by Hyprland binds running `jb-shell key <keysym>`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MediaKeysConfig {
+    /// Register those binds with Hyprland at startup, so hyprland.conf needs
+    /// none. `hyprctl reload` drops them until the shell restarts.
+    pub bind: bool,
+    /// Percent of the backlight's range per brightness key press.
+    pub brightness_step: u32,
+    /// Device under `/sys/class/backlight`; the first one if unset.
+    pub backlight: Option<String>,
+}
+
+impl Default for MediaKeysConfig {
+    fn default() -> Self {
+        Self {
+            bind: false,
+            brightness_step: 5,
+            backlight: None,
+        }
+    }
+}
+
+/// A long-running script speaking waybar's custom module protocol. Keys
+/// follow waybar's names so module configs can be pasted over.
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CustomModuleConfig {
+    /// Run with `sh -c`; every stdout line is an update.
+    pub exec: String,
+    /// `{}`/`{text}`, `{alt}`, `{percentage}` and `{icon}` are replaced.
+    pub format: String,
+    /// Picked by `percentage` for `{icon}`, lowest first.
+    pub format_icons: Vec<String>,
+    /// Seconds before re-running the script once it exits; unset leaves it
+    /// stopped.
+    #[serde(alias = "interval")]
+    pub restart_interval: Option<u64>,
+    /// Clicks and scrolls, as in `[actions]` (waybar's `on-click` works too).
+    #[serde(flatten)]
+    pub actions: WidgetActions,
+}
+
+impl Default for CustomModuleConfig {
+    fn default() -> Self {
+        CustomModuleConfig {
+            exec: String::new(),
+            format: "{}".to_string(),
+            format_icons: Vec::new(),
+            restart_interval: None,
+            actions: WidgetActions::default(),
+        }
+    }
+}
+
+/// Raising an app's window from a notification or the media player.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct WindowFocusConfig {
+    /// Window class regex per desktop entry id or app name, for apps whose
+    /// class doesn't resemble either (e.g. `"code-oss" = "(?i)^code"`).
+    pub classes: HashMap<String, String>,
+}
+
+/// The "taskbar" bar widget.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TaskbarConfig {
+    /// Desktop entry ids, left to right.
+    pub pinned: Vec<String>,
+}
+
+/// Instructions for the notification center's AI summary. The guard telling
+/// the model to treat notification text as data always stays in front.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    /// Replaces the built-in instructions (what to summarize and how).
+    pub prompt: Option<String>,
+    /// Appended to the instructions, e.g. "List OTP codes first".
+    pub prompt_append: Option<String>,
+}
+
+/// Toolchain switchers (`terraform`, `node`, `rustup` in the bar layout).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SwitchersConfig {
+    /// Which version manager the `node` switcher drives.
+    pub node: NodeManager,
+    /// Terraform root module whose workspace the `terraform-workspace`
+    /// switcher shows and selects.
+    pub terraform_dir: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodeManager {
+    #[default]
+    Fnm,
+    Nvm,
+}
+
+/// Repositories watched by the `git` bar widget.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    /// Repository paths; `~/` is expanded.
+    pub repos: Vec<String>,
+    pub poll_interval_secs: u64,
+    /// Opens a repository from the popup; run with its path as argument.
+    pub editor: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig {
+            repos: Vec::new(),
+            poll_interval_secs: 30,
+            editor: "code".into(),
+        }
+    }
+}
+
+/// Pipelines watched by the `ci` bar widget.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CiConfig {
+    pub poll_interval_secs: u64,
+    /// Wins over a `github-token` in the Secret Service, which wins over
+    /// `$GITHUB_TOKEN`. Public repositories work without one at a lower rate
+    /// limit.
+    pub github_token: Option<String>,
+    /// Falls back to `$GITLAB_TOKEN`; needs `read_api`.
+    pub gitlab_token: Option<String>,
+    pub pipelines: Vec<CiPipeline>,
+}
+
+impl Default for CiConfig {
+    fn default() -> Self {
+        CiConfig {
+            poll_interval_secs: 120,
+            github_token: None,
+            gitlab_token: None,
+            pipelines: Vec::new(),
+        }
+    }
+}
+
+/// One `[[ci.pipelines]]` entry: the latest run on `branch` of `repo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CiPipeline {
+    pub provider: CiProvider,
+    /// `owner/repo` on GitHub, `group/project` on GitLab.
+    pub repo: String,
+    pub branch: String,
+    /// GitLab host for self-managed instances; unset is gitlab.com.
+    #[serde(default)]
+    pub host: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CiProvider {
+    Github,
+    Gitlab,
+}
+
+/// Incident service behind the `oncall` bar widget.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct OnCallConfig {
+    pub provider: OnCallProvider,
+    /// PagerDuty user API token, or Opsgenie API key; unset leaves the widget
+    /// hidden.
+    pub token: Option<String>,
+    /// Sent as `From` on PagerDuty updates, and matched against Opsgenie
+    /// alert owners and on-call recipients; Opsgenie won't poll without it.
+    pub email: Option<String>,
+    /// Opsgenie schedule name whose on-call list decides "on call".
+    pub schedule: Option<String>,
+    /// Opsgenie API host, e.g. `api.eu.opsgenie.com`.
+    pub opsgenie_host: String,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for OnCallConfig {
+    fn default() -> Self {
+        OnCallConfig {
+            provider: OnCallProvider::default(),
+            token: None,
+            email: None,
+            schedule: None,
+            opsgenie_host: "api.opsgenie.com".into(),
+            poll_interval_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnCallProvider {
+    #[default]
+    Pagerduty,
+    Opsgenie,
+}
+
+/// Audit mode: the shell counts its own work so a busy widget can be found.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Count widget updates, thread wakeups and D-Bus calls, and sample CPU
+    /// and memory for the `metrics` bar widget.
+    pub enabled: bool,
+    /// Seconds between samples; rates in the popup are over this window.
+    pub interval_secs: u64,
+    /// `host:port` serving the counters as Prometheus text; unset doesn't
+    /// listen.
+    pub listen: Option<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: false,
+            interval_secs: 5,
+            listen: None,
+        }
+    }
+}
+
+/// A notification posted whenever `schedule` matches the local time.
+#[derive(Debug, Deserialize)]
+pub struct ReminderConfig {
+    /// Five cron fields: minute, hour, day of month, month, day of week.
+    pub schedule: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Cover the screen like the one-minute meeting alert instead of a toast.
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+/// An i3bar-protocol status generator rendered by the `i3bar` bar widget.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct I3barConfig {
+    /// Run with `sh -c`, e.g. `i3status-rs ~/.config/i3status-rust/config.toml`;
+    /// unset leaves the widget hidden.
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TooltipConfig {
+    /// Hover time before a bar widget's tooltip appears.
+    pub delay_ms: u64,
+}
+
+impl Default for TooltipConfig {
+    fn default() -> Self {
+        TooltipConfig { delay_ms: 500 }
+    }
+}
+
+/// How the active window's title fits the bar.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ActiveWindowConfig {
+    /// Longer titles are cut short with "...", or scroll with `marquee`.
+    pub max_chars: usize,
+    /// Scroll long titles through that width instead of cutting them;
+    /// hovering pauses it. Off while animations are disabled.
+    pub marquee: bool,
+}
+
+impl Default for ActiveWindowConfig {
+    fn default() -> Self {
+        ActiveWindowConfig {
+            max_chars: 60,
+            marquee: false,
+        }
+    }
+}
+
+/// Where the launcher card sits on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LauncherPlacement {
+    /// Vertically centered; the card grows both ways as results change.
+    Center,
+    /// Fixed top edge a sixth of the way down, so the search field never moves.
+    TopThird,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LauncherConfig {
+    /// Card width in pixels.
+    pub width: i32,
+    pub max_results: usize,
+    pub icon_size: i32,
+    pub placement: LauncherPlacement,
+    /// Show a details pane for the highlighted result next to the list.
+    pub preview: bool,
+    /// Terminal for "launch in terminal" (Shift+Enter / right-click); takes
+    /// `-e <command>`. Defaults to `$TERMINAL`, then `foot`.
+    pub terminal: Option<String>,
+    /// Desktop name(s) matched against `OnlyShowIn`/`NotShowIn`. Defaults to
+    /// `$XDG_CURRENT_DESKTOP`, then `Hyprland`.
+    pub desktop: Option<String>,
+    /// Folders `f <name>` searches (`~/` allowed), and how many levels below
+    /// them.
+    pub file_roots: Vec<String>,
+    pub file_depth: usize,
+}
+
+impl Default for LauncherConfig {
+    fn default() -> Self {
+        LauncherConfig {
+            width: 500,
+            max_results: 8,
+            icon_size: 24,
+            placement: LauncherPlacement::Center,
+            preview: false,
+            terminal: None,
+            desktop: None,
+            file_roots: vec!["~/".into()],
+            file_depth: 4,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AutostartConfig {
+    /// Launch XDG autostart entries once per login session.
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WallpaperBackend {
+    /// Leave the wallpaper to something else.
+    Off,
+    /// Drive a running hyprpaper via `hyprctl hyprpaper`.
+    Hyprpaper,
+    /// Draw it ourselves on a background layer-shell surface per monitor.
+    Builtin,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct WallpaperConfig {
+    pub backend: WallpaperBackend,
+    /// Images offered by the launcher's `wp` picker and the slideshow.
+    pub folder: String,
+    /// Default wallpaper for every monitor.
+    pub path: Option<String>,
+    /// Per-monitor wallpapers keyed by connector name (e.g. `DP-1`).
+    pub monitors: HashMap<String, String>,
+    /// Rotate through `folder` every N minutes; 0 disables.
+    pub slideshow_minutes: u32,
+}
+
+impl Default for WallpaperConfig {
+    fn default() -> Self {
+        WallpaperConfig {
+            backend: WallpaperBackend::Off,
+            folder: "~/Pictures/Wallpapers".into(),
+            path: None,
+            monitors: HashMap::new(),
+            slideshow_minutes: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NightLightBackend {
+    Hyprsunset,
+    Wlsunset,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct NightLightConfig {
+    pub backend: NightLightBackend,
+    /// Initial warm temperature in Kelvin; the popup slider changes it.
+    pub temperature: u32,
+    /// Turn on at sunset and off at sunrise. Needs a location: these
+    /// coordinates, else `[location]`'s.
+    pub auto: bool,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl Default for NightLightConfig {
+    fn default() -> Self {
+        NightLightConfig {
+            backend: NightLightBackend::Hyprsunset,
+            temperature: 4000,
+            auto: false,
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
+
+/// Where the user is, for features that follow the sun.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LocationConfig {
+    /// Consent to ask GeoClue2 for the location (city accuracy) when no
+    /// coordinates are set below.
+    pub geoclue: bool,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// When the shell holds an idle inhibitor on its own, besides the manual toggle.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CaffeineConfig {
+    /// Window classes (case-insensitive) that keep the screen on while focused.
+    pub apps: Vec<String>,
+    /// Keep the screen on while an MPRIS player is playing video.
+    pub video: bool,
+    /// Players whose playback always counts as video, by MPRIS name, desktop
+    /// entry or identity. Other players count when their track URL is a video file.
+    pub video_players: Vec<String>,
+}
+
+impl Default for CaffeineConfig {
+    fn default() -> Self {
+        CaffeineConfig {
+            apps: Vec::new(),
+            video: true,
+            video_players: ["mpv", "vlc", "celluloid", "totem", "haruna"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+/// Focus mode: distracting apps are parked out of sight and DND held on.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// Window classes (case-insensitive) moved away while focus mode is on.
+    pub apps: Vec<String>,
+    /// Special workspace they're parked on (`special:<name>`).
+    pub workspace: String,
+    /// Turn focus mode off when an accepted meeting starts.
+    pub end_for_meetings: bool,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        FocusConfig {
+            apps: ["Slack", "discord", "vesktop"].map(String::from).to_vec(),
+            workspace: "focus".to_string(),
+            end_for_meetings: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrivacyToasts {
+    Show,
+    /// Blur toast bodies; titles stay readable.
+    HideBody,
+    /// Send new notifications straight to the center, as under DND.
+    Hide,
+}
+
+/// What a screencast (portal share reported by Hyprland) shouldn't show.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    pub enabled: bool,
+    /// Bar layout names blurred while casting.
+    pub widgets: Vec<String>,
+    pub toasts: PrivacyToasts,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        PrivacyConfig {
+            enabled: true,
+            widgets: ["calendar", "kube", "gcloud", "mpris"]
+                .map(String::from)
+                .to_vec(),
+            toasts: PrivacyToasts::HideBody,
+        }
+    }
+}
+
+/// What a click in the workspace preview popup does.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreviewClick {
+    Nothing,
+    /// Focus the window under the pointer.
+    Focus,
+    /// Switch to the previewed workspace.
+    Switch,
+    /// Close the window under the pointer; the popup stays open.
+    Close,
+}
+
+/// Mouse bindings for the workspace preview thumbnails. Actions that need a
+/// window do nothing over empty space.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct WorkspacePreviewConfig {
+    pub click: PreviewClick,
+    /// Single clicks wait out the double-click time when this isn't `nothing`.
+    pub double_click: PreviewClick,
+    pub empty_click: PreviewClick,
+    pub middle_click: PreviewClick,
+}
+
+impl Default for WorkspacePreviewConfig {
+    fn default() -> Self {
+        WorkspacePreviewConfig {
+            click: PreviewClick::Focus,
+            double_click: PreviewClick::Switch,
+            empty_click: PreviewClick::Switch,
+            middle_click: PreviewClick::Close,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub hotspot: HotspotConfig,
+    pub usage: UsageConfig,
+    pub latency: LatencyConfig,
+}
+
+/// Data usage accounting, summed over every physical interface.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// Monthly allowance in GB (10^9 bytes); unset disables the warning.
+    pub monthly_cap_gb: Option<f64>,
+    /// Warn once this share of the cap is used, and again at 100%.
+    pub warn_percent: u8,
+    /// Day of the month the allowance resets.
+    pub month_start_day: u32,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        UsageConfig {
+            monthly_cap_gb: None,
+            warn_percent: 80,
+            month_start_day: 1,
+        }
+    }
+}
+
+/// Ping health shown on the network widget and in its popup.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LatencyConfig {
+    /// Host to ping; unset disables the monitor.
+    pub host: Option<String>,
+    pub interval_secs: u64,
+    /// Pings the verdict is taken over.
+    pub window: usize,
+    pub warn_ms: f64,
+    pub bad_ms: f64,
+    pub warn_loss_percent: f64,
+    pub bad_loss_percent: f64,
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        LatencyConfig {
+            host: None,
+            interval_secs: 5,
+            window: 12,
+            warn_ms: 100.0,
+            bad_ms: 300.0,
+            warn_loss_percent: 5.0,
+            bad_loss_percent: 20.0,
+        }
+    }
+}
+
+/// Wi-Fi access point started from the network popup via NetworkManager.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HotspotConfig {
+    pub ssid: String,
+    /// At least 8 characters; unset lets NetworkManager generate one.
+    pub password: Option<String>,
+    /// Wi-Fi device to run it on; unset lets NetworkManager pick.
+    pub interface: Option<String>,
+}
+
+impl Default for HotspotConfig {
+    fn default() -> Self {
+        HotspotConfig {
+            ssid: "jb-shell".to_string(),
+            password: None,
+            interface: None,
+        }
+    }
+}
+
+/// A named Hyprland monitor setup, picked from the "display" bar popup.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DisplayProfile {
+    pub name: String,
+    /// Connector names that select this profile on hotplug when exactly these
+    /// are connected; `"*"` stands for any one other output. Empty: manual only.
+    pub connected: Vec<String>,
+    /// `hyprctl keyword monitor` rules. Connected outputs no rule names are
+    /// disabled, unless a catch-all rule (empty name) covers them.
+    pub monitors: Vec<String>,
+}
+
+/// No profiles and `auto` off by default: monitor layout is left to
+/// hyprland.conf until profiles are configured.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Apply the first matching profile whenever the connected set changes.
+    pub auto: bool,
+    pub profiles: Vec<DisplayProfile>,
+}
+
+/// "Leave by" hints and reminders for calendar events somewhere physical.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CommuteConfig {
+    /// Minutes it takes to get to an event; 0 turns commute hints off.
+    pub buffer_minutes: u32,
+    /// Added when the forecast for the trip says rain, twice over for snow
+    /// or storms.
+    pub bad_weather_minutes: u32,
+    /// Fetch the hourly forecast for `[location]` from Open-Meteo.
+    pub weather: bool,
+}
+
+impl Default for CommuteConfig {
+    fn default() -> Self {
+        CommuteConfig {
+            buffer_minutes: 0,
+            bad_weather_minutes: 10,
+            weather: true,
+        }
+    }
+}
+
+/// What changes while an accepted calendar meeting is in progress.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MeetingConfig {
+    /// Start with "DND during meetings" enabled.
+    pub auto_dnd: bool,
+    /// Slack user token (`users.profile:write` + `users.profile:read`); unset
+    /// leaves Slack alone.
+    pub slack_token: Option<String>,
+    pub slack_status_text: String,
+    pub slack_status_emoji: String,
+    /// Show the bar's "Join" button this many minutes before a meeting starts.
+    pub join_button_minutes: u32,
+}
+
+impl Default for MeetingConfig {
+    fn default() -> Self {
+        MeetingConfig {
+            auto_dnd: false,
+            slack_token: None,
+            slack_status_text: "In a meeting".into(),
+            slack_status_emoji: ":calendar:".into(),
+            join_button_minutes: 5,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct IdleConfig {
+    /// Seconds without input before the user counts as idle; 0 disables idle backoff.
+    pub timeout_secs: u64,
+    /// Minimum polling interval for background pollers while idle.
+    pub poll_interval_secs: u64,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        IdleConfig {
+            timeout_secs: 300,
+            poll_interval_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// Enter degraded mode when discharging at or below `degraded_below` percent.
+    pub degraded_mode: bool,
+    pub degraded_below: u32,
+    /// Minimum polling interval for background pollers while degraded.
+    pub poll_interval_secs: u64,
+    /// Battery percentages that toast a warning once per discharge; empty
+    /// for none.
+    pub warn_below: Vec<u32>,
+    /// Argv the warning's "Suspend now" runs.
+    pub suspend_command: Vec<String>,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        PowerConfig {
+            degraded_mode: true,
+            degraded_below: 20,
+            poll_interval_secs: 60,
+            warn_below: vec![10, 5],
+            suspend_command: vec!["systemctl".into(), "suspend".into()],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct BatteryConfig {
+    /// Charge thresholds the battery popup's "Limit charging" sets, on
+    /// laptops with them: charging starts below `charge_start` percent and
+    /// stops at `charge_end`.
+    pub charge_start: u32,
+    pub charge_end: u32,
+}
+
+impl Default for BatteryConfig {
+    fn default() -> Self {
+        BatteryConfig {
+            charge_start: 75,
+            charge_end: 80,
+        }
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".into())).join(".config")
+        })
+        .join("jb-shell")
+}
+
+/// A configured path with a leading `~/` resolved against `$HOME` (the
+/// working directory without one).
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => {
+            PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".into())).join(rest)
+        }
+        None => PathBuf::from(path),
+    }
+}
+
+fn load() -> Config {
+    let path = config_dir().join("config.toml");
+    let data = match std::fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(_) => return Config::default(),
+    };
+    match toml::from_str(&data) {
+        Ok(config) => {
+            eprintln!("jb-shell: [config] loaded {}", path.display());
+            config
+        }
+        Err(e) => {
+            eprintln!("jb-shell: [config] failed to parse {}: {e}", path.display());
+            Config::default()
+        }
+    }
+}
+
+/// The process-wide config, read once on first access.
+pub fn get() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(load)
+}
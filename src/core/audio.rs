@@ -0,0 +1,226 @@
+//! Audio output ports from `pactl list sinks`: which sink and port is live and
+//! what kind of device it is, for the volume tooltip and port-switch toasts.
+//! Also the `wpctl` calls that step the volume within `[volume] max_percent`.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sink {
+    pub name: String,
+    pub description: String,
+    pub active_port: Option<String>,
+    pub ports: Vec<Port>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Port {
+    pub name: String,
+    pub description: String,
+}
+
+/// Where the sound comes out, judged from sink and port names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    Speakers,
+    Headphones,
+    Hdmi,
+    Bluetooth,
+    Other,
+}
+
+impl PortKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PortKind::Speakers => "Speakers",
+            PortKind::Headphones => "Headphones",
+            PortKind::Hdmi => "HDMI",
+            PortKind::Bluetooth => "Bluetooth",
+            PortKind::Other => "Output",
+        }
+    }
+}
+
+/// The default sink and the port it plays through.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveOutput {
+    pub sink: String,
+    pub sink_description: String,
+    pub port: Option<Port>,
+    pub kind: PortKind,
+}
+
+/// Sinks from `LC_ALL=C pactl list sinks`. Only the name, description and
+/// port lines are read; everything else is skipped.
+pub fn parse_sinks(text: &str) -> Vec<Sink> {
+    let mut sinks: Vec<Sink> = Vec::new();
+    let mut in_ports = false;
+    for line in text.lines() {
+        if line.starts_with("Sink #") {
+            sinks.push(Sink {
+                name: String::new(),
+                description: String::new(),
+                active_port: None,
+                ports: Vec::new(),
+            });
+            in_ports = false;
+            continue;
+        }
+        let Some(sink) = sinks.last_mut() else {
+            continue;
+        };
+        let depth = line.len() - line.trim_start_matches('\t').len();
+        let line = line.trim();
+        if depth >= 2 {
+            if in_ports {
+                if let Some((name, rest)) = line.split_once(": ") {
+                    let description = rest.split(" (").next().unwrap_or(rest);
+                    sink.ports.push(Port {
+                        name: name.to_string(),
+                        description: description.to_string(),
+                    });
+                }
+            }
+            continue;
+        }
+        in_ports = line == "Ports:";
+        if let Some(name) = line.strip_prefix("Name: ") {
+            sink.name = name.to_string();
+        } else if let Some(description) = line.strip_prefix("Description: ") {
+            sink.description = description.to_string();
+        } else if let Some(port) = line.strip_prefix("Active Port: ") {
+            sink.active_port = Some(port.to_string());
+        }
+    }
+    sinks
+}
+
+/// What `default_sink` (from `pactl get-default-sink`) is playing through.
+pub fn active_output(sinks: &[Sink], default_sink: &str) -> Option<ActiveOutput> {
+    let sink = sinks.iter().find(|s| s.name == default_sink)?;
+    let port = sink
+        .active_port
+        .as_ref()
+        .and_then(|active| sink.ports.iter().find(|p| &p.name == active))
+        .cloned();
+    Some(ActiveOutput {
+        sink: sink.name.clone(),
+        sink_description: sink.description.clone(),
+        kind: port_kind(&sink.name, port.as_ref().map_or("", |p| p.name.as_str())),
+        port,
+    })
+}
+
+pub fn port_kind(sink: &str, port: &str) -> PortKind {
+    let port = port.to_lowercase();
+    if sink.starts_with("bluez_") {
+        PortKind::Bluetooth
+    } else if port.contains("headphone") || port.contains("headset") {
+        PortKind::Headphones
+    } else if port.contains("hdmi") || port.contains("displayport") || sink.contains("hdmi") {
+        PortKind::Hdmi
+    } else if port.contains("speaker") {
+        PortKind::Speakers
+    } else {
+        PortKind::Other
+    }
+}
+
+/// `wpctl` arguments stepping `node` `step` percent up or down, capped at
+/// `max_percent` either way so a volume left above a lowered cap comes back
+/// under it.
+pub fn step_volume_args(node: &str, step: u32, up: bool, max_percent: u32) -> Vec<String> {
+    let limit = format!("{:.2}", max_percent as f64 / 100.0);
+    let step = format!("{step}%{}", if up { '+' } else { '-' });
+    ["set-volume", "-l", &limit, node, &step]
+        .map(str::to_string)
+        .to_vec()
+}
+
+/// Above 100%, where PipeWire amplifies and sound may clip.
+pub fn is_boosted(percent: u32) -> bool {
+    percent > 100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST: &str = "Sink #47
+\tState: RUNNING
+\tName: alsa_output.pci-0000_00_1f.3.analog-stereo
+\tDescription: Built-in Audio Analog Stereo
+\tProperties:
+\t\tdevice.description = \"Built-in Audio\"
+\tPorts:
+\t\tanalog-output-speaker: Speakers (type: Speaker, priority: 10000, availability unknown)
+\t\tanalog-output-headphones: Headphones (type: Headphones, priority: 9900, available)
+\tActive Port: analog-output-headphones
+\tFormats:
+\t\tpcm
+
+Sink #52
+\tState: SUSPENDED
+\tName: alsa_output.pci-0000_00_1f.3.hdmi-stereo
+\tDescription: Built-in Audio Digital Stereo (HDMI)
+\tPorts:
+\t\thdmi-output-0: HDMI / DisplayPort (type: HDMI, priority: 5900, not available)
+\tActive Port: hdmi-output-0
+";
+
+    #[test]
+    fn parses_names_and_ports() {
+        let sinks = parse_sinks(LIST);
+        assert_eq!(sinks.len(), 2);
+        assert_eq!(sinks[0].name, "alsa_output.pci-0000_00_1f.3.analog-stereo");
+        assert_eq!(sinks[0].description, "Built-in Audio Analog Stereo");
+        assert_eq!(
+            sinks[0].active_port.as_deref(),
+            Some("analog-output-headphones")
+        );
+        // Property lines aren't ports
+        assert_eq!(sinks[0].ports.len(), 2);
+        assert_eq!(sinks[0].ports[1].description, "Headphones");
+        assert_eq!(sinks[1].ports[0].description, "HDMI / DisplayPort");
+    }
+
+    #[test]
+    fn active_output_follows_the_default_sink() {
+        let sinks = parse_sinks(LIST);
+        let output = active_output(&sinks, "alsa_output.pci-0000_00_1f.3.analog-stereo").unwrap();
+        assert_eq!(output.kind, PortKind::Headphones);
+        assert_eq!(output.port.unwrap().name, "analog-output-headphones");
+
+        let hdmi = active_output(&sinks, "alsa_output.pci-0000_00_1f.3.hdmi-stereo").unwrap();
+        assert_eq!(hdmi.kind, PortKind::Hdmi);
+        assert!(active_output(&sinks, "missing").is_none());
+    }
+
+    #[test]
+    fn kinds_from_sink_and_port_names() {
+        assert_eq!(
+            port_kind("alsa_output.x", "analog-output-speaker"),
+            PortKind::Speakers
+        );
+        assert_eq!(
+            port_kind("alsa_output.x", "[Out] Headset"),
+            PortKind::Headphones
+        );
+        assert_eq!(
+            port_kind("bluez_output.AA_BB.1", "headset-output"),
+            PortKind::Bluetooth
+        );
+        assert_eq!(port_kind("alsa_output.usb-dac", ""), PortKind::Other);
+    }
+
+    #[test]
+    fn volume_steps_stay_under_the_cap() {
+        assert_eq!(
+            step_volume_args("@DEFAULT_AUDIO_SINK@", 5, true, 150),
+            ["set-volume", "-l", "1.50", "@DEFAULT_AUDIO_SINK@", "5%+"]
+        );
+        assert_eq!(
+            step_volume_args("@DEFAULT_AUDIO_SINK@", 2, false, 100),
+            ["set-volume", "-l", "1.00", "@DEFAULT_AUDIO_SINK@", "2%-"]
+        );
+        assert!(!is_boosted(100));
+        assert!(is_boosted(101));
+    }
+}
@@ -0,0 +1,48 @@
+//! Exponential backoff with jitter for retrying flaky network calls.
+
+use std::time::Duration;
+
+/// Delay before retry number `attempt` (0 for the first retry): `base`
+/// doubled per attempt up to `max`, then scaled into its upper half by
+/// `jitter` (0.0–1.0) so clients that failed together don't retry together.
+pub fn delay(attempt: u32, base: Duration, max: Duration, jitter: f64) -> Duration {
+    let exp = base.saturating_mul(2u32.saturating_pow(attempt.min(31)));
+    let capped = exp.min(max);
+    capped.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+}
+
+/// A jitter value in 0.0–1.0 from the clock's sub-second nanos; plenty for
+/// spreading retries without pulling in a random number generator.
+pub fn clock_jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    nanos as f64 / 1_000_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(5);
+    const MAX: Duration = Duration::from_secs(300);
+
+    #[test]
+    fn doubles_per_attempt_up_to_the_cap() {
+        assert_eq!(delay(0, BASE, MAX, 1.0), Duration::from_secs(5));
+        assert_eq!(delay(1, BASE, MAX, 1.0), Duration::from_secs(10));
+        assert_eq!(delay(3, BASE, MAX, 1.0), Duration::from_secs(40));
+        assert_eq!(delay(10, BASE, MAX, 1.0), MAX);
+        assert_eq!(delay(u32::MAX, BASE, MAX, 1.0), MAX);
+    }
+
+    #[test]
+    fn jitter_scales_into_the_upper_half() {
+        assert_eq!(delay(1, BASE, MAX, 0.0), Duration::from_secs(5));
+        assert_eq!(delay(1, BASE, MAX, 0.5), Duration::from_millis(7500));
+        // Out-of-range jitter is clamped
+        assert_eq!(delay(1, BASE, MAX, 7.0), Duration::from_secs(10));
+        assert_eq!(delay(1, BASE, MAX, -1.0), Duration::from_secs(5));
+    }
+}
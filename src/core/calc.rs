@@ -0,0 +1,191 @@
+//! The launcher's `=` calculator: arithmetic on decimal numbers with
+//! `+ - * / % ^` (`x`, `×` and `÷` too), parentheses and unary minus.
+
+/// The value of `expr`, or what's wrong with it.
+pub fn evaluate(expr: &str) -> Result<f64, String> {
+    let tokens = tokenize(expr)?;
+    if tokens.is_empty() {
+        return Err("empty expression".to_string());
+    }
+    let mut parser = Parser { tokens, at: 0 };
+    let value = parser.expr()?;
+    match parser.tokens.get(parser.at) {
+        None if value.is_finite() => Ok(value),
+        None => Err("not a finite number".to_string()),
+        Some(token) => Err(format!("unexpected {token:?}")),
+    }
+}
+
+/// `value` for display: whole numbers without a fraction, others rounded to
+/// ten decimals with trailing zeros dropped.
+pub fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{value:.0}");
+    }
+    let text = format!("{value:.10}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek() {
+                    if !(d.is_ascii_digit() || d == '.') {
+                        break;
+                    }
+                    number.push(d);
+                    chars.next();
+                }
+                let value = number
+                    .parse()
+                    .map_err(|_| format!("bad number {number:?}"))?;
+                tokens.push(Token::Number(value));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                chars.next();
+            }
+            'x' | '×' => {
+                tokens.push(Token::Op('*'));
+                chars.next();
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            _ => return Err(format!("unexpected {c:?}")),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.at).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.at += 1;
+        token
+    }
+
+    /// Sums and differences of terms.
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek() {
+            self.next();
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    /// Products, quotients and remainders of factors.
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.factor()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek() {
+            self.next();
+            let rhs = self.factor()?;
+            if op != '*' && rhs == 0.0 {
+                return Err("division by zero".to_string());
+            }
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    /// A signed atom, raised to a (right-associative) power.
+    fn factor(&mut self) -> Result<f64, String> {
+        if let Some(Token::Op('-')) = self.peek() {
+            self.next();
+            return Ok(-self.factor()?);
+        }
+        let base = self.atom()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.next();
+            return Ok(base.powf(self.factor()?));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Open) => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err("missing )".to_string()),
+                }
+            }
+            Some(token) => Err(format!("unexpected {token:?}")),
+            None => Err("incomplete expression".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence_and_parentheses() {
+        assert_eq!(evaluate("2 + 3 * 4"), Ok(14.0));
+        assert_eq!(evaluate("(2 + 3) * 4"), Ok(20.0));
+        assert_eq!(evaluate("2 ^ 3 ^ 2"), Ok(512.0));
+        assert_eq!(evaluate("-2 ^ 2"), Ok(-4.0));
+        assert_eq!(evaluate("10 % 4 - -1"), Ok(3.0));
+        assert_eq!(evaluate("3 x 4 ÷ 2"), Ok(6.0));
+    }
+
+    #[test]
+    fn bad_input_is_an_error() {
+        assert!(evaluate("").is_err());
+        assert!(evaluate("2 +").is_err());
+        assert!(evaluate("(1 + 2").is_err());
+        assert!(evaluate("1 / 0").is_err());
+        assert!(evaluate("firefox").is_err());
+        assert!(evaluate("1.2.3").is_err());
+    }
+
+    #[test]
+    fn values_are_formatted_without_noise() {
+        assert_eq!(format_value(14.0), "14");
+        assert_eq!(format_value(0.1 + 0.2), "0.3");
+        assert_eq!(format_value(-2.5), "-2.5");
+        assert_eq!(format_value(1.0 / 3.0), "0.3333333333");
+    }
+}
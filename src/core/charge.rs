@@ -0,0 +1,113 @@
+//! Battery charge limits: the kernel's start/stop charge thresholds
+//! (ThinkPad, ASUS and others) or an IdeaPad-style conservation mode, and
+//! the order to write thresholds in so start never ends up above stop.
+
+/// How a laptop's charging is capped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeLimit {
+    /// `charge_control_{start,end}_threshold` in percent. Some drivers (ASUS)
+    /// only have the end one.
+    Thresholds { start: Option<u32>, end: u32 },
+    /// On or off; the firmware picks the level.
+    Conservation(bool),
+}
+
+impl ChargeLimit {
+    /// Whether charging stops short of full.
+    pub fn is_limited(self) -> bool {
+        match self {
+            ChargeLimit::Thresholds { end, .. } => end < 100,
+            ChargeLimit::Conservation(on) => on,
+        }
+    }
+
+    /// "max 80%" or "conserving", shown next to the percentage; nothing
+    /// when it charges to full.
+    pub fn describe(self) -> Option<String> {
+        match self {
+            _ if !self.is_limited() => None,
+            ChargeLimit::Thresholds { end, .. } => Some(format!("max {end}%")),
+            ChargeLimit::Conservation(_) => Some("conserving".to_string()),
+        }
+    }
+}
+
+/// One of the two threshold files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threshold {
+    Start,
+    End,
+}
+
+/// The thresholds the popup's toggle sets: `[battery] charge_start` and
+/// `charge_end` when on (start kept below end), 0 and 100 when off.
+pub fn target_thresholds(on: bool, charge_start: u32, charge_end: u32) -> (u32, u32) {
+    if !on {
+        return (0, 100);
+    }
+    let end = charge_end.clamp(1, 100);
+    (charge_start.min(end - 1), end)
+}
+
+/// The writes taking thresholds whose end is now `current_end` to `start`
+/// (`None`: the driver has no start threshold) and `end`. Drivers reject a
+/// start at or above the end, so start goes first only when it fits under
+/// the current end; otherwise end is raised first.
+pub fn threshold_writes(current_end: u32, start: Option<u32>, end: u32) -> Vec<(Threshold, u32)> {
+    let Some(start) = start else {
+        return vec![(Threshold::End, end)];
+    };
+    if start < current_end {
+        vec![(Threshold::Start, start), (Threshold::End, end)]
+    } else {
+        vec![(Threshold::End, end), (Threshold::Start, start)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_limits_below_full_are_shown() {
+        let limited = ChargeLimit::Thresholds {
+            start: Some(75),
+            end: 80,
+        };
+        assert_eq!(limited.describe().as_deref(), Some("max 80%"));
+        let full = ChargeLimit::Thresholds {
+            start: None,
+            end: 100,
+        };
+        assert_eq!(full.describe(), None);
+        assert_eq!(
+            ChargeLimit::Conservation(true).describe().as_deref(),
+            Some("conserving")
+        );
+        assert!(!ChargeLimit::Conservation(false).is_limited());
+    }
+
+    #[test]
+    fn toggle_targets_keep_start_below_end() {
+        assert_eq!(target_thresholds(true, 75, 80), (75, 80));
+        assert_eq!(target_thresholds(true, 90, 80), (79, 80));
+        assert_eq!(target_thresholds(true, 0, 0), (0, 1));
+        assert_eq!(target_thresholds(false, 75, 80), (0, 100));
+    }
+
+    #[test]
+    fn writes_never_put_start_above_end() {
+        use Threshold::{End, Start};
+        // Limiting from 0–100: start fits under the old end
+        assert_eq!(
+            threshold_writes(100, Some(75), 80),
+            vec![(Start, 75), (End, 80)]
+        );
+        // 40–50 to 75–80: end has to move first
+        assert_eq!(
+            threshold_writes(50, Some(75), 80),
+            vec![(End, 80), (Start, 75)]
+        );
+        assert_eq!(threshold_writes(80, None, 100), vec![(End, 100)]);
+    }
+}
@@ -0,0 +1,223 @@
+//! Reading GitHub Actions and GitLab CI pipeline listings for the CI widget.
+
+use serde::Deserialize;
+
+/// Outcome of a pipeline, collapsed from either provider's vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStatus {
+    Passed,
+    Failed,
+    /// Queued, pending or running.
+    Running,
+    /// Canceled, skipped or anything else that is neither pass nor fail.
+    Other,
+}
+
+impl PipelineStatus {
+    /// Nerd Font glyph shown in the bar and popup.
+    pub fn icon(self) -> &'static str {
+        match self {
+            PipelineStatus::Passed => "\u{f00c}",
+            PipelineStatus::Failed => "\u{f00d}",
+            PipelineStatus::Running => "\u{f110}",
+            PipelineStatus::Other => "\u{f05e}",
+        }
+    }
+
+    /// CSS class for the bar trigger and popup rows.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            PipelineStatus::Passed => "passed",
+            PipelineStatus::Failed => "failed",
+            PipelineStatus::Running => "running",
+            PipelineStatus::Other => "other",
+        }
+    }
+
+    /// Map a workflow run's `status` and, once completed, `conclusion`.
+    pub fn from_github(status: &str, conclusion: Option<&str>) -> Self {
+        if status != "completed" {
+            return PipelineStatus::Running;
+        }
+        match conclusion {
+            Some("success") => PipelineStatus::Passed,
+            Some("failure" | "timed_out" | "startup_failure") => PipelineStatus::Failed,
+            _ => PipelineStatus::Other,
+        }
+    }
+
+    /// Map a GitLab pipeline `status`.
+    pub fn from_gitlab(status: &str) -> Self {
+        match status {
+            "success" => PipelineStatus::Passed,
+            "failed" => PipelineStatus::Failed,
+            "created"
+            | "waiting_for_resource"
+            | "preparing"
+            | "pending"
+            | "running"
+            | "scheduled" => PipelineStatus::Running,
+            _ => PipelineStatus::Other,
+        }
+    }
+}
+
+/// The newest run of a watched pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineRun {
+    pub status: PipelineStatus,
+    /// Workflow name, or the commit title for GitLab.
+    pub title: String,
+    /// The run's page, opened from the popup and the failure toast.
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRuns {
+    #[serde(default)]
+    workflow_runs: Vec<GithubRun>,
+}
+
+#[derive(Deserialize)]
+struct GithubRun {
+    status: String,
+    conclusion: Option<String>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    display_title: String,
+    html_url: String,
+}
+
+/// The first run of `GET /repos/{repo}/actions/runs`, newest first; None when
+/// the branch has none.
+pub fn parse_github_runs(json: &str) -> Result<Option<PipelineRun>, serde_json::Error> {
+    let runs: GithubRuns = serde_json::from_str(json)?;
+    Ok(runs.workflow_runs.into_iter().next().map(|run| {
+        let title = if run.display_title.is_empty() {
+            run.name
+        } else {
+            format!("{}: {}", run.name, run.display_title)
+        };
+        PipelineRun {
+            status: PipelineStatus::from_github(&run.status, run.conclusion.as_deref()),
+            title,
+            url: run.html_url,
+        }
+    }))
+}
+
+#[derive(Deserialize)]
+struct GitlabPipeline {
+    status: String,
+    #[serde(default)]
+    name: Option<String>,
+    web_url: String,
+}
+
+/// The first pipeline of `GET /projects/{id}/pipelines`, newest first.
+pub fn parse_gitlab_pipelines(json: &str) -> Result<Option<PipelineRun>, serde_json::Error> {
+    let pipelines: Vec<GitlabPipeline> = serde_json::from_str(json)?;
+    Ok(pipelines.into_iter().next().map(|p| PipelineRun {
+        status: PipelineStatus::from_gitlab(&p.status),
+        title: p.name.unwrap_or_else(|| "pipeline".to_string()),
+        url: p.web_url,
+    }))
+}
+
+/// `group/project` as a URL path segment for the GitLab API.
+pub fn gitlab_project_id(path: &str) -> String {
+    path.replace('%', "%25").replace('/', "%2F")
+}
+
+/// Whether a pipeline went from something else to failed, i.e. should toast.
+/// The first reading (`previous` None) never does, nor a failed run seen again.
+pub fn newly_failed(previous: Option<&PipelineRun>, current: &PipelineRun) -> bool {
+    current.status == PipelineStatus::Failed
+        && previous.is_some_and(|p| p.status != PipelineStatus::Failed || p.url != current.url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_runs_map_status_and_conclusion() {
+        let json = r#"{"total_count": 2, "workflow_runs": [
+            {"status": "completed", "conclusion": "failure", "name": "CI",
+             "display_title": "Fix the build", "html_url": "https://github.com/o/r/actions/runs/2"},
+            {"status": "completed", "conclusion": "success", "name": "CI",
+             "html_url": "https://github.com/o/r/actions/runs/1"}
+        ]}"#;
+        assert_eq!(
+            parse_github_runs(json).unwrap(),
+            Some(PipelineRun {
+                status: PipelineStatus::Failed,
+                title: "CI: Fix the build".to_string(),
+                url: "https://github.com/o/r/actions/runs/2".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_github_runs(r#"{"total_count": 0, "workflow_runs": []}"#).unwrap(),
+            None
+        );
+        assert_eq!(
+            PipelineStatus::from_github("in_progress", None),
+            PipelineStatus::Running
+        );
+        assert_eq!(
+            PipelineStatus::from_github("completed", Some("cancelled")),
+            PipelineStatus::Other
+        );
+    }
+
+    #[test]
+    fn gitlab_pipelines_map_status() {
+        let json = r#"[{"id": 7, "status": "running", "ref": "main",
+                        "web_url": "https://gitlab.com/g/p/-/pipelines/7"}]"#;
+        let run = parse_gitlab_pipelines(json).unwrap().unwrap();
+        assert_eq!(run.status, PipelineStatus::Running);
+        assert_eq!(run.title, "pipeline");
+        assert_eq!(
+            PipelineStatus::from_gitlab("success"),
+            PipelineStatus::Passed
+        );
+        assert_eq!(
+            PipelineStatus::from_gitlab("failed"),
+            PipelineStatus::Failed
+        );
+        assert_eq!(
+            PipelineStatus::from_gitlab("skipped"),
+            PipelineStatus::Other
+        );
+        assert_eq!(parse_gitlab_pipelines("[]").unwrap(), None);
+        assert_eq!(
+            gitlab_project_id("group/sub/project"),
+            "group%2Fsub%2Fproject"
+        );
+    }
+
+    #[test]
+    fn only_transitions_to_failure_notify() {
+        let run = |status, url: &str| PipelineRun {
+            status,
+            title: "CI".to_string(),
+            url: url.to_string(),
+        };
+        let failed = run(PipelineStatus::Failed, "runs/2");
+        assert!(!newly_failed(None, &failed));
+        assert!(newly_failed(
+            Some(&run(PipelineStatus::Running, "runs/2")),
+            &failed
+        ));
+        assert!(newly_failed(
+            Some(&run(PipelineStatus::Failed, "runs/1")),
+            &failed
+        ));
+        assert!(!newly_failed(Some(&failed), &failed));
+        assert!(!newly_failed(
+            Some(&run(PipelineStatus::Failed, "runs/2")),
+            &run(PipelineStatus::Passed, "runs/3")
+        ));
+    }
+}
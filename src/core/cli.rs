@@ -0,0 +1,262 @@
+//! Argument parsing for the `jb-shell` subcommands that run instead of
+//! starting the shell: `notify` and `key` talk to a running one, `secret` to
+//! the keyring.
+
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, TimeZone};
+
+use super::media_keys::MediaKey;
+
+pub const NOTIFY_USAGE: &str = "usage: jb-shell notify [options] TITLE
+  -b, --body TEXT           second line
+  -i, --icon NAME           icon name
+  -u, --urgency LEVEL       low, normal or critical
+  -a, --action LABEL=CMD    button running CMD with sh -c (repeatable)
+  -c, --countdown TIME      count down to HH:MM (next one) or an RFC 3339 time
+  -t, --timeout MS          expire after MS milliseconds, 0 for never";
+
+/// A parsed `jb-shell notify`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotifyArgs {
+    pub title: String,
+    pub body: Option<String>,
+    pub icon: Option<String>,
+    /// Freedesktop levels: 0 low, 1 normal, 2 critical.
+    pub urgency: u8,
+    /// Button label and the shell command it runs.
+    pub actions: Vec<(String, String)>,
+    pub countdown: Option<DateTime<Local>>,
+    pub timeout_ms: Option<u32>,
+}
+
+/// Parse the arguments after `notify`; the error is a message for stderr.
+pub fn parse_notify_args(args: &[String], now: DateTime<Local>) -> Result<NotifyArgs, String> {
+    let mut parsed = NotifyArgs {
+        title: String::new(),
+        body: None,
+        icon: None,
+        urgency: 1,
+        actions: Vec::new(),
+        countdown: None,
+        timeout_ms: None,
+    };
+    let mut title: Option<String> = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{arg} needs a value"))
+        };
+        match arg.as_str() {
+            "-b" | "--body" => parsed.body = Some(value()?),
+            "-i" | "--icon" => parsed.icon = Some(value()?),
+            "-u" | "--urgency" => {
+                parsed.urgency = match value()?.as_str() {
+                    "low" => 0,
+                    "normal" => 1,
+                    "critical" => 2,
+                    other => return Err(format!("unknown urgency {other:?}")),
+                }
+            }
+            "-a" | "--action" => {
+                let action = value()?;
+                let Some((label, command)) = action.split_once('=') else {
+                    return Err(format!("action {action:?} isn't LABEL=COMMAND"));
+                };
+                parsed
+                    .actions
+                    .push((label.to_string(), command.to_string()));
+            }
+            "-c" | "--countdown" => parsed.countdown = Some(parse_countdown(&value()?, now)?),
+            "-t" | "--timeout" => {
+                let ms = value()?;
+                parsed.timeout_ms = Some(
+                    ms.parse()
+                        .map_err(|_| format!("timeout {ms:?} isn't milliseconds"))?,
+                );
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option {flag}"));
+            }
+            _ if title.is_some() => return Err(format!("unexpected argument {arg:?}")),
+            _ => title = Some(arg.clone()),
+        }
+    }
+    parsed.title = title.ok_or("missing TITLE")?;
+    Ok(parsed)
+}
+
+pub const SECRET_USAGE: &str = "usage: jb-shell secret set NAME     store the value read from stdin
+       jb-shell secret clear NAME   remove it
+names: cerebras-api-key, github-token, google-tokens (clear only; sign in again from
+the calendar widget to store new ones)";
+
+/// Secrets kept in the Secret Service, and whether `set` may write them.
+const SECRETS: [(&str, bool); 3] = [
+    ("cerebras-api-key", true),
+    ("github-token", true),
+    ("google-tokens", false),
+];
+
+/// A parsed `jb-shell secret`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretArgs {
+    Set(String),
+    Clear(String),
+}
+
+/// Parse the arguments after `secret`; the error is a message for stderr.
+pub fn parse_secret_args(args: &[String]) -> Result<SecretArgs, String> {
+    let [action, name] = args else {
+        return Err("expected an action and a NAME".to_string());
+    };
+    let Some(&(_, settable)) = SECRETS.iter().find(|(n, _)| n == name) else {
+        return Err(format!("unknown secret {name:?}"));
+    };
+    match action.as_str() {
+        "set" if settable => Ok(SecretArgs::Set(name.clone())),
+        "set" => Err(format!("{name} is written by signing in, not by hand")),
+        "clear" => Ok(SecretArgs::Clear(name.clone())),
+        other => Err(format!("unknown action {other:?}")),
+    }
+}
+
+pub const KEY_USAGE: &str = "usage: jb-shell key KEYSYM
+keys: XF86AudioRaiseVolume, XF86AudioLowerVolume, XF86AudioMute, XF86AudioMicMute,
+XF86MonBrightnessUp, XF86MonBrightnessDown, XF86AudioPlay, XF86AudioPause,
+XF86AudioStop, XF86AudioNext, XF86AudioPrev";
+
+/// Parse the arguments after `key`; the error is a message for stderr.
+pub fn parse_key_args(args: &[String]) -> Result<MediaKey, String> {
+    let [name] = args else {
+        return Err("expected one KEYSYM".to_string());
+    };
+    MediaKey::parse(name).ok_or_else(|| format!("unknown key {name:?}"))
+}
+
+/// `HH:MM` is the next such time (tomorrow if already past); anything else
+/// must be RFC 3339.
+fn parse_countdown(value: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    if let Ok(time) = NaiveTime::parse_from_str(value, "%H:%M") {
+        let today = now.date_naive().and_time(time);
+        let target = Local
+            .from_local_datetime(&today)
+            .earliest()
+            .ok_or_else(|| format!("{value} doesn't exist today"))?;
+        return Ok(if target <= now {
+            target + TimeDelta::days(1)
+        } else {
+            target
+        });
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Local))
+        .map_err(|_| format!("countdown {value:?} isn't HH:MM or RFC 3339"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 14, 15, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn title_alone_is_a_normal_toast() {
+        let parsed = parse_notify_args(&args(&["Build done"]), now()).unwrap();
+        assert_eq!(parsed.title, "Build done");
+        assert_eq!(parsed.urgency, 1);
+        assert!(parsed.actions.is_empty());
+        assert_eq!(parsed.timeout_ms, None);
+    }
+
+    #[test]
+    fn options_in_any_order() {
+        let parsed = parse_notify_args(
+            &args(&[
+                "-u",
+                "critical",
+                "Deploy failed",
+                "--body",
+                "prod-eu",
+                "-a",
+                "Logs=kitty -e journalctl -f",
+                "-a",
+                "Retry=make deploy",
+                "-t",
+                "0",
+            ]),
+            now(),
+        )
+        .unwrap();
+        assert_eq!(parsed.title, "Deploy failed");
+        assert_eq!(parsed.body.as_deref(), Some("prod-eu"));
+        assert_eq!(parsed.urgency, 2);
+        assert_eq!(
+            parsed.actions,
+            vec![
+                ("Logs".to_string(), "kitty -e journalctl -f".to_string()),
+                ("Retry".to_string(), "make deploy".to_string()),
+            ]
+        );
+        assert_eq!(parsed.timeout_ms, Some(0));
+    }
+
+    #[test]
+    fn countdown_picks_the_next_clock_time() {
+        let later = parse_notify_args(&args(&["-c", "16:30", "Standup"]), now()).unwrap();
+        assert_eq!(
+            later.countdown,
+            Some(Local.with_ymd_and_hms(2025, 3, 14, 16, 30, 0).unwrap())
+        );
+        let past = parse_notify_args(&args(&["-c", "09:00", "Standup"]), now()).unwrap();
+        assert_eq!(
+            past.countdown,
+            Some(Local.with_ymd_and_hms(2025, 3, 15, 9, 0, 0).unwrap())
+        );
+        let exact = parse_notify_args(&args(&["-c", "2025-03-14T18:00:00Z", "x"]), now()).unwrap();
+        assert_eq!(exact.countdown.map(|t| t.timestamp()), Some(1741975200));
+    }
+
+    #[test]
+    fn bad_input_is_an_error() {
+        assert!(parse_notify_args(&args(&[]), now()).is_err());
+        assert!(parse_notify_args(&args(&["-u", "urgent", "x"]), now()).is_err());
+        assert!(parse_notify_args(&args(&["-a", "no-command", "x"]), now()).is_err());
+        assert!(parse_notify_args(&args(&["x", "--body"]), now()).is_err());
+        assert!(parse_notify_args(&args(&["x", "y"]), now()).is_err());
+        assert!(parse_notify_args(&args(&["--frobnicate", "x"]), now()).is_err());
+    }
+
+    #[test]
+    fn secret_names_are_checked() {
+        assert_eq!(
+            parse_secret_args(&args(&["set", "github-token"])),
+            Ok(SecretArgs::Set("github-token".to_string()))
+        );
+        assert_eq!(
+            parse_secret_args(&args(&["clear", "google-tokens"])),
+            Ok(SecretArgs::Clear("google-tokens".to_string()))
+        );
+        assert!(parse_secret_args(&args(&["set", "google-tokens"])).is_err());
+        assert!(parse_secret_args(&args(&["set", "slack-token"])).is_err());
+        assert!(parse_secret_args(&args(&["get", "github-token"])).is_err());
+        assert!(parse_secret_args(&args(&["set"])).is_err());
+    }
+
+    #[test]
+    fn key_takes_one_keysym() {
+        assert_eq!(
+            parse_key_args(&args(&["XF86AudioMute"])),
+            Ok(MediaKey::Mute)
+        );
+        assert!(parse_key_args(&args(&["XF86Calculator"])).is_err());
+        assert!(parse_key_args(&args(&["XF86AudioMute", "XF86AudioPlay"])).is_err());
+        assert!(parse_key_args(&[]).is_err());
+    }
+}
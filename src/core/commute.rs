@@ -0,0 +1,186 @@
+//! "Leave by" times for calendar events held somewhere physical: a fixed
+//! commute buffer, stretched when the hourly forecast for the trip calls for
+//! rain or snow.
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::Deserialize;
+
+/// What the forecast means for getting somewhere, from WMO weather codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    Clear,
+    /// Drizzle, rain, showers.
+    Rain,
+    /// Snow, sleet, freezing rain.
+    Snow,
+    Storm,
+}
+
+impl Weather {
+    /// The WMO 4677 code Open-Meteo reports as `weather_code`.
+    pub fn from_wmo(code: u32) -> Self {
+        match code {
+            56 | 57 | 66 | 67 | 71..=77 | 85 | 86 => Weather::Snow,
+            51..=55 | 61..=65 | 80..=82 => Weather::Rain,
+            95..=99 => Weather::Storm,
+            _ => Weather::Clear,
+        }
+    }
+
+    /// Multiples of `[commute] bad_weather_minutes` added to the buffer.
+    fn delay_factor(self) -> i64 {
+        match self {
+            Weather::Clear => 0,
+            Weather::Rain => 1,
+            Weather::Snow | Weather::Storm => 2,
+        }
+    }
+
+    /// "rain expected", for hints and toasts; nothing for clear weather.
+    pub fn describe(self) -> Option<&'static str> {
+        match self {
+            Weather::Clear => None,
+            Weather::Rain => Some("rain expected"),
+            Weather::Snow => Some("snow or ice expected"),
+            Weather::Storm => Some("storms expected"),
+        }
+    }
+}
+
+/// Hourly forecast: each hour's start (Unix seconds) and its weather.
+pub type Forecast = Vec<(i64, Weather)>;
+
+#[derive(Deserialize)]
+struct OpenMeteo {
+    hourly: OpenMeteoHourly,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<i64>,
+    weather_code: Vec<Option<u32>>,
+}
+
+/// An Open-Meteo `/v1/forecast` response asked for `hourly=weather_code`
+/// with `timeformat=unixtime`. Hours without a code are left out.
+pub fn parse_open_meteo(json: &str) -> Result<Forecast, serde_json::Error> {
+    let hourly = serde_json::from_str::<OpenMeteo>(json)?.hourly;
+    Ok(hourly
+        .time
+        .into_iter()
+        .zip(hourly.weather_code)
+        .filter_map(|(time, code)| Some((time, Weather::from_wmo(code?))))
+        .collect())
+}
+
+/// The worst weather forecast for any hour of the trip from `leave` to
+/// `arrive`; `None` when the forecast doesn't reach that far.
+pub fn weather_between(forecast: &[(i64, Weather)], leave: i64, arrive: i64) -> Option<Weather> {
+    let hours: Vec<Weather> = forecast
+        .iter()
+        .filter(|&&(hour, _)| hour + 3600 > leave && hour <= arrive)
+        .map(|&(_, weather)| weather)
+        .collect();
+    hours.into_iter().max_by_key(|w| w.delay_factor())
+}
+
+/// Whether a calendar location is a place to travel to rather than a video
+/// link or a "remote" note.
+pub fn is_physical(location: &str) -> bool {
+    let location = location.trim().to_lowercase();
+    !location.is_empty()
+        && !location.contains("://")
+        && ![
+            "zoom.us",
+            "meet.google",
+            "teams.microsoft",
+            "webex",
+            "online",
+            "remote",
+        ]
+        .iter()
+        .any(|hint| location.contains(hint))
+}
+
+/// When to leave for an event at `start`: `buffer_minutes` before it, plus
+/// `bad_weather_minutes` for rain (twice that for snow or storms) in the
+/// forecast for the trip. The weather it allowed for comes back with it.
+pub fn leave_by(
+    start: DateTime<Local>,
+    buffer_minutes: u32,
+    bad_weather_minutes: u32,
+    forecast: &[(i64, Weather)],
+) -> (DateTime<Local>, Weather) {
+    let plain = start - TimeDelta::minutes(buffer_minutes as i64);
+    let weather =
+        weather_between(forecast, plain.timestamp(), start.timestamp()).unwrap_or(Weather::Clear);
+    let extra = bad_weather_minutes as i64 * weather.delay_factor();
+    (plain - TimeDelta::minutes(extra), weather)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 14, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn wmo_codes_map_to_travel_weather() {
+        assert_eq!(Weather::from_wmo(0), Weather::Clear);
+        assert_eq!(Weather::from_wmo(3), Weather::Clear);
+        assert_eq!(Weather::from_wmo(61), Weather::Rain);
+        assert_eq!(Weather::from_wmo(81), Weather::Rain);
+        assert_eq!(Weather::from_wmo(67), Weather::Snow);
+        assert_eq!(Weather::from_wmo(75), Weather::Snow);
+        assert_eq!(Weather::from_wmo(95), Weather::Storm);
+    }
+
+    #[test]
+    fn open_meteo_hours_are_parsed() {
+        let json = r#"{"latitude":52.5,"hourly_units":{"time":"unixtime"},
+            "hourly":{"time":[1741939200,1741942800,1741946400],"weather_code":[0,null,63]}}"#;
+        assert_eq!(
+            parse_open_meteo(json).unwrap(),
+            vec![(1741939200, Weather::Clear), (1741946400, Weather::Rain)]
+        );
+        assert!(parse_open_meteo("{}").is_err());
+    }
+
+    #[test]
+    fn video_calls_are_not_physical() {
+        assert!(is_physical("Café Einstein, Unter den Linden 42"));
+        assert!(is_physical("Room 4.12"));
+        assert!(!is_physical("https://zoom.us/j/123"));
+        assert!(!is_physical("Google Meet (meet.google.com/abc-defg-hij)"));
+        assert!(!is_physical("Online"));
+        assert!(!is_physical("  "));
+    }
+
+    #[test]
+    fn bad_weather_on_the_way_moves_leave_by_earlier() {
+        let start = at(10, 0);
+        let hour = |h: u32| at(h, 0).timestamp();
+        let dry = vec![(hour(9), Weather::Clear), (hour(10), Weather::Clear)];
+        assert_eq!(leave_by(start, 20, 10, &dry), (at(9, 40), Weather::Clear));
+
+        let wet = vec![(hour(9), Weather::Rain), (hour(10), Weather::Clear)];
+        assert_eq!(leave_by(start, 20, 10, &wet), (at(9, 30), Weather::Rain));
+
+        // Snow in the hour the event starts still counts
+        let snow = vec![(hour(9), Weather::Rain), (hour(10), Weather::Snow)];
+        assert_eq!(leave_by(start, 20, 10, &snow), (at(9, 20), Weather::Snow));
+
+        // Rain that stops before leaving doesn't
+        let earlier = vec![(hour(8), Weather::Storm), (hour(9), Weather::Clear)];
+        assert_eq!(
+            leave_by(start, 20, 10, &earlier),
+            (at(9, 40), Weather::Clear)
+        );
+
+        // No forecast: the plain buffer
+        assert_eq!(leave_by(start, 20, 10, &[]), (at(9, 40), Weather::Clear));
+    }
+}
@@ -0,0 +1,96 @@
+//! Display profile matching: which profile a set of connected outputs selects,
+//! and which outputs a profile's monitor rules leave switched off.
+
+/// Whether `pattern` describes exactly the `connected` outputs. Named entries
+/// must all be connected; each `"*"` takes one output no name claimed. An
+/// empty pattern never matches, so manual-only profiles stay manual.
+pub fn matches_outputs(pattern: &[String], connected: &[String]) -> bool {
+    if pattern.is_empty() || pattern.len() != connected.len() {
+        return false;
+    }
+    let named: Vec<&String> = pattern.iter().filter(|p| *p != "*").collect();
+    let all_named_present = named.iter().all(|name| connected.contains(name));
+    let mut unique = named.clone();
+    unique.sort();
+    unique.dedup();
+    all_named_present && unique.len() == named.len()
+}
+
+/// The connector a `hyprctl keyword monitor` rule applies to; empty for the
+/// catch-all rule.
+pub fn rule_output(rule: &str) -> &str {
+    rule.split(',').next().unwrap_or_default().trim()
+}
+
+/// Connected outputs none of `rules` mention, which the profile switches off.
+/// A catch-all rule keeps everything on.
+pub fn disabled_outputs<'a>(rules: &[String], connected: &'a [String]) -> Vec<&'a String> {
+    let named: Vec<&str> = rules.iter().map(|r| rule_output(r)).collect();
+    if named.contains(&"") {
+        return Vec::new();
+    }
+    connected
+        .iter()
+        .filter(|output| !named.contains(&output.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn exact_names_match_only_that_set() {
+        let laptop = list(&["eDP-1"]);
+        assert!(matches_outputs(&laptop, &list(&["eDP-1"])));
+        assert!(!matches_outputs(&laptop, &list(&["eDP-1", "DP-1"])));
+        assert!(!matches_outputs(&laptop, &list(&["HDMI-A-1"])));
+    }
+
+    #[test]
+    fn wildcards_take_one_output_each() {
+        let docked = list(&["eDP-1", "*"]);
+        assert!(matches_outputs(&docked, &list(&["DP-3", "eDP-1"])));
+        assert!(!matches_outputs(&docked, &list(&["eDP-1"])));
+        assert!(!matches_outputs(&docked, &list(&["eDP-1", "DP-1", "DP-2"])));
+        // The wildcard can't stand in for the missing laptop panel
+        assert!(!matches_outputs(&docked, &list(&["DP-1", "DP-2"])));
+    }
+
+    #[test]
+    fn empty_patterns_are_manual_only() {
+        assert!(!matches_outputs(&[], &[]));
+        assert!(!matches_outputs(&[], &list(&["eDP-1"])));
+    }
+
+    #[test]
+    fn repeated_names_do_not_match() {
+        let pattern = list(&["eDP-1", "eDP-1"]);
+        assert!(!matches_outputs(&pattern, &list(&["eDP-1", "DP-1"])));
+    }
+
+    #[test]
+    fn rule_output_is_the_first_field() {
+        assert_eq!(rule_output("DP-1,2560x1440@144,0x0,1"), "DP-1");
+        assert_eq!(rule_output(" eDP-1 , disable"), "eDP-1");
+        assert_eq!(rule_output(",preferred,auto,1"), "");
+    }
+
+    #[test]
+    fn unnamed_outputs_are_disabled() {
+        let connected = list(&["eDP-1", "DP-1", "HDMI-A-1"]);
+        let rules = list(&["eDP-1,preferred,auto,1"]);
+        assert_eq!(disabled_outputs(&rules, &connected), ["DP-1", "HDMI-A-1"]);
+    }
+
+    #[test]
+    fn catch_all_rule_disables_nothing() {
+        let connected = list(&["eDP-1", "DP-1"]);
+        let rules = list(&["eDP-1,disable", ",preferred,auto,1"]);
+        assert!(disabled_outputs(&rules, &connected).is_empty());
+    }
+}
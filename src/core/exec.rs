@@ -0,0 +1,244 @@
+//! Desktop entry `Exec` handling: key-file unescaping, Exec quoting, field
+//! codes, and re-quoting the result for `sh -c`.
+
+use super::launcher::DesktopApp;
+
+/// The app's `Exec` as a `sh -c` command line: arguments unquoted per the
+/// desktop entry spec, field codes expanded, then re-quoted for the shell.
+pub fn process_exec(app: &DesktopApp) -> String {
+    exec_args(app)
+        .iter()
+        .map(|arg| shell_word(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `process_exec` for entries the launcher doesn't list (e.g. autostart).
+pub fn exec_command_line(
+    path: &std::path::Path,
+    name: &str,
+    exec: &str,
+    icon: Option<&str>,
+) -> String {
+    process_exec(&DesktopApp {
+        id: String::new(),
+        path: path.to_path_buf(),
+        name: name.to_string(),
+        exec: exec.to_string(),
+        icon: icon.map(str::to_string),
+        comment: None,
+        categories: Vec::new(),
+        keywords: Vec::new(),
+    })
+}
+
+/// The argv `Exec` describes, for launching without a file or URL.
+pub fn exec_args(app: &DesktopApp) -> Vec<String> {
+    let mut args = Vec::new();
+    for (word, quoted) in split_exec(&unescape_value(&app.exec)) {
+        // Field codes aren't allowed inside quotes; only %% means anything there
+        if quoted {
+            args.push(word.replace("%%", "%"));
+            continue;
+        }
+        match word.as_str() {
+            // No files/URLs to pass; deprecated codes are dropped
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {}
+            "%i" => {
+                if let Some(icon) = &app.icon {
+                    args.push("--icon".to_string());
+                    args.push(icon.clone());
+                }
+            }
+            _ => args.push(expand_field_codes(&word, app)),
+        }
+    }
+    args
+}
+
+/// Expand `%c`, `%k` and `%%` inside an unquoted word; other codes vanish.
+fn expand_field_codes(word: &str, app: &DesktopApp) -> String {
+    let mut out = String::new();
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => out.push('%'),
+            Some('c') => out.push_str(&app.name),
+            Some('k') => out.push_str(&app.path.to_string_lossy()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Key-file string escapes (`\s`, `\n`, `\t`, `\r`, `\\`), applied before
+/// Exec-level quoting.
+fn unescape_value(value: &str) -> String {
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Split an Exec line into words, honoring double quotes and the backslash
+/// escapes allowed inside them. The flag is set for words with a quoted part.
+fn split_exec(exec: &str) -> Vec<(String, bool)> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_word = true;
+                quoted = true;
+            }
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if in_word {
+                    words.push((std::mem::take(&mut current), quoted));
+                }
+                in_word = false;
+                quoted = false;
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push((current, quoted));
+    }
+    words
+}
+
+/// Leave plain words (including `VAR=val` prefixes) bare; quote the rest.
+pub fn shell_word(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-+=:,./@%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        shell_quote(arg)
+    }
+}
+
+/// Single-quote for `sh -c`.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn app(exec: &str) -> DesktopApp {
+        DesktopApp {
+            id: "my-app.desktop".into(),
+            path: PathBuf::from("/usr/share/applications/my-app.desktop"),
+            name: "My App".into(),
+            exec: exec.into(),
+            icon: Some("my-app".into()),
+            comment: None,
+            categories: Vec::new(),
+            keywords: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn strips_file_and_url_codes() {
+        assert_eq!(exec_args(&app("firefox %u")), ["firefox"]);
+        assert_eq!(
+            exec_args(&app("code --new-window %F")),
+            ["code", "--new-window"]
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_arguments_together() {
+        assert_eq!(
+            exec_args(&app(r#"sh -c "foo bar""#)),
+            ["sh", "-c", "foo bar"]
+        );
+        assert_eq!(process_exec(&app(r#"sh -c "foo bar""#)), "sh -c 'foo bar'");
+    }
+
+    #[test]
+    fn unescapes_inside_quotes() {
+        // As written in the .desktop file: key-file escapes, then Exec quoting
+        assert_eq!(
+            exec_args(&app(r#"sh -c "echo \\"hi\\" \\$HOME""#)),
+            ["sh", "-c", r#"echo "hi" $HOME"#]
+        );
+        assert_eq!(
+            exec_args(&app(r#"prog "C:\\\\path""#)),
+            ["prog", r"C:\path"]
+        );
+    }
+
+    #[test]
+    fn expands_name_path_icon_and_percent() {
+        assert_eq!(
+            exec_args(&app("prog --class=%c %k %i 100%%")),
+            [
+                "prog",
+                "--class=My App",
+                "/usr/share/applications/my-app.desktop",
+                "--icon",
+                "my-app",
+                "100%",
+            ]
+        );
+    }
+
+    #[test]
+    fn field_codes_inside_quotes_are_literal() {
+        assert_eq!(exec_args(&app(r#"prog "%c 50%%""#)), ["prog", "%c 50%"]);
+    }
+
+    #[test]
+    fn env_prefixes_stay_unquoted() {
+        assert_eq!(
+            process_exec(&app("env GDK_BACKEND=x11 prog --flag")),
+            "env GDK_BACKEND=x11 prog --flag"
+        );
+        assert_eq!(process_exec(&app(r#"prog "it's""#)), r#"prog 'it'\''s'"#);
+    }
+
+    #[test]
+    fn collapses_extra_whitespace_and_empty_quotes() {
+        assert_eq!(exec_args(&app("prog   a  \"\"")), ["prog", "a", ""]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Reading `git status --porcelain=v2 --branch` and `git log` output for the
+//! git widget.
+
+/// Branch and working tree state of one repository.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoStatus {
+    /// Branch name, or the short commit when detached.
+    pub branch: String,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    /// Tracked files with staged or unstaged changes (including conflicts).
+    pub changed: u32,
+    pub untracked: u32,
+}
+
+impl RepoStatus {
+    pub fn is_dirty(&self) -> bool {
+        self.changed > 0 || self.untracked > 0
+    }
+
+    /// "main ●3 ↑1 ↓2": branch, then changed+untracked, ahead and behind
+    /// counts where non-zero.
+    pub fn summary(&self) -> String {
+        let mut text = self.branch.clone();
+        let dirty = self.changed + self.untracked;
+        if dirty > 0 {
+            text.push_str(&format!(" \u{25cf}{dirty}"));
+        }
+        if self.ahead > 0 {
+            text.push_str(&format!(" \u{2191}{}", self.ahead));
+        }
+        if self.behind > 0 {
+            text.push_str(&format!(" \u{2193}{}", self.behind));
+        }
+        text
+    }
+}
+
+/// Parse `git status --porcelain=v2 --branch`.
+pub fn parse_status(output: &str) -> RepoStatus {
+    let mut status = RepoStatus::default();
+    let mut oid = String::new();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" => oid = value.to_string(),
+                "branch.head" => status.branch = value.to_string(),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        } else if ["1 ", "2 ", "u "].iter().any(|p| line.starts_with(p)) {
+            status.changed += 1;
+        }
+    }
+    if status.branch == "(detached)" {
+        let short: String = oid.chars().take(7).collect();
+        if !short.is_empty() && oid != "(initial)" {
+            status.branch = short;
+        }
+    }
+    status
+}
+
+/// A commit as listed in the widget's popup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub subject: String,
+    /// Relative commit date, e.g. "3 hours ago".
+    pub age: String,
+}
+
+/// `git log` format whose output `parse_log` reads: unit-separated fields.
+pub const LOG_FORMAT: &str = "--format=%h%x1f%s%x1f%cr";
+
+/// Parse `git log` run with [`LOG_FORMAT`].
+pub fn parse_log(output: &str) -> Vec<Commit> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\u{1f}');
+            let hash = fields.next()?.trim();
+            if hash.is_empty() {
+                return None;
+            }
+            Some(Commit {
+                hash: hash.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+                age: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_counts_changes_and_divergence() {
+        let output = "# branch.oid 1a2b3c4d5e6f\n\
+                      # branch.head main\n\
+                      # branch.upstream origin/main\n\
+                      # branch.ab +2 -1\n\
+                      1 .M N... 100644 100644 100644 abc abc src/main.rs\n\
+                      2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs\n\
+                      u UU N... 100644 100644 100644 100644 a b c conflict.rs\n\
+                      ? notes.txt\n";
+        let status = parse_status(output);
+        assert_eq!(
+            status,
+            RepoStatus {
+                branch: "main".to_string(),
+                upstream: Some("origin/main".to_string()),
+                ahead: 2,
+                behind: 1,
+                changed: 3,
+                untracked: 1,
+            }
+        );
+        assert!(status.is_dirty());
+        assert_eq!(status.summary(), "main \u{25cf}4 \u{2191}2 \u{2193}1");
+    }
+
+    #[test]
+    fn clean_and_detached_trees() {
+        let clean = parse_status("# branch.oid 1a2b3c4d\n# branch.head feature/x\n");
+        assert!(!clean.is_dirty());
+        assert_eq!(clean.upstream, None);
+        assert_eq!(clean.summary(), "feature/x");
+
+        let detached = parse_status("# branch.oid 1a2b3c4d5e6f\n# branch.head (detached)\n");
+        assert_eq!(detached.branch, "1a2b3c4");
+        let fresh = parse_status("# branch.oid (initial)\n# branch.head main\n");
+        assert_eq!(fresh.branch, "main");
+    }
+
+    #[test]
+    fn log_lines_split_on_unit_separators() {
+        let output = "abc1234\u{1f}Fix the thing\u{1f}2 hours ago\n\
+                      def5678\u{1f}Subject with \u{2014} dash\u{1f}3 days ago\n\n";
+        let commits = parse_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(
+            commits[0],
+            Commit {
+                hash: "abc1234".to_string(),
+                subject: "Fix the thing".to_string(),
+                age: "2 hours ago".to_string(),
+            }
+        );
+        assert_eq!(commits[1].age, "3 days ago");
+    }
+}
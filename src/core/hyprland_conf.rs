@@ -0,0 +1,58 @@
+//! `hyprland.conf` line handling for the keybinds overlay's fallback parser.
+
+/// `line` without its comment: a `#` at the start of a token begins one,
+/// `##` is a literal `#`, and a `#` inside a token (`"#1"`, `a#b`) is kept.
+pub fn strip_comment(line: &str) -> String {
+    let mut kept = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    let mut token_start = true;
+    while let Some(c) = chars.next() {
+        if c == '#' {
+            if chars.peek() == Some(&'#') {
+                chars.next();
+            } else if token_start {
+                break;
+            }
+        }
+        token_start = c.is_whitespace();
+        kept.push(c);
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_comment_is_dropped() {
+        assert_eq!(
+            strip_comment("bind = SUPER, E, exec, foo # comment"),
+            "bind = SUPER, E, exec, foo "
+        );
+    }
+
+    #[test]
+    fn line_leading_hash_comments_out_the_line() {
+        assert_eq!(strip_comment("# bind = SUPER, E, exec, foo"), "");
+        assert_eq!(strip_comment("    #bind = SUPER, E, exec, foo"), "    ");
+    }
+
+    #[test]
+    fn double_hash_is_a_literal_hash() {
+        assert_eq!(
+            strip_comment("exec, echo ## not a comment"),
+            "exec, echo # not a comment"
+        );
+        assert_eq!(strip_comment("##"), "#");
+    }
+
+    #[test]
+    fn hash_inside_a_token_is_kept() {
+        assert_eq!(
+            strip_comment(r##"exec, notify-send "#1" # first"##),
+            r##"exec, notify-send "#1" "##
+        );
+        assert_eq!(strip_comment("exec, a#b"), "exec, a#b");
+    }
+}
@@ -0,0 +1,125 @@
+//! The i3bar protocol spoken by i3status-rust, bumblebee-status and friends:
+//! a JSON header line, then an endless JSON array with one array of blocks
+//! per status update. Clicks go back on stdin as another endless array.
+
+use serde::Deserialize;
+
+/// The generator's first line.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Header {
+    pub version: u32,
+    pub click_events: bool,
+}
+
+/// One status block. Unknown keys (`min_width`, `align`, borders) are ignored.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Block {
+    pub full_text: String,
+    pub name: Option<String>,
+    pub instance: Option<String>,
+    /// `#rrggbb` or `#rrggbbaa`.
+    pub color: Option<String>,
+    pub background: Option<String>,
+    pub urgent: bool,
+    /// Draw a separator after this block.
+    pub separator: bool,
+    /// `"pango"` when `full_text` is Pango markup.
+    pub markup: Option<String>,
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Block {
+            full_text: String::new(),
+            name: None,
+            instance: None,
+            color: None,
+            background: None,
+            urgent: false,
+            separator: true,
+            markup: None,
+        }
+    }
+}
+
+impl Block {
+    pub fn is_pango(&self) -> bool {
+        self.markup.as_deref() == Some("pango")
+    }
+}
+
+/// `None` unless the line is a header object; the protocol requires one.
+pub fn parse_header(line: &str) -> Option<Header> {
+    serde_json::from_str(line.trim()).ok()
+}
+
+/// One line of the status stream. `Ok(None)` for the opening `[` and blank
+/// lines; each update may carry a leading or trailing comma.
+pub fn parse_status_line(line: &str) -> Result<Option<Vec<Block>>, serde_json::Error> {
+    let line = line.trim();
+    let line = line.strip_prefix(',').unwrap_or(line).trim_start();
+    let line = line.strip_suffix(',').unwrap_or(line).trim_end();
+    if line.is_empty() || line == "[" {
+        return Ok(None);
+    }
+    serde_json::from_str(line).map(Some)
+}
+
+/// A click event for the generator's stdin, without the array punctuation.
+/// Buttons use X11 numbering: 1–3 left/middle/right, 4/5 scroll up/down.
+pub fn click_event(name: Option<&str>, instance: Option<&str>, button: u32) -> String {
+    serde_json::json!({
+        "name": name,
+        "instance": instance,
+        "button": button,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_and_stream_punctuation() {
+        let header = parse_header(r#"{"version": 1, "click_events": true}"#).unwrap();
+        assert!(header.click_events);
+        assert_eq!(parse_header("not json"), None);
+
+        assert_eq!(parse_status_line("[").unwrap(), None);
+        assert_eq!(parse_status_line("  ").unwrap(), None);
+        let first = parse_status_line(r#"[{"full_text": "CPU 4%"}],"#).unwrap();
+        assert_eq!(first.unwrap()[0].full_text, "CPU 4%");
+        let next = parse_status_line(r#",[{"full_text": "CPU 6%"}]"#).unwrap();
+        assert_eq!(next.unwrap()[0].full_text, "CPU 6%");
+        assert!(parse_status_line("[{").is_err());
+    }
+
+    #[test]
+    fn block_fields_and_defaults() {
+        let blocks = parse_status_line(
+            r##"[{"name": "cpu", "full_text": "<b>4%</b>", "markup": "pango", "color": "#ff0000", "min_width": 40},
+                {"full_text": "bat", "urgent": true, "separator": false}]"##,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(blocks[0].name.as_deref(), Some("cpu"));
+        assert!(blocks[0].is_pango());
+        assert_eq!(blocks[0].color.as_deref(), Some("#ff0000"));
+        assert!(blocks[0].separator);
+        assert!(blocks[1].urgent);
+        assert!(!blocks[1].separator);
+        assert!(!blocks[1].is_pango());
+    }
+
+    #[test]
+    fn click_events_name_the_block() {
+        let event: serde_json::Value =
+            serde_json::from_str(&click_event(Some("cpu"), None, 3)).unwrap();
+        assert_eq!(event["name"], "cpu");
+        assert!(event["instance"].is_null());
+        assert_eq!(event["button"], 3);
+    }
+}
@@ -0,0 +1,134 @@
+//! Ping health for the network widget: round-trip parsing, loss and the
+//! good/degraded/bad verdict the bar colors by.
+
+/// How the recent pings look against the configured thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    Good,
+    Degraded,
+    Bad,
+}
+
+/// Latency (ms) and loss (percent) limits; reaching a limit counts.
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    pub warn_ms: f64,
+    pub bad_ms: f64,
+    pub warn_loss: f64,
+    pub bad_loss: f64,
+}
+
+/// Round-trip time in ms from `ping -c 1` output, or `None` if no reply
+/// came back. Fast links print `time<1 ms`, taken as the bound.
+pub fn parse_ping_ms(output: &str) -> Option<f64> {
+    let rest = output
+        .lines()
+        .find(|line| line.contains("bytes from"))?
+        .split_once("time")?
+        .1;
+    let value = rest.trim_start_matches(['=', '<']);
+    let end = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// Share of `samples` (in percent) that got no reply.
+pub fn loss_percent(samples: &[Option<f64>]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let lost = samples.iter().filter(|s| s.is_none()).count();
+    lost as f64 * 100.0 / samples.len() as f64
+}
+
+/// Mean round-trip of the replies among `samples`.
+pub fn mean_ms(samples: &[Option<f64>]) -> Option<f64> {
+    let replies: Vec<f64> = samples.iter().flatten().copied().collect();
+    if replies.is_empty() {
+        return None;
+    }
+    Some(replies.iter().sum::<f64>() / replies.len() as f64)
+}
+
+/// The worse of the latency and loss verdicts over `samples`. No samples yet
+/// is good; all lost is bad whatever the thresholds.
+pub fn health(samples: &[Option<f64>], thresholds: &Thresholds) -> Health {
+    if samples.is_empty() {
+        return Health::Good;
+    }
+    let loss = loss_percent(samples);
+    let Some(mean) = mean_ms(samples) else {
+        return Health::Bad;
+    };
+    if mean >= thresholds.bad_ms || loss >= thresholds.bad_loss {
+        Health::Bad
+    } else if mean >= thresholds.warn_ms || loss >= thresholds.warn_loss {
+        Health::Degraded
+    } else {
+        Health::Good
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: Thresholds = Thresholds {
+        warn_ms: 100.0,
+        bad_ms: 300.0,
+        warn_loss: 5.0,
+        bad_loss: 20.0,
+    };
+
+    #[test]
+    fn parses_iputils_and_busybox_replies() {
+        let iputils = "PING 1.1.1.1 (1.1.1.1) 56(84) bytes of data.\n\
+                       64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.4 ms\n\n\
+                       --- 1.1.1.1 ping statistics ---\n";
+        assert_eq!(parse_ping_ms(iputils), Some(12.4));
+        let busybox = "64 bytes from 10.0.0.1: seq=0 ttl=64 time=0.512 ms";
+        assert_eq!(parse_ping_ms(busybox), Some(0.512));
+        assert_eq!(
+            parse_ping_ms("64 bytes from ::1: icmp_seq=1 ttl=64 time<1 ms"),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn no_reply_parses_as_none() {
+        let timeout = "PING 10.9.9.9 (10.9.9.9) 56(84) bytes of data.\n\n\
+                       --- 10.9.9.9 ping statistics ---\n\
+                       1 packets transmitted, 0 received, 100% packet loss, time 0ms\n";
+        assert_eq!(parse_ping_ms(timeout), None);
+        assert_eq!(parse_ping_ms(""), None);
+    }
+
+    #[test]
+    fn loss_and_mean_ignore_the_other_kind() {
+        let samples = [Some(10.0), None, Some(30.0), None];
+        assert_eq!(loss_percent(&samples), 50.0);
+        assert_eq!(mean_ms(&samples), Some(20.0));
+        assert_eq!(mean_ms(&[None, None]), None);
+        assert_eq!(loss_percent(&[]), 0.0);
+    }
+
+    #[test]
+    fn health_takes_the_worse_verdict() {
+        assert_eq!(health(&[Some(20.0); 10], &LIMITS), Health::Good);
+        assert_eq!(health(&[Some(150.0); 10], &LIMITS), Health::Degraded);
+        assert_eq!(health(&[Some(400.0); 10], &LIMITS), Health::Bad);
+
+        let mut lossy = vec![Some(20.0); 9];
+        lossy.push(None);
+        assert_eq!(health(&lossy, &LIMITS), Health::Degraded);
+        lossy.extend([None, None, None]);
+        assert_eq!(health(&lossy, &LIMITS), Health::Bad);
+    }
+
+    #[test]
+    fn nothing_yet_is_good_and_all_lost_is_bad() {
+        assert_eq!(health(&[], &LIMITS), Health::Good);
+        assert_eq!(health(&[None, None], &LIMITS), Health::Bad);
+    }
+}
@@ -0,0 +1,264 @@
+//! App launcher search: frecency scoring and the tiered name/keyword ranking.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+pub struct DesktopApp {
+    pub id: String, // e.g. "firefox.desktop"
+    pub path: PathBuf,
+    pub name: String,
+    pub exec: String,
+    pub icon: Option<String>,
+    pub comment: Option<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+}
+
+/// Launch history for one app, persisted by the launcher.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FrecencyEntry {
+    pub count: u32,
+    /// Unix seconds.
+    pub last_used: u64,
+}
+
+/// Launch count weighted by how recently the app was last used; `now` is Unix
+/// seconds.
+pub fn frecency_score(entry: &FrecencyEntry, now: u64) -> f64 {
+    let age_secs = now.saturating_sub(entry.last_used);
+    let recency_weight = if age_secs < 3600 {
+        1.0
+    } else if age_secs < 86400 {
+        0.8
+    } else if age_secs < 604800 {
+        0.5
+    } else {
+        0.2
+    };
+    entry.count as f64 * recency_weight
+}
+
+/// Indices into `apps` matching `query`, best first, at most `max_results`.
+/// Name prefix matches beat word-boundary matches, which beat substrings,
+/// which beat keyword/category hits; frecency orders each tier. An empty
+/// query lists the most frecent apps.
+pub fn filter_and_rank(
+    apps: &[DesktopApp],
+    query: &str,
+    frecency: &HashMap<String, FrecencyEntry>,
+    now: u64,
+    max_results: usize,
+) -> Vec<usize> {
+    let sort_by_frecency = |indices: &mut Vec<usize>| {
+        let score = |i: usize| {
+            frecency
+                .get(&apps[i].id)
+                .map(|entry| frecency_score(entry, now))
+                .unwrap_or(0.0)
+        };
+        indices.sort_by(|&a, &b| {
+            score(b)
+                .partial_cmp(&score(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    };
+
+    if query.is_empty() {
+        // Return top frecent apps
+        let mut indices: Vec<usize> = (0..apps.len()).collect();
+        sort_by_frecency(&mut indices);
+        indices.truncate(max_results);
+        return indices;
+    }
+
+    let q = query.to_lowercase();
+
+    // Tier 1: exact prefix match on name
+    let mut tier1 = Vec::new();
+    // Tier 2: word-boundary match on name
+    let mut tier2 = Vec::new();
+    // Tier 3: substring match on name
+    let mut tier3 = Vec::new();
+    // Tier 4: match on keywords/categories
+    let mut tier4 = Vec::new();
+
+    for (i, app) in apps.iter().enumerate() {
+        let name_lower = app.name.to_lowercase();
+        if name_lower.starts_with(&q) {
+            tier1.push(i);
+        } else if word_boundary_match(&name_lower, &q) {
+            tier2.push(i);
+        } else if name_lower.contains(&q) {
+            tier3.push(i);
+        } else if app.keywords.iter().any(|k| k.to_lowercase().contains(&q))
+            || app.categories.iter().any(|c| c.to_lowercase().contains(&q))
+        {
+            tier4.push(i);
+        }
+    }
+
+    sort_by_frecency(&mut tier1);
+    sort_by_frecency(&mut tier2);
+    sort_by_frecency(&mut tier3);
+    sort_by_frecency(&mut tier4);
+
+    let mut result = Vec::new();
+    for tier in [tier1, tier2, tier3, tier4] {
+        for idx in tier {
+            if result.len() >= max_results {
+                break;
+            }
+            result.push(idx);
+        }
+        if result.len() >= max_results {
+            break;
+        }
+    }
+    result
+}
+
+fn word_boundary_match(name: &str, query: &str) -> bool {
+    // Check if query matches starting at any word boundary in name
+    for (i, _) in name.char_indices() {
+        if i == 0 {
+            continue; // Skip — prefix match is tier 1
+        }
+        let before = name.as_bytes().get(i.wrapping_sub(1)).copied().unwrap_or(0);
+        if (before == b' ' || before == b'-' || before == b'_') && name[i..].starts_with(query) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+    const HOUR: u64 = 3600;
+    const DAY: u64 = 86400;
+
+    fn app(id: &str, name: &str, keywords: &[&str]) -> DesktopApp {
+        DesktopApp {
+            id: id.into(),
+            path: PathBuf::from(format!("/usr/share/applications/{id}")),
+            name: name.into(),
+            exec: String::new(),
+            icon: None,
+            comment: None,
+            categories: vec!["Utility".into()],
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    fn used(count: u32, ago: u64) -> FrecencyEntry {
+        FrecencyEntry {
+            count,
+            last_used: NOW - ago,
+        }
+    }
+
+    fn ids<'a>(apps: &'a [DesktopApp], ranked: &[usize]) -> Vec<&'a str> {
+        ranked.iter().map(|&i| apps[i].id.as_str()).collect()
+    }
+
+    #[test]
+    fn recency_discounts_launch_count() {
+        assert_eq!(frecency_score(&used(10, 0), NOW), 10.0);
+        assert_eq!(frecency_score(&used(10, 2 * HOUR), NOW), 8.0);
+        assert_eq!(frecency_score(&used(10, 2 * DAY), NOW), 5.0);
+        assert_eq!(frecency_score(&used(10, 30 * DAY), NOW), 2.0);
+    }
+
+    #[test]
+    fn future_timestamps_count_as_recent() {
+        // Clock went backwards since the launch was recorded
+        assert_eq!(frecency_score(&used(3, 0), NOW - HOUR), 3.0);
+    }
+
+    #[test]
+    fn ranks_prefix_then_word_then_substring_then_keywords() {
+        let apps = [
+            app("keyword.desktop", "Editor", &["firefox"]),
+            app("substring.desktop", "Xfirefox", &[]),
+            app("word.desktop", "Mozilla Firefox", &[]),
+            app("prefix.desktop", "Firefox Nightly", &[]),
+            app("other.desktop", "Terminal", &[]),
+        ];
+        let ranked = filter_and_rank(&apps, "fire", &HashMap::new(), NOW, 10);
+        assert_eq!(
+            ids(&apps, &ranked),
+            [
+                "prefix.desktop",
+                "word.desktop",
+                "substring.desktop",
+                "keyword.desktop"
+            ]
+        );
+    }
+
+    #[test]
+    fn frecency_orders_within_a_tier() {
+        let apps = [
+            app("rare.desktop", "Files", &[]),
+            app("often.desktop", "Firefox", &[]),
+            app("never.desktop", "Fiddle", &[]),
+        ];
+        let frecency = HashMap::from([
+            ("rare.desktop".to_string(), used(2, 30 * DAY)),
+            ("often.desktop".to_string(), used(5, 0)),
+        ]);
+        let ranked = filter_and_rank(&apps, "fi", &frecency, NOW, 10);
+        assert_eq!(
+            ids(&apps, &ranked),
+            ["often.desktop", "rare.desktop", "never.desktop"]
+        );
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        let apps = [app("gimp.desktop", "GNU Image Manipulation Program", &[])];
+        let ranked = filter_and_rank(&apps, "IMAGE", &HashMap::new(), NOW, 10);
+        assert_eq!(ranked, [0]);
+    }
+
+    #[test]
+    fn empty_query_lists_most_frecent() {
+        let apps = [
+            app("a.desktop", "A", &[]),
+            app("b.desktop", "B", &[]),
+            app("c.desktop", "C", &[]),
+        ];
+        let frecency = HashMap::from([
+            ("b.desktop".to_string(), used(9, 0)),
+            ("c.desktop".to_string(), used(1, 0)),
+        ]);
+        let ranked = filter_and_rank(&apps, "", &frecency, NOW, 2);
+        assert_eq!(ids(&apps, &ranked), ["b.desktop", "c.desktop"]);
+    }
+
+    #[test]
+    fn caps_results_across_tiers() {
+        let apps = [
+            app("1.desktop", "Term", &[]),
+            app("2.desktop", "Terminal", &[]),
+            app("3.desktop", "My Term", &[]),
+        ];
+        let ranked = filter_and_rank(&apps, "term", &HashMap::new(), NOW, 2);
+        assert_eq!(ranked.len(), 2);
+        assert!(!ranked.contains(&2), "word-boundary tier is past the cap");
+    }
+
+    #[test]
+    fn word_boundaries_are_space_dash_and_underscore() {
+        assert!(word_boundary_match("gnome system-monitor", "monitor"));
+        assert!(word_boundary_match("my_app", "app"));
+        assert!(word_boundary_match("visual studio code", "code"));
+        assert!(!word_boundary_match("firefox", "fox"));
+        // Prefixes belong to tier 1, not here
+        assert!(!word_boundary_match("firefox", "fire"));
+    }
+}
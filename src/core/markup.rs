@@ -0,0 +1,278 @@
+//! Notification body markup. The spec allows `<b>`, `<i>`, `<u>` and
+//! `<a href>`, but apps also send HTML entities and whatever tags their
+//! toolkit produced; this turns a body into plain text or into Pango markup
+//! a GTK label can't choke on.
+
+/// Tags kept as formatting in Pango output.
+const FORMATTING: &[&str] = &["b", "i", "u", "a"];
+
+enum Token {
+    Text(String),
+    Tag {
+        name: String,
+        closing: bool,
+        href: Option<String>,
+    },
+}
+
+/// `text` as plain text: every tag dropped, `<br>` and paragraph ends as
+/// line breaks, entities decoded. `strict` also drops entities it doesn't
+/// know; otherwise they stay as written.
+pub fn to_plain(text: &str, strict: bool) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for token in tokens(text, strict) {
+        match token {
+            Token::Text(t) => plain.push_str(&t),
+            Token::Tag { name, closing, .. } if is_line_break(&name, closing) => {
+                plain.push('\n');
+            }
+            Token::Tag { .. } => {}
+        }
+    }
+    plain.trim().to_string()
+}
+
+/// `text` as Pango markup: bold, italic, underline and links kept (closed
+/// if the sender left them open), other tags dropped, and everything else
+/// escaped, including entities this doesn't know.
+pub fn to_pango(text: &str) -> String {
+    let mut markup = String::with_capacity(text.len());
+    let mut open: Vec<String> = Vec::new();
+    for token in tokens(text, false) {
+        match token {
+            Token::Text(t) => markup.push_str(&escape(&t)),
+            Token::Tag { name, closing, .. } if is_line_break(&name, closing) => {
+                markup.push('\n');
+            }
+            Token::Tag { name, .. } if !FORMATTING.contains(&name.as_str()) => {}
+            Token::Tag {
+                name,
+                closing: true,
+                ..
+            } => {
+                // Close anything opened inside it too; a stray close is dropped
+                if let Some(at) = open.iter().rposition(|n| *n == name) {
+                    for inner in open.drain(at..).rev() {
+                        markup.push_str(&format!("</{inner}>"));
+                    }
+                }
+            }
+            Token::Tag { name, href, .. } => {
+                if name == "a" {
+                    // A link without a target is just text
+                    let Some(href) = href else { continue };
+                    markup.push_str(&format!("<a href=\"{}\">", escape(&href)));
+                } else {
+                    markup.push_str(&format!("<{name}>"));
+                }
+                open.push(name);
+            }
+        }
+    }
+    for name in open.into_iter().rev() {
+        markup.push_str(&format!("</{name}>"));
+    }
+    markup.trim().to_string()
+}
+
+/// The target of the first `<a href>` in `text`, entities decoded. Plain
+/// text drops it with the tag, so quick actions ask for it separately.
+pub fn first_link(text: &str) -> Option<String> {
+    tokens(text, false)
+        .into_iter()
+        .find_map(|token| match token {
+            Token::Tag {
+                name,
+                closing: false,
+                href,
+            } if name == "a" => href,
+            _ => None,
+        })
+}
+
+fn is_line_break(name: &str, closing: bool) -> bool {
+    name == "br" || (closing && matches!(name, "p" | "div" | "li"))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn tokens(text: &str, strict: bool) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            if let Some((tag, after)) = parse_tag(rest) {
+                if !plain.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut plain)));
+                }
+                tokens.push(tag);
+                rest = after;
+                continue;
+            }
+        } else if c == '&' {
+            if let Some((decoded, after)) = parse_entity(rest) {
+                match decoded {
+                    Some(ch) => plain.push(ch),
+                    None if strict => {}
+                    None => plain.push_str(&rest[..rest.len() - after.len()]),
+                }
+                rest = after;
+                continue;
+            }
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        tokens.push(Token::Text(plain));
+    }
+    tokens
+}
+
+/// A tag at the start of `text`: `<` then a letter, `/` or `!`, up to the
+/// next `>`. Anything else, like "a < b", is text.
+fn parse_tag(text: &str) -> Option<(Token, &str)> {
+    let inner_start = text[1..].chars().next()?;
+    if !(inner_start.is_ascii_alphabetic() || inner_start == '/' || inner_start == '!') {
+        return None;
+    }
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+    if inner.contains('<') {
+        return None;
+    }
+    let (closing, body) = match inner.strip_prefix('/') {
+        Some(body) => (true, body),
+        None => (false, inner),
+    };
+    let name: String = body
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let tag = Token::Tag {
+        href: attribute(body, "href"),
+        name,
+        closing,
+    };
+    Some((tag, &text[end + 1..]))
+}
+
+/// The value of `name="…"` or `name='…'` in a tag's contents.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find(&format!("{name}="))? + name.len() + 1;
+    let value = &tag[start..];
+    let quote = value.chars().next().filter(|q| *q == '"' || *q == '\'')?;
+    let value = &value[1..];
+    let (value, _) = value.split_once(quote)?;
+    Some(to_plain(value, false))
+}
+
+/// An entity at the start of `text` and what follows it; the character is
+/// `None` for a well-formed entity this doesn't know, or one naming a control
+/// character other than a line break or tab (`&#0;` would otherwise put a
+/// NUL in the text). A bare `&` isn't one.
+fn parse_entity(text: &str) -> Option<(Option<char>, &str)> {
+    let end = text.find(';')?;
+    let name = &text[1..end];
+    if name.is_empty()
+        || name.len() > 10
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+    {
+        return None;
+    }
+    let decoded = match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => name.strip_prefix('#').and_then(|number| {
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => number.parse().ok(),
+            };
+            code.and_then(char::from_u32)
+                .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        }),
+    };
+    Some((decoded, &text[end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_drops_tags_and_decodes_entities() {
+        assert_eq!(
+            to_plain(
+                "<b>Build</b> failed<br/>see <a href=\"x\">log</a> &amp; retry",
+                false
+            ),
+            "Build failed\nsee log & retry"
+        );
+        assert_eq!(
+            to_plain("it&#39;s 5 &lt; 6 &#x1F600;", false),
+            "it's 5 < 6 😀"
+        );
+        assert_eq!(to_plain("<p>one</p><p>two</p>", false), "one\ntwo");
+        // Not tags or entities
+        assert_eq!(to_plain("a < b && c > d", false), "a < b && c > d");
+        assert_eq!(to_plain("Tom & Jerry", true), "Tom & Jerry");
+    }
+
+    #[test]
+    fn strict_drops_unknown_entities() {
+        assert_eq!(
+            to_plain("caf&eacute; &copy;2025", false),
+            "caf&eacute; &copy;2025"
+        );
+        assert_eq!(to_plain("caf&eacute; &copy;2025", true), "caf 2025");
+    }
+
+    #[test]
+    fn control_character_entities_are_not_decoded() {
+        assert_eq!(to_plain("a&#0;b&#x0;c", false), "a&#0;b&#x0;c");
+        assert_eq!(to_plain("a&#0;b&#x1b;c&#127;", true), "abc");
+        assert_eq!(to_plain("one&#10;two&#x9;three", true), "one\ntwo\tthree");
+    }
+
+    #[test]
+    fn first_link_is_decoded() {
+        assert_eq!(
+            first_link("PR <a href=\"https://x.example/?a=1&amp;b=2\">#12</a> merged"),
+            Some("https://x.example/?a=1&b=2".to_string())
+        );
+        assert_eq!(first_link("<a>no target</a> <b>bold</b>"), None);
+        assert_eq!(first_link("just https://x.example/"), None);
+    }
+
+    #[test]
+    fn pango_keeps_formatting_and_escapes_the_rest() {
+        assert_eq!(
+            to_pango("<b>Alice</b>: <span style=\"x\">hi</span> <3 &copy;"),
+            "<b>Alice</b>: hi &lt;3 &amp;copy;"
+        );
+        assert_eq!(
+            to_pango("<A HREF='https://x.io/?a=1&amp;b=2'>PR</A>"),
+            "<a href=\"https://x.io/?a=1&amp;b=2\">PR</a>"
+        );
+        assert_eq!(to_pango("<a>no target</a>"), "no target");
+    }
+
+    #[test]
+    fn pango_output_is_balanced() {
+        assert_eq!(to_pango("<b><i>open"), "<b><i>open</i></b>");
+        assert_eq!(to_pango("<b>x<i>y</b>z</i>"), "<b>x<i>y</i></b>z");
+        assert_eq!(to_pango("stray</u>"), "stray");
+    }
+}
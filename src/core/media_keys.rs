@@ -0,0 +1,183 @@
+//! Volume, brightness and media keys handled by the shell itself: the XF86
+//! key names Hyprland hands over, how far a press steps, and the binds that
+//! route the keys to `jb-shell key`.
+
+use super::exec::shell_word;
+
+/// A hardware key the shell acts on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKey {
+    RaiseVolume,
+    LowerVolume,
+    Mute,
+    MicMute,
+    BrightnessUp,
+    BrightnessDown,
+    /// Most keyboards' only play key, so it toggles.
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Prev,
+}
+
+impl MediaKey {
+    pub const ALL: [MediaKey; 11] = [
+        MediaKey::RaiseVolume,
+        MediaKey::LowerVolume,
+        MediaKey::Mute,
+        MediaKey::MicMute,
+        MediaKey::BrightnessUp,
+        MediaKey::BrightnessDown,
+        MediaKey::Play,
+        MediaKey::Pause,
+        MediaKey::Stop,
+        MediaKey::Next,
+        MediaKey::Prev,
+    ];
+
+    /// The XKB keysym name, as written in hyprland.conf.
+    pub fn keysym(self) -> &'static str {
+        match self {
+            MediaKey::RaiseVolume => "XF86AudioRaiseVolume",
+            MediaKey::LowerVolume => "XF86AudioLowerVolume",
+            MediaKey::Mute => "XF86AudioMute",
+            MediaKey::MicMute => "XF86AudioMicMute",
+            MediaKey::BrightnessUp => "XF86MonBrightnessUp",
+            MediaKey::BrightnessDown => "XF86MonBrightnessDown",
+            MediaKey::Play => "XF86AudioPlay",
+            MediaKey::Pause => "XF86AudioPause",
+            MediaKey::Stop => "XF86AudioStop",
+            MediaKey::Next => "XF86AudioNext",
+            MediaKey::Prev => "XF86AudioPrev",
+        }
+    }
+
+    /// A keysym name, case-insensitively and with or without the `XF86`.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let bare = |key: MediaKey| &key.keysym()[4..];
+        Self::ALL.into_iter().find(|&key| {
+            name.eq_ignore_ascii_case(key.keysym()) || name.eq_ignore_ascii_case(bare(key))
+        })
+    }
+
+    /// The `org.mpris.MediaPlayer2.Player` method a playback key calls.
+    pub fn mpris_method(self) -> Option<&'static str> {
+        match self {
+            MediaKey::Play => Some("PlayPause"),
+            MediaKey::Pause => Some("Pause"),
+            MediaKey::Stop => Some("Stop"),
+            MediaKey::Next => Some("Next"),
+            MediaKey::Prev => Some("Previous"),
+            _ => None,
+        }
+    }
+
+    /// Keys that step a level keep stepping while held.
+    pub fn repeats(self) -> bool {
+        matches!(
+            self,
+            MediaKey::RaiseVolume
+                | MediaKey::LowerVolume
+                | MediaKey::BrightnessUp
+                | MediaKey::BrightnessDown
+        )
+    }
+}
+
+/// The hyprland.conf keyword and value that hand `key` to `exe key <name>`:
+/// `bindel` for the stepping keys, `bindl` for the rest, both working on the
+/// lock screen.
+pub fn bind(key: MediaKey, exe: &str) -> (&'static str, String) {
+    let keyword = if key.repeats() { "bindel" } else { "bindl" };
+    let name = key.keysym();
+    (
+        keyword,
+        format!(", {name}, exec, {} key {name}", shell_word(exe)),
+    )
+}
+
+/// The backlight `step_percent` of `max` up or down from `current`. Never
+/// goes all the way to 0, which turns many panels off rather than dimming
+/// them.
+pub fn step_brightness(current: u32, max: u32, step_percent: u32, up: bool) -> u32 {
+    if max == 0 {
+        return 0;
+    }
+    let step = (max as u64 * step_percent as u64 / 100).max(1) as u32;
+    let floor = (max / 100).max(1);
+    if up {
+        current.saturating_add(step).min(max)
+    } else {
+        current.saturating_sub(step).max(floor)
+    }
+}
+
+/// `value` of `max` as a whole percentage.
+pub fn percent(value: u32, max: u32) -> u32 {
+    if max == 0 {
+        return 0;
+    }
+    ((value as f64 / max as f64) * 100.0).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keysyms_parse_loosely() {
+        assert_eq!(
+            MediaKey::parse("XF86AudioRaiseVolume"),
+            Some(MediaKey::RaiseVolume)
+        );
+        assert_eq!(
+            MediaKey::parse("monbrightnessdown"),
+            Some(MediaKey::BrightnessDown)
+        );
+        assert_eq!(MediaKey::parse(" xf86audioplay "), Some(MediaKey::Play));
+        assert_eq!(MediaKey::parse("XF86Calculator"), None);
+        assert_eq!(MediaKey::Prev.mpris_method(), Some("Previous"));
+        assert_eq!(MediaKey::Mute.mpris_method(), None);
+        for key in MediaKey::ALL {
+            assert_eq!(MediaKey::parse(key.keysym()), Some(key));
+        }
+    }
+
+    #[test]
+    fn binds_repeat_only_stepping_keys() {
+        assert_eq!(
+            bind(MediaKey::RaiseVolume, "/usr/bin/jb-shell"),
+            (
+                "bindel",
+                ", XF86AudioRaiseVolume, exec, /usr/bin/jb-shell key XF86AudioRaiseVolume"
+                    .to_string()
+            )
+        );
+        let (keyword, value) = bind(MediaKey::Mute, "/home/me/my builds/jb-shell");
+        assert_eq!(keyword, "bindl");
+        assert_eq!(
+            value,
+            ", XF86AudioMute, exec, '/home/me/my builds/jb-shell' key XF86AudioMute"
+        );
+    }
+
+    #[test]
+    fn brightness_steps_stay_in_range() {
+        assert_eq!(step_brightness(500, 1000, 5, true), 550);
+        assert_eq!(step_brightness(980, 1000, 5, true), 1000);
+        assert_eq!(step_brightness(30, 1000, 5, false), 10);
+        // Coarse backlights still move
+        assert_eq!(step_brightness(3, 7, 5, true), 4);
+        assert_eq!(step_brightness(1, 7, 5, false), 1);
+        assert_eq!(step_brightness(0, 0, 5, true), 0);
+    }
+
+    #[test]
+    fn percent_rounds() {
+        assert_eq!(percent(550, 1000), 55);
+        assert_eq!(percent(1, 7), 14);
+        assert_eq!(percent(0, 0), 0);
+    }
+}
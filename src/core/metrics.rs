@@ -0,0 +1,293 @@
+//! The shell's own resource use for `[metrics]` audit mode (counter names,
+//! `/proc/self` parsing, per-interval rates) and the Prometheus text served on
+//! `[metrics] listen`, desktop telemetry included.
+
+/// Kernel clock ticks per second (`USER_HZ`), fixed at 100 on Linux.
+pub const TICKS_PER_SEC: f64 = 100.0;
+
+/// Counter totals since startup, each family sorted by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub widget_updates: Vec<(String, u64)>,
+    pub thread_wakeups: Vec<(String, u64)>,
+    pub dbus_calls: Vec<(String, u64)>,
+    pub process: ProcessStats,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessStats {
+    pub rss_bytes: u64,
+    pub threads: u64,
+    /// User plus system CPU time, in clock ticks.
+    pub cpu_ticks: u64,
+}
+
+/// Desktop activity for people who graph it, served whether or not audit mode
+/// is on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Telemetry {
+    /// Notifications received in the past hour, by app.
+    pub notifications_last_hour: Vec<(String, u64)>,
+    /// Accepted, timed events starting today.
+    pub meetings_today: u64,
+    pub workspace_switches: u64,
+    pub launcher_launches: u64,
+    /// `None` without a battery.
+    pub battery_percent: Option<u32>,
+}
+
+/// Resident memory and thread count from `/proc/self/status`.
+pub fn parse_status(contents: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.split_whitespace().next()?.parse::<u64>().ok())
+    };
+    (field("VmRSS:").map(|kib| kib * 1024), field("Threads:"))
+}
+
+/// utime + stime from `/proc/self/stat`. The command name may contain spaces
+/// and parentheses, so fields are counted from its closing `)`.
+pub fn parse_cpu_ticks(contents: &str) -> Option<u64> {
+    let rest = &contents[contents.rfind(')')? + 1..];
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// "CaffeineInput" from `std::any::type_name`'s full path.
+pub fn type_label(type_name: &str) -> &str {
+    let name = type_name.split('<').next().unwrap_or(type_name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// "widgets/volume" from a caller's `src/widgets/volume.rs`.
+pub fn source_label(file: &str) -> &str {
+    let file = file.strip_prefix("src/").unwrap_or(file);
+    file.strip_suffix(".rs").unwrap_or(file)
+}
+
+/// How much each counter grew between two snapshots of one family, busiest
+/// first. Counters that didn't move are left out.
+pub fn deltas(prev: &[(String, u64)], cur: &[(String, u64)]) -> Vec<(String, u64)> {
+    let mut grown: Vec<(String, u64)> = cur
+        .iter()
+        .filter_map(|(name, total)| {
+            let before = prev.iter().find(|(n, _)| n == name).map_or(0, |(_, t)| *t);
+            let delta = total.saturating_sub(before);
+            (delta > 0).then(|| (name.clone(), delta))
+        })
+        .collect();
+    grown.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    grown
+}
+
+/// CPU use between two samples `secs` apart, as a percentage of one core.
+pub fn cpu_percent(prev_ticks: u64, cur_ticks: u64, secs: f64) -> f64 {
+    if secs <= 0.0 {
+        return 0.0;
+    }
+    cur_ticks.saturating_sub(prev_ticks) as f64 / TICKS_PER_SEC / secs * 100.0
+}
+
+/// The snapshot in the Prometheus text exposition format.
+pub fn render_prometheus(snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+    let families = [
+        (
+            "jb_shell_widget_updates_total",
+            "Messages published to per-bar widgets",
+            "widget",
+            &snapshot.widget_updates,
+        ),
+        (
+            "jb_shell_thread_wakeups_total",
+            "Background thread wakeups",
+            "thread",
+            &snapshot.thread_wakeups,
+        ),
+        (
+            "jb_shell_dbus_calls_total",
+            "D-Bus method calls received",
+            "method",
+            &snapshot.dbus_calls,
+        ),
+    ];
+    for (name, help, label, counters) in families {
+        out.push_str(&format!("# HELP {name} {help}.\n# TYPE {name} counter\n"));
+        for (key, value) in counters {
+            out.push_str(&format!("{name}{{{label}=\"{}\"}} {value}\n", escape(key)));
+        }
+    }
+    let process = &snapshot.process;
+    let gauges = [
+        (
+            "jb_shell_resident_memory_bytes",
+            "gauge",
+            "Resident memory",
+            process.rss_bytes as f64,
+        ),
+        (
+            "jb_shell_threads",
+            "gauge",
+            "OS threads",
+            process.threads as f64,
+        ),
+        (
+            "jb_shell_cpu_seconds_total",
+            "counter",
+            "User and system CPU time",
+            process.cpu_ticks as f64 / TICKS_PER_SEC,
+        ),
+    ];
+    for (name, kind, help, value) in gauges {
+        out.push_str(&format!(
+            "# HELP {name} {help}.\n# TYPE {name} {kind}\n{name} {value}\n"
+        ));
+    }
+    out
+}
+
+/// Telemetry in the Prometheus text exposition format.
+pub fn render_telemetry(telemetry: &Telemetry) -> String {
+    let mut out = String::from(
+        "# HELP jb_shell_notifications_last_hour Notifications received in the past hour.\n\
+         # TYPE jb_shell_notifications_last_hour gauge\n",
+    );
+    for (app, count) in &telemetry.notifications_last_hour {
+        out.push_str(&format!(
+            "jb_shell_notifications_last_hour{{app=\"{}\"}} {count}\n",
+            escape(app)
+        ));
+    }
+    let mut values = vec![
+        (
+            "jb_shell_meetings_today",
+            "gauge",
+            "Accepted meetings starting today",
+            telemetry.meetings_today,
+        ),
+        (
+            "jb_shell_workspace_switches_total",
+            "counter",
+            "Workspace switches",
+            telemetry.workspace_switches,
+        ),
+        (
+            "jb_shell_launcher_launches_total",
+            "counter",
+            "Apps started from the launcher",
+            telemetry.launcher_launches,
+        ),
+    ];
+    if let Some(percent) = telemetry.battery_percent {
+        values.push((
+            "jb_shell_battery_percent",
+            "gauge",
+            "Battery charge",
+            percent as u64,
+        ));
+    }
+    for (name, kind, help, value) in values {
+        out.push_str(&format!(
+            "# HELP {name} {help}.\n# TYPE {name} {kind}\n{name} {value}\n"
+        ));
+    }
+    out
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(pairs: &[(&str, u64)]) -> Vec<(String, u64)> {
+        pairs.iter().map(|(n, v)| (n.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn proc_self_files_parse() {
+        let status = "Name:\tjb-shell\nVmRSS:\t   81234 kB\nThreads:\t14\n";
+        assert_eq!(parse_status(status), (Some(81234 * 1024), Some(14)));
+        assert_eq!(parse_status("Name:\tjb-shell\n"), (None, None));
+
+        let stat = "4242 (jb shell (x)) S 1 4242 4242 0 -1 4194560 9000 0 12 0 310 95 0 0 20 0";
+        assert_eq!(parse_cpu_ticks(stat), Some(405));
+        assert_eq!(parse_cpu_ticks("4242 (jb-shell) S 1"), None);
+    }
+
+    #[test]
+    fn labels_are_short() {
+        assert_eq!(
+            type_label("jb_shell::widgets::caffeine::CaffeineInput"),
+            "CaffeineInput"
+        );
+        assert_eq!(
+            type_label("jb_shell::widgets::switcher::SwitcherInput<alloc::string::String>"),
+            "SwitcherInput"
+        );
+        assert_eq!(source_label("src/widgets/volume.rs"), "widgets/volume");
+        assert_eq!(source_label("net_usage"), "net_usage");
+    }
+
+    #[test]
+    fn deltas_rank_busiest_first() {
+        let prev = counters(&[("clock", 10), ("volume", 100), ("idle", 3)]);
+        let cur = counters(&[("clock", 15), ("volume", 160), ("idle", 3), ("ci", 2)]);
+        assert_eq!(
+            deltas(&prev, &cur),
+            counters(&[("volume", 60), ("clock", 5), ("ci", 2)])
+        );
+        assert_eq!(cpu_percent(1000, 1050, 5.0), 10.0);
+        assert_eq!(cpu_percent(1000, 1050, 0.0), 0.0);
+    }
+
+    #[test]
+    fn prometheus_text() {
+        let snapshot = Snapshot {
+            widget_updates: counters(&[("CaffeineInput", 3)]),
+            thread_wakeups: Vec::new(),
+            dbus_calls: counters(&[("dev.jb.shell.Launcher.\"Toggle\"", 1)]),
+            process: ProcessStats {
+                rss_bytes: 4096,
+                threads: 9,
+                cpu_ticks: 250,
+            },
+        };
+        let text = render_prometheus(&snapshot);
+        assert!(text.contains("# TYPE jb_shell_widget_updates_total counter\n"));
+        assert!(text.contains("jb_shell_widget_updates_total{widget=\"CaffeineInput\"} 3\n"));
+        assert!(text.contains("{method=\"dev.jb.shell.Launcher.\\\"Toggle\\\"\"} 1\n"));
+        assert!(text.contains("jb_shell_resident_memory_bytes 4096\n"));
+        assert!(text.contains("jb_shell_cpu_seconds_total 2.5\n"));
+    }
+
+    #[test]
+    fn telemetry_text() {
+        let mut telemetry = Telemetry {
+            notifications_last_hour: counters(&[("Slack", 12), ("firefox", 1)]),
+            meetings_today: 3,
+            workspace_switches: 40,
+            launcher_launches: 2,
+            battery_percent: Some(81),
+        };
+        let text = render_telemetry(&telemetry);
+        assert!(text.contains("# TYPE jb_shell_notifications_last_hour gauge\n"));
+        assert!(text.contains("jb_shell_notifications_last_hour{app=\"Slack\"} 12\n"));
+        assert!(text.contains("jb_shell_meetings_today 3\n"));
+        assert!(text.contains("# TYPE jb_shell_workspace_switches_total counter\n"));
+        assert!(text.contains("jb_shell_launcher_launches_total 2\n"));
+        assert!(text.contains("jb_shell_battery_percent 81\n"));
+
+        telemetry.battery_percent = None;
+        assert!(!render_telemetry(&telemetry).contains("battery"));
+    }
+}
@@ -0,0 +1,37 @@
+//! Display-independent logic behind the widgets. Nothing here touches GTK or
+//! the config, and ranking and formatting take `now` from the caller, so it
+//! can be unit tested and benchmarked without a display server.
+
+pub mod audio;
+pub mod backoff;
+pub mod calc;
+pub mod charge;
+pub mod ci;
+pub mod cli;
+pub mod commute;
+pub mod display;
+pub mod exec;
+pub mod git;
+pub mod hyprland_conf;
+pub mod i3bar;
+pub mod latency;
+pub mod launcher;
+pub mod markup;
+pub mod media_keys;
+pub mod metrics;
+pub mod oncall;
+pub mod power;
+pub mod preview;
+pub mod quick_actions;
+pub mod scale;
+pub mod schedule;
+pub mod setup;
+pub mod summary;
+pub mod sun;
+pub mod sysinfo;
+pub mod time;
+pub mod toolchains;
+pub mod usage;
+pub mod waybar;
+pub mod window_focus;
+pub mod window_rules;
@@ -0,0 +1,216 @@
+//! Reading PagerDuty and Opsgenie API responses for the on-call widget.
+
+use serde::Deserialize;
+
+/// Where an open incident stands; resolved ones aren't listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncidentStatus {
+    Triggered,
+    Acknowledged,
+}
+
+/// An open incident (PagerDuty) or alert (Opsgenie) assigned to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incident {
+    /// Id the acknowledge/resolve calls take.
+    pub id: String,
+    pub title: String,
+    /// Service (PagerDuty) or priority (Opsgenie), shown under the title.
+    pub source: String,
+    pub status: IncidentStatus,
+    /// Web page of the incident; Opsgenie alerts have none in the API.
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PagerdutyUser {
+    user: PagerdutyUserId,
+}
+
+#[derive(Deserialize)]
+struct PagerdutyUserId {
+    id: String,
+}
+
+/// The user id in `GET /users/me`.
+pub fn parse_pagerduty_user(json: &str) -> Result<String, serde_json::Error> {
+    Ok(serde_json::from_str::<PagerdutyUser>(json)?.user.id)
+}
+
+#[derive(Deserialize)]
+struct PagerdutyIncidents {
+    #[serde(default)]
+    incidents: Vec<PagerdutyIncident>,
+}
+
+#[derive(Deserialize)]
+struct PagerdutyIncident {
+    id: String,
+    #[serde(default)]
+    title: String,
+    status: String,
+    html_url: Option<String>,
+    service: Option<PagerdutyReference>,
+}
+
+#[derive(Deserialize)]
+struct PagerdutyReference {
+    #[serde(default)]
+    summary: String,
+}
+
+/// Triggered and acknowledged incidents from `GET /incidents`.
+pub fn parse_pagerduty_incidents(json: &str) -> Result<Vec<Incident>, serde_json::Error> {
+    let response: PagerdutyIncidents = serde_json::from_str(json)?;
+    Ok(response
+        .incidents
+        .into_iter()
+        .filter_map(|i| {
+            let status = match i.status.as_str() {
+                "triggered" => IncidentStatus::Triggered,
+                "acknowledged" => IncidentStatus::Acknowledged,
+                _ => return None,
+            };
+            Some(Incident {
+                id: i.id,
+                title: i.title,
+                source: i.service.map(|s| s.summary).unwrap_or_default(),
+                status,
+                url: i.html_url,
+            })
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct PagerdutyOncalls {
+    #[serde(default)]
+    oncalls: Vec<serde_json::Value>,
+}
+
+/// Whether `GET /oncalls?user_ids[]=…` lists any current on-call entry.
+pub fn parse_pagerduty_oncalls(json: &str) -> Result<bool, serde_json::Error> {
+    Ok(!serde_json::from_str::<PagerdutyOncalls>(json)?
+        .oncalls
+        .is_empty())
+}
+
+#[derive(Deserialize)]
+struct OpsgenieAlerts {
+    #[serde(default)]
+    data: Vec<OpsgenieAlert>,
+}
+
+#[derive(Deserialize)]
+struct OpsgenieAlert {
+    id: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    acknowledged: bool,
+    #[serde(default)]
+    priority: String,
+}
+
+/// Open alerts from `GET /v2/alerts`.
+pub fn parse_opsgenie_alerts(json: &str) -> Result<Vec<Incident>, serde_json::Error> {
+    let response: OpsgenieAlerts = serde_json::from_str(json)?;
+    Ok(response
+        .data
+        .into_iter()
+        .filter(|a| a.status == "open")
+        .map(|a| Incident {
+            id: a.id,
+            title: a.message,
+            source: a.priority,
+            status: if a.acknowledged {
+                IncidentStatus::Acknowledged
+            } else {
+                IncidentStatus::Triggered
+            },
+            url: None,
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct OpsgenieOncalls {
+    data: OpsgenieOncallData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpsgenieOncallData {
+    #[serde(default)]
+    on_call_recipients: Vec<String>,
+}
+
+/// Whether `email` is among a schedule's `GET /v2/schedules/{id}/on-calls?flat=true`
+/// recipients.
+pub fn parse_opsgenie_oncalls(json: &str, email: &str) -> Result<bool, serde_json::Error> {
+    let response: OpsgenieOncalls = serde_json::from_str(json)?;
+    Ok(response
+        .data
+        .on_call_recipients
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(email)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pagerduty_incidents_and_oncalls() {
+        assert_eq!(
+            parse_pagerduty_user(r#"{"user": {"id": "PABC123", "name": "Me"}}"#).unwrap(),
+            "PABC123"
+        );
+        let json = r#"{"incidents": [
+            {"id": "Q1", "title": "DB down", "status": "triggered",
+             "html_url": "https://acme.pagerduty.com/incidents/Q1",
+             "service": {"id": "S1", "summary": "Postgres"}},
+            {"id": "Q2", "title": "Slow API", "status": "acknowledged", "service": null},
+            {"id": "Q3", "title": "Old", "status": "resolved"}
+        ], "more": false}"#;
+        let incidents = parse_pagerduty_incidents(json).unwrap();
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(
+            incidents[0],
+            Incident {
+                id: "Q1".to_string(),
+                title: "DB down".to_string(),
+                source: "Postgres".to_string(),
+                status: IncidentStatus::Triggered,
+                url: Some("https://acme.pagerduty.com/incidents/Q1".to_string()),
+            }
+        );
+        assert_eq!(incidents[1].status, IncidentStatus::Acknowledged);
+        assert_eq!(incidents[1].source, "");
+
+        assert!(parse_pagerduty_oncalls(r#"{"oncalls": [{"escalation_level": 1}]}"#).unwrap());
+        assert!(!parse_pagerduty_oncalls(r#"{"oncalls": []}"#).unwrap());
+    }
+
+    #[test]
+    fn opsgenie_alerts_and_oncalls() {
+        let json = r#"{"data": [
+            {"id": "a1", "message": "Disk full", "status": "open", "acknowledged": false, "priority": "P1"},
+            {"id": "a2", "message": "Flapping", "status": "open", "acknowledged": true, "priority": "P3"},
+            {"id": "a3", "message": "Done", "status": "closed", "acknowledged": true}
+        ]}"#;
+        let alerts = parse_opsgenie_alerts(json).unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].status, IncidentStatus::Triggered);
+        assert_eq!(alerts[0].source, "P1");
+        assert_eq!(alerts[1].status, IncidentStatus::Acknowledged);
+        assert_eq!(alerts[1].url, None);
+
+        let json = r#"{"data": {"_parent": {"name": "primary"},
+                       "onCallRecipients": ["Me@Example.com", "you@example.com"]}}"#;
+        assert!(parse_opsgenie_oncalls(json, "me@example.com").unwrap());
+        assert!(!parse_opsgenie_oncalls(json, "them@example.com").unwrap());
+    }
+}
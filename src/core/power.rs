@@ -0,0 +1,35 @@
+//! Low-battery warnings: which `[power] warn_below` threshold a reading has
+//! just crossed.
+
+/// The threshold to warn about at `pct` percent, discharging: the lowest
+/// one at or above `pct` that's below `warned`, the last one warned about
+/// since the charger was unplugged. Readings that skip past several
+/// thresholds get one warning, for the lowest.
+pub fn warning_due(thresholds: &[u32], pct: u32, warned: Option<u32>) -> Option<u32> {
+    thresholds
+        .iter()
+        .copied()
+        .filter(|&t| pct <= t && warned.is_none_or(|w| t < w))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_threshold_warns_once() {
+        let thresholds = [10, 5];
+        assert_eq!(warning_due(&thresholds, 11, None), None);
+        assert_eq!(warning_due(&thresholds, 10, None), Some(10));
+        assert_eq!(warning_due(&thresholds, 9, Some(10)), None);
+        assert_eq!(warning_due(&thresholds, 5, Some(10)), Some(5));
+        assert_eq!(warning_due(&thresholds, 2, Some(5)), None);
+    }
+
+    #[test]
+    fn skipped_thresholds_warn_for_the_lowest() {
+        assert_eq!(warning_due(&[5, 10, 20], 4, None), Some(5));
+        assert_eq!(warning_due(&[], 4, None), None);
+    }
+}
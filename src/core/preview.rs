@@ -0,0 +1,227 @@
+//! CPU scaling for the workspace preview: window captures are downscaled
+//! before upload, and `PreviewCanvas` draws each as its own texture.
+
+/// A captured window: BGRA rows `stride` bytes apart.
+pub struct Frame<'a> {
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub stride: u32,
+}
+
+impl Frame<'_> {
+    /// Rows actually present in `data`; a short buffer loses its tail rows
+    /// rather than reading out of bounds.
+    fn usable_rows(&self) -> u32 {
+        let row_bytes = self.width as usize * 4;
+        let stride = self.stride as usize;
+        if row_bytes == 0 || stride < row_bytes || self.data.len() < row_bytes {
+            return 0;
+        }
+        let rows = (self.data.len() - row_bytes) / stride + 1;
+        rows.min(self.height as usize) as u32
+    }
+}
+
+/// Bilinear resample of `frame` to `dst_w`x`dst_h` (stride `dst_w * 4`), with
+/// alpha forced opaque. Weights are 8-bit fixed point; the per-column taps
+/// are computed once and shared by every row.
+pub fn downscale_bilinear(frame: &Frame, dst_w: u32, dst_h: u32) -> Vec<u8> {
+    let dst_stride = dst_w as usize * 4;
+    let mut dst = vec![0u8; dst_stride * dst_h as usize];
+    let src_h = frame.usable_rows();
+    if frame.width == 0 || src_h == 0 || dst_w == 0 {
+        return dst;
+    }
+    let src = frame.data;
+    let src_stride = frame.stride as usize;
+
+    let columns: Vec<Tap> = (0..dst_w)
+        .map(|dx| Tap::new(dx, dst_w, frame.width))
+        .collect();
+
+    for dy in 0..dst_h {
+        let Tap { near, far, weight } = Tap::new(dy, dst_h, src_h);
+        let top = &src[near * src_stride..];
+        let bottom = &src[far * src_stride..];
+        let out = &mut dst[dy as usize * dst_stride..(dy as usize + 1) * dst_stride];
+
+        for (pixel, col) in out.chunks_exact_mut(4).zip(&columns) {
+            let (left, right) = (col.near * 4, col.far * 4);
+            for (c, channel) in pixel[..3].iter_mut().enumerate() {
+                let upper =
+                    top[left + c] as u32 * (256 - col.weight) + top[right + c] as u32 * col.weight;
+                let lower = bottom[left + c] as u32 * (256 - col.weight)
+                    + bottom[right + c] as u32 * col.weight;
+                *channel = ((upper * (256 - weight) + lower * weight + (1 << 15)) >> 16) as u8;
+            }
+            pixel[3] = 0xFF;
+        }
+    }
+
+    dst
+}
+
+/// Sort key for drawing a workspace's windows bottom to top: tiled, then
+/// floating, then fullscreen; within each, least recently focused first
+/// (Hyprland's focus history id is 0 for the focused window).
+pub fn stacking_key(floating: bool, fullscreen: bool, focus_history_id: i32) -> (u8, i32) {
+    let layer = if fullscreen {
+        2
+    } else if floating {
+        1
+    } else {
+        0
+    };
+    (layer, -focus_history_id)
+}
+
+/// `width`x`height` scaled down to fit `max_w`x`max_h`, keeping the aspect
+/// ratio. Never scales up; each side stays at least 1.
+pub fn fit_within(width: u32, height: u32, max_w: u32, max_h: u32) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+    let scale = (max_w as f64 / width as f64)
+        .min(max_h as f64 / height as f64)
+        .min(1.0);
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// The two source samples around a destination pixel's center, and how far
+/// (out of 256) it sits toward `far`.
+struct Tap {
+    near: usize,
+    far: usize,
+    weight: u32,
+}
+
+impl Tap {
+    fn new(dst: u32, dst_len: u32, src_len: u32) -> Self {
+        let center = (dst as f64 + 0.5) * src_len as f64 / dst_len as f64 - 0.5;
+        let center = center.clamp(0.0, (src_len - 1) as f64);
+        let near = center.floor();
+        Tap {
+            near: near as usize,
+            far: (near as usize + 1).min(src_len as usize - 1),
+            weight: ((center - near) * 256.0).round() as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Solid-color BGRA frame with `pad` junk bytes after each row.
+    fn solid(width: u32, height: u32, pad: u32, bgra: [u8; 4]) -> Vec<u8> {
+        let stride = width * 4 + pad;
+        let mut data = vec![0xAB; (stride * height) as usize];
+        for row in data.chunks_exact_mut(stride as usize) {
+            for px in row[..(width * 4) as usize].chunks_exact_mut(4) {
+                px.copy_from_slice(&bgra);
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn solid_colors_survive_scaling_and_become_opaque() {
+        let data = solid(100, 50, 12, [10, 20, 30, 0]);
+        let frame = Frame {
+            data: &data,
+            width: 100,
+            height: 50,
+            stride: 412,
+        };
+        let scaled = downscale_bilinear(&frame, 7, 3);
+        assert_eq!(scaled.len(), 7 * 3 * 4);
+        for px in scaled.chunks_exact(4) {
+            assert_eq!(px, [10, 20, 30, 0xFF]);
+        }
+    }
+
+    #[test]
+    fn halving_averages_neighbours() {
+        // Two columns, black and white: one output pixel sits between them
+        let data = [0, 0, 0, 255, 255, 255, 255, 255];
+        let frame = Frame {
+            data: &data,
+            width: 2,
+            height: 1,
+            stride: 8,
+        };
+        let scaled = downscale_bilinear(&frame, 1, 1);
+        assert_eq!(scaled, [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn upscaling_a_single_pixel_repeats_it() {
+        let data = [1, 2, 3, 4];
+        let frame = Frame {
+            data: &data,
+            width: 1,
+            height: 1,
+            stride: 4,
+        };
+        assert_eq!(downscale_bilinear(&frame, 2, 2), [1, 2, 3, 255].repeat(4));
+    }
+
+    #[test]
+    fn short_buffers_do_not_panic() {
+        let data = solid(10, 4, 0, [9, 9, 9, 9]);
+        let frame = Frame {
+            data: &data[..data.len() - 1],
+            width: 10,
+            height: 4,
+            stride: 40,
+        };
+        assert_eq!(frame.usable_rows(), 3);
+        downscale_bilinear(&frame, 5, 5);
+
+        let empty = Frame {
+            data: &[],
+            width: 10,
+            height: 4,
+            stride: 40,
+        };
+        assert!(downscale_bilinear(&empty, 3, 3).iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn stacking_puts_fullscreen_then_floating_on_top() {
+        let mut windows = [
+            ("focused tile", false, false, 0),
+            ("old float", true, false, 3),
+            ("fullscreen", false, true, 2),
+            ("old tile", false, false, 4),
+            ("new float", true, false, 1),
+        ];
+        windows.sort_by_key(|&(_, floating, fullscreen, focus)| {
+            stacking_key(floating, fullscreen, focus)
+        });
+        let order: Vec<&str> = windows.iter().map(|w| w.0).collect();
+        assert_eq!(
+            order,
+            [
+                "old tile",
+                "focused tile",
+                "old float",
+                "new float",
+                "fullscreen"
+            ]
+        );
+    }
+
+    #[test]
+    fn fit_keeps_aspect_and_never_grows() {
+        assert_eq!(fit_within(2560, 1440, 240, 160), (240, 135));
+        assert_eq!(fit_within(800, 1600, 240, 160), (80, 160));
+        assert_eq!(fit_within(100, 50, 240, 160), (100, 50));
+        assert_eq!(fit_within(10000, 2, 240, 160), (240, 1));
+        assert_eq!(fit_within(0, 50, 240, 160), (0, 0));
+    }
+}
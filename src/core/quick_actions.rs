@@ -0,0 +1,121 @@
+//! Links and one-time codes found in notification bodies, offered as "Open
+//! link" and "Copy code" buttons on toasts and center rows.
+
+/// Characters a URL can't end with when it's followed by prose.
+const URL_TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '\''];
+
+/// The first `http(s)://` URL in `text`, without trailing punctuation.
+pub fn find_url(text: &str) -> Option<String> {
+    urls(text).next().map(str::to_string)
+}
+
+fn urls(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '"' | '<' | '>'))
+        .filter_map(|word| {
+            let start = word.find("https://").or_else(|| word.find("http://"))?;
+            let url = word[start..].trim_end_matches(URL_TRAILING);
+            // Nothing after the scheme
+            url.split_once("://")
+                .is_some_and(|(_, rest)| !rest.is_empty())
+                .then_some(url)
+        })
+}
+
+/// The first 6-digit one-time code in `text`, outside any URL: a run of
+/// exactly six digits, `123-456`, or a prefixed code like Google's
+/// `G-123456`. Returned as digits only.
+pub fn find_otp(text: &str) -> Option<String> {
+    let mut prose = text.to_string();
+    for url in urls(text) {
+        prose = prose.replace(url, " ");
+    }
+    prose
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .find_map(otp_token)
+}
+
+fn otp_token(token: &str) -> Option<String> {
+    let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let token = match token.split_once('-') {
+        // G-123456
+        Some((prefix, code))
+            if !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_alphabetic()) =>
+        {
+            code
+        }
+        _ => token,
+    };
+    if token.len() == 6 && all_digits(token) {
+        return Some(token.to_string());
+    }
+    match token.split_once('-') {
+        Some((a, b)) if a.len() == 3 && b.len() == 3 && all_digits(a) && all_digits(b) => {
+            Some(format!("{a}{b}"))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::markup::to_plain;
+
+    #[test]
+    fn urls_lose_trailing_punctuation() {
+        assert_eq!(
+            find_url("Review requested: https://github.com/o/r/pull/12."),
+            Some("https://github.com/o/r/pull/12".to_string())
+        );
+        assert_eq!(
+            find_url("(see http://example.com/a?b=c)"),
+            Some("http://example.com/a?b=c".to_string())
+        );
+        assert_eq!(
+            find_url("<a href=\"https://x.io/y\">link</a>"),
+            Some("https://x.io/y".to_string())
+        );
+        assert_eq!(find_url("no link, just https:// here"), None);
+        assert_eq!(find_url("ftp://old.example.com"), None);
+    }
+
+    #[test]
+    fn six_digit_codes_are_found() {
+        assert_eq!(
+            find_otp("Your verification code is 482913."),
+            Some("482913".to_string())
+        );
+        assert_eq!(
+            find_otp("G-730215 is your Google verification code"),
+            Some("730215".to_string())
+        );
+        assert_eq!(find_otp("Code: 123-456"), Some("123456".to_string()));
+    }
+
+    #[test]
+    fn other_numbers_are_not_codes() {
+        assert_eq!(find_otp("Call 555-123-4567 or 12345"), None);
+        assert_eq!(find_otp("Order 1234567 shipped"), None);
+        assert_eq!(find_otp("Build 12ab34 failed"), None);
+        assert_eq!(find_otp("Open https://x.io/login/482913 to continue"), None);
+    }
+
+    #[test]
+    fn entity_encoded_urls_are_decoded_first() {
+        let plain = to_plain("Pipeline https://x.example/?a=1&amp;b=2 failed", false);
+        assert_eq!(
+            find_url(&plain),
+            Some("https://x.example/?a=1&b=2".to_string())
+        );
+    }
+
+    #[test]
+    fn numeric_entities_are_not_codes() {
+        assert_eq!(find_otp(&to_plain("Great job &#128512;", false)), None);
+        assert_eq!(find_otp(&to_plain("Great job &#128512;", true)), None);
+        assert_eq!(
+            find_otp(&to_plain("Code&#58; 482913", false)),
+            Some("482913".to_string())
+        );
+    }
+}
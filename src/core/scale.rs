@@ -0,0 +1,91 @@
+//! Logical versus device pixels on scaled monitors. Layer-shell margins and
+//! widget sizes are logical; Hyprland multiplies them by the monitor's scale,
+//! which may be fractional (1.25, 1.5, 1.6, ...).
+
+/// The closest whole device pixel counts at most this far off.
+const EPSILON: f64 = 0.01;
+
+/// Searched at most this many logical pixels ahead before giving up; every
+/// scale Hyprland accepts lines up within a few.
+const MAX_STEPS: i32 = 8;
+
+/// The first whole logical offset at or after `logical` that lands on a whole
+/// device pixel at `scale`. A surface placed between device pixels gets
+/// resampled by the compositor and every edge of it comes out blurry. Falls
+/// back to rounding up when no offset nearby lines up.
+pub fn snap(logical: f64, scale: f64) -> i32 {
+    let start = (logical - EPSILON).ceil() as i32;
+    if scale <= 0.0 {
+        return start;
+    }
+    (start..start + MAX_STEPS)
+        .find(|&offset| {
+            let device = offset as f64 * scale;
+            (device - device.round()).abs() < EPSILON
+        })
+        .unwrap_or(start)
+}
+
+/// Scale GTK renders a surface at on a monitor Hyprland scales by `scale`:
+/// GTK before 4.14 only draws at whole scales, rounded up, and leaves the
+/// compositor to shrink the buffer. Textures sized by this are never
+/// upscaled, and so never blurry, on either side of that.
+pub fn buffer_scale(scale: f64) -> i32 {
+    (scale - EPSILON).ceil().max(1.0) as i32
+}
+
+/// The logical size of a `physical`-pixel output at `scale`.
+pub fn logical_size(physical: u32, scale: f64) -> u32 {
+    if scale <= 0.0 {
+        return physical;
+    }
+    (physical as f64 / scale).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_scales_only_round_up() {
+        assert_eq!(snap(31.0, 1.0), 31);
+        assert_eq!(snap(30.4, 1.0), 31);
+        assert_eq!(snap(31.0, 2.0), 31);
+    }
+
+    #[test]
+    fn fractional_scales_move_to_the_next_whole_device_pixel() {
+        // 1.5: even offsets only
+        assert_eq!(snap(31.0, 1.5), 32);
+        assert_eq!(snap(32.0, 1.5), 32);
+        // 1.25: multiples of four
+        assert_eq!(snap(33.0, 1.25), 36);
+        // Hyprland reports 4/3 rounded
+        assert_eq!(snap(31.0, 1.333333), 33);
+    }
+
+    #[test]
+    fn unalignable_scales_fall_back_to_rounding_up() {
+        assert_eq!(snap(10.2, 1.0 + 1.0 / 97.0), 11);
+        assert_eq!(snap(10.0, 0.0), 10);
+    }
+
+    #[test]
+    fn buffer_scale_rounds_up_to_whole() {
+        assert_eq!(buffer_scale(1.0), 1);
+        assert_eq!(buffer_scale(1.25), 2);
+        assert_eq!(buffer_scale(2.0), 2);
+        assert_eq!(buffer_scale(2.000001), 2);
+        assert_eq!(buffer_scale(0.0), 1);
+    }
+
+    #[test]
+    fn logical_size_rounds_rather_than_truncating() {
+        assert_eq!(logical_size(2560, 1.0), 2560);
+        assert_eq!(logical_size(2560, 1.6), 1600);
+        // 2560 / 1.333333 = 1920.0005
+        assert_eq!(logical_size(2560, 1.333333), 1920);
+        // 2256 / 1.566667 = 1439.9996
+        assert_eq!(logical_size(2256, 1.566667), 1440);
+    }
+}
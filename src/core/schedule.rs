@@ -0,0 +1,155 @@
+//! Cron-style schedules for `[[reminders]]`: five fields (minute, hour,
+//! day of month, month, day of week), each `*`, a number, a range `a-b`, a
+//! step `*/n` or `a-b/n`, or a comma-separated list of those.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+/// A parsed schedule; each field is a bitmask of the values it allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether day of month / day of week were `*`; cron matches either of
+    /// the two when both are restricted.
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(CronSchedule {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// Whether the schedule fires in the minute containing `time`.
+    pub fn matches(&self, time: NaiveDateTime) -> bool {
+        let bit = |mask: u64, value: u32| mask & (1u64 << value) != 0;
+        let day = bit(self.days, time.day());
+        let weekday = bit(self.weekdays, time.weekday().num_days_from_sunday());
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        bit(self.minutes, time.minute())
+            && bit(self.hours, time.hour())
+            && bit(self.months, time.month())
+            && day_matches
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|&s| s > 0)
+                    .ok_or_else(|| format!("bad step in `{part}`"))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else {
+            let number = |s: &str| {
+                s.parse::<u32>()
+                    .ok()
+                    .filter(|n| (min..=max).contains(n))
+                    .ok_or_else(|| format!("`{part}` is outside {min}-{max}"))
+            };
+            match range.split_once('-') {
+                Some((a, b)) => (number(a)?, number(b)?),
+                None => {
+                    let n = number(range)?;
+                    // `5/15` means from 5 to the end, every 15
+                    (n, if step > 1 { max } else { n })
+                }
+            }
+        };
+        if start > end {
+            return Err(format!("`{part}` runs backwards"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1u64 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // March 2025: the 3rd is a Monday
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn weekday_standup() {
+        let standup = CronSchedule::parse("45 9 * * 1-5").unwrap();
+        assert!(standup.matches(at(3, 9, 45)));
+        assert!(standup.matches(at(7, 9, 45)));
+        assert!(!standup.matches(at(8, 9, 45)), "Saturday");
+        assert!(!standup.matches(at(3, 9, 46)));
+    }
+
+    #[test]
+    fn steps_lists_and_sunday() {
+        let stretch = CronSchedule::parse("*/50 9-17/2 * * *").unwrap();
+        assert!(stretch.matches(at(4, 9, 0)));
+        assert!(stretch.matches(at(4, 11, 50)));
+        assert!(!stretch.matches(at(4, 10, 0)));
+        assert!(!stretch.matches(at(4, 9, 25)));
+
+        let meds = CronSchedule::parse("0 8,20 * * 7").unwrap();
+        assert!(meds.matches(at(9, 20, 0)), "Sunday as 7");
+        assert!(!meds.matches(at(10, 20, 0)));
+    }
+
+    #[test]
+    fn day_of_month_or_weekday() {
+        // The 1st, or any Friday
+        let payday = CronSchedule::parse("0 12 1 * 5").unwrap();
+        assert!(payday.matches(at(1, 12, 0)));
+        assert!(payday.matches(at(7, 12, 0)));
+        assert!(!payday.matches(at(6, 12, 0)));
+    }
+
+    #[test]
+    fn rejects_malformed_specs() {
+        assert!(CronSchedule::parse("0 9 * *").is_err());
+        assert!(CronSchedule::parse("60 9 * * *").is_err());
+        assert!(CronSchedule::parse("0 17-9 * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 nine * * *").is_err());
+    }
+}
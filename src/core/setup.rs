@@ -0,0 +1,135 @@
+//! The first-run wizard's choices and the `config.toml` it writes.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    Left,
+    Center,
+    Right,
+}
+
+/// A bar widget the wizard offers, in the order it's placed.
+#[derive(Debug, Clone, Copy)]
+pub struct WidgetChoice {
+    /// Layout name, as in `[bar.layout]`.
+    pub name: &'static str,
+    pub section: Section,
+    /// Part of the built-in layout, so ticked to begin with.
+    pub default: bool,
+    pub description: &'static str,
+}
+
+const fn choice(
+    name: &'static str,
+    section: Section,
+    default: bool,
+    description: &'static str,
+) -> WidgetChoice {
+    WidgetChoice {
+        name,
+        section,
+        default,
+        description,
+    }
+}
+
+pub const WIDGETS: &[WidgetChoice] = &[
+    choice("workspaces", Section::Left, true, "Workspaces"),
+    choice("kube", Section::Left, true, "Kubernetes context"),
+    choice("gcloud", Section::Left, true, "gcloud configuration"),
+    choice("terraform", Section::Left, false, "Terraform version"),
+    choice("node", Section::Left, false, "Node version"),
+    choice("rustup", Section::Left, false, "Rust toolchain"),
+    choice("mpris", Section::Left, true, "Media player"),
+    choice("active-window", Section::Center, true, "Window title"),
+    choice("join-meeting", Section::Right, true, "Join next meeting"),
+    choice("notifications", Section::Right, true, "Notification center"),
+    choice("calendar", Section::Right, true, "Google Calendar"),
+    choice("git", Section::Right, false, "Git repositories"),
+    choice("ci", Section::Right, false, "CI pipelines"),
+    choice("oncall", Section::Right, false, "On-call incidents"),
+    choice("night-light", Section::Right, false, "Night light"),
+    choice("caffeine", Section::Right, false, "Keep awake"),
+    choice("focus", Section::Right, false, "Focus mode"),
+    choice("display", Section::Right, false, "Display profiles"),
+    choice("color-picker", Section::Right, false, "Color picker"),
+    choice("airplane", Section::Right, false, "Airplane mode"),
+    choice("volume", Section::Right, true, "Volume"),
+    choice("network", Section::Right, true, "Network"),
+    choice("battery", Section::Right, true, "Battery"),
+    choice("clock", Section::Right, true, "Clock"),
+    choice("screen-off", Section::Right, false, "Screen off button"),
+];
+
+/// `config.toml` placing the `selected` widgets in catalog order.
+pub fn render_config(selected: &[&str]) -> String {
+    let section = |section: Section| {
+        let names: Vec<String> = WIDGETS
+            .iter()
+            .filter(|w| w.section == section && selected.contains(&w.name))
+            .map(|w| format!("\"{}\"", w.name))
+            .collect();
+        format!("[{}]", names.join(", "))
+    };
+    format!(
+        "# Written by the jb-shell setup wizard. Every key is optional; the README\n\
+         # lists them all.\n\
+         \n\
+         [bar.layout]\n\
+         left = {}\n\
+         center = {}\n\
+         right = {}\n",
+        section(Section::Left),
+        section(Section::Center),
+        section(Section::Right),
+    )
+}
+
+/// `cerebras.json` holding just the API key.
+pub fn render_cerebras(api_key: &str) -> String {
+    let json = serde_json::json!({ "api_key": api_key.trim() });
+    // A map of one string can't fail to serialize
+    let mut text = serde_json::to_string_pretty(&json).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_render_the_built_in_layout() {
+        let defaults: Vec<&str> = WIDGETS
+            .iter()
+            .filter(|w| w.default)
+            .map(|w| w.name)
+            .collect();
+        let config = render_config(&defaults);
+        assert!(config.contains("left = [\"workspaces\", \"kube\", \"gcloud\", \"mpris\"]\n"));
+        assert!(config.contains("center = [\"active-window\"]\n"));
+        assert!(config.contains(
+            "right = [\"join-meeting\", \"notifications\", \"calendar\", \"volume\", \
+             \"network\", \"battery\", \"clock\"]\n"
+        ));
+    }
+
+    #[test]
+    fn selection_keeps_catalog_order() {
+        let config = render_config(&["clock", "focus", "workspaces"]);
+        assert!(config.contains("left = [\"workspaces\"]\n"));
+        assert!(config.contains("center = []\n"));
+        assert!(config.contains("right = [\"focus\", \"clock\"]\n"));
+    }
+
+    #[test]
+    fn api_key_is_json_escaped() {
+        assert_eq!(
+            render_cerebras(" csk-a\"b\\c\n"),
+            "{\n  \"api_key\": \"csk-a\\\"b\\\\c\"\n}\n"
+        );
+        assert_eq!(
+            render_cerebras("csk-\u{1}"),
+            "{\n  \"api_key\": \"csk-\\u0001\"\n}\n"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Sunrise and sunset for the night light's `auto` schedule.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+/// Sunrise and sunset for a date via the NOAA sunrise equation. In polar
+/// night both collapse onto solar noon; under the midnight sun they sit half a
+/// day either side of it.
+pub fn sun_times(date: NaiveDate, lat: f64, lon: f64) -> (DateTime<Utc>, DateTime<Utc>) {
+    let noon_utc = date
+        .and_hms_opt(12, 0, 0)
+        .map(|t| Utc.from_utc_datetime(&t).timestamp())
+        .unwrap_or_default();
+    let julian_day = noon_utc as f64 / 86400.0 + 2440587.5;
+    let n = (julian_day - 2451545.0).round();
+
+    let mean_noon = n - lon / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let center =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_lon = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit =
+        2451545.0 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_lon).sin();
+
+    let declination = (ecliptic_lon.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let lat = lat.to_radians();
+    let cos_hour_angle = ((-0.833_f64).to_radians().sin() - lat.sin() * declination.sin())
+        / (lat.cos() * declination.cos());
+    let hour_angle = cos_hour_angle.clamp(-1.0, 1.0).acos().to_degrees();
+
+    let from_julian = |julian: f64| {
+        let secs = ((julian - 2440587.5) * 86400.0) as i64;
+        DateTime::from_timestamp(secs, 0).unwrap_or_default()
+    };
+    let sunrise = from_julian(transit - hour_angle / 360.0);
+    let sunset = from_julian(transit + hour_angle / 360.0);
+    (sunrise, sunset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn assert_near(actual: DateTime<Utc>, hour: u32, minute: u32) {
+        let expected = Utc
+            .from_utc_datetime(&actual.date_naive().and_hms_opt(hour, minute, 0).unwrap());
+        let off = (actual - expected).abs();
+        assert!(off <= TimeDelta::minutes(3), "{actual} is not near {hour}:{minute:02}");
+    }
+
+    #[test]
+    fn london_at_midsummer() {
+        let (sunrise, sunset) = sun_times(date(2024, 6, 21), 51.5, -0.13);
+        assert_near(sunrise, 3, 43);
+        assert_near(sunset, 20, 21);
+    }
+
+    #[test]
+    fn equator_at_the_equinox_is_twelve_hours() {
+        let (sunrise, sunset) = sun_times(date(2024, 3, 20), 0.0, 0.0);
+        assert_near(sunrise, 6, 4);
+        assert_near(sunset, 18, 11);
+    }
+
+    #[test]
+    fn polar_day_and_night() {
+        // Tromsø: midnight sun in June, polar night in December
+        let (sunrise, sunset) = sun_times(date(2024, 6, 21), 69.65, 18.96);
+        assert_eq!((sunset - sunrise).num_hours(), 24);
+        let (sunrise, sunset) = sun_times(date(2024, 12, 21), 69.65, 18.96);
+        assert_eq!(sunrise, sunset);
+    }
+}
@@ -0,0 +1,64 @@
+//! Reading `/proc/uptime` and `/proc/loadavg` for the clock tooltip.
+
+/// Seconds since boot, suspended time included, from `/proc/uptime`.
+pub fn parse_uptime(contents: &str) -> Option<f64> {
+    contents.split_whitespace().next()?.parse().ok()
+}
+
+/// 1, 5 and 15 minute load averages from `/proc/loadavg`.
+pub fn parse_loadavg(contents: &str) -> Option<[f64; 3]> {
+    let mut fields = contents.split_whitespace().map(|f| f.parse().ok());
+    Some([fields.next()??, fields.next()??, fields.next()??])
+}
+
+/// "3d 4h", "4h 12m" or "12m": the largest non-zero unit and the next one
+/// down, which is left off when it's zero ("3d", not "3d 0h").
+pub fn format_duration(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    match (days, hours, mins) {
+        (0, 0, mins) => format!("{mins}m"),
+        (0, hours, 0) => format!("{hours}h"),
+        (0, hours, mins) => format!("{hours}h {mins}m"),
+        (days, 0, _) => format!("{days}d"),
+        (days, hours, _) => format!("{days}d {hours}h"),
+    }
+}
+
+/// Whether the gap between two clock ticks means the machine was suspended:
+/// boot time (which counts suspend) moved more than `slack` seconds further
+/// than the monotonic clock (which doesn't).
+pub fn resumed_between(boot_delta: f64, monotonic_delta: f64, slack: f64) -> bool {
+    boot_delta - monotonic_delta > slack
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proc_files_parse() {
+        assert_eq!(parse_uptime("35912.47 140021.92\n"), Some(35912.47));
+        assert_eq!(parse_uptime(""), None);
+        assert_eq!(
+            parse_loadavg("0.52 0.58 0.59 2/1234 56789\n"),
+            Some([0.52, 0.58, 0.59])
+        );
+        assert_eq!(parse_loadavg("0.52 0.58\n"), None);
+    }
+
+    #[test]
+    fn durations_keep_two_units() {
+        assert_eq!(format_duration(59), "0m");
+        assert_eq!(format_duration(12 * 60 + 30), "12m");
+        assert_eq!(format_duration(4 * 3600 + 12 * 60), "4h 12m");
+        assert_eq!(format_duration(3 * 86400 + 4 * 3600 + 59 * 60), "3d 4h");
+        assert_eq!(format_duration(3 * 86400 + 59 * 60), "3d");
+        assert_eq!(format_duration(2 * 3600 + 30), "2h");
+    }
+
+    #[test]
+    fn suspend_shows_as_boot_clock_running_ahead() {
+        assert!(!resumed_between(1.01, 1.0, 5.0));
+        assert!(resumed_between(3601.0, 1.0, 5.0));
+    }
+}
@@ -0,0 +1,215 @@
+//! Time formatting for notifications and the history DB's UTC timestamps.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Format of SQLite's `datetime('now')`, which the history DB stores in UTC.
+const DB_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// "just now" / "5m ago" / "3h ago" / "2d ago" for a DB `created_at`. Values
+/// that don't parse are shown as-is.
+pub fn format_relative_time(created_at: &str, now: DateTime<Local>) -> String {
+    let Ok(dt) = NaiveDateTime::parse_from_str(created_at, DB_FORMAT) else {
+        return created_at.to_string();
+    };
+    // datetime('now') stores UTC — interpret as UTC then convert to local
+    let created = dt.and_utc().with_timezone(&Local);
+    let diff = now - created;
+
+    let mins = diff.num_minutes();
+    if mins < 1 {
+        "just now".to_string()
+    } else if mins < 60 {
+        format!("{mins}m ago")
+    } else {
+        let hours = diff.num_hours();
+        if hours < 24 {
+            format!("{hours}h ago")
+        } else {
+            format!("{}d ago", diff.num_days())
+        }
+    }
+}
+
+/// Meeting alert title counting down to `target`.
+pub fn format_countdown(target: DateTime<Local>, now: DateTime<Local>) -> String {
+    let secs = (target - now).num_seconds().max(0);
+    let mins = secs / 60;
+    let remaining_secs = secs % 60;
+    if mins > 0 {
+        format!("Starting in {}m {}s", mins, remaining_secs)
+    } else {
+        format!("Starting in {}s", remaining_secs)
+    }
+}
+
+/// When the meetings in progress at `now` end, given each one's start and
+/// end, or None if there are none. Moves on to the next meeting's end once a
+/// back-to-back one takes over.
+pub fn meeting_end(
+    meetings: impl IntoIterator<Item = (DateTime<Local>, DateTime<Local>)>,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    meetings
+        .into_iter()
+        .filter(|&(start, end)| start <= now && end > now)
+        .map(|(_, end)| end)
+        .max()
+}
+
+/// Returns the local midnight starting `now`'s day as a UTC datetime string (for SQL
+/// `created_at >= ?`). This ensures timezone-correct "today" filtering since `created_at` is
+/// stored in UTC.
+pub fn today_start_utc(now: DateTime<Local>) -> String {
+    days_ago_start_utc(0, now)
+}
+
+/// Local midnight `days` days before `now`'s day, as a UTC datetime string.
+pub fn days_ago_start_utc(days: i64, now: DateTime<Local>) -> String {
+    local_midnight_utc(now.date_naive() - chrono::Days::new(days.max(0) as u64))
+}
+
+/// `time` as the history DB stores it.
+pub fn to_db_time(time: DateTime<Utc>) -> String {
+    time.format(DB_FORMAT).to_string()
+}
+
+/// Local midnight starting `day_local`, as a UTC datetime string. When DST
+/// skips midnight, the day starts at the earliest valid local time.
+pub fn local_midnight_utc(day_local: NaiveDate) -> String {
+    // DST gaps are at most an hour or two
+    let midnight_utc = (0..=3)
+        .filter_map(|hour| day_local.and_hms_opt(hour, 0, 0))
+        .find_map(|t| Local.from_local_datetime(&t).earliest())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| day_local.and_time(chrono::NaiveTime::MIN).and_utc());
+    to_db_time(midnight_utc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    /// What `datetime('now')` would have stored `ago` before `now`.
+    fn stored(now: DateTime<Local>, ago: TimeDelta) -> String {
+        (now - ago)
+            .with_timezone(&Utc)
+            .format(DB_FORMAT)
+            .to_string()
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap()
+    }
+
+    #[test]
+    fn relative_time_buckets() {
+        let now = now();
+        let cases = [
+            (TimeDelta::seconds(30), "just now"),
+            (TimeDelta::minutes(1), "1m ago"),
+            (TimeDelta::minutes(59), "59m ago"),
+            (TimeDelta::minutes(60), "1h ago"),
+            (TimeDelta::hours(23) + TimeDelta::minutes(59), "23h ago"),
+            (TimeDelta::hours(24), "1d ago"),
+            (TimeDelta::days(45), "45d ago"),
+        ];
+        for (ago, expected) in cases {
+            assert_eq!(format_relative_time(&stored(now, ago), now), expected);
+        }
+    }
+
+    #[test]
+    fn relative_time_of_future_rows_is_just_now() {
+        let now = now();
+        let ahead = stored(now, TimeDelta::minutes(-5));
+        assert_eq!(format_relative_time(&ahead, now), "just now");
+    }
+
+    #[test]
+    fn unparseable_timestamps_pass_through() {
+        assert_eq!(format_relative_time("yesterday", now()), "yesterday");
+        assert_eq!(format_relative_time("", now()), "");
+    }
+
+    #[test]
+    fn countdown_formats_minutes_and_seconds() {
+        let now = now();
+        assert_eq!(
+            format_countdown(now + TimeDelta::seconds(125), now),
+            "Starting in 2m 5s"
+        );
+        assert_eq!(
+            format_countdown(now + TimeDelta::seconds(60), now),
+            "Starting in 1m 0s"
+        );
+        assert_eq!(
+            format_countdown(now + TimeDelta::seconds(59), now),
+            "Starting in 59s"
+        );
+    }
+
+    #[test]
+    fn countdown_stops_at_zero() {
+        let now = now();
+        assert_eq!(format_countdown(now, now), "Starting in 0s");
+        assert_eq!(
+            format_countdown(now - TimeDelta::minutes(3), now),
+            "Starting in 0s"
+        );
+    }
+
+    #[test]
+    fn meeting_end_follows_back_to_back_meetings() {
+        let now = now();
+        let first = (now, now + TimeDelta::minutes(30));
+        let second = (first.1, first.1 + TimeDelta::minutes(30));
+        let meetings = [first, second];
+        assert_eq!(meeting_end(meetings, now), Some(first.1));
+        assert_eq!(
+            meeting_end(meetings, first.1 - TimeDelta::seconds(1)),
+            Some(first.1)
+        );
+        assert_eq!(meeting_end(meetings, first.1), Some(second.1));
+        assert_eq!(meeting_end(meetings, second.1), None);
+    }
+
+    #[test]
+    fn meeting_end_is_the_latest_overlapping_end() {
+        let now = now();
+        let short = (now - TimeDelta::minutes(10), now + TimeDelta::minutes(5));
+        let long = (now - TimeDelta::minutes(5), now + TimeDelta::minutes(50));
+        let later = (now + TimeDelta::hours(2), now + TimeDelta::hours(3));
+        assert_eq!(meeting_end([short, long, later], now), Some(long.1));
+        assert_eq!(meeting_end([later], now), None);
+    }
+
+    #[test]
+    fn local_midnight_round_trips_to_local_time() {
+        let day = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let utc = NaiveDateTime::parse_from_str(&local_midnight_utc(day), DB_FORMAT).unwrap();
+        let local = utc.and_utc().with_timezone(&Local);
+        assert_eq!(local.date_naive(), day);
+        assert_eq!(local.time(), chrono::NaiveTime::MIN);
+    }
+
+    #[test]
+    fn today_start_is_now_s_local_midnight() {
+        let now = now();
+        let start = NaiveDateTime::parse_from_str(&today_start_utc(now), DB_FORMAT)
+            .unwrap()
+            .and_utc()
+            .with_timezone(&Local);
+        assert_eq!(start.date_naive(), now.date_naive());
+        assert_eq!(start.time(), chrono::NaiveTime::MIN);
+    }
+
+    #[test]
+    fn days_ago_counts_back_from_now() {
+        let now = now();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(days_ago_start_utc(1, now), local_midnight_utc(day(13)));
+        assert_eq!(days_ago_start_utc(6, now), local_midnight_utc(day(8)));
+        assert_eq!(days_ago_start_utc(-3, now), today_start_utc(now));
+    }
+}
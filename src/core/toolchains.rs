@@ -0,0 +1,179 @@
+//! Parsing the version managers behind the toolchain switchers: each listing
+//! gives the installed items and which one is the default, as
+//! `(current, items)`; `current` is empty when none is marked.
+
+/// `rustup toolchain list`: `stable-x86_64-unknown-linux-gnu (active, default)`.
+pub fn parse_rustup_toolchains(output: &str) -> (String, Vec<String>) {
+    let mut current = String::new();
+    let mut items = Vec::new();
+    for line in output.lines() {
+        let Some(name) = line.split_whitespace().next() else {
+            continue;
+        };
+        let flags = line.trim_start()[name.len()..].trim();
+        if flags.starts_with('(') && flags.contains("default") {
+            current = name.to_string();
+        }
+        items.push(name.to_string());
+    }
+    (current, items)
+}
+
+/// `tfenv list`: `* 1.5.7 (set by /home/me/.tfenv/version)` marks the one in use.
+pub fn parse_tfenv_list(output: &str) -> (String, Vec<String>) {
+    parse_starred(output)
+}
+
+/// `terraform workspace list`: `* dev` marks the selected workspace.
+pub fn parse_terraform_workspaces(output: &str) -> (String, Vec<String>) {
+    parse_starred(output)
+}
+
+/// One item per line, the first word after an optional `*` that marks the
+/// current one.
+fn parse_starred(output: &str) -> (String, Vec<String>) {
+    let mut current = String::new();
+    let mut items = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        let (marked, rest) = match line.strip_prefix('*') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let Some(version) = rest.split_whitespace().next() else {
+            continue;
+        };
+        if marked {
+            current = version.to_string();
+        }
+        items.push(version.to_string());
+    }
+    (current, items)
+}
+
+/// `fnm list`: `* v18.17.0 default`; every line is starred, aliases follow.
+pub fn parse_fnm_list(output: &str) -> (String, Vec<String>) {
+    let mut current = String::new();
+    let mut items = Vec::new();
+    for line in output.lines() {
+        let mut words = line.trim().trim_start_matches('*').split_whitespace();
+        let Some(version) = words.next() else {
+            continue;
+        };
+        if words.any(|alias| alias.trim_end_matches(',') == "default") {
+            current = version.to_string();
+        }
+        items.push(version.to_string());
+    }
+    (current, items)
+}
+
+/// `nvm ls --no-colors`: installed versions (`->     v18.17.0 *`), then
+/// aliases, of which `default -> 18 (-> v18.17.0 *)` names the default.
+pub fn parse_nvm_ls(output: &str) -> (String, Vec<String>) {
+    let mut current = String::new();
+    let mut items = Vec::new();
+    for line in output.lines() {
+        let line = line.trim().trim_start_matches("->").trim();
+        if let Some(target) = line.strip_prefix("default ->") {
+            let resolved = target.rsplit("(->").next().unwrap_or(target);
+            if let Some(version) = resolved.split_whitespace().next() {
+                let version = version.trim_end_matches(')');
+                if version.starts_with('v') {
+                    current = version.to_string();
+                }
+            }
+            continue;
+        }
+        let Some(version) = line.split_whitespace().next() else {
+            continue;
+        };
+        if version.starts_with('v') && version[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            items.push(version.to_string());
+        }
+    }
+    (current, items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rustup_marks_the_default_toolchain() {
+        let output = "stable-x86_64-unknown-linux-gnu (active, default)\n\
+                      nightly-x86_64-unknown-linux-gnu\n\
+                      1.75-x86_64-unknown-linux-gnu\n";
+        let (current, items) = parse_rustup_toolchains(output);
+        assert_eq!(current, "stable-x86_64-unknown-linux-gnu");
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2], "1.75-x86_64-unknown-linux-gnu");
+
+        // Older rustup: plain "(default)"; an override elsewhere is "(active)"
+        let (current, _) = parse_rustup_toolchains(
+            "stable-x86_64-unknown-linux-gnu\nnightly-x86_64-unknown-linux-gnu (default)\n",
+        );
+        assert_eq!(current, "nightly-x86_64-unknown-linux-gnu");
+        assert_eq!(
+            parse_rustup_toolchains("beta-x86_64-unknown-linux-gnu (active)\n").0,
+            ""
+        );
+    }
+
+    #[test]
+    fn tfenv_star_is_the_version_in_use() {
+        let output = "  1.6.0\n* 1.5.7 (set by /home/me/.tfenv/version)\n  1.4.0\n";
+        assert_eq!(
+            parse_tfenv_list(output),
+            ("1.5.7".to_string(), strings(&["1.6.0", "1.5.7", "1.4.0"]))
+        );
+        assert_eq!(parse_tfenv_list(""), (String::new(), Vec::new()));
+    }
+
+    #[test]
+    fn terraform_star_is_the_selected_workspace() {
+        let output = "  default\n* staging\n  prod\n\n";
+        assert_eq!(
+            parse_terraform_workspaces(output),
+            (
+                "staging".to_string(),
+                strings(&["default", "staging", "prod"])
+            )
+        );
+    }
+
+    #[test]
+    fn fnm_default_alias_is_current() {
+        let output = "* v18.17.0 default\n* v20.5.1 lts-latest, work\n* system\n";
+        assert_eq!(
+            parse_fnm_list(output),
+            (
+                "v18.17.0".to_string(),
+                strings(&["v18.17.0", "v20.5.1", "system"])
+            )
+        );
+        let output = "* v20.5.1 lts, default\n";
+        assert_eq!(parse_fnm_list(output).0, "v20.5.1");
+    }
+
+    #[test]
+    fn nvm_resolves_the_default_alias() {
+        let output = "->     v18.17.0 *\n\
+                      \x20      v20.5.1 *\n\
+                      default -> 18 (-> v18.17.0 *)\n\
+                      node -> stable (-> v20.5.1 *) (default)\n\
+                      iojs -> N/A (default)\n\
+                      lts/hydrogen -> v18.17.0 *\n";
+        assert_eq!(
+            parse_nvm_ls(output),
+            ("v18.17.0".to_string(), strings(&["v18.17.0", "v20.5.1"]))
+        );
+        let output = "       v20.5.1 *\ndefault -> v20.5.1 *\n";
+        assert_eq!(parse_nvm_ls(output).0, "v20.5.1");
+        assert_eq!(parse_nvm_ls("default -> N/A\n").0, "");
+    }
+}
@@ -0,0 +1,135 @@
+//! Network data usage: kernel counter deltas, billing periods and byte counts
+//! for the network popup and the data-cap warning.
+
+use chrono::{Datelike, Months, NaiveDate};
+
+/// Bytes moved since the `previous` reading of an interface counter. A smaller
+/// reading means the counter restarted (interface re-created), so all of it
+/// is new.
+pub fn counter_delta(previous: u64, current: u64) -> u64 {
+    current.checked_sub(previous).unwrap_or(current)
+}
+
+/// First day of the billing month containing `today`, for months that start
+/// on `start_day`. Start days past the end of a month fall on its last day.
+pub fn billing_period_start(today: NaiveDate, start_day: u32) -> NaiveDate {
+    let this_month = start_in_month(today, start_day);
+    if today >= this_month {
+        return this_month;
+    }
+    let last_month = today.with_day(1).unwrap_or(today) - Months::new(1);
+    start_in_month(last_month, start_day)
+}
+
+fn start_in_month(date: NaiveDate, start_day: u32) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    let last_day = (first + Months::new(1)).pred_opt().map_or(28, |d| d.day());
+    first
+        .with_day(start_day.clamp(1, last_day))
+        .unwrap_or(first)
+}
+
+/// The highest of `thresholds` (percent of `cap`) that `used` has reached.
+pub fn cap_threshold(used: u64, cap: u64, thresholds: &[u8]) -> Option<u8> {
+    if cap == 0 {
+        return None;
+    }
+    thresholds
+        .iter()
+        .copied()
+        .filter(|&percent| used as u128 * 100 >= cap as u128 * percent as u128)
+        .max()
+}
+
+/// "12 B" / "340 KB" / "1.2 GB", in decimal units as carriers bill.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else if value < 10.0 {
+        format!("{value:.1} {}", UNITS[unit])
+    } else {
+        format!("{value:.0} {}", UNITS[unit])
+    }
+}
+
+/// Throughput for the popup, e.g. "1.2 MB/s".
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec.max(0.0).round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn deltas_survive_counter_resets() {
+        assert_eq!(counter_delta(1000, 1500), 500);
+        assert_eq!(counter_delta(1500, 1500), 0);
+        assert_eq!(counter_delta(1_000_000, 300), 300);
+    }
+
+    #[test]
+    fn billing_period_starts_this_month_or_last() {
+        assert_eq!(billing_period_start(date(2025, 3, 14), 1), date(2025, 3, 1));
+        assert_eq!(
+            billing_period_start(date(2025, 3, 14), 15),
+            date(2025, 2, 15)
+        );
+        assert_eq!(
+            billing_period_start(date(2025, 3, 15), 15),
+            date(2025, 3, 15)
+        );
+        // Across a year boundary
+        assert_eq!(
+            billing_period_start(date(2025, 1, 3), 20),
+            date(2024, 12, 20)
+        );
+    }
+
+    #[test]
+    fn late_start_days_clamp_to_short_months() {
+        assert_eq!(
+            billing_period_start(date(2025, 2, 28), 31),
+            date(2025, 2, 28)
+        );
+        assert_eq!(
+            billing_period_start(date(2025, 3, 30), 31),
+            date(2025, 2, 28)
+        );
+        assert_eq!(
+            billing_period_start(date(2024, 3, 1), 30),
+            date(2024, 2, 29)
+        );
+    }
+
+    #[test]
+    fn cap_threshold_picks_the_highest_reached() {
+        let gb = 1_000_000_000;
+        assert_eq!(cap_threshold(7 * gb, 10 * gb, &[80, 100]), None);
+        assert_eq!(cap_threshold(8 * gb, 10 * gb, &[80, 100]), Some(80));
+        assert_eq!(cap_threshold(12 * gb, 10 * gb, &[80, 100]), Some(100));
+        assert_eq!(cap_threshold(5, 0, &[80, 100]), None);
+    }
+
+    #[test]
+    fn bytes_use_decimal_units() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1_200), "1.2 KB");
+        assert_eq!(format_bytes(340_000), "340 KB");
+        assert_eq!(format_bytes(15_400_000_000), "15 GB");
+        assert_eq!(format_rate(2_500_000.0), "2.5 MB/s");
+        assert_eq!(format_rate(-1.0), "0 B/s");
+    }
+}
@@ -0,0 +1,109 @@
+//! Waybar's custom module protocol: each stdout line of the script is an
+//! update, either a JSON object or plain text.
+
+use serde::Deserialize;
+
+/// One update from a custom module script.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ModuleOutput {
+    pub text: String,
+    pub alt: String,
+    pub tooltip: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub class: Vec<String>,
+    pub percentage: Option<f64>,
+}
+
+fn one_or_many<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(d)? {
+        OneOrMany::One(class) if class.is_empty() => Vec::new(),
+        OneOrMany::One(class) => vec![class],
+        OneOrMany::Many(classes) => classes,
+    })
+}
+
+/// A JSON line (`{"text": …, "class": …}`) or, like waybar without
+/// `return-type`, a plain line taken as the text. `None` for malformed JSON.
+pub fn parse_line(line: &str) -> Option<ModuleOutput> {
+    let line = line.trim_end();
+    if line.trim_start().starts_with('{') {
+        return serde_json::from_str(line).ok();
+    }
+    Some(ModuleOutput {
+        text: line.to_string(),
+        ..ModuleOutput::default()
+    })
+}
+
+/// Expand waybar's `format` placeholders: `{}` and `{text}`, `{alt}`,
+/// `{percentage}` and `{icon}` (from `icons`, picked by percentage).
+pub fn format_output(format: &str, output: &ModuleOutput, icons: &[String]) -> String {
+    let percentage = output.percentage.unwrap_or(0.0).clamp(0.0, 100.0);
+    let icon = if icons.is_empty() {
+        ""
+    } else {
+        let index = (percentage / 100.0 * icons.len() as f64) as usize;
+        icons[index.min(icons.len() - 1)].as_str()
+    };
+    format
+        .replace("{}", &output.text)
+        .replace("{text}", &output.text)
+        .replace("{alt}", &output.alt)
+        .replace("{percentage}", &format!("{percentage:.0}"))
+        .replace("{icon}", icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines_with_string_or_array_class() {
+        let output = parse_line(r#"{"text": "21°", "tooltip": "Sunny", "class": "warm"}"#).unwrap();
+        assert_eq!(output.text, "21°");
+        assert_eq!(output.tooltip, "Sunny");
+        assert_eq!(output.class, vec!["warm"]);
+
+        let output = parse_line(r#"{"text": "x", "class": ["a", "b"], "percentage": 40}"#);
+        let output = output.unwrap();
+        assert_eq!(output.class, vec!["a", "b"]);
+        assert_eq!(output.percentage, Some(40.0));
+    }
+
+    #[test]
+    fn plain_lines_are_text_and_bad_json_is_skipped() {
+        assert_eq!(parse_line("3 updates\n").unwrap().text, "3 updates");
+        assert_eq!(parse_line("").unwrap(), ModuleOutput::default());
+        assert_eq!(parse_line(r#"{"text": "#), None);
+        assert!(parse_line(r#"{"class": ""}"#).unwrap().class.is_empty());
+    }
+
+    #[test]
+    fn format_placeholders() {
+        let output = ModuleOutput {
+            text: "72".to_string(),
+            alt: "charging".to_string(),
+            percentage: Some(72.0),
+            ..ModuleOutput::default()
+        };
+        let icons: Vec<String> = ["▁", "▄", "█"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(format_output("{}", &output, &[]), "72");
+        assert_eq!(
+            format_output("{icon} {text}% ({alt})", &output, &icons),
+            "█ 72% (charging)"
+        );
+        assert_eq!(format_output("{percentage}", &output, &icons), "72");
+        let full = ModuleOutput {
+            percentage: Some(100.0),
+            ..output
+        };
+        assert_eq!(format_output("{icon}", &full, &icons), "█");
+    }
+}
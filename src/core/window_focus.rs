@@ -0,0 +1,193 @@
+//! Picking the window that belongs to a notification, player or app: by
+//! sender process, desktop entry, class pattern or loose class hints, with
+//! title keywords to choose between several windows of one app.
+
+use regex::Regex;
+
+/// A window as the compositor lists it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowCandidate {
+    pub class: String,
+    pub title: String,
+    pub pid: i32,
+}
+
+/// What is known about the app; every part is optional.
+#[derive(Debug, Clone, Default)]
+pub struct FocusQuery {
+    /// Process that asked (e.g. a notification's sender). Its nearest
+    /// ancestor owning a window wins outright.
+    pub pid: Option<u32>,
+    /// Desktop entry id, with or without `.desktop`; matches the class
+    /// exactly or as the last reverse-DNS component.
+    pub desktop_entry: Option<String>,
+    pub class_pattern: Option<Regex>,
+    /// App names and the like, matched loosely either way round.
+    pub class_hints: Vec<String>,
+    /// Prefer a window whose title contains one of these.
+    pub title_keywords: Vec<String>,
+}
+
+/// Index of the window to focus. `parent_of` gives a process's parent, for
+/// walking from `pid` up to the process that owns a window.
+pub fn pick_window(
+    windows: &[WindowCandidate],
+    query: &FocusQuery,
+    parent_of: impl Fn(i32) -> Option<i32>,
+) -> Option<usize> {
+    if let Some(pid) = query.pid {
+        let mut pid = Some(pid as i32);
+        while let Some(current) = pid.filter(|&p| p > 1) {
+            if let Some(index) = windows.iter().position(|w| w.pid == current) {
+                return Some(index);
+            }
+            pid = parent_of(current);
+        }
+    }
+
+    // Best class match tier first: desktop entry, pattern, then hints
+    let mut candidates: Vec<(u8, usize)> = windows
+        .iter()
+        .enumerate()
+        .filter_map(|(i, w)| class_tier(&w.class, query).map(|tier| (tier, i)))
+        .collect();
+    candidates.sort();
+    let best = candidates.first()?.0;
+    candidates.retain(|(tier, _)| *tier == best);
+
+    let keywords: Vec<String> = query
+        .title_keywords
+        .iter()
+        .map(|k| k.to_lowercase())
+        .filter(|k| !k.is_empty())
+        .collect();
+    candidates
+        .iter()
+        .find(|(_, i)| {
+            let title = windows[*i].title.to_lowercase();
+            keywords.iter().any(|k| title.contains(k.as_str()))
+        })
+        .or(candidates.first())
+        .map(|(_, i)| *i)
+}
+
+fn class_tier(class: &str, query: &FocusQuery) -> Option<u8> {
+    let class_lower = class.to_lowercase();
+    if let Some(entry) = &query.desktop_entry {
+        let entry = entry.trim_end_matches(".desktop").to_lowercase();
+        let short = entry.rsplit('.').next().unwrap_or(&entry);
+        if !entry.is_empty() && (class_lower == entry || class_lower == short) {
+            return Some(0);
+        }
+    }
+    if query
+        .class_pattern
+        .as_ref()
+        .is_some_and(|re| re.is_match(class))
+    {
+        return Some(1);
+    }
+    let loose = query
+        .class_hints
+        .iter()
+        .chain(&query.desktop_entry)
+        .map(|hint| hint.to_lowercase())
+        .filter(|hint| !hint.is_empty() && !class_lower.is_empty())
+        .any(|hint| class_lower.contains(&hint) || hint.contains(&class_lower));
+    loose.then_some(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(class: &str, title: &str, pid: i32) -> WindowCandidate {
+        WindowCandidate {
+            class: class.to_string(),
+            title: title.to_string(),
+            pid,
+        }
+    }
+
+    fn windows() -> Vec<WindowCandidate> {
+        vec![
+            window("firefox", "Inbox — Mozilla Firefox", 100),
+            window("org.gnome.Nautilus", "Downloads", 200),
+            window("Slack", "general | Slack", 300),
+            window("firefox", "Pull request #42 — Mozilla Firefox", 101),
+        ]
+    }
+
+    #[test]
+    fn sender_pid_walks_up_to_the_window_owner() {
+        let query = FocusQuery {
+            pid: Some(7),
+            class_hints: vec!["slack".to_string()],
+            ..FocusQuery::default()
+        };
+        // 7 → 6 → 200 (Nautilus)
+        let parents = |pid| match pid {
+            7 => Some(6),
+            6 => Some(200),
+            _ => None,
+        };
+        assert_eq!(pick_window(&windows(), &query, parents), Some(1));
+        // Unknown ancestry falls through to the class
+        let orphan = FocusQuery {
+            pid: Some(9),
+            ..query
+        };
+        assert_eq!(pick_window(&windows(), &orphan, |_| Some(1)), Some(2));
+    }
+
+    #[test]
+    fn desktop_entry_beats_pattern_beats_hints() {
+        let entry = FocusQuery {
+            desktop_entry: Some("org.gnome.Nautilus.desktop".to_string()),
+            class_hints: vec!["firefox".to_string()],
+            ..FocusQuery::default()
+        };
+        assert_eq!(pick_window(&windows(), &entry, |_| None), Some(1));
+
+        let pattern = FocusQuery {
+            class_pattern: Some(Regex::new("(?i)^slack$").unwrap()),
+            class_hints: vec!["firefox".to_string()],
+            ..FocusQuery::default()
+        };
+        assert_eq!(pick_window(&windows(), &pattern, |_| None), Some(2));
+
+        let short_entry = FocusQuery {
+            desktop_entry: Some("org.mozilla.firefox".to_string()),
+            ..FocusQuery::default()
+        };
+        assert_eq!(pick_window(&windows(), &short_entry, |_| None), Some(0));
+    }
+
+    #[test]
+    fn title_keywords_pick_between_windows_of_one_app() {
+        let query = FocusQuery {
+            class_hints: vec!["Firefox".to_string()],
+            title_keywords: vec!["pull request".to_string()],
+            ..FocusQuery::default()
+        };
+        assert_eq!(pick_window(&windows(), &query, |_| None), Some(3));
+        let no_match = FocusQuery {
+            title_keywords: vec!["calendar".to_string()],
+            ..query
+        };
+        assert_eq!(pick_window(&windows(), &no_match, |_| None), Some(0));
+    }
+
+    #[test]
+    fn nothing_to_go_on_focuses_nothing() {
+        assert_eq!(
+            pick_window(&windows(), &FocusQuery::default(), |_| None),
+            None
+        );
+        let stranger = FocusQuery {
+            class_hints: vec!["thunderbird".to_string()],
+            ..FocusQuery::default()
+        };
+        assert_eq!(pick_window(&windows(), &stranger, |_| None), None);
+    }
+}
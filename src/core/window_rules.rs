@@ -0,0 +1,117 @@
+//! Hyprland window rules suggested from how a window is set up right now,
+//! for the active-window widget to save to a snippets file.
+
+/// The parts of a window's state a rule can pin down.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WindowState {
+    pub floating: bool,
+    pub pinned: bool,
+    pub fullscreen: bool,
+    /// Logical size, kept for floating windows.
+    pub size: (i32, i32),
+}
+
+/// `class:^(...)$` matching exactly `class`.
+pub fn class_matcher(class: &str) -> String {
+    format!("class:^({})$", regex::escape(class))
+}
+
+/// Rule bodies (`windowrulev2 = ` values) that would bring windows of
+/// `class` back to `state`: floating with the same size or tiled, then
+/// pinned and fullscreen when they are.
+pub fn suggest(class: &str, state: &WindowState) -> Vec<String> {
+    let matcher = class_matcher(class);
+    let mut rules = Vec::new();
+    if state.floating {
+        rules.push(format!("float, {matcher}"));
+        let (width, height) = state.size;
+        if width > 0 && height > 0 {
+            rules.push(format!("size {width} {height}, {matcher}"));
+        }
+    } else {
+        rules.push(format!("tile, {matcher}"));
+    }
+    if state.pinned {
+        rules.push(format!("pin, {matcher}"));
+    }
+    if state.fullscreen {
+        rules.push(format!("fullscreen, {matcher}"));
+    }
+    rules
+}
+
+/// The `windowrulev2` line for a rule body.
+pub fn line(rule: &str) -> String {
+    format!("windowrulev2 = {rule}")
+}
+
+/// The rules of `rules` that `existing` (a snippets file) doesn't have yet.
+/// Rules for the same class that contradict a new one (`float` and `tile`,
+/// an older `size`) are left for the user to clean up; Hyprland applies the
+/// last one.
+pub fn missing(existing: &str, rules: &[String]) -> Vec<String> {
+    rules
+        .iter()
+        .filter(|rule| {
+            let wanted = line(rule);
+            !existing.lines().any(|l| l.trim() == wanted)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_is_matched_exactly_and_escaped() {
+        assert_eq!(class_matcher("firefox"), "class:^(firefox)$");
+        assert_eq!(
+            class_matcher("org.gnome.Nautilus"),
+            r"class:^(org\.gnome\.Nautilus)$"
+        );
+    }
+
+    #[test]
+    fn floating_windows_keep_their_size() {
+        let state = WindowState {
+            floating: true,
+            pinned: true,
+            size: (800, 600),
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest("mpv", &state),
+            vec![
+                "float, class:^(mpv)$",
+                "size 800 600, class:^(mpv)$",
+                "pin, class:^(mpv)$",
+            ]
+        );
+    }
+
+    #[test]
+    fn tiled_windows_get_a_tile_rule() {
+        let state = WindowState {
+            fullscreen: true,
+            size: (1920, 1080),
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest("steam", &state),
+            vec!["tile, class:^(steam)$", "fullscreen, class:^(steam)$"]
+        );
+    }
+
+    #[test]
+    fn rules_already_saved_are_skipped() {
+        let existing = "# mpv\nwindowrulev2 = float, class:^(mpv)$\n";
+        let rules = vec![
+            "float, class:^(mpv)$".to_string(),
+            "pin, class:^(mpv)$".to_string(),
+        ];
+        assert_eq!(missing(existing, &rules), vec!["pin, class:^(mpv)$"]);
+        assert_eq!(missing("", &rules), rules);
+    }
+}
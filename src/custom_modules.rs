@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::config::CustomModuleConfig;
+use crate::core::waybar::parse_line;
+use crate::shell::Subscribers;
+use crate::widgets::custom::CustomInput;
+
+/// Start one reader thread per `[custom.<name>]` script, shared by every bar.
+/// Keyed by module name (without the `custom/` prefix).
+pub fn spawn_custom_modules() -> HashMap<String, Subscribers<CustomInput>> {
+    crate::config::get()
+        .custom
+        .iter()
+        .map(|(name, module)| {
+            let feed = Subscribers::<CustomInput>::new();
+            spawn_module(name.clone(), module, feed.clone());
+            (name.clone(), feed)
+        })
+        .collect()
+}
+
+fn spawn_module(name: String, module: &'static CustomModuleConfig, feed: Subscribers<CustomInput>) {
+    if module.exec.is_empty() {
+        eprintln!("jb-shell: [custom] {name} has no exec, skipping");
+        return;
+    }
+    std::thread::spawn(move || loop {
+        run_script(&name, &module.exec, &feed);
+        let Some(secs) = module.restart_interval else {
+            eprintln!("jb-shell: [custom] {name} exited; set restart-interval to re-run it");
+            break;
+        };
+        crate::idle::sleep(Duration::from_secs(secs.max(1)));
+    });
+}
+
+/// Publish each stdout line until the script exits.
+fn run_script(name: &str, exec: &str, feed: &Subscribers<CustomInput>) {
+    let mut child = match Command::new("sh")
+        .args(["-c", exec])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("jb-shell: [custom] failed to start {name}: {e}");
+            return;
+        }
+    };
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            match parse_line(&line) {
+                Some(output) => feed.publish(CustomInput::Update(output)),
+                None => eprintln!("jb-shell: [custom] {name}: ignoring malformed line {line:?}"),
+            }
+        }
+    }
+    match child.wait() {
+        Ok(status) if !status.success() => {
+            eprintln!("jb-shell: [custom] {name} exited with {status}");
+        }
+        Err(e) => eprintln!("jb-shell: [custom] failed to wait for {name}: {e}"),
+        _ => {}
+    }
+}
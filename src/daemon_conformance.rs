@@ -0,0 +1,649 @@
+//! Freedesktop notification spec conformance for `notification_daemon`, run
+//! against a private `dbus-daemon` so the desktop's own notification server is
+//! never touched. Behind the `daemon-tests` feature:
+//!
+//!     cargo test --features daemon-tests daemon_conformance
+//!
+//! Every test drives the daemon over D-Bus the way `notify-send` would, then
+//! checks the history row, the request handed to the toast UI and the signals
+//! on the bus. The daemon and bus are per process, so tests take turns.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use zbus::zvariant::Value;
+
+use crate::notification_daemon::{
+    spawn_notification_daemon, DaemonCommand, CLOSE_REASON_CLOSED, CLOSE_REASON_DISMISSED,
+    CLOSE_REASON_EXPIRED, CLOSE_REASON_UNDEFINED,
+};
+use crate::widgets::notifications::{
+    ActionCallback, NotificationInput, NotificationRequest, NotificationSource,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait before concluding a signal is *not* coming.
+const QUIET: Duration = Duration::from_millis(300);
+
+const BUS_NAME: &str = "org.freedesktop.Notifications";
+const OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+const JB_BUS_NAME: &str = "dev.jb.shell.Notifications";
+const JB_OBJECT_PATH: &str = "/dev/jb/shell/Notifications";
+
+#[derive(Debug, PartialEq)]
+enum Signal {
+    Closed(u32, u32),
+    ActionInvoked(u32, String),
+    ActivationToken(u32, String),
+}
+
+/// A `Notify` call; `new` fills in what `notify-send <summary>` sends.
+struct Notify<'a> {
+    app_name: &'a str,
+    replaces_id: u32,
+    summary: &'a str,
+    body: &'a str,
+    actions: Vec<&'a str>,
+    urgency: Option<u8>,
+    expire_timeout: i32,
+}
+
+impl<'a> Notify<'a> {
+    fn new(summary: &'a str) -> Self {
+        Notify {
+            app_name: "conformance",
+            replaces_id: 0,
+            summary,
+            body: "",
+            actions: Vec::new(),
+            urgency: None,
+            expire_timeout: -1,
+        }
+    }
+}
+
+/// The history columns the spec behavior shows up in.
+#[derive(Debug)]
+struct Row {
+    app_name: String,
+    summary: String,
+    body: String,
+    urgency: u8,
+    actions: Option<String>,
+    closed_at: Option<String>,
+    close_reason: Option<u32>,
+    read: bool,
+}
+
+struct Harness {
+    /// `sh` wrapper around the bus; it kills `dbus-daemon` once its stdin
+    /// closes, i.e. when the test process exits.
+    _bus: Child,
+    client: zbus::blocking::Connection,
+    daemon_tx: mpsc::Sender<DaemonCommand>,
+    inputs: mpsc::Receiver<NotificationInput>,
+    signals: mpsc::Receiver<Signal>,
+    db_path: PathBuf,
+}
+
+fn harness() -> MutexGuard<'static, Harness> {
+    static HARNESS: OnceLock<Mutex<Harness>> = OnceLock::new();
+    let mut harness = HARNESS
+        .get_or_init(|| Mutex::new(Harness::start()))
+        .lock()
+        // A failed test must not take the rest down with it
+        .unwrap_or_else(|e| e.into_inner());
+    harness.drain();
+    harness
+}
+
+impl Harness {
+    fn start() -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("jb-shell-daemon-tests-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("failed to create test dir");
+        // Default config and an empty history DB
+        std::env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+        std::env::set_var("XDG_DATA_HOME", dir.join("data"));
+
+        let mut bus = Command::new("sh")
+            .args([
+                "-c",
+                "dbus-daemon --session --nofork --print-address=1 & read _; kill $!",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start dbus-daemon");
+        let mut address = String::new();
+        BufReader::new(bus.stdout.take().expect("piped stdout"))
+            .read_line(&mut address)
+            .expect("dbus-daemon printed no address");
+        let address = address.trim().to_string();
+        assert!(!address.is_empty(), "dbus-daemon printed no address");
+        // The daemon connects through `crate::dbus::session`
+        std::env::set_var("DBUS_SESSION_BUS_ADDRESS", &address);
+
+        let (notif_sender, notif_rx) = relm4::channel::<NotificationInput>();
+        let daemon_tx = spawn_notification_daemon(notif_sender);
+        let (input_tx, inputs) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Some(input) = notif_rx.recv_sync() {
+                if input_tx.send(input).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let client = crate::runtime::handle()
+            .block_on(async {
+                zbus::connection::Builder::address(address.as_str())?
+                    .build()
+                    .await
+            })
+            .map(zbus::blocking::Connection::from)
+            .expect("failed to connect to the private bus");
+
+        let signals = subscribe_signals(&client);
+        let harness = Harness {
+            _bus: bus,
+            client,
+            daemon_tx,
+            inputs,
+            signals,
+            db_path: dir.join("data/jb-shell/notifications.db"),
+        };
+        harness.wait_for_name(BUS_NAME);
+        harness
+    }
+
+    fn wait_for_name(&self, name: &str) {
+        let deadline = Instant::now() + TIMEOUT;
+        while Instant::now() < deadline {
+            let owned = self
+                .client
+                .call_method(
+                    Some("org.freedesktop.DBus"),
+                    "/org/freedesktop/DBus",
+                    Some("org.freedesktop.DBus"),
+                    "NameHasOwner",
+                    &(name,),
+                )
+                .and_then(|reply| reply.body().deserialize::<bool>());
+            if owned.unwrap_or(false) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("notification daemon never claimed {name}");
+    }
+
+    /// Forget what earlier tests left behind.
+    fn drain(&mut self) {
+        while self.inputs.try_recv().is_ok() {}
+        while self.signals.try_recv().is_ok() {}
+    }
+
+    fn call<B>(&self, method: &str, body: &B) -> zbus::Message
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        self.client
+            .call_method(Some(BUS_NAME), OBJECT_PATH, Some(BUS_NAME), method, body)
+            .unwrap_or_else(|e| panic!("{method} failed: {e}"))
+    }
+
+    fn call_jb<B>(&self, method: &str, body: &B) -> zbus::Message
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        self.client
+            .call_method(
+                Some(JB_BUS_NAME),
+                JB_OBJECT_PATH,
+                Some(JB_BUS_NAME),
+                method,
+                body,
+            )
+            .unwrap_or_else(|e| panic!("{method} failed: {e}"))
+    }
+
+    fn notify(&self, n: Notify) -> u32 {
+        let mut hints: HashMap<&str, Value> = HashMap::new();
+        if let Some(urgency) = n.urgency {
+            hints.insert("urgency", Value::U8(urgency));
+        }
+        let body = (
+            n.app_name,
+            n.replaces_id,
+            "",
+            n.summary,
+            n.body,
+            n.actions,
+            hints,
+            n.expire_timeout,
+        );
+        self.call("Notify", &body)
+            .body()
+            .deserialize()
+            .expect("Notify returns a u32")
+    }
+
+    fn close(&self, id: u32) {
+        self.call("CloseNotification", &(id,));
+    }
+
+    fn send(&self, command: DaemonCommand) {
+        self.daemon_tx.send(command).expect("daemon thread is gone");
+    }
+
+    fn next_input(&self) -> NotificationInput {
+        self.inputs
+            .recv_timeout(TIMEOUT)
+            .expect("daemon sent nothing to the UI")
+    }
+
+    fn next_toast(&self) -> NotificationRequest {
+        match self.next_input() {
+            NotificationInput::Show(request) => request,
+            other => panic!("expected a toast, got {other:?}"),
+        }
+    }
+
+    fn next_signal(&self) -> Signal {
+        self.signals
+            .recv_timeout(TIMEOUT)
+            .expect("daemon emitted no signal")
+    }
+
+    fn assert_no_signal(&self) {
+        if let Ok(signal) = self.signals.recv_timeout(QUIET) {
+            panic!("unexpected signal {signal:?}");
+        }
+    }
+
+    fn row(&self, id: u32) -> Row {
+        let db = rusqlite::Connection::open(&self.db_path).expect("history DB exists");
+        db.query_row(
+            "SELECT app_name, summary, body, urgency, actions, closed_at, close_reason, read \
+             FROM notifications WHERE id = ?1",
+            rusqlite::params![id],
+            |row| {
+                Ok(Row {
+                    app_name: row.get(0)?,
+                    summary: row.get(1)?,
+                    body: row.get(2)?,
+                    urgency: row.get(3)?,
+                    actions: row.get(4)?,
+                    closed_at: row.get(5)?,
+                    close_reason: row.get(6)?,
+                    read: row.get(7)?,
+                })
+            },
+        )
+        .unwrap_or_else(|e| panic!("no history row {id}: {e}"))
+    }
+
+    fn row_count(&self, id: u32) -> u32 {
+        let db = rusqlite::Connection::open(&self.db_path).expect("history DB exists");
+        db.query_row(
+            "SELECT COUNT(*) FROM notifications WHERE id = ?1",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )
+        .expect("count query")
+    }
+}
+
+/// Collect `NotificationClosed`/`ActionInvoked`/`ActivationToken` from the bus on a thread.
+fn subscribe_signals(client: &zbus::blocking::Connection) -> mpsc::Receiver<Signal> {
+    let rule = zbus::MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .interface(BUS_NAME)
+        .expect("valid interface name")
+        .build();
+    let messages = zbus::blocking::MessageIterator::for_match_rule(rule, client, None)
+        .expect("failed to subscribe to daemon signals");
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for msg in messages.flatten() {
+            let header = msg.header();
+            let signal = match header.member().map(|m| m.as_str()) {
+                Some("NotificationClosed") => msg
+                    .body()
+                    .deserialize::<(u32, u32)>()
+                    .map(|(id, reason)| Signal::Closed(id, reason)),
+                Some("ActionInvoked") => msg
+                    .body()
+                    .deserialize::<(u32, String)>()
+                    .map(|(id, key)| Signal::ActionInvoked(id, key)),
+                Some("ActivationToken") => msg
+                    .body()
+                    .deserialize::<(u32, String)>()
+                    .map(|(id, token)| Signal::ActivationToken(id, token)),
+                _ => continue,
+            };
+            if let Ok(signal) = signal {
+                if tx.send(signal).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+#[test]
+fn reports_server_information_and_capabilities() {
+    let h = harness();
+    let (name, vendor, _version, spec): (String, String, String, String) = h
+        .call("GetServerInformation", &())
+        .body()
+        .deserialize()
+        .unwrap();
+    assert_eq!((name.as_str(), vendor.as_str()), ("jb-shell", "jb"));
+    assert_eq!(spec, "1.2");
+
+    let caps: Vec<String> = h.call("GetCapabilities", &()).body().deserialize().unwrap();
+    for cap in ["actions", "body", "body-markup"] {
+        assert!(caps.iter().any(|c| c == cap), "missing capability {cap}");
+    }
+}
+
+#[test]
+fn notify_records_history_and_shows_a_toast() {
+    let h = harness();
+    let id = h.notify(Notify {
+        body: "Body text",
+        ..Notify::new("Stored and shown")
+    });
+    assert_ne!(id, 0, "0 is reserved for \"no replacement\"");
+
+    let row = h.row(id);
+    assert_eq!(row.app_name, "conformance");
+    assert_eq!(row.summary, "Stored and shown");
+    assert_eq!(row.body, "Body text");
+    assert_eq!(row.urgency, 1, "normal urgency without a hint");
+    assert_eq!(row.closed_at, None);
+    assert!(!row.read);
+
+    let toast = h.next_toast();
+    assert_eq!(toast.id, id as u64);
+    assert_eq!(toast.title, "Stored and shown");
+    assert_eq!(toast.body.as_deref(), Some("Body text"));
+    assert_eq!(toast.timeout_ms, Some(5000));
+    match toast.source {
+        NotificationSource::Freedesktop {
+            fd_id, app_name, ..
+        } => {
+            assert_eq!(fd_id, id);
+            assert_eq!(app_name, "conformance");
+        }
+        other => panic!("expected a freedesktop source, got {other:?}"),
+    }
+}
+
+#[test]
+fn ids_are_unique_and_increasing() {
+    let h = harness();
+    let first = h.notify(Notify::new("First of two"));
+    let second = h.notify(Notify::new("Second of two"));
+    assert!(second > first, "{second} should follow {first}");
+}
+
+#[test]
+fn replaces_id_updates_the_same_notification() {
+    let h = harness();
+    let id = h.notify(Notify::new("Downloading 10%"));
+    h.next_toast();
+
+    let replaced = h.notify(Notify {
+        replaces_id: id,
+        ..Notify::new("Downloading 90%")
+    });
+    assert_eq!(replaced, id, "the replaced id is reused");
+    assert_eq!(h.row_count(id), 1);
+    assert_eq!(h.row(id).summary, "Downloading 90%");
+
+    let toast = h.next_toast();
+    assert_eq!(toast.id, id as u64);
+    assert_eq!(toast.title, "Downloading 90%");
+}
+
+#[test]
+fn replacing_a_closed_notification_reopens_it() {
+    let h = harness();
+    let id = h.notify(Notify::new("Closed then replaced"));
+    h.close(id);
+    assert_eq!(h.next_signal(), Signal::Closed(id, CLOSE_REASON_CLOSED));
+
+    h.notify(Notify {
+        replaces_id: id,
+        ..Notify::new("Replaced after close")
+    });
+    let row = h.row(id);
+    assert_eq!(row.closed_at, None);
+    assert_eq!(row.close_reason, None);
+}
+
+#[test]
+fn urgency_hint_is_stored_and_styled() {
+    let h = harness();
+    let low = h.notify(Notify {
+        urgency: Some(0),
+        ..Notify::new("Low urgency")
+    });
+    assert_eq!(h.row(low).urgency, 0);
+    let toast = h.next_toast();
+    assert_eq!(toast.css_card_class.as_deref(), Some("urgency-low"));
+    assert_eq!(toast.timeout_ms, Some(5000));
+
+    let critical = h.notify(Notify {
+        urgency: Some(2),
+        ..Notify::new("Critical urgency")
+    });
+    assert_eq!(h.row(critical).urgency, 2);
+    let toast = h.next_toast();
+    assert_eq!(toast.css_card_class.as_deref(), Some("urgency-critical"));
+    assert_eq!(toast.timeout_ms, None, "critical stays until dismissed");
+}
+
+#[test]
+fn expire_timeout_is_honored() {
+    let h = harness();
+    h.notify(Notify {
+        expire_timeout: 1234,
+        ..Notify::new("Explicit timeout")
+    });
+    assert_eq!(h.next_toast().timeout_ms, Some(1234));
+
+    h.notify(Notify {
+        expire_timeout: 0,
+        ..Notify::new("Never expires")
+    });
+    assert_eq!(h.next_toast().timeout_ms, None);
+}
+
+#[test]
+fn close_notification_closes_once() {
+    let h = harness();
+    let id = h.notify(Notify::new("Closed by the sender"));
+    h.next_toast();
+
+    h.close(id);
+    assert_eq!(h.next_signal(), Signal::Closed(id, CLOSE_REASON_CLOSED));
+    assert!(matches!(h.next_input(), NotificationInput::Remove(r) if r == id as u64));
+    let row = h.row(id);
+    assert!(row.closed_at.is_some());
+    assert_eq!(row.close_reason, Some(CLOSE_REASON_CLOSED));
+    assert!(row.read);
+
+    // Already closed, and never existed: empty reply, no signal
+    h.close(id);
+    h.close(u32::MAX);
+    h.assert_no_signal();
+}
+
+#[test]
+fn actions_are_stored_offered_and_invoked() {
+    let h = harness();
+    let id = h.notify(Notify {
+        actions: vec!["default", "", "reply", "Reply"],
+        ..Notify::new("With actions")
+    });
+    assert_eq!(
+        h.row(id).actions.as_deref(),
+        Some(r#"[["default",""],["reply","Reply"]]"#)
+    );
+
+    let toast = h.next_toast();
+    let labels: Vec<&str> = toast.actions.iter().map(|a| a.label.as_str()).collect();
+    assert_eq!(labels, ["Open", "Reply", "Dismiss"]);
+    assert!(matches!(
+        &toast.actions[1].callback,
+        ActionCallback::FdAction { fd_id, action_key } if *fd_id == id && action_key == "reply"
+    ));
+    assert_eq!(toast.timeout_ms, Some(15000), "actionable toasts linger");
+
+    // What the toast does when "Reply" is clicked
+    h.send(DaemonCommand::ActionInvoked {
+        id,
+        action_key: "reply".into(),
+        activation_token: Some("token-1".into()),
+    });
+    h.send(DaemonCommand::NotificationClosed {
+        id,
+        reason: CLOSE_REASON_DISMISSED,
+    });
+    // The token comes first so the app has it when it handles the action
+    assert_eq!(
+        h.next_signal(),
+        Signal::ActivationToken(id, "token-1".into())
+    );
+    assert_eq!(h.next_signal(), Signal::ActionInvoked(id, "reply".into()));
+    assert_eq!(h.next_signal(), Signal::Closed(id, CLOSE_REASON_DISMISSED));
+    assert!(h.row(id).read);
+}
+
+#[test]
+fn expired_toasts_stay_unread_only_with_actions() {
+    let h = harness();
+    let plain = h.notify(Notify::new("Expired without actions"));
+    let actionable = h.notify(Notify {
+        actions: vec!["open", "Open"],
+        ..Notify::new("Expired with actions")
+    });
+
+    for id in [plain, actionable] {
+        h.send(DaemonCommand::NotificationClosed {
+            id,
+            reason: CLOSE_REASON_EXPIRED,
+        });
+        assert_eq!(h.next_signal(), Signal::Closed(id, CLOSE_REASON_EXPIRED));
+        assert_eq!(h.row(id).close_reason, Some(CLOSE_REASON_EXPIRED));
+    }
+    assert!(h.row(plain).read);
+    assert!(!h.row(actionable).read, "missed actions stay in the badge");
+
+    // A second close (e.g. dismissed after expiry) is not signalled again
+    h.send(DaemonCommand::NotificationClosed {
+        id: plain,
+        reason: CLOSE_REASON_DISMISSED,
+    });
+    h.assert_no_signal();
+}
+
+#[test]
+fn muted_apps_are_recorded_but_not_shown() {
+    let h = harness();
+    h.send(DaemonCommand::MuteApp {
+        app_name: "muted-app".into(),
+        until: None,
+    });
+    // The mute is written on the daemon thread, outside any D-Bus call
+    std::thread::sleep(QUIET);
+
+    let id = h.notify(Notify {
+        app_name: "muted-app",
+        ..Notify::new("From a muted app")
+    });
+    assert!(
+        h.row(id).read,
+        "muted notifications land in history as read"
+    );
+    assert!(
+        h.inputs.recv_timeout(QUIET).is_err(),
+        "muted notifications never toast"
+    );
+    // Never displayed, so the sender hears it's gone like a silent toast
+    assert_eq!(h.next_signal(), Signal::Closed(id, CLOSE_REASON_UNDEFINED));
+    assert!(h.row(id).closed_at.is_some());
+}
+
+#[test]
+fn jb_interface_lists_history_and_marks_read() {
+    let h = harness();
+    h.wait_for_name(JB_BUS_NAME);
+    let id = h.notify(Notify {
+        body: "for tooling",
+        ..Notify::new("History entry")
+    });
+    h.next_toast();
+
+    let history: Vec<(u32, String, String, String, String, bool)> =
+        h.call_jb("History", &(5u32,)).body().deserialize().unwrap();
+    assert!(history.len() <= 5);
+    let (newest, app, summary, body, _created, read) = &history[0];
+    assert_eq!(*newest, id, "newest first");
+    assert_eq!(
+        (app.as_str(), summary.as_str(), body.as_str(), *read),
+        ("conformance", "History entry", "for tooling", false)
+    );
+
+    let changed: u32 = h
+        .call_jb("MarkRead", &(vec![id],))
+        .body()
+        .deserialize()
+        .unwrap();
+    assert_eq!(changed, 1);
+    assert!(h.row(id).read);
+    assert!(matches!(h.next_input(), NotificationInput::CenterChanged));
+
+    // Already read: nothing changes, no refresh
+    let changed: u32 = h
+        .call_jb("MarkRead", &(vec![id],))
+        .body()
+        .deserialize()
+        .unwrap();
+    assert_eq!(changed, 0);
+}
+
+#[test]
+fn center_read_commands_update_history_and_refresh() {
+    let h = harness();
+    let first = h.notify(Notify::new("Unread one"));
+    let second = h.notify(Notify::new("Unread two"));
+    h.next_toast();
+    h.next_toast();
+
+    h.send(DaemonCommand::MarkRead { id: first });
+    assert!(matches!(h.next_input(), NotificationInput::CenterChanged));
+    assert!(h.row(first).read);
+    assert!(!h.row(second).read);
+
+    h.send(DaemonCommand::MarkAllRead {
+        since: "1970-01-01 00:00:00".into(),
+    });
+    assert!(matches!(h.next_input(), NotificationInput::CenterChanged));
+    assert!(h.row(second).read);
+
+    h.send(DaemonCommand::Delete { id: first });
+    assert!(matches!(h.next_input(), NotificationInput::CenterChanged));
+    assert_eq!(h.row_count(first), 0);
+    assert_eq!(h.row_count(second), 1);
+}
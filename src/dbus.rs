@@ -0,0 +1,117 @@
+use std::sync::OnceLock;
+use tokio::sync::OnceCell;
+use zbus::object_server::Interface;
+
+/// The process-wide session bus connection, opened once on the shared runtime.
+/// Every interface and bus name the shell owns lives on this connection; widgets
+/// that need signal subscriptions build their streams from it too.
+pub async fn session() -> zbus::Result<zbus::Connection> {
+    static SESSION: OnceCell<zbus::Connection> = OnceCell::const_new();
+    SESSION
+        .get_or_try_init(zbus::Connection::session)
+        .await
+        .cloned()
+}
+
+/// Blocking view of [`session`] for threads outside the runtime.
+pub fn session_blocking() -> zbus::Result<zbus::blocking::Connection> {
+    crate::runtime::handle()
+        .block_on(session())
+        .map(zbus::blocking::Connection::from)
+}
+
+/// The system bus, opened once and shared by callers that hit it often, like
+/// the media keys stepping the backlight through logind on every key repeat.
+pub fn system_blocking() -> zbus::Result<zbus::blocking::Connection> {
+    static SYSTEM: OnceLock<zbus::blocking::Connection> = OnceLock::new();
+    if let Some(conn) = SYSTEM.get() {
+        return Ok(conn.clone());
+    }
+    let conn = zbus::blocking::Connection::system()?;
+    Ok(SYSTEM.get_or_init(|| conn).clone())
+}
+
+/// Root of the shell's object tree; every `dev.jb.shell.*` object lives
+/// below it.
+const ROOT_PATH: &str = "/dev/jb/shell";
+const ROOT_NAME: &str = "dev.jb.shell";
+
+/// Bumped when a method, signal or property of a `dev.jb.shell.*` interface
+/// changes incompatibly; additions don't bump it.
+const API_VERSION: u32 = 1;
+
+/// `dev.jb.shell.Shell` at the root: what this shell offers, so tools can
+/// check before calling. The objects themselves, with their properties,
+/// come from the root's `ObjectManager.GetManagedObjects`.
+#[derive(Default)]
+struct ShellDbus {
+    interfaces: Vec<String>,
+}
+
+#[zbus::interface(name = "dev.jb.shell.Shell")]
+impl ShellDbus {
+    #[zbus(property)]
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+
+    #[zbus(property)]
+    fn api_version(&self) -> u32 {
+        API_VERSION
+    }
+
+    /// Interfaces exported so far, announced as each one comes up.
+    #[zbus(property)]
+    fn interfaces(&self) -> Vec<String> {
+        self.interfaces.clone()
+    }
+}
+
+/// Export the root object and claim `dev.jb.shell`, once.
+async fn root(conn: &zbus::Connection) -> zbus::Result<()> {
+    static ROOT: OnceCell<()> = OnceCell::const_new();
+    ROOT.get_or_try_init(|| async {
+        let server = conn.object_server();
+        server.at(ROOT_PATH, zbus::fdo::ObjectManager).await?;
+        server.at(ROOT_PATH, ShellDbus::default()).await?;
+        conn.request_name(ROOT_NAME).await?;
+        eprintln!("jb-shell: [dbus] serving {ROOT_NAME} at {ROOT_PATH}");
+        Ok::<(), zbus::Error>(())
+    })
+    .await
+    .map(|_| ())
+}
+
+/// Export `iface` at `path` and claim `name`, for callers that need to reach
+/// the interface afterwards (e.g. to emit property changes). The root's
+/// `Interfaces` property picks it up.
+pub async fn register<I: Interface>(
+    path: &'static str,
+    name: &'static str,
+    iface: I,
+) -> zbus::Result<()> {
+    let conn = session().await?;
+    root(&conn).await?;
+    conn.object_server().at(path, iface).await?;
+    conn.request_name(name).await?;
+
+    let shell = conn
+        .object_server()
+        .interface::<_, ShellDbus>(ROOT_PATH)
+        .await?;
+    let mut state = shell.get_mut().await;
+    state.interfaces.push(I::name().to_string());
+    state.interfaces.sort();
+    state.interfaces_changed(shell.signal_emitter()).await
+}
+
+/// Export `iface` at `path` and claim the well-known bus `name` on the shared
+/// connection, in the background.
+pub fn serve<I: Interface>(path: &'static str, name: &'static str, iface: I) {
+    crate::runtime::handle().spawn(async move {
+        match register(path, name, iface).await {
+            Ok(()) => eprintln!("jb-shell: [dbus] serving {name} at {path}"),
+            Err(e) => eprintln!("jb-shell: [dbus] failed to serve {name}: {e}"),
+        }
+    });
+}
@@ -0,0 +1,131 @@
+use hyprland::data::Monitors;
+use hyprland::keyword::Keyword;
+use hyprland::shared::{HyprData, HyprDataVec};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::config::DisplayProfile;
+use crate::core::display::{disabled_outputs, matches_outputs};
+
+/// Hotplug events arrive in bursts (one per output, plus our own rule
+/// changes); wait this long for the set to settle.
+const SETTLE: Duration = Duration::from_millis(500);
+
+#[derive(Debug)]
+pub enum DisplayCommand {
+    /// Apply the `[display]` profile with this name.
+    Apply(String),
+    /// Hyprland added or removed a monitor.
+    Hotplug,
+}
+
+/// Snapshot published to every bar after each change.
+#[derive(Debug, Clone)]
+pub struct DisplayState {
+    pub profiles: Vec<String>,
+    /// The profile applied last, if any since startup.
+    pub active: Option<String>,
+    /// Connector names of every connected output, enabled or not.
+    pub outputs: Vec<String>,
+}
+
+/// Applies `[display]` profiles through the `monitor` keyword. With `auto`,
+/// the first profile whose `connected` list matches is applied at startup and
+/// whenever the set of connected outputs changes.
+pub fn spawn_display_thread(
+    publish: impl Fn(DisplayState) + Send + 'static,
+) -> mpsc::Sender<DisplayCommand> {
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let config = &crate::config::get().display;
+        let names: Vec<String> = config.profiles.iter().map(|p| p.name.clone()).collect();
+        let mut outputs = connected_outputs();
+        let mut active = None;
+        if config.auto {
+            active = auto_apply(&config.profiles, &outputs);
+        }
+
+        loop {
+            publish(DisplayState {
+                profiles: names.clone(),
+                active: active.clone(),
+                outputs: outputs.clone(),
+            });
+
+            let command = rx.recv();
+            crate::metrics::wakeup();
+            match command {
+                Ok(DisplayCommand::Apply(name)) => {
+                    match config.profiles.iter().find(|p| p.name == name) {
+                        Some(profile) => {
+                            apply(profile, &outputs);
+                            active = Some(name);
+                        }
+                        None => eprintln!("jb-shell: [display] no profile named {name:?}"),
+                    }
+                }
+                Ok(DisplayCommand::Hotplug) => {
+                    // Events queued behind this one find the set unchanged
+                    std::thread::sleep(SETTLE);
+                    let now = connected_outputs();
+                    if now == outputs {
+                        // Only enabled/disabled changed, e.g. by our own rules
+                        continue;
+                    }
+                    eprintln!("jb-shell: [display] outputs now {}", now.join(", "));
+                    outputs = now;
+                    if config.auto {
+                        active = auto_apply(&config.profiles, &outputs).or(active);
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    tx
+}
+
+/// Apply the first profile matching `outputs`; its name if there was one.
+fn auto_apply(profiles: &[DisplayProfile], outputs: &[String]) -> Option<String> {
+    let profile = profiles
+        .iter()
+        .find(|p| matches_outputs(&p.connected, outputs))?;
+    apply(profile, outputs);
+    Some(profile.name.clone())
+}
+
+fn apply(profile: &DisplayProfile, outputs: &[String]) {
+    eprintln!("jb-shell: [display] applying profile {:?}", profile.name);
+    // Turn outputs on before switching others off so one is always lit
+    for rule in &profile.monitors {
+        keyword_monitor(rule);
+    }
+    for output in disabled_outputs(&profile.monitors, outputs) {
+        keyword_monitor(&format!("{output},disable"));
+    }
+}
+
+/// Set one monitor rule. Unlike `hyprctl`, which exits 0 either way, the
+/// crate reports a rule Hyprland rejects.
+fn keyword_monitor(rule: &str) {
+    if let Err(e) = Keyword::set("monitor", rule) {
+        eprintln!("jb-shell: [display] keyword monitor {rule} failed: {e}");
+    }
+}
+
+/// Sorted connector names, including outputs a profile has disabled.
+fn connected_outputs() -> Vec<String> {
+    // The crate asks for `monitors all`, so disabled outputs are listed too
+    let monitors = match Monitors::get() {
+        Ok(monitors) => monitors.to_vec(),
+        Err(e) => {
+            eprintln!("jb-shell: [display] failed to list monitors: {e}");
+            Vec::new()
+        }
+    };
+    let mut names: Vec<String> = monitors.into_iter().map(|m| m.name).collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,165 @@
+use chrono::Local;
+use hyprland::data::Clients;
+use hyprland::dispatch::{
+    Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial,
+};
+use hyprland::shared::{Address, HyprData, HyprDataVec};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::google_calendar::CalendarEvent;
+use crate::widgets::notifications::NotificationInput;
+
+/// While on, windows opened since the last sweep are parked this often.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Published to every bar's focus toggle.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FocusState {
+    pub active: bool,
+    /// Classes of the windows currently parked, for the tooltip.
+    pub parked: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum FocusCommand {
+    Toggle,
+    /// The calendar's events, to end focus mode when a meeting starts.
+    Events(Vec<CalendarEvent>),
+}
+
+/// A window moved to the parking workspace, and where it came from.
+struct Parked {
+    address: Address,
+    class: String,
+    workspace_id: i32,
+    workspace_name: String,
+}
+
+/// Focus mode: parks `[focus] apps` windows on a special workspace and turns
+/// on do-not-disturb; turning it off moves them back and lifts DND. Ends by
+/// itself when an accepted meeting starts if `[focus] end_for_meetings`.
+pub fn spawn_focus_thread(
+    notif_sender: relm4::Sender<NotificationInput>,
+    publish: impl Fn(FocusState) + Send + 'static,
+) -> mpsc::Sender<FocusCommand> {
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let config = &crate::config::get().focus;
+        let mut active = false;
+        let mut parked: Vec<Parked> = Vec::new();
+        let mut events: Vec<CalendarEvent> = Vec::new();
+        let mut in_meeting = false;
+        let mut last = FocusState::default();
+        publish(last.clone());
+
+        loop {
+            let timeout = if active {
+                SWEEP_INTERVAL
+            } else {
+                Duration::from_secs(3600)
+            };
+            let command = rx.recv_timeout(timeout);
+            crate::metrics::wakeup();
+            let toggle = match command {
+                Ok(FocusCommand::Toggle) => true,
+                Ok(FocusCommand::Events(new_events)) => {
+                    events = new_events;
+                    false
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => false,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+
+            // Only a meeting starting ends it, not one already running when
+            // focus mode was turned on
+            let was_in_meeting = in_meeting;
+            in_meeting = crate::widgets::calendar::meeting_end(&events, Local::now()).is_some();
+            let meeting_started = in_meeting && !was_in_meeting && config.end_for_meetings;
+
+            let end = active && (toggle || meeting_started);
+            if end {
+                if meeting_started {
+                    eprintln!("jb-shell: [focus] meeting started, ending focus mode");
+                }
+                restore(&mut parked);
+                active = false;
+                notif_sender.emit(NotificationInput::SetFocus(false));
+            } else if toggle {
+                active = true;
+                notif_sender.emit(NotificationInput::SetFocus(true));
+            }
+            if !active && !end {
+                continue;
+            }
+
+            if active {
+                park(&mut parked, &config.apps, &config.workspace);
+            }
+            // The sweep runs every few seconds; only report what it changed
+            let state = FocusState {
+                active,
+                parked: parked.iter().map(|p| p.class.clone()).collect(),
+            };
+            if state == last {
+                continue;
+            }
+            eprintln!(
+                "jb-shell: [focus] {} ({} parked)",
+                if active { "on" } else { "off" },
+                parked.len()
+            );
+            last = state.clone();
+            publish(state);
+        }
+    });
+
+    tx
+}
+
+/// Move every window of `apps` not already parked to `special:<workspace>`.
+fn park(parked: &mut Vec<Parked>, apps: &[String], workspace: &str) {
+    let Ok(clients) = Clients::get().map(|c| c.to_vec()) else {
+        return;
+    };
+    for client in clients {
+        let distracting = apps
+            .iter()
+            .any(|app| app.eq_ignore_ascii_case(&client.class));
+        if !distracting || parked.iter().any(|p| p.address == client.address) {
+            continue;
+        }
+        // Already on the parking workspace, e.g. left there by hand
+        if client.workspace.name == format!("special:{workspace}") {
+            continue;
+        }
+        if let Err(e) = Dispatch::call(DispatchType::MoveToWorkspaceSilent(
+            WorkspaceIdentifierWithSpecial::Special(Some(workspace)),
+            Some(WindowIdentifier::Address(client.address.clone())),
+        )) {
+            eprintln!("jb-shell: [focus] failed to park {}: {e}", client.class);
+            continue;
+        }
+        parked.push(Parked {
+            address: client.address,
+            class: client.class,
+            workspace_id: client.workspace.id,
+            workspace_name: client.workspace.name,
+        });
+    }
+}
+
+/// Move parked windows back where they were. Closed ones are skipped.
+fn restore(parked: &mut Vec<Parked>) {
+    for window in parked.drain(..) {
+        let target = match window.workspace_name.strip_prefix("special:") {
+            Some(name) => WorkspaceIdentifierWithSpecial::Special(Some(name)),
+            None => WorkspaceIdentifierWithSpecial::Id(window.workspace_id),
+        };
+        let _ = Dispatch::call(DispatchType::MoveToWorkspaceSilent(
+            target,
+            Some(WindowIdentifier::Address(window.address)),
+        ));
+    }
+}
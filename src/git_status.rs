@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::config::expand_home;
+use crate::core::git::{parse_log, parse_status, Commit, RepoStatus, LOG_FORMAT};
+
+/// Commits listed per repository in the popup.
+const RECENT_COMMITS: usize = 5;
+
+/// One `[git] repos` entry as last polled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitRepoState {
+    /// Directory name, shown in the bar and popup.
+    pub name: String,
+    pub path: PathBuf,
+    /// None when `git status` failed, e.g. the path isn't a repository.
+    pub status: Option<RepoStatus>,
+    pub commits: Vec<Commit>,
+}
+
+/// Polls every `[git] repos` entry and publishes all of them whenever any
+/// changed. Nothing runs unless the bar layout places "git".
+pub fn spawn_git_thread(publish: impl Fn(Vec<GitRepoState>) + Send + 'static) {
+    let config = &crate::config::get().git;
+    if config.repos.is_empty() || !crate::config::get().bar.places("git") {
+        return;
+    }
+    let paths: Vec<PathBuf> = config.repos.iter().map(|r| expand_home(r)).collect();
+    let interval = Duration::from_secs(config.poll_interval_secs.max(1));
+
+    std::thread::spawn(move || {
+        let mut last: Option<Vec<GitRepoState>> = None;
+        loop {
+            let states: Vec<GitRepoState> = paths.iter().map(|p| poll_repo(p)).collect();
+            if last.as_ref() != Some(&states) {
+                publish(states.clone());
+                last = Some(states);
+            }
+            crate::idle::sleep(interval);
+        }
+    });
+}
+
+fn poll_repo(path: &Path) -> GitRepoState {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let status =
+        git(path, &["status", "--porcelain=v2", "--branch"]).map(|output| parse_status(&output));
+    let commits = git(path, &["log", &format!("-{RECENT_COMMITS}"), LOG_FORMAT])
+        .map(|output| parse_log(&output))
+        .unwrap_or_default();
+    GitRepoState {
+        name,
+        path: path.to_path_buf(),
+        status,
+        commits,
+    }
+}
+
+/// Stdout of `git -C path args…`, or None if it failed.
+fn git(path: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        // Don't take index.lock just to refresh stat info
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
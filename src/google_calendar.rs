@@ -1,8 +1,10 @@
 use chrono::{DateTime, Local, TimeZone, Utc};
 use google_calendar3::{hyper_rustls, hyper_util, yup_oauth2 as oauth2, CalendarHub};
+use oauth2::storage::{TokenInfo, TokenStorage};
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
+use std::sync::Mutex;
 use tokio::sync::mpsc;
 
 #[derive(Clone, Debug)]
@@ -13,10 +15,26 @@ pub struct CalendarEvent {
     pub end: DateTime<Local>,
     pub meeting_link: Option<String>,
     pub is_all_day: bool,
+    /// I accepted (or own) the event, as opposed to tentative / no reply.
+    pub accepted: bool,
+    /// Plain text; Google's HTML markup is stripped.
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub attendees: Vec<Attendee>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Attendee {
+    /// Display name, falling back to the email address.
+    pub name: String,
+    /// `accepted`, `declined`, `tentative` or `needsAction`.
+    pub response: String,
 }
 
 pub enum CalendarThreadMsg {
     TriggerAuth,
+    /// The system timezone changed: re-show the last events in it, then fetch.
+    TimezoneChanged,
 }
 
 #[derive(Debug)]
@@ -48,6 +66,61 @@ impl oauth2::authenticator_delegate::InstalledFlowDelegate for BrowserFlowDelega
     }
 }
 
+/// OAuth tokens in the Secret Service under `google-tokens`, used instead of
+/// `google-tokens.json` when a Secret Service is running. Only one scope set
+/// is ever requested, so the scopes aren't part of the key.
+struct KeyringTokenStorage {
+    /// What was last read or written, so token refreshes don't go through
+    /// D-Bus every poll.
+    cached: Mutex<Option<TokenInfo>>,
+    /// A file left by a sign-in before the keyring was used; moved over on
+    /// first read.
+    legacy_path: PathBuf,
+}
+
+#[async_trait::async_trait]
+impl TokenStorage for KeyringTokenStorage {
+    async fn set(&self, _scopes: &[&str], token: TokenInfo) -> anyhow::Result<()> {
+        let json = serde_json::to_string(&token)?;
+        tokio::task::spawn_blocking(move || {
+            crate::secrets::set(crate::secrets::GOOGLE_TOKENS, &json, true)
+        })
+        .await??;
+        if let Ok(mut cached) = self.cached.lock() {
+            *cached = Some(token);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, scopes: &[&str]) -> Option<TokenInfo> {
+        let cached = self.cached.lock().ok()?.clone();
+        if cached.is_some() {
+            return cached;
+        }
+        let token = match crate::secrets::get_async(crate::secrets::GOOGLE_TOKENS).await {
+            Some(json) => serde_json::from_str(&json).ok()?,
+            None => {
+                let token = read_token_file(&self.legacy_path)?;
+                if self.set(scopes, token.clone()).await.is_ok() {
+                    eprintln!("jb-shell: moved Google tokens into the Secret Service");
+                    let _ = std::fs::remove_file(&self.legacy_path);
+                }
+                token
+            }
+        };
+        *self.cached.lock().ok()? = Some(token.clone());
+        Some(token)
+    }
+}
+
+/// The first token in a yup-oauth2 token file, a list of
+/// `{"scopes": [...], "token": {...}}` entries.
+fn read_token_file(path: &Path) -> Option<TokenInfo> {
+    let data = std::fs::read_to_string(path).ok()?;
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&data).ok()?;
+    serde_json::from_value(entries.into_iter().next()?.get("token")?.clone()).ok()
+}
+
 pub fn credentials_path() -> PathBuf {
     config_dir().join("google-credentials.json")
 }
@@ -71,14 +144,12 @@ fn data_dir() -> PathBuf {
 }
 
 pub fn spawn_calendar_thread(
+    rt: &tokio::runtime::Handle,
     send: impl Fn(CalendarResult) + Send + 'static,
 ) -> mpsc::Sender<CalendarThreadMsg> {
     let (tx, rx) = mpsc::channel(8);
 
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
-        rt.block_on(calendar_thread_main(send, rx));
-    });
+    rt.spawn(calendar_thread_main(send, rx));
 
     tx
 }
@@ -113,14 +184,30 @@ async fn calendar_thread_main(
         let _ = std::fs::create_dir_all(parent);
     }
 
-    let auth = match oauth2::InstalledFlowAuthenticator::builder(
+    // Tokens go to the keyring when there is one, else to the file as before
+    let keyring = tokio::task::spawn_blocking(crate::secrets::available)
+        .await
+        .unwrap_or(false);
+    let builder = oauth2::InstalledFlowAuthenticator::builder(
         secret,
         oauth2::InstalledFlowReturnMethod::HTTPRedirect,
-    )
-    .persist_tokens_to_disk(&token_path)
-    .flow_delegate(Box::new(BrowserFlowDelegate))
-    .build()
-    .await
+    );
+    let builder = if keyring {
+        builder.with_storage(Box::new(KeyringTokenStorage {
+            cached: Mutex::new(None),
+            legacy_path: token_path.clone(),
+        }))
+    } else {
+        eprintln!(
+            "jb-shell: no Secret Service, keeping Google tokens in {}",
+            token_path.display()
+        );
+        builder.persist_tokens_to_disk(&token_path)
+    };
+    let auth = match builder
+        .flow_delegate(Box::new(BrowserFlowDelegate))
+        .build()
+        .await
     {
         Ok(a) => a,
         Err(e) => {
@@ -145,7 +232,11 @@ async fn calendar_thread_main(
     let has_tokens = token_path.exists()
         && std::fs::metadata(&token_path)
             .map(|m| m.len() > 2)
-            .unwrap_or(false);
+            .unwrap_or(false)
+        || keyring
+            && crate::secrets::get_async(crate::secrets::GOOGLE_TOKENS)
+                .await
+                .is_some();
 
     let mut authenticated = has_tokens;
     if !has_tokens {
@@ -153,6 +244,7 @@ async fn calendar_thread_main(
     }
 
     let mut poll_interval = tokio::time::interval(std::time::Duration::from_secs(60));
+    let mut last_events: Vec<CalendarEvent> = Vec::new();
 
     loop {
         tokio::select! {
@@ -163,6 +255,13 @@ async fn calendar_thread_main(
                         send(CalendarResult::AuthComplete);
                         poll_interval.reset();
                     }
+                    CalendarThreadMsg::TimezoneChanged => {
+                        // Offline after a flight, the refetch below fails;
+                        // the events already known still move to local time
+                        last_events.iter_mut().for_each(relocalize);
+                        send(CalendarResult::EventsUpdated(last_events.clone()));
+                        poll_interval.reset();
+                    }
                 }
             }
             _ = poll_interval.tick() => {}
@@ -171,6 +270,7 @@ async fn calendar_thread_main(
         if authenticated {
             match fetch_events(&hub).await {
                 Ok(events) => {
+                    last_events = events.clone();
                     send(CalendarResult::EventsUpdated(events));
                 }
                 Err(e) => {
@@ -186,6 +286,25 @@ async fn calendar_thread_main(
     }
 }
 
+/// `DateTime<Local>` keeps the offset it was converted with, so after a
+/// timezone change timed events are converted again. All-day events stay on
+/// their dates, at midnight in the new zone.
+fn relocalize(event: &mut CalendarEvent) {
+    let convert = |t: DateTime<Local>| {
+        if event.is_all_day {
+            Local
+                .from_local_datetime(&t.naive_local())
+                .earliest()
+                .unwrap_or(t)
+        } else {
+            t.with_timezone(&Local)
+        }
+    };
+    let (start, end) = (convert(event.start), convert(event.end));
+    event.start = start;
+    event.end = end;
+}
+
 type HubConnector =
     hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>;
 
@@ -227,6 +346,40 @@ async fn fetch_events(
             }
         }
 
+        let accepted = event
+            .attendees
+            .as_ref()
+            .and_then(|attendees| attendees.iter().find(|a| a.self_.unwrap_or(false)))
+            .map(|me| me.response_status.as_deref() == Some("accepted"))
+            .unwrap_or(true);
+
+        let attendees = event
+            .attendees
+            .as_ref()
+            .map(|list| {
+                list.iter()
+                    .filter(|a| !a.resource.unwrap_or(false))
+                    .map(|a| Attendee {
+                        name: a
+                            .display_name
+                            .clone()
+                            .or_else(|| a.email.clone())
+                            .unwrap_or_default(),
+                        response: a
+                            .response_status
+                            .clone()
+                            .unwrap_or_else(|| "needsAction".into()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let description = event
+            .description
+            .as_deref()
+            .map(strip_html)
+            .filter(|d| !d.is_empty());
+        let location = event.location.clone().filter(|l| !l.is_empty());
+
         let id = event.id.unwrap_or_default();
         let title = event.summary.unwrap_or_else(|| "(no title)".to_string());
 
@@ -266,8 +419,40 @@ async fn fetch_events(
             end,
             meeting_link,
             is_all_day,
+            accepted,
+            description,
+            location,
+            attendees,
         });
     }
 
     Ok(events)
 }
+
+/// Event descriptions are HTML; keep line breaks, drop tags, decode the
+/// common entities.
+fn strip_html(html: &str) -> String {
+    let html = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p>", "\n");
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,9 @@
+/// Body of a successful response; HTTP errors become their status line.
+pub async fn fetch(request: reqwest::RequestBuilder) -> Result<String, String> {
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    response.text().await.map_err(|e| e.to_string())
+}
@@ -1,8 +1,12 @@
-use hyprland::data::{Workspace, Workspaces};
+use hyprland::data::{Clients, Monitor, Workspace, Workspaces};
 use hyprland::event_listener::EventListener;
-use hyprland::shared::{HyprData, HyprDataActive, HyprDataVec};
+use hyprland::shared::{Address, HyprData, HyprDataActive, HyprDataVec};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Sender;
 
+/// Portal screencasts in progress; blur stays on until the last one stops.
+static SCREENCASTS: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Debug, Clone)]
 pub enum HyprlandMsg {
     WorkspaceChanged {
@@ -12,6 +16,7 @@ pub enum HyprlandMsg {
     WorkspaceCreated {
         workspace_id: i32,
         monitor_name: String,
+        name: String,
     },
     WorkspaceDestroyed {
         workspace_id: i32,
@@ -19,23 +24,44 @@ pub enum HyprlandMsg {
     WorkspaceMoved {
         workspace_id: i32,
         monitor_name: String,
+        name: String,
+    },
+    WorkspaceRenamed {
+        workspace_id: i32,
+        name: String,
     },
+    /// Focus moved to a window (empty title/class: none) on `monitor_name`.
     ActiveWindowChanged {
         title: String,
+        class: String,
+        monitor_name: String,
     },
     MonitorFocusChanged {
         monitor_name: String,
         workspace_id: i32,
     },
+    /// A monitor was connected or disconnected (or enabled/disabled).
+    MonitorsChanged,
+    /// A portal screencast (screen or window share) started or stopped;
+    /// `active` while any is still running.
+    ScreencastChanged {
+        active: bool,
+    },
 }
 
-fn workspace_monitor(ws_id: i32) -> Option<String> {
+fn find_workspace(ws_id: i32) -> Option<Workspace> {
     let workspaces = Workspaces::get().ok()?;
-    workspaces
-        .to_vec()
-        .into_iter()
-        .find(|ws| ws.id == ws_id)
-        .map(|ws| ws.monitor.clone())
+    workspaces.to_vec().into_iter().find(|ws| ws.id == ws_id)
+}
+
+fn workspace_monitor(ws_id: i32) -> Option<String> {
+    find_workspace(ws_id).map(|ws| ws.monitor)
+}
+
+fn window_monitor(address: &Address) -> Option<String> {
+    let clients = Clients::get().ok()?;
+    let client = clients.iter().find(|c| &c.address == address)?;
+    workspace_monitor(client.workspace.id)
 }
 
 pub fn spawn_listener(tx: Sender<HyprlandMsg>) {
@@ -62,10 +88,11 @@ pub fn spawn_listener(tx: Sender<HyprlandMsg>) {
                 let tx = tx.clone();
                 listener.add_workspace_added_handler(move |data| {
                     let ws_id = data.id;
-                    if let Some(monitor_name) = workspace_monitor(ws_id) {
+                    if let Some(ws) = find_workspace(ws_id) {
                         let _ = tx.send(HyprlandMsg::WorkspaceCreated {
                             workspace_id: ws_id,
-                            monitor_name,
+                            monitor_name: ws.monitor,
+                            name: ws.name,
                         });
                     }
                 });
@@ -85,9 +112,24 @@ pub fn spawn_listener(tx: Sender<HyprlandMsg>) {
             {
                 let tx = tx.clone();
                 listener.add_workspace_moved_handler(move |data| {
+                    let name = find_workspace(data.id)
+                        .map(|ws| ws.name)
+                        .unwrap_or_default();
                     let _ = tx.send(HyprlandMsg::WorkspaceMoved {
                         workspace_id: data.id,
                         monitor_name: data.monitor.clone(),
+                        name,
+                    });
+                });
+            }
+
+            // Workspace renamed
+            {
+                let tx = tx.clone();
+                listener.add_workspace_renamed_handler(move |data| {
+                    let _ = tx.send(HyprlandMsg::WorkspaceRenamed {
+                        workspace_id: data.id,
+                        name: data.name.clone(),
                     });
                 });
             }
@@ -97,7 +139,19 @@ pub fn spawn_listener(tx: Sender<HyprlandMsg>) {
                 let tx = tx.clone();
                 listener.add_active_window_changed_handler(move |data| {
                     let title = data.as_ref().map(|d| d.title.clone()).unwrap_or_default();
-                    let _ = tx.send(HyprlandMsg::ActiveWindowChanged { title });
+                    let class = data.as_ref().map(|d| d.class.clone()).unwrap_or_default();
+                    // The window's own workspace says where it is; querying
+                    // the active monitor could race a later focus change
+                    let monitor_name = match &data {
+                        Some(d) => window_monitor(&d.address),
+                        None => Monitor::get_active().ok().map(|m| m.name),
+                    }
+                    .unwrap_or_default();
+                    let _ = tx.send(HyprlandMsg::ActiveWindowChanged {
+                        title,
+                        class,
+                        monitor_name,
+                    });
                 });
             }
 
@@ -114,6 +168,41 @@ pub fn spawn_listener(tx: Sender<HyprlandMsg>) {
                 });
             }
 
+            // Monitor hotplug
+            {
+                let tx = tx.clone();
+                listener.add_monitor_added_handler(move |_| {
+                    let _ = tx.send(HyprlandMsg::MonitorsChanged);
+                });
+            }
+            {
+                let tx = tx.clone();
+                listener.add_monitor_removed_handler(move |_| {
+                    let _ = tx.send(HyprlandMsg::MonitorsChanged);
+                });
+            }
+
+            // A window asked for focus, maybe one just launched from a toast
+            listener.add_urgent_state_changed_handler(crate::activation::urgent);
+
+            // Screencast started/stopped (xdg-desktop-portal-hyprland). Each
+            // cast reports its own start and stop, so several can overlap.
+            {
+                let tx = tx.clone();
+                listener.add_screencast_handler(move |data| {
+                    let casts = if data.turning_on {
+                        SCREENCASTS.fetch_add(1, Ordering::Relaxed) + 1
+                    } else {
+                        SCREENCASTS
+                            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                                Some(n.saturating_sub(1))
+                            })
+                            .map_or(0, |n| n.saturating_sub(1))
+                    };
+                    let _ = tx.send(HyprlandMsg::ScreencastChanged { active: casts > 0 });
+                });
+            }
+
             if let Err(e) = listener.start_listener() {
                 eprintln!("jb-shell: hyprland listener error: {e}, restarting in 2s");
                 std::thread::sleep(std::time::Duration::from_secs(2));
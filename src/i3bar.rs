@@ -0,0 +1,91 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+
+use crate::core::i3bar::{click_event, parse_header, parse_status_line, Block};
+
+/// A click on one of the generator's blocks, echoed back to it.
+#[derive(Debug)]
+pub struct I3barClick {
+    pub name: Option<String>,
+    pub instance: Option<String>,
+    /// X11 numbering: 1–3 left/middle/right, 4/5 scroll up/down.
+    pub button: u32,
+}
+
+/// Runs `[i3bar] command` and publishes each status update's blocks; an empty
+/// list once it exits. Clicks are forwarded to its stdin if the header asked
+/// for them.
+pub fn spawn_i3bar_thread(
+    publish: impl Fn(Vec<Block>) + Send + 'static,
+) -> mpsc::Sender<I3barClick> {
+    let (tx, rx) = mpsc::channel();
+    let Some(command) = crate::config::get().i3bar.command.as_deref() else {
+        return tx;
+    };
+
+    std::thread::spawn(move || {
+        let mut child = match Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("jb-shell: [i3bar] failed to start {command:?}: {e}");
+                return;
+            }
+        };
+        let stdin = child.stdin.take();
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        let mut lines = BufReader::new(stdout).lines().map_while(Result::ok);
+
+        match lines.next().as_deref().and_then(parse_header) {
+            Some(header) if header.click_events => {
+                if let Some(stdin) = stdin {
+                    std::thread::spawn(move || forward_clicks(stdin, rx));
+                }
+            }
+            Some(_) => {}
+            None => eprintln!("jb-shell: [i3bar] {command:?} didn't send an i3bar header"),
+        }
+
+        for line in lines {
+            match parse_status_line(&line) {
+                Ok(Some(blocks)) => publish(blocks),
+                Ok(None) => {}
+                Err(e) => eprintln!("jb-shell: [i3bar] ignoring malformed status line: {e}"),
+            }
+        }
+        publish(Vec::new());
+        match child.wait() {
+            Ok(status) => eprintln!("jb-shell: [i3bar] {command:?} exited with {status}"),
+            Err(e) => eprintln!("jb-shell: [i3bar] failed to wait for {command:?}: {e}"),
+        }
+    });
+    tx
+}
+
+/// Write clicks as the protocol's endless JSON array until the generator
+/// closes its stdin.
+fn forward_clicks(mut stdin: std::process::ChildStdin, rx: mpsc::Receiver<I3barClick>) {
+    if writeln!(stdin, "[").is_err() {
+        return;
+    }
+    while let Ok(click) = rx.recv() {
+        let event = click_event(
+            click.name.as_deref(),
+            click.instance.as_deref(),
+            click.button,
+        );
+        if writeln!(stdin, "{event},")
+            .and_then(|_| stdin.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::future::Future;
+use std::panic::Location;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+
+// Background pollers sleep via `sleep`/`sleep_async` instead of directly, so
+// their intervals stretch while the user is idle and snap back on activity.
+static IDLE: Mutex<bool> = Mutex::new(false);
+static IDLE_CHANGED: Condvar = Condvar::new();
+
+pub fn is_idle() -> bool {
+    IDLE.lock().map(|idle| *idle).unwrap_or(false)
+}
+
+fn set_idle(idle: bool) {
+    if let Ok(mut state) = IDLE.lock() {
+        if *state != idle {
+            eprintln!("jb-shell: [idle] {}", if idle { "idle" } else { "active" });
+            *state = idle;
+            IDLE_CHANGED.notify_all();
+        }
+    }
+}
+
+fn idle_interval(active: Duration) -> Duration {
+    active.max(Duration::from_secs(
+        crate::config::get().idle.poll_interval_secs,
+    ))
+}
+
+/// The interval a poller with the given active interval should currently use.
+pub fn interval(active: Duration) -> Duration {
+    let active = crate::power::interval(active);
+    if is_idle() {
+        idle_interval(active)
+    } else {
+        active
+    }
+}
+
+/// Sleep for `active`, or the longer idle/degraded interval while the user is
+/// away or power is low.
+/// Returns early when the user becomes active again. Each return counts as a
+/// wakeup of the caller's poller in `[metrics]` audit mode.
+#[track_caller]
+pub fn sleep(active: Duration) {
+    let caller = Location::caller();
+    sleep_until_due(active);
+    crate::metrics::wakeup_at(caller);
+}
+
+fn sleep_until_due(active: Duration) {
+    let active = crate::power::interval(active);
+    let start = Instant::now();
+    let Ok(mut idle) = IDLE.lock() else {
+        std::thread::sleep(active);
+        return;
+    };
+    loop {
+        let wanted = if *idle { idle_interval(active) } else { active };
+        let elapsed = start.elapsed();
+        if elapsed >= wanted {
+            return;
+        }
+        let was_idle = *idle;
+        idle = match IDLE_CHANGED.wait_timeout(idle, wanted - elapsed) {
+            Ok((guard, _)) => guard,
+            Err(_) => return,
+        };
+        if was_idle && !*idle {
+            return;
+        }
+    }
+}
+
+/// Async counterpart of [`sleep`] for tokio pollers; notices activity within a second.
+#[track_caller]
+pub fn sleep_async(active: Duration) -> impl Future<Output = ()> {
+    let caller = Location::caller();
+    async move {
+        sleep_until_due_async(active).await;
+        crate::metrics::wakeup_at(caller);
+    }
+}
+
+async fn sleep_until_due_async(active: Duration) {
+    let start = Instant::now();
+    loop {
+        let wanted = interval(active);
+        let elapsed = start.elapsed();
+        if elapsed >= wanted {
+            return;
+        }
+        let was_idle = is_idle();
+        tokio::time::sleep((wanted - elapsed).min(Duration::from_secs(1))).await;
+        if was_idle && !is_idle() {
+            return;
+        }
+    }
+}
+
+#[derive(Default)]
+struct IdleState {
+    seat: Option<wl_seat::WlSeat>,
+    notifier: Option<ExtIdleNotifierV1>,
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for IdleState {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            match interface.as_str() {
+                "wl_seat" if state.seat.is_none() => {
+                    state.seat = Some(registry.bind(name, version.min(1), qh, ()));
+                }
+                "ext_idle_notifier_v1" => {
+                    state.notifier = Some(registry.bind(name, version.min(1), qh, ()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for IdleState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &wl_seat::WlSeat,
+        _event: wl_seat::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, ()> for IdleState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ExtIdleNotifierV1,
+        _event: <ExtIdleNotifierV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for IdleState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_idle_notification_v1::Event::Idled => set_idle(true),
+            ext_idle_notification_v1::Event::Resumed => set_idle(false),
+            _ => {}
+        }
+    }
+}
+
+/// Start the idle-notify listener on its own Wayland connection. If the
+/// compositor lacks the protocol, the user is simply never considered idle.
+pub fn spawn_idle_listener() {
+    let timeout_ms = crate::config::get()
+        .idle
+        .timeout_secs
+        .saturating_mul(1000)
+        .min(u32::MAX as u64) as u32;
+    if timeout_ms == 0 {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let conn = match Connection::connect_to_env() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("jb-shell: [idle] failed to connect to wayland: {e}");
+                return;
+            }
+        };
+
+        let mut event_queue = conn.new_event_queue::<IdleState>();
+        let qh = event_queue.handle();
+        let mut state = IdleState::default();
+        conn.display().get_registry(&qh, ());
+
+        if event_queue.roundtrip(&mut state).is_err() {
+            eprintln!("jb-shell: [idle] roundtrip failed");
+            return;
+        }
+
+        let (Some(notifier), Some(seat)) = (&state.notifier, &state.seat) else {
+            eprintln!("jb-shell: [idle] ext_idle_notifier_v1 not available");
+            return;
+        };
+        let _notification = notifier.get_idle_notification(timeout_ms, seat, &qh, ());
+
+        loop {
+            if let Err(e) = event_queue.blocking_dispatch(&mut state) {
+                eprintln!("jb-shell: [idle] dispatch failed: {e}");
+                set_idle(false);
+                return;
+            }
+        }
+    });
+}
@@ -0,0 +1,70 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crate::core::latency::{health, loss_percent, mean_ms, parse_ping_ms, Health, Thresholds};
+
+/// Pings kept for the popup sparkline.
+pub const HISTORY_LEN: usize = 60;
+
+/// Snapshot published to every bar's network widget after each ping.
+#[derive(Debug, Clone)]
+pub struct LatencyState {
+    pub host: String,
+    /// Round-trip ms per ping, oldest first; `None` for no reply.
+    pub history: Vec<Option<f64>>,
+    /// Mean round-trip and loss over the configured window.
+    pub mean_ms: Option<f64>,
+    pub loss_percent: f64,
+    pub health: Health,
+}
+
+/// Pings `[network.latency] host` once per interval and judges the last
+/// `window` replies against the thresholds. Does nothing without a host.
+pub fn spawn_latency_thread(publish: impl Fn(LatencyState) + Send + 'static) {
+    let config = &crate::config::get().network.latency;
+    let Some(host) = config.host.clone() else {
+        return;
+    };
+    let thresholds = Thresholds {
+        warn_ms: config.warn_ms,
+        bad_ms: config.bad_ms,
+        warn_loss: config.warn_loss_percent,
+        bad_loss: config.bad_loss_percent,
+    };
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    let window = config.window.clamp(1, HISTORY_LEN);
+
+    std::thread::spawn(move || {
+        let mut history: Vec<Option<f64>> = Vec::with_capacity(HISTORY_LEN);
+        loop {
+            if history.len() == HISTORY_LEN {
+                history.remove(0);
+            }
+            history.push(ping(&host));
+
+            let recent = &history[history.len().saturating_sub(window)..];
+            publish(LatencyState {
+                host: host.clone(),
+                history: history.clone(),
+                mean_ms: mean_ms(recent),
+                loss_percent: loss_percent(recent),
+                health: health(recent, &thresholds),
+            });
+            crate::idle::sleep(interval);
+        }
+    });
+}
+
+/// One echo request with a 2s deadline.
+fn ping(host: &str) -> Option<f64> {
+    match Command::new("ping")
+        .args(["-n", "-c", "1", "-W", "2", host])
+        .output()
+    {
+        Ok(out) => parse_ping_ms(&String::from_utf8_lossy(&out.stdout)),
+        Err(e) => {
+            eprintln!("jb-shell: [latency] failed to run ping: {e}");
+            None
+        }
+    }
+}
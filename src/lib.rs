@@ -0,0 +1,4 @@
+//! The GTK-free part of jb-shell, built as a library so tests and benchmarks
+//! can link it without a display server. The binary uses it as `crate::core`.
+
+pub mod core;
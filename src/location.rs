@@ -0,0 +1,115 @@
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+const GEOCLUE: &str = "org.freedesktop.GeoClue2";
+/// GeoClue's agent and `geoclue.conf` authorize apps by this ID.
+const DESKTOP_ID: &str = "dev.jb.shell";
+/// `GCLUE_ACCURACY_LEVEL_CITY`: enough for sunset times, and doesn't need
+/// GPS or Wi-Fi scanning.
+const ACCURACY_CITY: u32 = 4;
+/// Metres moved before GeoClue reports a new fix.
+const DISTANCE_THRESHOLD: u32 = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Whether `[location]` gives any way to find the location, so features can
+/// tell "not known yet" from "never".
+pub fn configured() -> bool {
+    let config = &crate::config::get().location;
+    config.geoclue || config.latitude.zip(config.longitude).is_some()
+}
+
+/// Publishes the location once from `[location] latitude`/`longitude`, or,
+/// with `[location] geoclue = true` and no coordinates, from GeoClue2 on the
+/// system bus whenever it moves by more than a few kilometres. GeoClue is
+/// only asked after that opt-in; its agent may ask the user again.
+pub fn spawn_location_thread(publish: impl Fn(Location) + Send + 'static) {
+    let config = &crate::config::get().location;
+    if let Some((latitude, longitude)) = config.latitude.zip(config.longitude) {
+        publish(Location {
+            latitude,
+            longitude,
+        });
+        return;
+    }
+    if !config.geoclue {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        if let Err(e) = follow_geoclue(&publish) {
+            eprintln!(
+                "jb-shell: [location] GeoClue unavailable: {e}; set [location] latitude/longitude instead"
+            );
+        }
+    });
+}
+
+fn follow_geoclue(publish: &impl Fn(Location)) -> zbus::Result<()> {
+    let conn = zbus::blocking::Connection::system()?;
+    let client: OwnedObjectPath = conn
+        .call_method(
+            Some(GEOCLUE),
+            "/org/freedesktop/GeoClue2/Manager",
+            Some("org.freedesktop.GeoClue2.Manager"),
+            "GetClient",
+            &(),
+        )?
+        .body()
+        .deserialize()?;
+
+    let set = |name: &str, value: Value| {
+        conn.call_method(
+            Some(GEOCLUE),
+            client.as_str(),
+            Some("org.freedesktop.DBus.Properties"),
+            "Set",
+            &("org.freedesktop.GeoClue2.Client", name, value),
+        )
+    };
+    set("DesktopId", Value::from(DESKTOP_ID))?;
+    set("RequestedAccuracyLevel", Value::from(ACCURACY_CITY))?;
+    set("DistanceThreshold", Value::from(DISTANCE_THRESHOLD))?;
+
+    let rule = zbus::MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .interface("org.freedesktop.GeoClue2.Client")?
+        .member("LocationUpdated")?
+        .path(client.as_str())?
+        .build();
+    // Subscribed before starting, since the first fix can come right away
+    let updates = zbus::blocking::MessageIterator::for_match_rule(rule, &conn, None)?;
+    conn.call_method(
+        Some(GEOCLUE),
+        client.as_str(),
+        Some("org.freedesktop.GeoClue2.Client"),
+        "Start",
+        &(),
+    )?;
+    eprintln!("jb-shell: [location] following GeoClue");
+
+    for message in updates {
+        let (_, new): (OwnedObjectPath, OwnedObjectPath) = message?.body().deserialize()?;
+        let get = |name: &str| -> zbus::Result<f64> {
+            let value: OwnedValue = conn
+                .call_method(
+                    Some(GEOCLUE),
+                    new.as_str(),
+                    Some("org.freedesktop.DBus.Properties"),
+                    "Get",
+                    &("org.freedesktop.GeoClue2.Location", name),
+                )?
+                .body()
+                .deserialize()?;
+            Ok(f64::try_from(value)?)
+        };
+        publish(Location {
+            latitude: get("Latitude")?,
+            longitude: get("Longitude")?,
+        });
+    }
+    Ok(())
+}
@@ -1,16 +1,55 @@
+mod actions;
+mod activation;
+mod app_icons;
+mod audio_ports;
+mod autostart;
 mod bar;
+mod caffeine;
+mod charge_limit;
+mod ci;
+mod cli;
+mod config;
+mod custom_modules;
+#[cfg(all(test, feature = "daemon-tests"))]
+mod daemon_conformance;
+mod dbus;
+mod display_profiles;
+mod focus_mode;
+mod git_status;
 mod google_calendar;
+mod http;
 mod hyprland_listener;
+mod i3bar;
+mod idle;
+mod latency;
+mod location;
+mod media_keys;
+mod metrics;
+mod net_usage;
+mod night_light;
 mod notification_daemon;
+mod oncall;
+mod power;
+mod presence;
+mod reminders;
+mod runtime;
+mod secrets;
+mod shell;
+mod state_dbus;
 mod summary_thread;
+mod timezone;
+mod weather;
 mod widgets;
+mod window_focus;
 mod workspace_capture;
 
 use bar::StatusBar;
 use hyprland::data::Monitors;
 use hyprland::shared::{HyprData, HyprDataVec};
 use hyprland_listener::HyprlandMsg;
-use relm4::Component;
+use jb_shell::core;
+use shell::Shell;
+use widgets::notifications::NotificationInput;
 
 use gdk4::prelude::*;
 use gtk4::prelude::*;
@@ -21,25 +60,105 @@ use std::sync::mpsc;
 
 const APP_ID: &str = "dev.jb.shell";
 
+/// How often a hotplugged monitor is re-matched, 200ms apart, before its bar
+/// falls back to the GDK connector name.
+const MATCH_ATTEMPTS: u32 = 10;
+
+/// The Hyprland output a GDK monitor shows. The connector name is
+/// authoritative; position is only consulted when GDK doesn't report one, since
+/// layouts shift while outputs wake from DPMS. `None` while Hyprland doesn't
+/// list the output (yet).
 fn match_hyprland_monitor(
     gdk_mon: &gdk4::Monitor,
     hypr_monitors: &[hyprland::data::Monitor],
-    index: u32,
-) -> String {
+) -> Option<String> {
+    if let Some(connector) = gdk_mon.connector() {
+        return hypr_monitors
+            .iter()
+            .find(|hm| hm.name == connector.as_str())
+            .map(|hm| hm.name.clone());
+    }
     let geo = gdk_mon.geometry();
     hypr_monitors
         .iter()
         .find(|hm| hm.x == geo.x() && hm.y == geo.y())
         .map(|hm| hm.name.clone())
-        .unwrap_or_else(|| {
+}
+
+/// Best name for a monitor Hyprland couldn't be matched to.
+fn fallback_monitor_name(gdk_mon: &gdk4::Monitor, index: u32) -> String {
+    gdk_mon
+        .connector()
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| format!("unknown-{index}"))
+}
+
+/// Create the bar for a hotplugged monitor once Hyprland lists its output,
+/// retrying every 200ms. A bar already on that output is replaced when it
+/// still holds a stale GDK monitor from before the re-enumeration.
+fn add_bar_when_matched(
+    gdk_mon: gdk4::Monitor,
+    index: u32,
+    bars: Rc<RefCell<Vec<StatusBar>>>,
+    app: Application,
+    shell: Rc<Shell>,
+    attempts_left: u32,
+) {
+    glib::timeout_add_local_once(std::time::Duration::from_millis(200), move || {
+        if !gdk_mon.is_valid() {
+            eprintln!("jb-shell: [monitor] skipping invalid monitor at index {index}");
+            return;
+        }
+        let hypr_monitors = Monitors::get().map(|m| m.to_vec()).unwrap_or_default();
+        eprintln!(
+            "jb-shell: [monitor] deferred add — hyprland monitors: [{}]",
             hypr_monitors
-                .get(index as usize)
-                .map(|hm| hm.name.clone())
-                .unwrap_or_else(|| format!("unknown-{index}"))
-        })
+                .iter()
+                .map(|m| format!("{}@{}x{}", m.name, m.x, m.y))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let hypr_name = match match_hyprland_monitor(&gdk_mon, &hypr_monitors) {
+            Some(name) => name,
+            None if attempts_left > 1 => {
+                add_bar_when_matched(gdk_mon, index, bars, app, shell, attempts_left - 1);
+                return;
+            }
+            None => {
+                let name = fallback_monitor_name(&gdk_mon, index);
+                eprintln!("jb-shell: [monitor] hyprland never listed {name}, adding bar anyway");
+                name
+            }
+        };
+
+        let mut bars = bars.borrow_mut();
+        if let Some(pos) = bars.iter().position(|b| b.monitor_name() == hypr_name) {
+            if bars[pos].monitor == gdk_mon && bars[pos].monitor.is_valid() {
+                eprintln!("jb-shell: [monitor] bar already exists for {hypr_name}, skipping");
+                return;
+            }
+            eprintln!("jb-shell: [monitor] replacing stale bar for {hypr_name}");
+            bars.remove(pos).destroy();
+        }
+        eprintln!("jb-shell: [monitor] adding bar for new monitor: {hypr_name}");
+        let bar = StatusBar::new(&gdk_mon, &hypr_name, &shell);
+        bar.window.set_application(Some(&app));
+        bar.window.present();
+        bars.push(bar);
+        shell.set_monitors(bar_monitors(&bars));
+    });
+}
+
+fn bar_monitors(bars: &[StatusBar]) -> Vec<gdk4::Monitor> {
+    bars.iter().map(|b| b.monitor.clone()).collect()
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let app = Application::builder().application_id(APP_ID).build();
 
     app.connect_shutdown(|_| {
@@ -96,6 +215,18 @@ fn main() {
             gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
         );
 
+        // Bar style options from config.toml take precedence over the stylesheet
+        let overrides = bar::style_overrides(&config::get().bar);
+        if !overrides.is_empty() {
+            let override_provider = CssProvider::new();
+            override_provider.load_from_data(&overrides);
+            gtk4::style_context_add_provider_for_display(
+                &gdk::Display::default().expect("Could not get default display"),
+                &override_provider,
+                gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+            );
+        }
+
         // Match GDK monitors to Hyprland monitors
         let display = gdk::Display::default().expect("Could not get default display");
         let gdk_monitors = display.monitors();
@@ -111,6 +242,17 @@ fn main() {
                 .join(", ")
         );
 
+        // Process-wide singletons (notification daemon + toasts, calendar, summary,
+        // mpris, launcher, keybinds). Bars subscribe to these rather than owning them.
+        let primary_monitor = gdk_monitors
+            .item(0)
+            .and_then(|obj| obj.downcast::<gdk4::Monitor>().ok())
+            .expect("no monitor for shell");
+        idle::spawn_idle_listener();
+        autostart::run();
+        let shell = Rc::new(Shell::new(&primary_monitor));
+        let notif_sender = shell.notification_sender().clone();
+
         let bars: Rc<RefCell<Vec<StatusBar>>> = Rc::new(RefCell::new(Vec::new()));
 
         for i in 0..gdk_monitors.n_items() {
@@ -123,37 +265,31 @@ fn main() {
                 None => continue,
             };
 
-            let hypr_name = match_hyprland_monitor(&gdk_mon, &hypr_monitors, i);
+            let hypr_name =
+                match_hyprland_monitor(&gdk_mon, &hypr_monitors).unwrap_or_else(|| {
+                    let name = fallback_monitor_name(&gdk_mon, i);
+                    eprintln!("jb-shell: [monitor] no hyprland output for {name} at startup");
+                    name
+                });
+
+            if hypr_monitors
+                .iter()
+                .any(|hm| hm.name == hypr_name && hm.focused)
+            {
+                notif_sender.emit(NotificationInput::FocusMonitor(gdk_mon.clone()));
+            }
 
-            let bar = StatusBar::new(&gdk_mon, &hypr_name);
+            let bar = StatusBar::new(&gdk_mon, &hypr_name, &shell);
             bar.window.set_application(Some(app));
             bar.window.present();
             bars.borrow_mut().push(bar);
         }
-
-        // Start notification daemon using the first bar's notification sender
-        if !bars.borrow().is_empty() {
-            let notif_sender = bars.borrow()[0].notification_sender().clone();
-            let daemon_tx = notification_daemon::spawn_notification_daemon(notif_sender.clone());
-            notif_sender.emit(
-                crate::widgets::notifications::NotificationInput::SetDaemonChannel(daemon_tx),
-            );
-        }
-
-        // Create global application launcher (not per-bar).
-        // Leak the controller so the component lives for the process lifetime.
-        let primary_monitor = gdk_monitors
-            .item(0)
-            .and_then(|obj| obj.downcast::<gdk4::Monitor>().ok())
-            .expect("no monitor for launcher");
-        let launcher = widgets::launcher::LauncherModel::builder()
-            .launch(primary_monitor)
-            .detach();
-        std::mem::forget(launcher);
+        shell.set_monitors(bar_monitors(&bars.borrow()));
 
         // Listen for monitor additions/removals (DPMS, hotplug)
         let bars_for_signal = bars.clone();
         let app_for_signal = app.clone();
+        let shell_for_signal = shell.clone();
         gdk_monitors.connect_items_changed(move |list, position, removed, added| {
             let total_gdk = list.n_items();
             eprintln!(
@@ -206,6 +342,7 @@ fn main() {
                     }
                     still_valid
                 });
+                shell_for_signal.set_monitors(bar_monitors(&bars));
             }
 
             // Add bars for new monitors — deferred to let the compositor/GPU
@@ -219,48 +356,14 @@ fn main() {
                     })
                     .collect();
 
-                if !new_monitors.is_empty() {
-                    let bars_deferred = bars_for_signal.clone();
-                    let app_deferred = app_for_signal.clone();
-                    glib::timeout_add_local_once(
-                        std::time::Duration::from_millis(200),
-                        move || {
-                            let hypr_monitors =
-                                Monitors::get().map(|m| m.to_vec()).unwrap_or_default();
-                            eprintln!(
-                                "jb-shell: [monitor] deferred add — hyprland monitors: [{}]",
-                                hypr_monitors
-                                    .iter()
-                                    .map(|m| format!("{}@{}x{}", m.name, m.x, m.y))
-                                    .collect::<Vec<_>>()
-                                    .join(", ")
-                            );
-                            let mut bars = bars_deferred.borrow_mut();
-                            for (gdk_mon, idx) in &new_monitors {
-                                if !gdk_mon.is_valid() {
-                                    eprintln!(
-                                        "jb-shell: [monitor] skipping invalid monitor at index {idx}"
-                                    );
-                                    continue;
-                                }
-                                let hypr_name =
-                                    match_hyprland_monitor(gdk_mon, &hypr_monitors, *idx);
-                                // Skip if we already have a bar for this monitor name
-                                if bars.iter().any(|b| b.monitor_name() == hypr_name) {
-                                    eprintln!(
-                                        "jb-shell: [monitor] bar already exists for {hypr_name}, skipping"
-                                    );
-                                    continue;
-                                }
-                                eprintln!(
-                                    "jb-shell: [monitor] adding bar for new monitor: {hypr_name}"
-                                );
-                                let bar = StatusBar::new(gdk_mon, &hypr_name);
-                                bar.window.set_application(Some(&app_deferred));
-                                bar.window.present();
-                                bars.push(bar);
-                            }
-                        },
+                for (gdk_mon, idx) in new_monitors {
+                    add_bar_when_matched(
+                        gdk_mon,
+                        idx,
+                        bars_for_signal.clone(),
+                        app_for_signal.clone(),
+                        shell_for_signal.clone(),
+                        MATCH_ATTEMPTS,
                     );
                 }
             }
@@ -344,12 +447,40 @@ fn main() {
 
         // Poll the channel from the GTK main loop
         let bars_clone = bars.clone();
+        let shell_for_hypr = shell.clone();
         glib::timeout_add_local(std::time::Duration::from_millis(16), move || {
+            metrics::wakeup();
             while let Ok(msg) = rx.try_recv() {
                 let bars = bars_clone.borrow();
                 for bar in bars.iter() {
                     bar.handle_hyprland_msg(&msg);
                 }
+                if let HyprlandMsg::ActiveWindowChanged { title, class, .. } = &msg {
+                    shell_for_hypr.set_focused_class(class);
+                    state_dbus::set(state_dbus::StateChange::ActiveWindow {
+                        title: title.clone(),
+                        class: class.clone(),
+                    });
+                }
+                if let HyprlandMsg::WorkspaceChanged { .. } = &msg {
+                    metrics::workspace_switched();
+                }
+                if let HyprlandMsg::WorkspaceChanged { workspace_id, .. }
+                | HyprlandMsg::MonitorFocusChanged { workspace_id, .. } = &msg
+                {
+                    state_dbus::set(state_dbus::StateChange::FocusedWorkspace(*workspace_id));
+                }
+                if let HyprlandMsg::MonitorsChanged = &msg {
+                    shell_for_hypr.monitors_changed();
+                }
+                if let HyprlandMsg::ScreencastChanged { active } = &msg {
+                    notif_sender.emit(NotificationInput::SetScreencast(*active));
+                }
+                if let HyprlandMsg::MonitorFocusChanged { monitor_name, .. } = &msg {
+                    if let Some(bar) = bars.iter().find(|b| b.monitor_name() == monitor_name) {
+                        notif_sender.emit(NotificationInput::FocusMonitor(bar.monitor.clone()));
+                    }
+                }
             }
             glib::ControlFlow::Continue
         });
@@ -0,0 +1,245 @@
+use hyprland::keyword::Keyword;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc;
+
+use crate::core::media_keys::{self, MediaKey};
+use crate::widgets::osd::{OsdInput, OsdLevel};
+
+const SINK: &str = "@DEFAULT_AUDIO_SINK@";
+const SOURCE: &str = "@DEFAULT_AUDIO_SOURCE@";
+
+// ── D-Bus activation ─────────────────────────────────────────────────
+
+struct MediaDbus {
+    tx: mpsc::Sender<MediaKey>,
+}
+
+#[zbus::interface(name = "dev.jb.shell.Media")]
+impl MediaDbus {
+    /// Act on a volume, brightness or media key, by keysym name (e.g.
+    /// `XF86AudioRaiseVolume`).
+    fn key(&self, name: String) -> zbus::fdo::Result<()> {
+        let key = MediaKey::parse(&name)
+            .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("unknown key {name:?}")))?;
+        self.tx
+            .send(key)
+            .map_err(|_| zbus::fdo::Error::Failed("media key thread is gone".into()))
+    }
+}
+
+/// Handle the media keys on a worker thread: presses come in over
+/// `dev.jb.shell.Media` (from `jb-shell key`), and volume and brightness
+/// changes show on the OSD. With `[media_keys] bind`, also registers the
+/// Hyprland binds that send them.
+pub fn spawn_media_keys(osd: relm4::Sender<OsdInput>) {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for key in rx {
+            crate::metrics::wakeup();
+            if let Some(level) = press(key) {
+                osd.emit(OsdInput::Show(level));
+            }
+        }
+    });
+    crate::dbus::serve(
+        "/dev/jb/shell/Media",
+        "dev.jb.shell.Media",
+        MediaDbus { tx },
+    );
+
+    if crate::config::get().media_keys.bind {
+        bind_keys();
+    }
+}
+
+/// Point Hyprland's media key binds at this binary.
+fn bind_keys() {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe.to_string_lossy().into_owned(),
+        Err(e) => {
+            eprintln!("jb-shell: [media-keys] can't bind keys, no path to jb-shell: {e}");
+            return;
+        }
+    };
+    for key in MediaKey::ALL {
+        let (keyword, value) = media_keys::bind(key, &exe);
+        if let Err(e) = Keyword::set(keyword, value.as_str()) {
+            eprintln!("jb-shell: [media-keys] {keyword} {value} failed: {e}");
+        }
+    }
+}
+
+/// Make the change `key` asks for; the new level if it has one to show.
+fn press(key: MediaKey) -> Option<OsdLevel> {
+    let config = &crate::config::get().media_keys;
+    match key {
+        MediaKey::RaiseVolume | MediaKey::LowerVolume => {
+            let up = key == MediaKey::RaiseVolume;
+            let step = crate::config::get().volume.step;
+            crate::widgets::volume::step_volume(step, up);
+            Some(volume_level())
+        }
+        MediaKey::Mute => {
+            wpctl(&["set-mute", SINK, "toggle"]);
+            Some(volume_level())
+        }
+        MediaKey::MicMute => {
+            wpctl(&["set-mute", SOURCE, "toggle"]);
+            let (percent, muted) = crate::widgets::volume::get_volume(SOURCE);
+            Some(OsdLevel {
+                icon: if muted {
+                    "microphone-sensitivity-muted-symbolic"
+                } else {
+                    "audio-input-microphone-symbolic"
+                },
+                name: "Microphone",
+                percent,
+                muted,
+                max: 100,
+            })
+        }
+        MediaKey::BrightnessUp => step_backlight(config.brightness_step, true),
+        MediaKey::BrightnessDown => step_backlight(config.brightness_step, false),
+        MediaKey::Play | MediaKey::Pause | MediaKey::Stop | MediaKey::Next | MediaKey::Prev => {
+            mpris(key.mpris_method()?);
+            None
+        }
+    }
+}
+
+fn wpctl(args: &[&str]) {
+    match Command::new("wpctl").args(args).status() {
+        Ok(status) if !status.success() => {
+            eprintln!(
+                "jb-shell: [media-keys] wpctl {} exited with {status}",
+                args.join(" ")
+            );
+        }
+        Err(e) => eprintln!("jb-shell: [media-keys] failed to run wpctl: {e}"),
+        _ => {}
+    }
+}
+
+fn volume_level() -> OsdLevel {
+    let (percent, muted) = crate::widgets::volume::get_volume(SINK);
+    let icon = if muted {
+        "audio-volume-muted-symbolic"
+    } else if percent < 33 {
+        "audio-volume-low-symbolic"
+    } else if percent < 66 {
+        "audio-volume-medium-symbolic"
+    } else {
+        "audio-volume-high-symbolic"
+    };
+    OsdLevel {
+        icon,
+        name: "Volume",
+        percent,
+        muted,
+        max: crate::config::get().volume.max_percent.max(100),
+    }
+}
+
+/// `[media_keys] backlight`, or the first device there is.
+fn backlight_dir() -> Option<PathBuf> {
+    let root = PathBuf::from("/sys/class/backlight");
+    if let Some(name) = &crate::config::get().media_keys.backlight {
+        return Some(root.join(name));
+    }
+    let mut devices: Vec<PathBuf> = std::fs::read_dir(&root)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    devices.sort();
+    devices.into_iter().next()
+}
+
+fn read_number(path: PathBuf) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Step the backlight through logind, which lets the session's user set it
+/// without write access to sysfs.
+fn step_backlight(step_percent: u32, up: bool) -> Option<OsdLevel> {
+    let Some(dir) = backlight_dir() else {
+        eprintln!("jb-shell: [media-keys] no backlight in /sys/class/backlight");
+        return None;
+    };
+    let name = dir.file_name()?.to_string_lossy().into_owned();
+    let current = read_number(dir.join("brightness"))?;
+    let max = read_number(dir.join("max_brightness"))?;
+    let value = media_keys::step_brightness(current, max, step_percent, up);
+    let set = crate::dbus::system_blocking().and_then(|conn| {
+        conn.call_method(
+            Some("org.freedesktop.login1"),
+            "/org/freedesktop/login1/session/auto",
+            Some("org.freedesktop.login1.Session"),
+            "SetBrightness",
+            &("backlight", name.as_str(), value),
+        )
+    });
+    if let Err(e) = set {
+        eprintln!("jb-shell: [media-keys] can't set {name} brightness: {e}");
+        return None;
+    }
+    Some(OsdLevel {
+        icon: "display-brightness-symbolic",
+        name: "Brightness",
+        percent: media_keys::percent(value, max),
+        muted: false,
+        max: 100,
+    })
+}
+
+/// Call `method` on the playing MPRIS player, or the first one there is.
+fn mpris(method: &str) {
+    let result = crate::dbus::session_blocking().and_then(|conn| {
+        let names: Vec<String> = conn
+            .call_method(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                Some("org.freedesktop.DBus"),
+                "ListNames",
+                &(),
+            )?
+            .body()
+            .deserialize()?;
+        let players: Vec<&String> = names
+            .iter()
+            .filter(|n| n.starts_with("org.mpris.MediaPlayer2."))
+            .collect();
+        let playing = players.iter().find(|name| {
+            conn.call_method(
+                Some(name.as_str()),
+                "/org/mpris/MediaPlayer2",
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.mpris.MediaPlayer2.Player", "PlaybackStatus"),
+            )
+            .ok()
+            .and_then(|reply| {
+                reply
+                    .body()
+                    .deserialize::<zbus::zvariant::OwnedValue>()
+                    .ok()
+            })
+            .and_then(|status| String::try_from(status).ok())
+            .is_some_and(|status| status == "Playing")
+        });
+        let Some(player) = playing.or(players.first()) else {
+            return Ok(());
+        };
+        conn.call_method(
+            Some(player.as_str()),
+            "/org/mpris/MediaPlayer2",
+            Some("org.mpris.MediaPlayer2.Player"),
+            method,
+            &(),
+        )
+        .map(|_| ())
+    });
+    if let Err(e) = result {
+        eprintln!("jb-shell: [media-keys] MPRIS {method} failed: {e}");
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::core::metrics::{self as core_metrics, ProcessStats, Snapshot, Telemetry};
+use crate::google_calendar::CalendarEvent;
+
+// Counting is a no-op until `[metrics] enabled` turns audit mode on, so the
+// hooks can stay in hot paths.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNTERS: Mutex<Counters> = Mutex::new(Counters {
+    widget_updates: BTreeMap::new(),
+    thread_wakeups: BTreeMap::new(),
+    dbus_calls: BTreeMap::new(),
+});
+
+// Telemetry for `[metrics] listen`, kept whether or not anyone scrapes it
+static WORKSPACE_SWITCHES: AtomicU64 = AtomicU64::new(0);
+static LAUNCHER_LAUNCHES: AtomicU64 = AtomicU64::new(0);
+static CALENDAR_EVENTS: Mutex<Vec<CalendarEvent>> = Mutex::new(Vec::new());
+
+struct Counters {
+    widget_updates: BTreeMap<String, u64>,
+    thread_wakeups: BTreeMap<String, u64>,
+    dbus_calls: BTreeMap<String, u64>,
+}
+
+fn bump(family: fn(&mut Counters) -> &mut BTreeMap<String, u64>, key: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(mut counters) = COUNTERS.lock() else {
+        return;
+    };
+    let counts = family(&mut counters);
+    match counts.get_mut(key) {
+        Some(count) => *count += 1,
+        None => {
+            counts.insert(key.to_string(), 1);
+        }
+    }
+}
+
+/// A `Subscribers` publish of `type_name`'s messages to every bar.
+pub fn widget_update(type_name: &'static str) {
+    bump(
+        |c| &mut c.widget_updates,
+        core_metrics::type_label(type_name),
+    );
+}
+
+/// A background loop woke up; counted under the caller's source file.
+#[track_caller]
+pub fn wakeup() {
+    wakeup_at(Location::caller());
+}
+
+/// [`wakeup`] for callers that captured their location earlier, e.g. before
+/// an `.await`.
+pub fn wakeup_at(caller: &'static Location<'static>) {
+    bump(
+        |c| &mut c.thread_wakeups,
+        core_metrics::source_label(caller.file()),
+    );
+}
+
+/// Hyprland focused another workspace.
+pub fn workspace_switched() {
+    WORKSPACE_SWITCHES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The launcher started an app.
+pub fn app_launched() {
+    LAUNCHER_LAUNCHES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The calendar's latest events, for the meetings-today gauge.
+pub fn set_calendar_events(events: Vec<CalendarEvent>) {
+    if let Ok(mut stored) = CALENDAR_EVENTS.lock() {
+        *stored = events;
+    }
+}
+
+fn telemetry() -> Telemetry {
+    let today = chrono::Local::now().date_naive();
+    let meetings_today = CALENDAR_EVENTS.lock().map_or(0, |events| {
+        events
+            .iter()
+            .filter(|e| !e.is_all_day && e.accepted && e.start.date_naive() == today)
+            .count() as u64
+    });
+    let notifications_last_hour =
+        crate::notification_daemon::counts_last_hour().unwrap_or_else(|e| {
+            eprintln!("jb-shell: [metrics] notification counts: {e}");
+            Vec::new()
+        });
+    Telemetry {
+        notifications_last_hour,
+        meetings_today,
+        workspace_switches: WORKSPACE_SWITCHES.load(Ordering::Relaxed),
+        launcher_launches: LAUNCHER_LAUNCHES.load(Ordering::Relaxed),
+        battery_percent: crate::power::battery_percent(),
+    }
+}
+
+/// Every counter so far, plus the process's current CPU time and memory.
+pub fn snapshot() -> Snapshot {
+    let family = |counts: &BTreeMap<String, u64>| {
+        counts
+            .iter()
+            .map(|(name, count)| (name.clone(), *count))
+            .collect()
+    };
+    let mut snapshot = match COUNTERS.lock() {
+        Ok(counters) => Snapshot {
+            widget_updates: family(&counters.widget_updates),
+            thread_wakeups: family(&counters.thread_wakeups),
+            dbus_calls: family(&counters.dbus_calls),
+            process: ProcessStats::default(),
+        },
+        Err(_) => Snapshot::default(),
+    };
+    snapshot.process = process_stats();
+    snapshot
+}
+
+fn process_stats() -> ProcessStats {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let (rss_bytes, threads) = core_metrics::parse_status(&status);
+    let stat = std::fs::read_to_string("/proc/self/stat").unwrap_or_default();
+    ProcessStats {
+        rss_bytes: rss_bytes.unwrap_or(0),
+        threads: threads.unwrap_or(0),
+        cpu_ticks: core_metrics::parse_cpu_ticks(&stat).unwrap_or(0),
+    }
+}
+
+/// One sampling interval, for the `metrics` bar widget.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSample {
+    pub interval_secs: f64,
+    /// Of one core, over the interval.
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+    pub threads: u64,
+    /// How much each counter grew over the interval, busiest first.
+    pub widget_updates: Vec<(String, u64)>,
+    pub thread_wakeups: Vec<(String, u64)>,
+    pub dbus_calls: Vec<(String, u64)>,
+}
+
+/// Serves telemetry on `[metrics] listen` if set, and turns on audit mode if
+/// `[metrics] enabled`: starts counting, samples every `interval_secs` and adds
+/// the counters to the endpoint.
+pub fn spawn_metrics_thread(publish: impl Fn(MetricsSample) + Send + 'static) {
+    let config = &crate::config::get().metrics;
+    if let Some(addr) = &config.listen {
+        spawn_endpoint(addr);
+    }
+    if !config.enabled {
+        return;
+    }
+    ENABLED.store(true, Ordering::Relaxed);
+    eprintln!("jb-shell: [metrics] audit mode on");
+    spawn_dbus_counter();
+
+    std::thread::spawn(move || {
+        // Not `idle::sleep`: an audit should see the idle behaviour, not
+        // change with it
+        let interval = Duration::from_secs(config.interval_secs.max(1));
+        let mut prev = snapshot();
+        let mut prev_at = Instant::now();
+        loop {
+            std::thread::sleep(interval);
+            let cur = snapshot();
+            let secs = prev_at.elapsed().as_secs_f64();
+            prev_at = Instant::now();
+            publish(MetricsSample {
+                interval_secs: secs,
+                cpu_percent: core_metrics::cpu_percent(
+                    prev.process.cpu_ticks,
+                    cur.process.cpu_ticks,
+                    secs,
+                ),
+                rss_bytes: cur.process.rss_bytes,
+                threads: cur.process.threads,
+                widget_updates: core_metrics::deltas(&prev.widget_updates, &cur.widget_updates),
+                thread_wakeups: core_metrics::deltas(&prev.thread_wakeups, &cur.thread_wakeups),
+                dbus_calls: core_metrics::deltas(&prev.dbus_calls, &cur.dbus_calls),
+            });
+            prev = cur;
+        }
+    });
+}
+
+/// Counts method calls reaching the shared session connection, by
+/// `interface.member`.
+fn spawn_dbus_counter() {
+    std::thread::spawn(|| {
+        let conn = match crate::dbus::session_blocking() {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("jb-shell: [metrics] not counting D-Bus calls: {e}");
+                return;
+            }
+        };
+        for message in zbus::blocking::MessageIterator::from(conn) {
+            let Ok(message) = message else {
+                continue;
+            };
+            let header = message.header();
+            if header.message_type() != zbus::message::Type::MethodCall {
+                continue;
+            }
+            if let (Some(interface), Some(member)) = (header.interface(), header.member()) {
+                bump(|c| &mut c.dbus_calls, &format!("{interface}.{member}"));
+            }
+        }
+    });
+}
+
+/// Minimal HTTP/1.1 responder: every request gets the Prometheus text.
+/// Anything but a loopback address is refused, since the notification counts
+/// name the apps in use.
+fn spawn_endpoint(addr: &str) {
+    let loopback = addr
+        .to_socket_addrs()
+        .is_ok_and(|mut addrs| addrs.all(|a| a.ip().is_loopback()));
+    if !loopback {
+        eprintln!(
+            "jb-shell: [metrics] not listening on {addr}: only loopback addresses are allowed"
+        );
+        return;
+    }
+    let listener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("jb-shell: [metrics] can't listen on {addr}: {e}");
+            return;
+        }
+    };
+    eprintln!("jb-shell: [metrics] serving http://{addr}/metrics");
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            // Read up to the blank line ending the request head
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+            let mut body = core_metrics::render_telemetry(&telemetry());
+            if ENABLED.load(Ordering::Relaxed) {
+                body.push_str(&core_metrics::render_prometheus(&snapshot()));
+            }
+            let _ = write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+}
@@ -0,0 +1,263 @@
+use chrono::{Local, NaiveDate};
+use rusqlite::Connection as DbConnection;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::core::usage::{billing_period_start, cap_threshold, counter_delta, format_bytes};
+use crate::widgets::notifications::{
+    NotificationId, NotificationInput, NotificationKind, NotificationRequest, NotificationSource,
+};
+
+/// Throughput samples kept for the popup sparkline.
+pub const HISTORY_LEN: usize = 60;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+/// Counted bytes are written to the DB this often, not every sample.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+const CAP_NOTIFICATION_ID: NotificationId = u64::MAX - 0xd0;
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+/// Snapshot published to every bar's network widget after each sample.
+#[derive(Debug, Clone)]
+pub struct UsageState {
+    /// Bytes per second over the last sample, all interfaces together.
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    /// Combined rx+tx rates, oldest first, one per sample.
+    pub history: Vec<f64>,
+    /// Bytes moved today (local time) and this billing month.
+    pub today: u64,
+    pub month: u64,
+    pub cap: Option<u64>,
+}
+
+/// Samples `/sys/class/net/*/statistics` every couple of seconds, keeps daily
+/// per-interface totals in `usage.db` and warns through a toast as the
+/// monthly cap nears.
+pub fn spawn_usage_thread(
+    notif_sender: relm4::Sender<NotificationInput>,
+    publish: impl Fn(UsageState) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let config = &crate::config::get().network.usage;
+        let cap = config
+            .monthly_cap_gb
+            .map(|gb| (gb.max(0.0) * 1e9) as u64)
+            .filter(|&cap| cap > 0);
+        let db = match open_db() {
+            Ok(db) => Some(db),
+            Err(e) => {
+                eprintln!("jb-shell: [usage] failed to open usage DB, totals won't persist: {e}");
+                None
+            }
+        };
+
+        let mut counters = read_counters();
+        let mut sampled_at = Instant::now();
+        let mut flushed_at = Instant::now();
+        let mut pending: HashMap<String, (u64, u64)> = HashMap::new();
+        let mut history: Vec<f64> = Vec::with_capacity(HISTORY_LEN);
+        let (mut stored_today, mut stored_month) = (0, 0);
+        if let Some(db) = &db {
+            (stored_today, stored_month) = totals(db, config.month_start_day);
+        }
+
+        loop {
+            crate::idle::sleep(SAMPLE_INTERVAL);
+            let now = read_counters();
+            let elapsed = sampled_at.elapsed().as_secs_f64().max(0.001);
+            sampled_at = Instant::now();
+
+            let (mut rx_total, mut tx_total) = (0, 0);
+            for (iface, &(rx, tx)) in &now {
+                let (prev_rx, prev_tx) = counters.get(iface).copied().unwrap_or((rx, tx));
+                let (rx, tx) = (counter_delta(prev_rx, rx), counter_delta(prev_tx, tx));
+                let entry = pending.entry(iface.clone()).or_default();
+                entry.0 += rx;
+                entry.1 += tx;
+                rx_total += rx;
+                tx_total += tx;
+            }
+            counters = now;
+
+            let rx_rate = rx_total as f64 / elapsed;
+            let tx_rate = tx_total as f64 / elapsed;
+            if history.len() == HISTORY_LEN {
+                history.remove(0);
+            }
+            history.push(rx_rate + tx_rate);
+
+            if flushed_at.elapsed() >= FLUSH_INTERVAL {
+                if let Some(db) = &db {
+                    flush(db, &mut pending);
+                    (stored_today, stored_month) = totals(db, config.month_start_day);
+                    if let Some(cap) = cap {
+                        check_cap(db, &notif_sender, stored_month, cap, config.warn_percent);
+                    }
+                }
+                flushed_at = Instant::now();
+            }
+
+            let unflushed: u64 = pending.values().map(|(rx, tx)| rx + tx).sum();
+            publish(UsageState {
+                rx_rate,
+                tx_rate,
+                history: history.clone(),
+                today: stored_today + unflushed,
+                month: stored_month + unflushed,
+                cap,
+            });
+        }
+    });
+}
+
+/// Cumulative (rx, tx) byte counters per physical interface, those with a
+/// `device` link in sysfs. Virtual ones (loopback, bridges, VPN tunnels like
+/// `wg0` or `tun0`) are skipped: their traffic is already counted on the
+/// interface carrying it.
+fn read_counters() -> HashMap<String, (u64, u64)> {
+    let Ok(entries) = fs::read_dir("/sys/class/net") else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let iface = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().join("device").exists() {
+                return None;
+            }
+            let stats = entry.path().join("statistics");
+            Some((
+                iface,
+                (
+                    read_counter(&stats, "rx_bytes")?,
+                    read_counter(&stats, "tx_bytes")?,
+                ),
+            ))
+        })
+        .collect()
+}
+
+fn read_counter(stats: &Path, name: &str) -> Option<u64> {
+    fs::read_to_string(stats.join(name))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn db_path() -> std::path::PathBuf {
+    crate::notification_daemon::db_path().with_file_name("usage.db")
+}
+
+fn open_db() -> Result<DbConnection, rusqlite::Error> {
+    let db = DbConnection::open(db_path())?;
+    db.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usage (
+            day         TEXT NOT NULL,
+            interface   TEXT NOT NULL,
+            rx          INTEGER NOT NULL DEFAULT 0,
+            tx          INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, interface)
+        );
+        CREATE TABLE IF NOT EXISTS cap_warnings (
+            period      TEXT NOT NULL,
+            percent     INTEGER NOT NULL,
+            PRIMARY KEY (period, percent)
+        );",
+    )?;
+    Ok(db)
+}
+
+/// Add the pending deltas to today's rows (local date) and clear them.
+fn flush(db: &DbConnection, pending: &mut HashMap<String, (u64, u64)>) {
+    let day = Local::now().format(DAY_FORMAT).to_string();
+    for (iface, (rx, tx)) in pending.drain() {
+        if rx == 0 && tx == 0 {
+            continue;
+        }
+        if let Err(e) = db.execute(
+            "INSERT INTO usage (day, interface, rx, tx) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(day, interface) DO UPDATE SET rx = rx + ?3, tx = tx + ?4",
+            rusqlite::params![day, iface, rx as i64, tx as i64],
+        ) {
+            eprintln!("jb-shell: [usage] failed to record {iface}: {e}");
+        }
+    }
+}
+
+/// Stored bytes for today and for the current billing month.
+fn totals(db: &DbConnection, month_start_day: u32) -> (u64, u64) {
+    let today = Local::now().date_naive();
+    let since = |day: NaiveDate| -> u64 {
+        db.query_row(
+            "SELECT COALESCE(SUM(rx + tx), 0) FROM usage WHERE day >= ?1",
+            rusqlite::params![day.format(DAY_FORMAT).to_string()],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|total| total.max(0) as u64)
+        .unwrap_or(0)
+    };
+    (
+        since(today),
+        since(billing_period_start(today, month_start_day)),
+    )
+}
+
+/// Toast the first time this billing month crosses `warn_percent` or 100% of
+/// the cap; `cap_warnings` remembers it across restarts.
+fn check_cap(
+    db: &DbConnection,
+    notif_sender: &relm4::Sender<NotificationInput>,
+    used: u64,
+    cap: u64,
+    warn_percent: u8,
+) {
+    let Some(percent) = cap_threshold(used, cap, &[warn_percent, 100]) else {
+        return;
+    };
+    let period = billing_period_start(
+        Local::now().date_naive(),
+        crate::config::get().network.usage.month_start_day,
+    );
+    let inserted = db
+        .execute(
+            "INSERT OR IGNORE INTO cap_warnings (period, percent) VALUES (?1, ?2)",
+            rusqlite::params![period.format(DAY_FORMAT).to_string(), percent],
+        )
+        .unwrap_or(0);
+    if inserted == 0 {
+        return;
+    }
+
+    eprintln!("jb-shell: [usage] {percent}% of the monthly cap used");
+    let title = if percent >= 100 {
+        "Data cap reached".to_string()
+    } else {
+        format!("{percent}% of data cap used")
+    };
+    notif_sender.emit(NotificationInput::Show(NotificationRequest {
+        id: CAP_NOTIFICATION_ID,
+        kind: NotificationKind::Toast,
+        icon: Some("\u{f0ec}".to_string()),
+        title,
+        body: Some(format!(
+            "{} of {} since {}",
+            format_bytes(used),
+            format_bytes(cap),
+            period.format("%b %-d")
+        )),
+        subtitle: None,
+        countdown_target: None,
+        actions: Vec::new(),
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: (percent >= 100).then(|| "urgency-critical".to_string()),
+        timeout_ms: Some(10000),
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    }));
+}
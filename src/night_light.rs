@@ -0,0 +1,192 @@
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::config::NightLightBackend;
+use crate::core::sun::sun_times;
+use crate::location::Location;
+
+pub const MIN_TEMPERATURE: u32 = 2500;
+pub const MAX_TEMPERATURE: u32 = 6500;
+
+#[derive(Debug)]
+pub enum NightLightCommand {
+    Toggle,
+    SetTemperature(u32),
+    SetAuto(bool),
+    /// From `location::spawn_location_thread`; ignored when `[night_light]`
+    /// has its own coordinates.
+    Location(Location),
+}
+
+/// Snapshot published to every bar after each change.
+#[derive(Debug, Clone)]
+pub struct NightLightState {
+    /// The warm tint is currently applied.
+    pub active: bool,
+    pub temperature: u32,
+    pub auto: bool,
+    /// The location is known, so `auto` can follow the sun.
+    pub auto_available: bool,
+    /// With `auto`: when the next sunrise/sunset flips `active`.
+    pub next_change: Option<DateTime<Local>>,
+}
+
+/// Owns the `hyprsunset`/`wlsunset` child process. Bars send commands and get
+/// `NightLightState` back through `publish`; with `auto` on, the thread also
+/// wakes every minute to follow sunset and sunrise. Until a location
+/// arrives, `auto` keeps the tint as it is.
+pub fn spawn_night_light_thread(
+    publish: impl Fn(NightLightState) + Send + 'static,
+) -> mpsc::Sender<NightLightCommand> {
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let config = &crate::config::get().night_light;
+        let pinned = config.latitude.zip(config.longitude);
+        let mut location = pinned;
+        let locatable = pinned.is_some() || crate::location::configured();
+        if config.auto && !locatable {
+            eprintln!("jb-shell: [night-light] auto needs latitude and longitude; ignoring");
+        }
+
+        let mut auto = config.auto && locatable;
+        let mut manual_on = false;
+        let mut temperature = config.temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+        let mut applied: Option<u32> = None;
+        let mut child: Option<Child> = None;
+
+        loop {
+            let now = Local::now();
+            let (active, next_change) = match (auto, location) {
+                (true, Some((lat, lon))) => {
+                    let (night, next) = schedule(now, lat, lon);
+                    (night, Some(next))
+                }
+                _ => (manual_on, None),
+            };
+
+            let wanted = active.then_some(temperature);
+            if wanted != applied {
+                if let Some(mut old) = child.take() {
+                    let _ = old.kill();
+                    let _ = old.wait();
+                }
+                if let Some(temp) = wanted {
+                    child = start_backend(config.backend, temp);
+                }
+                applied = wanted;
+            }
+
+            publish(NightLightState {
+                active,
+                temperature,
+                auto,
+                auto_available: location.is_some(),
+                next_change,
+            });
+
+            let command = rx.recv_timeout(Duration::from_secs(60));
+            crate::metrics::wakeup();
+            match command {
+                Ok(NightLightCommand::Toggle) => {
+                    // A manual toggle takes over from the schedule
+                    manual_on = !active;
+                    auto = false;
+                }
+                Ok(NightLightCommand::SetTemperature(temp)) => {
+                    temperature = temp.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+                }
+                Ok(NightLightCommand::SetAuto(on)) => {
+                    auto = on && location.is_some();
+                    manual_on = active;
+                }
+                Ok(NightLightCommand::Location(found)) => {
+                    if pinned.is_none() {
+                        location = Some((found.latitude, found.longitude));
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        if let Some(mut old) = child {
+            let _ = old.kill();
+        }
+    });
+
+    tx
+}
+
+fn start_backend(backend: NightLightBackend, temperature: u32) -> Option<Child> {
+    let (program, args) = match backend {
+        NightLightBackend::Hyprsunset => (
+            "hyprsunset",
+            vec!["-t".to_string(), temperature.to_string()],
+        ),
+        // wlsunset only knows schedules: make "night" last all day but one
+        // minute at midnight so the low temperature is always in effect.
+        NightLightBackend::Wlsunset => (
+            "wlsunset",
+            vec![
+                "-t".to_string(),
+                temperature.to_string(),
+                "-T".to_string(),
+                (temperature + 1).to_string(),
+                "-S".to_string(),
+                "00:00".to_string(),
+                "-s".to_string(),
+                "00:01".to_string(),
+                "-d".to_string(),
+                "1".to_string(),
+            ],
+        ),
+    };
+
+    // Take over from an instance started outside the shell
+    let _ = Command::new("pkill").args(["-x", program]).status();
+
+    match Command::new(program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => {
+            eprintln!("jb-shell: [night-light] {program} at {temperature}K");
+            Some(child)
+        }
+        Err(e) => {
+            eprintln!("jb-shell: [night-light] failed to start {program}: {e}");
+            None
+        }
+    }
+}
+
+/// Whether it is night at `now`, and when that next changes.
+fn schedule(now: DateTime<Local>, lat: f64, lon: f64) -> (bool, DateTime<Local>) {
+    let today = now.date_naive();
+    let (sunrise, sunset) = sun_times(today, lat, lon);
+    if now < sunrise {
+        (true, sunrise.with_timezone(&Local))
+    } else if now < sunset {
+        (false, sunset.with_timezone(&Local))
+    } else {
+        let tomorrow = today.succ_opt().unwrap_or(today);
+        (true, sun_times(tomorrow, lat, lon).0.with_timezone(&Local))
+    }
+}
+
+/// "2h 5m" / "12m" until `when`, for the tooltip.
+pub fn format_until(when: DateTime<Local>) -> String {
+    let left = when - Local::now();
+    let minutes = left.num_minutes().max(0);
+    if left < ChronoDuration::hours(1) {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
@@ -1,27 +1,96 @@
+use crate::config::LowUrgency;
+use crate::core::preview::{downscale_bilinear, fit_within, Frame};
+use crate::core::window_focus::FocusQuery;
 use crate::widgets::notifications::{
-    ActionCallback, NotificationAction, NotificationId, NotificationInput, NotificationKind,
-    NotificationRequest, NotificationSource,
+    ActionCallback, DndPreset, NotificationAction, NotificationId, NotificationInput,
+    NotificationKind, NotificationRequest, NotificationSource,
 };
-use chrono::TimeZone;
 use rusqlite::Connection as DbConnection;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{mpsc, Mutex};
 use std::thread;
-use zbus::blocking;
+use zbus::fdo::{RequestNameFlags, RequestNameReply};
 use zbus::interface;
 use zbus::zvariant;
 
+const FDO_NAME: &str = "org.freedesktop.Notifications";
+const JB_PATH: &str = "/dev/jb/shell/Notifications";
+const JB_NAME: &str = "dev.jb.shell.Notifications";
+
+/// Largest sender-window thumbnail kept with a history row.
+const THUMBNAIL_MAX: (u32, u32) = (240, 160);
+
+/// `NotificationClosed` reasons from the freedesktop notification spec.
+pub const CLOSE_REASON_EXPIRED: u32 = 1;
+pub const CLOSE_REASON_DISMISSED: u32 = 2;
+pub const CLOSE_REASON_CLOSED: u32 = 3;
+pub const CLOSE_REASON_UNDEFINED: u32 = 4;
+
 #[derive(Debug)]
 pub enum DaemonCommand {
     NotificationClosed { id: u32, reason: u32 },
-    ActionInvoked { id: u32, action_key: String },
+    ActionInvoked {
+        id: u32,
+        action_key: String,
+        /// Sent ahead as `ActivationToken` so the app can raise its window.
+        activation_token: Option<String>,
+    },
+    /// Silence toasts from `app_name` until the given UTC datetime (`None` = forever).
+    MuteApp {
+        app_name: String,
+        until: Option<String>,
+    },
+    /// Notification center: one row was read (opened or acted on).
+    MarkRead {
+        id: u32,
+    },
+    /// Notification center: mark unread rows created since `since` (UTC) read.
+    MarkAllRead {
+        since: String,
+    },
+    /// Notification center: clear every row created since `since` (UTC).
+    ClearAll {
+        since: String,
+    },
+    /// Notification center: remove one row from the history.
+    Delete {
+        id: u32,
+    },
+}
+
+/// How long "Mute <app>" from a toast should last.
+#[derive(Clone, Copy, Debug)]
+pub enum MuteDuration {
+    Hour,
+    Today,
+    Forever,
+}
+
+impl MuteDuration {
+    /// UTC datetime string the mute expires at, or `None` for a permanent mute.
+    pub fn until_utc(self) -> Option<String> {
+        match self {
+            MuteDuration::Hour => Some(
+                (chrono::Utc::now() + chrono::Duration::hours(1))
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            ),
+            MuteDuration::Today => Some(crate::core::time::local_midnight_utc(
+                chrono::Local::now().date_naive() + chrono::Days::new(1),
+            )),
+            MuteDuration::Forever => None,
+        }
+    }
 }
 
 struct NotificationServer {
     notif_sender: relm4::Sender<NotificationInput>,
     db: Mutex<DbConnection>,
     next_id: AtomicU32,
+    /// Closes notifications the UI never sees (muted apps) through the
+    /// command loop below, like the toasts do.
+    cmd_tx: mpsc::Sender<DaemonCommand>,
 }
 
 #[interface(name = "org.freedesktop.Notifications")]
@@ -60,7 +129,7 @@ impl NotificationServer {
             None
         };
 
-        let id = if replaces_id != 0 {
+        let mut id = if replaces_id != 0 {
             replaces_id
         } else {
             self.next_id.fetch_add(1, Ordering::Relaxed)
@@ -94,15 +163,25 @@ impl NotificationServer {
             .and_then(|v| bool::try_from(v).ok())
             .unwrap_or(false);
 
+        // Spec `value`: progress percentage, e.g. for downloads or volume
+        let progress: Option<u8> = hints
+            .get("value")
+            .and_then(|v| <i32>::try_from(v).ok())
+            .map(|v| v.clamp(0, 100) as u8);
+
         let actions_json = serialize_actions_json(&actions);
+        let mut muted = false;
+        let mut repeat_count = 1;
 
         // Store in DB
         if let Ok(db) = self.db.lock() {
+            muted = is_app_muted(&db, app_name);
             if replaces_id != 0 {
                 let _ = db.execute(
                     "UPDATE notifications SET app_name=?1, app_icon=?2, summary=?3, body=?4, \
                      urgency=?5, category=?6, desktop_entry=?7, actions=?8, transient=?9, \
-                     resident=?10, expire_timeout=?11 WHERE id=?12",
+                     resident=?10, expire_timeout=?11, closed_at=NULL, close_reason=NULL \
+                     WHERE id=?12",
                     rusqlite::params![
                         app_name,
                         _app_icon,
@@ -118,11 +197,21 @@ impl NotificationServer {
                         id,
                     ],
                 );
+            } else if let Some((repeat_id, count)) = find_repeat(&db, app_name, summary, body) {
+                // Same notification again within the window — bump the existing row
+                id = repeat_id;
+                repeat_count = count + 1;
+                let _ = db.execute(
+                    "UPDATE notifications SET repeat_count = ?1, actions = ?2, urgency = ?3, \
+                     created_at = datetime('now'), closed_at = NULL, close_reason = NULL, \
+                     read = ?4 WHERE id = ?5",
+                    rusqlite::params![repeat_count, actions_json, urgency, muted, id],
+                );
             } else {
                 let _ = db.execute(
                     "INSERT INTO notifications (id, app_name, app_icon, summary, body, urgency, \
-                     category, desktop_entry, actions, transient, resident, expire_timeout) \
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                     category, desktop_entry, actions, transient, resident, expire_timeout, read) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
                     rusqlite::params![
                         id,
                         app_name,
@@ -136,12 +225,29 @@ impl NotificationServer {
                         transient,
                         resident,
                         expire_timeout,
+                        muted,
                     ],
                 );
             }
         }
 
-        let request = fd_notification_to_request(
+        // Muted apps still land in history (already read) but never toast;
+        // never displayed, so tell the sender it's gone
+        if muted {
+            let _ = self.cmd_tx.send(DaemonCommand::NotificationClosed {
+                id,
+                reason: CLOSE_REASON_UNDEFINED,
+            });
+            return id;
+        }
+
+        // Low urgency can skip the toast but still counts toward the badge
+        if urgency == 0 && crate::config::get().toasts.low_urgency == LowUrgency::Silent {
+            self.notif_sender.emit(NotificationInput::Silent(id));
+            return id;
+        }
+
+        let mut request = fd_notification_to_request(
             id,
             app_name,
             summary,
@@ -149,24 +255,44 @@ impl NotificationServer {
             &actions,
             urgency,
             expire_timeout,
+            resident,
             desktop_entry,
             sender_pid,
         );
+        request.repeat_count = repeat_count;
+        request.progress = progress;
         self.notif_sender.emit(NotificationInput::Show(request));
 
         id
     }
 
-    fn close_notification(&self, id: u32) {
-        let notif_id = id as NotificationId;
-        self.notif_sender.emit(NotificationInput::Dismiss(notif_id));
-
-        if let Ok(db) = self.db.lock() {
-            let _ = db.execute(
-                "UPDATE notifications SET closed_at = datetime('now'), close_reason = 3 WHERE id = ?1",
-                rusqlite::params![id],
-            );
+    async fn close_notification(
+        &self,
+        #[zbus(signal_emitter)] emitter: zbus::object_server::SignalEmitter<'_>,
+        id: u32,
+    ) {
+        // Only close notifications that are still open. Unknown or already-closed
+        // ids get the spec-mandated empty reply with no DB write or signal.
+        let closed = self
+            .db
+            .lock()
+            .ok()
+            .and_then(|db| {
+                db.execute(
+                    "UPDATE notifications SET closed_at = datetime('now'), close_reason = ?1, \
+                     read = 1 WHERE id = ?2 AND closed_at IS NULL",
+                    rusqlite::params![CLOSE_REASON_CLOSED, id],
+                )
+                .ok()
+            })
+            .unwrap_or(0);
+        if closed == 0 {
+            return;
         }
+
+        self.notif_sender
+            .emit(NotificationInput::Remove(id as NotificationId));
+        let _ = Self::notification_closed(&emitter, id, CLOSE_REASON_CLOSED).await;
     }
 
     fn get_server_information(&self) -> (String, String, String, String) {
@@ -191,6 +317,148 @@ impl NotificationServer {
         id: u32,
         action_key: &str,
     ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn activation_token(
+        signal_emitter: &zbus::object_server::SignalEmitter<'_>,
+        id: u32,
+        activation_token: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// One `History` row: id, app name, summary, body, created at (UTC
+/// `YYYY-MM-DD HH:MM:SS`) and read.
+type HistoryRow = (u32, String, String, String, String, bool);
+
+/// jb-specific extras next to the standard interface, under their own name so
+/// tooling never confuses them with another daemon's.
+struct JbNotifications {
+    notif_sender: relm4::Sender<NotificationInput>,
+    db: Mutex<DbConnection>,
+}
+
+#[interface(name = "dev.jb.shell.Notifications")]
+impl JbNotifications {
+    /// The newest `limit` notifications, newest first.
+    fn history(&self, limit: u32) -> zbus::fdo::Result<Vec<HistoryRow>> {
+        let db = self
+            .db
+            .lock()
+            .map_err(|_| zbus::fdo::Error::Failed("history unavailable".into()))?;
+        let rows = db
+            .prepare(
+                "SELECT id, app_name, summary, body, created_at, read FROM notifications \
+                 ORDER BY created_at DESC, id DESC LIMIT ?1",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map(rusqlite::params![limit], |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
+                })?
+                .collect::<Result<Vec<HistoryRow>, _>>()
+            })
+            .map_err(|e| zbus::fdo::Error::Failed(format!("history query failed: {e}")))?;
+        Ok(rows)
+    }
+
+    /// Mark `ids` read, or every unread notification for an empty list.
+    /// Returns how many changed.
+    fn mark_read(&self, ids: Vec<u32>) -> zbus::fdo::Result<u32> {
+        let db = self
+            .db
+            .lock()
+            .map_err(|_| zbus::fdo::Error::Failed("history unavailable".into()))?;
+        let changed = if ids.is_empty() {
+            db.execute("UPDATE notifications SET read = 1 WHERE read = 0", [])
+        } else {
+            ids.iter().try_fold(0, |n, id| {
+                db.execute(
+                    "UPDATE notifications SET read = 1 WHERE id = ?1 AND read = 0",
+                    rusqlite::params![id],
+                )
+                .map(|changed| n + changed)
+            })
+        }
+        .map_err(|e| zbus::fdo::Error::Failed(format!("mark read failed: {e}")))?;
+        if changed > 0 {
+            self.notif_sender.emit(NotificationInput::CenterChanged);
+        }
+        Ok(changed as u32)
+    }
+
+    /// Do not disturb for `minutes`; 0 turns it off, negative lasts until
+    /// midnight.
+    fn set_dnd(&self, minutes: i32) {
+        self.notif_sender
+            .emit(NotificationInput::SetDnd(match minutes {
+                0 => DndPreset::Off,
+                m if m < 0 => DndPreset::UntilTomorrow,
+                m => DndPreset::Minutes(m as i64),
+            }));
+    }
+}
+
+/// Log losing `name` to another daemon (started with `--replace` or
+/// similar) and getting it back once that one exits. Shell toasts and the
+/// jb interface keep working meanwhile.
+fn watch_name(conn: &zbus::blocking::Connection, name: &'static str) {
+    let conn_lost = conn.clone();
+    thread::spawn(move || {
+        let Ok(proxy) = zbus::blocking::fdo::DBusProxy::new(&conn_lost) else {
+            return;
+        };
+        let Ok(lost) = proxy.receive_name_lost() else {
+            return;
+        };
+        for signal in lost {
+            if signal.args().is_ok_and(|a| a.name().as_str() == name) {
+                eprintln!("jb-shell: notification daemon lost {name} to another daemon; queued");
+            }
+        }
+    });
+    let conn = conn.clone();
+    thread::spawn(move || {
+        let Ok(proxy) = zbus::blocking::fdo::DBusProxy::new(&conn) else {
+            return;
+        };
+        let Ok(acquired) = proxy.receive_name_acquired() else {
+            return;
+        };
+        for signal in acquired {
+            if signal.args().is_ok_and(|a| a.name().as_str() == name) {
+                eprintln!("jb-shell: notification daemon acquired {name}");
+            }
+        }
+    });
+}
+
+/// The history write behind a notification center command.
+fn update_history(db: &DbConnection, command: &DaemonCommand) -> rusqlite::Result<usize> {
+    match command {
+        DaemonCommand::MarkRead { id } => db.execute(
+            "UPDATE notifications SET read = 1 WHERE id = ?1",
+            rusqlite::params![id],
+        ),
+        DaemonCommand::MarkAllRead { since } => db.execute(
+            "UPDATE notifications SET read = 1 WHERE created_at >= ?1 AND read = 0",
+            rusqlite::params![since],
+        ),
+        DaemonCommand::ClearAll { since } => db.execute(
+            "UPDATE notifications SET read = 1 WHERE created_at >= ?1",
+            rusqlite::params![since],
+        ),
+        DaemonCommand::Delete { id } => db.execute(
+            "DELETE FROM notifications WHERE id = ?1",
+            rusqlite::params![id],
+        ),
+        _ => Ok(0),
+    }
 }
 
 fn serialize_actions_json(actions: &[String]) -> String {
@@ -207,6 +475,23 @@ fn serialize_actions_json(actions: &[String]) -> String {
     serde_json::to_string(&pairs).unwrap_or_else(|_| "[]".into())
 }
 
+/// Button label for a spec action; the unnamed "default" action reads "Open".
+pub fn action_label(key: &str, label: &str) -> String {
+    if !label.is_empty() {
+        label.to_string()
+    } else if key == "default" {
+        "Open".to_string()
+    } else {
+        key.to_string()
+    }
+}
+
+/// Inverse of `serialize_actions_json` for the stored `actions` column.
+pub fn parse_actions_json(json: Option<&str>) -> Vec<(String, String)> {
+    json.and_then(|j| serde_json::from_str(j).ok())
+        .unwrap_or_default()
+}
+
 fn fd_notification_to_request(
     fd_id: u32,
     app_name: &str,
@@ -215,11 +500,15 @@ fn fd_notification_to_request(
     actions: &[String],
     urgency: u8,
     expire_timeout: i32,
+    resident: bool,
     desktop_entry: Option<String>,
     sender_pid: Option<u32>,
 ) -> NotificationRequest {
     let has_actions = actions.len() >= 2;
     let timeout_ms = match expire_timeout {
+        // Critical notifications stay until the user deals with them unless
+        // the sender asked for an explicit timeout
+        -1 if urgency == 2 => None,
         -1 => Some(if has_actions { 15000 } else { 5000 }),
         0 => None,
         ms if ms > 0 => Some(ms as u32),
@@ -233,16 +522,7 @@ fn fd_notification_to_request(
         .filter_map(|chunk| {
             if chunk.len() == 2 {
                 let key = &chunk[0];
-                let label = &chunk[1];
-                let display_label = if label.is_empty() {
-                    if key == "default" {
-                        "Open".to_string()
-                    } else {
-                        key.clone()
-                    }
-                } else {
-                    label.clone()
-                };
+                let display_label = action_label(key, &chunk[1]);
                 let css_class = if key == "default" {
                     "notif-default-action"
                 } else {
@@ -291,27 +571,134 @@ fn fd_notification_to_request(
         css_box_name: Some("fd-notification".to_string()),
         css_card_class: urgency_class,
         timeout_ms,
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
         source: NotificationSource::Freedesktop {
             fd_id,
             app_name: app_name.to_string(),
             desktop_entry,
             sender_pid,
+            resident,
         },
     }
 }
 
-/// Returns today's local midnight as a UTC datetime string (for SQL `created_at >= ?`).
-/// This ensures timezone-correct "today" filtering since `created_at` is stored in UTC.
-pub fn today_start_utc() -> String {
-    let today_local = chrono::Local::now().date_naive();
-    let midnight_local = today_local
-        .and_hms_opt(0, 0, 0)
-        .expect("valid midnight time");
-    let midnight_utc = chrono::Local
-        .from_local_datetime(&midnight_local)
-        .unwrap()
-        .with_timezone(&chrono::Utc);
-    midnight_utc.format("%Y-%m-%d %H:%M:%S").to_string()
+/// Capture the sender's window as it is now into the row's `thumbnail`, so
+/// the center can show which window a notification came from. Senders
+/// without a window leave it empty, and the first capture is kept (a missed
+/// toast re-shown after a restart must not pick up whatever is there now).
+/// Called by the model, which knows whether DND or a screencast should keep
+/// the window out of history.
+pub fn store_thumbnail(id: u32, query: FocusQuery, notif_sender: relm4::Sender<NotificationInput>) {
+    thread::spawn(move || {
+        let Some(client) = crate::window_focus::find(query).filter(|c| c.mapped) else {
+            return;
+        };
+        let Some((data, width, height, stride)) =
+            crate::workspace_capture::capture_window(&client.address)
+        else {
+            return;
+        };
+        let (w, h) = fit_within(width, height, THUMBNAIL_MAX.0, THUMBNAIL_MAX.1);
+        if w == 0 {
+            return;
+        }
+        let frame = Frame {
+            data: &data,
+            width,
+            height,
+            stride,
+        };
+        let pixels = downscale_bilinear(&frame, w, h);
+        let stored = DbConnection::open(db_path()).and_then(|db| {
+            db.busy_timeout(std::time::Duration::from_secs(2))?;
+            db.execute(
+                "UPDATE notifications SET thumbnail = ?1, thumbnail_width = ?2, \
+                 thumbnail_height = ?3 WHERE id = ?4 AND thumbnail IS NULL",
+                rusqlite::params![pixels, w, h, id],
+            )
+        });
+        match stored {
+            Ok(0) => {}
+            Ok(_) => notif_sender.emit(NotificationInput::CenterChanged),
+            Err(e) => eprintln!("jb-shell: [notifications] failed to store thumbnail: {e}"),
+        }
+    });
+}
+
+/// Re-show notifications whose toast never got a close recorded — the shell
+/// died while they were up — so a restart doesn't silently eat them.
+fn restore_missed(db: &DbConnection, notif_sender: &relm4::Sender<NotificationInput>) {
+    let minutes = crate::config::get().toasts.restore_missed_minutes;
+    if minutes == 0 {
+        return;
+    }
+    let Ok(mut stmt) = db.prepare(
+        "SELECT id, app_name, summary, body, actions, urgency, expire_timeout, resident, \
+         desktop_entry, CAST((julianday('now') - julianday(created_at)) * 1440 AS INTEGER), \
+         repeat_count \
+         FROM notifications \
+         WHERE read = 0 AND closed_at IS NULL AND transient = 0 \
+         AND created_at >= datetime('now', ?1) \
+         ORDER BY created_at ASC",
+    ) else {
+        return;
+    };
+    let since = format!("-{minutes} minutes");
+    let rows = stmt.query_map(rusqlite::params![since], |row| {
+        let actions: Vec<String> = parse_actions_json(row.get::<_, Option<String>>(4)?.as_deref())
+            .into_iter()
+            .flat_map(|(key, label)| [key, label])
+            .collect();
+        let mut request = fd_notification_to_request(
+            row.get(0)?,
+            &row.get::<_, String>(1)?,
+            &row.get::<_, String>(2)?,
+            &row.get::<_, String>(3)?,
+            &actions,
+            row.get(5)?,
+            row.get(6)?,
+            row.get(7)?,
+            row.get(8)?,
+            None,
+        );
+        request.repeat_count = row.get(10)?;
+        let age: i64 = row.get(9)?;
+        request.subtitle = Some(if age < 1 {
+            "Missed".to_string()
+        } else {
+            format!("Missed · {age}m ago")
+        });
+        Ok((row.get::<_, String>(1)?, request))
+    });
+    let Ok(rows) = rows else { return };
+
+    let mut restored = 0;
+    for (app_name, request) in rows.filter_map(|r| r.ok()) {
+        if is_app_muted(db, &app_name) {
+            continue;
+        }
+        notif_sender.emit(NotificationInput::Show(request));
+        restored += 1;
+    }
+    if restored > 0 {
+        eprintln!("jb-shell: restored {restored} missed notification(s)");
+    }
+}
+
+/// Notifications received in the past hour, by app, for `[metrics] listen`.
+pub fn counts_last_hour() -> rusqlite::Result<Vec<(String, u64)>> {
+    let db = DbConnection::open_with_flags(
+        db_path(),
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let mut stmt = db.prepare(
+        "SELECT app_name, COUNT(*) FROM notifications \
+         WHERE created_at >= datetime('now', '-1 hour') GROUP BY app_name ORDER BY app_name",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
 }
 
 pub fn db_path() -> std::path::PathBuf {
@@ -357,13 +744,67 @@ fn open_db() -> Result<DbConnection, rusqlite::Error> {
     let _ =
         db.execute_batch("ALTER TABLE notifications ADD COLUMN read INTEGER NOT NULL DEFAULT 0;");
 
+    // Migration: identical notifications collapse into one row
+    let _ = db.execute_batch(
+        "ALTER TABLE notifications ADD COLUMN repeat_count INTEGER NOT NULL DEFAULT 1;",
+    );
+
+    // Migration: sender window thumbnail, BGRA rows `thumbnail_width * 4` apart
+    let _ = db.execute_batch(
+        "ALTER TABLE notifications ADD COLUMN thumbnail BLOB;
+         ALTER TABLE notifications ADD COLUMN thumbnail_width INTEGER;
+         ALTER TABLE notifications ADD COLUMN thumbnail_height INTEGER;",
+    );
+
+    // Per-app rules. A muted row with NULL muted_until is muted forever.
+    db.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_rules (
+            app_name     TEXT PRIMARY KEY,
+            muted        INTEGER NOT NULL DEFAULT 0,
+            muted_until  TEXT
+        );",
+    )?;
+
     Ok(db)
 }
 
+/// Most recent row with the same app, summary and body inside the
+/// `collapse_repeats_secs` window, with its repeat count.
+fn find_repeat(db: &DbConnection, app_name: &str, summary: &str, body: &str) -> Option<(u32, u32)> {
+    let secs = crate::config::get().toasts.collapse_repeats_secs;
+    if secs == 0 {
+        return None;
+    }
+    db.query_row(
+        "SELECT id, repeat_count FROM notifications \
+         WHERE app_name = ?1 AND summary = ?2 AND body = ?3 \
+         AND created_at >= datetime('now', ?4) \
+         ORDER BY id DESC LIMIT 1",
+        rusqlite::params![app_name, summary, body, format!("-{secs} seconds")],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .ok()
+}
+
+fn is_app_muted(db: &DbConnection, app_name: &str) -> bool {
+    if app_name.is_empty() {
+        return false;
+    }
+    db.query_row(
+        "SELECT COUNT(*) FROM app_rules WHERE app_name = ?1 AND muted = 1 \
+         AND (muted_until IS NULL OR muted_until > datetime('now'))",
+        rusqlite::params![app_name],
+        |row| row.get::<_, u32>(0),
+    )
+    .map(|n| n > 0)
+    .unwrap_or(false)
+}
+
 pub fn spawn_notification_daemon(
     notif_sender: relm4::Sender<NotificationInput>,
 ) -> mpsc::Sender<DaemonCommand> {
     let (cmd_tx, cmd_rx) = mpsc::channel::<DaemonCommand>();
+    let server_tx = cmd_tx.clone();
 
     thread::spawn(move || {
         let db = match open_db() {
@@ -384,88 +825,162 @@ pub fn spawn_notification_daemon(
             .unwrap_or(0);
         let next_id = AtomicU32::new(max_id + 1);
 
+        match open_db() {
+            Ok(jb_db) => crate::dbus::serve(
+                JB_PATH,
+                JB_NAME,
+                JbNotifications {
+                    notif_sender: notif_sender.clone(),
+                    db: Mutex::new(jb_db),
+                },
+            ),
+            Err(e) => eprintln!("jb-shell: notification daemon skipping {JB_NAME}: {e}"),
+        }
+
         let server = NotificationServer {
             notif_sender,
             db: Mutex::new(db),
             next_id,
+            cmd_tx: server_tx,
         };
 
-        let conn = match blocking::connection::Builder::session()
-            .expect("failed to create session bus builder")
-            .serve_at("/org/freedesktop/Notifications", server)
-            .expect("failed to register notification interface")
-            .name("org.freedesktop.Notifications")
-            .expect("failed to set bus name")
-            .build()
-        {
+        let conn = match crate::dbus::session_blocking() {
             Ok(conn) => conn,
             Err(e) => {
-                eprintln!("jb-shell: notification daemon failed to acquire bus name: {e}");
+                eprintln!("jb-shell: notification daemon failed to connect to session bus: {e}");
                 return;
             }
         };
+        if let Err(e) = conn
+            .object_server()
+            .at("/org/freedesktop/Notifications", server)
+        {
+            eprintln!("jb-shell: notification daemon failed to register interface: {e}");
+            return;
+        }
+        // Another daemon (mako, dunst) may hold the name: queue behind it
+        // rather than give up, so the UI commands below keep being served and
+        // the name comes back when it exits
+        match conn.request_name_with_flags(
+            FDO_NAME,
+            RequestNameFlags::AllowReplacement | RequestNameFlags::ReplaceExisting,
+        ) {
+            Ok(RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner) => {
+                eprintln!("jb-shell: notification daemon listening on D-Bus");
+            }
+            Ok(_) => {
+                eprintln!("jb-shell: another notification daemon owns {FDO_NAME}; queued behind it")
+            }
+            Err(e) => {
+                eprintln!("jb-shell: notification daemon failed to request {FDO_NAME}: {e}");
+            }
+        }
+        watch_name(&conn, FDO_NAME);
 
-        eprintln!("jb-shell: notification daemon listening on D-Bus");
-
-        // Keep a reference to the interface for signal emission and DB access
-        let iface_ref = conn
+        // Keep a reference to the interface for signal emission and DB access.
+        // `conn` must stay alive for the object server to keep serving.
+        let iface_ref = match conn
             .object_server()
             .interface::<_, NotificationServer>("/org/freedesktop/Notifications")
-            .expect("failed to get interface ref");
+        {
+            Ok(iface_ref) => iface_ref,
+            Err(e) => {
+                eprintln!("jb-shell: notification daemon lost its interface: {e}");
+                return;
+            }
+        };
+
+        if let Ok(db) = iface_ref.get().db.lock() {
+            restore_missed(&db, &iface_ref.get().notif_sender);
+        }
 
         // Process DaemonCommands from the UI thread.
-        // zbus dispatches incoming D-Bus method calls on its own internal executor,
+        // zbus dispatches incoming D-Bus method calls on the shared tokio runtime,
         // so blocking here on cmd_rx is fine.
         loop {
             match cmd_rx.recv() {
                 Ok(DaemonCommand::NotificationClosed { id, reason }) => {
-                    // Update DB with close info + read status
-                    {
+                    // Update DB with close info + read status. Rows that were already
+                    // closed (e.g. via CloseNotification) don't get a second signal.
+                    let updated = {
                         let iface = iface_ref.get();
                         let db = iface.db.lock();
-                        if let Ok(db) = db {
-                            if reason == 2 || reason == 3 {
-                                // User dismissed/acted or caller closed — mark read
-                                let _ = db.execute(
+                        match db {
+                            Ok(db) => {
+                                let sql = if reason == CLOSE_REASON_DISMISSED
+                                    || reason == CLOSE_REASON_CLOSED
+                                {
+                                    // User dismissed/acted or caller closed — mark read
                                     "UPDATE notifications SET closed_at = datetime('now'), \
-                                     close_reason = ?1, read = 1 WHERE id = ?2",
-                                    rusqlite::params![reason, id],
-                                );
-                            } else if reason == 1 {
-                                // Expired — unread only if had real actions
-                                let _ = db.execute(
+                                     close_reason = ?1, read = 1 \
+                                     WHERE id = ?2 AND closed_at IS NULL"
+                                } else if reason == CLOSE_REASON_EXPIRED {
+                                    // Expired — unread only if had real actions
                                     "UPDATE notifications SET closed_at = datetime('now'), \
                                      close_reason = ?1, \
                                      read = CASE WHEN actions = '[]' OR actions IS NULL THEN 1 ELSE 0 END \
-                                     WHERE id = ?2",
-                                    rusqlite::params![reason, id],
-                                );
-                            } else {
-                                let _ = db.execute(
+                                     WHERE id = ?2 AND closed_at IS NULL"
+                                } else {
                                     "UPDATE notifications SET closed_at = datetime('now'), \
-                                     close_reason = ?1 WHERE id = ?2",
-                                    rusqlite::params![reason, id],
-                                );
+                                     close_reason = ?1 WHERE id = ?2 AND closed_at IS NULL"
+                                };
+                                db.execute(sql, rusqlite::params![reason, id]).unwrap_or(0)
                             }
+                            Err(_) => 0,
                         }
+                    };
+                    if updated > 0 {
+                        let _ = zbus::block_on(NotificationServer::notification_closed(
+                            iface_ref.signal_emitter(),
+                            id,
+                            reason,
+                        ));
                     }
-                    // Emit D-Bus signal via raw connection API
-                    let _ = conn.emit_signal(
-                        None::<zbus::names::BusName>,
-                        "/org/freedesktop/Notifications",
-                        "org.freedesktop.Notifications",
-                        "NotificationClosed",
-                        &(id, reason),
-                    );
                 }
-                Ok(DaemonCommand::ActionInvoked { id, action_key }) => {
-                    let _ = conn.emit_signal(
-                        None::<zbus::names::BusName>,
-                        "/org/freedesktop/Notifications",
-                        "org.freedesktop.Notifications",
-                        "ActionInvoked",
-                        &(id, action_key.as_str()),
-                    );
+                Ok(DaemonCommand::ActionInvoked {
+                    id,
+                    action_key,
+                    activation_token,
+                }) => {
+                    if let Some(token) = activation_token {
+                        let _ = zbus::block_on(NotificationServer::activation_token(
+                            iface_ref.signal_emitter(),
+                            id,
+                            &token,
+                        ));
+                    }
+                    let _ = zbus::block_on(NotificationServer::action_invoked(
+                        iface_ref.signal_emitter(),
+                        id,
+                        &action_key,
+                    ));
+                }
+                Ok(DaemonCommand::MuteApp { app_name, until }) => {
+                    let iface = iface_ref.get();
+                    if let Ok(db) = iface.db.lock() {
+                        if let Err(e) = db.execute(
+                            "INSERT INTO app_rules (app_name, muted, muted_until) VALUES (?1, 1, ?2) \
+                             ON CONFLICT(app_name) DO UPDATE SET muted = 1, muted_until = ?2",
+                            rusqlite::params![app_name, until],
+                        ) {
+                            eprintln!("jb-shell: failed to mute {app_name}: {e}");
+                        }
+                    }
+                }
+                Ok(
+                    command @ (DaemonCommand::MarkRead { .. }
+                    | DaemonCommand::MarkAllRead { .. }
+                    | DaemonCommand::ClearAll { .. }
+                    | DaemonCommand::Delete { .. }),
+                ) => {
+                    let iface = iface_ref.get();
+                    if let Ok(db) = iface.db.lock() {
+                        if let Err(e) = update_history(&db, &command) {
+                            eprintln!("jb-shell: failed to update history: {e}");
+                        }
+                    }
+                    // Every center's badge and list, not just the sender's
+                    iface.notif_sender.emit(NotificationInput::CenterChanged);
                 }
                 Err(_) => break,
             }
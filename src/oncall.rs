@@ -0,0 +1,246 @@
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::config::{OnCallConfig, OnCallProvider};
+use crate::core::oncall::{
+    parse_opsgenie_alerts, parse_opsgenie_oncalls, parse_pagerduty_incidents,
+    parse_pagerduty_oncalls, parse_pagerduty_user, Incident, IncidentStatus,
+};
+use crate::http::fetch;
+
+const PAGERDUTY_API: &str = "https://api.pagerduty.com";
+
+/// Published to every bar's on-call widget after each poll or action.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OnCallState {
+    pub on_call: bool,
+    /// Open incidents assigned to the user, as the API orders them.
+    pub incidents: Vec<Incident>,
+    /// Why the last poll or action failed; the previous incidents are kept.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum OnCallCommand {
+    Acknowledge(String),
+    Resolve(String),
+}
+
+/// Polls `[oncall] provider` for the user's open incidents and on-call status,
+/// and carries out acknowledge/resolve from the popup. Nothing runs without a
+/// token or unless the bar layout places "oncall".
+pub fn spawn_oncall_thread(
+    rt: &tokio::runtime::Handle,
+    publish: impl Fn(OnCallState) + Send + 'static,
+) -> mpsc::Sender<OnCallCommand> {
+    let (tx, rx) = mpsc::channel(8);
+    let config = &crate::config::get().oncall;
+    if config.token.is_some() && crate::config::get().bar.places("oncall") {
+        rt.spawn(oncall_thread_main(publish, rx));
+    }
+    tx
+}
+
+async fn oncall_thread_main(
+    publish: impl Fn(OnCallState) + Send + 'static,
+    mut rx: mpsc::Receiver<OnCallCommand>,
+) {
+    let config = &crate::config::get().oncall;
+    let Some(token) = config.token.as_deref() else {
+        return;
+    };
+    let interval = Duration::from_secs(config.poll_interval_secs.max(15));
+    let mut client = Client {
+        http: reqwest::Client::new(),
+        config,
+        token,
+        user_id: None,
+    };
+    let mut state = OnCallState::default();
+    let mut last: Option<OnCallState> = None;
+    let mut poll_now = true;
+
+    loop {
+        if poll_now {
+            match client.poll().await {
+                Ok((on_call, incidents)) => {
+                    state = OnCallState {
+                        on_call,
+                        incidents,
+                        error: None,
+                    };
+                }
+                Err(e) => {
+                    eprintln!("jb-shell: [oncall] poll failed: {e}");
+                    state.error = Some(e);
+                }
+            }
+        }
+        if last.as_ref() != Some(&state) {
+            publish(state.clone());
+            last = Some(state.clone());
+        }
+
+        let command = match tokio::time::timeout(crate::idle::interval(interval), rx.recv()).await {
+            Err(_) => {
+                poll_now = true;
+                continue;
+            }
+            Ok(None) => return,
+            Ok(Some(command)) => command,
+        };
+        // Opsgenie applies actions asynchronously, so rather than re-polling
+        // right away the change is made locally until the next poll
+        poll_now = false;
+        let result = match &command {
+            OnCallCommand::Acknowledge(id) => client.update(id, false).await,
+            OnCallCommand::Resolve(id) => client.update(id, true).await,
+        };
+        if let Err(e) = result {
+            eprintln!("jb-shell: [oncall] {command:?} failed: {e}");
+            state.error = Some(e);
+            continue;
+        }
+        eprintln!("jb-shell: [oncall] {command:?} done");
+        state.error = None;
+        match command {
+            OnCallCommand::Acknowledge(id) => {
+                for incident in state.incidents.iter_mut().filter(|i| i.id == id) {
+                    incident.status = IncidentStatus::Acknowledged;
+                }
+            }
+            OnCallCommand::Resolve(id) => state.incidents.retain(|i| i.id != id),
+        }
+    }
+}
+
+struct Client {
+    http: reqwest::Client,
+    config: &'static OnCallConfig,
+    token: &'static str,
+    /// PagerDuty's id for the token's user, looked up on the first poll.
+    user_id: Option<String>,
+}
+
+impl Client {
+    /// Whether the user is on call, and their open incidents.
+    async fn poll(&mut self) -> Result<(bool, Vec<Incident>), String> {
+        match self.config.provider {
+            OnCallProvider::Pagerduty => {
+                let user_id = match &self.user_id {
+                    Some(id) => id.clone(),
+                    None => {
+                        let body = fetch(self.pagerduty("/users/me")).await?;
+                        let id = parse_pagerduty_user(&body).map_err(|e| e.to_string())?;
+                        self.user_id = Some(id.clone());
+                        id
+                    }
+                };
+                let body = fetch(self.pagerduty("/incidents").query(&[
+                    ("user_ids[]", user_id.as_str()),
+                    ("statuses[]", "triggered"),
+                    ("statuses[]", "acknowledged"),
+                ]))
+                .await?;
+                let incidents = parse_pagerduty_incidents(&body).map_err(|e| e.to_string())?;
+                let body = fetch(
+                    self.pagerduty("/oncalls")
+                        .query(&[("user_ids[]", user_id.as_str())]),
+                )
+                .await?;
+                let on_call = parse_pagerduty_oncalls(&body).map_err(|e| e.to_string())?;
+                Ok((on_call, incidents))
+            }
+            OnCallProvider::Opsgenie => {
+                // Without an owner filter the query lists every open alert in
+                // the org, not just the user's
+                let email = self
+                    .config
+                    .email
+                    .as_deref()
+                    .ok_or("Opsgenie needs [oncall] email")?;
+                let query = format!("status:open AND owner:\"{email}\"");
+                let body = fetch(
+                    self.opsgenie(reqwest::Method::GET, "/v2/alerts")
+                        .query(&[("query", query.as_str()), ("limit", "50")]),
+                )
+                .await?;
+                let incidents = parse_opsgenie_alerts(&body).map_err(|e| e.to_string())?;
+                let on_call = match &self.config.schedule {
+                    Some(schedule) => {
+                        let path = format!("/v2/schedules/{schedule}/on-calls");
+                        let body = fetch(
+                            self.opsgenie(reqwest::Method::GET, &path)
+                                .query(&[("scheduleIdentifierType", "name"), ("flat", "true")]),
+                        )
+                        .await?;
+                        parse_opsgenie_oncalls(&body, email).map_err(|e| e.to_string())?
+                    }
+                    None => false,
+                };
+                Ok((on_call, incidents))
+            }
+        }
+    }
+
+    /// Acknowledge the incident, or resolve (close) it.
+    async fn update(&self, id: &str, resolve: bool) -> Result<(), String> {
+        let request = match self.config.provider {
+            OnCallProvider::Pagerduty => {
+                let mut request = self
+                    .http
+                    .put(format!("{PAGERDUTY_API}/incidents/{id}"))
+                    .header(reqwest::header::AUTHORIZATION, self.pagerduty_auth())
+                    .header(
+                        reqwest::header::ACCEPT,
+                        "application/vnd.pagerduty+json;version=2",
+                    )
+                    .json(&serde_json::json!({
+                        "incident": {
+                            "type": "incident_reference",
+                            "status": if resolve { "resolved" } else { "acknowledged" },
+                        }
+                    }));
+                if let Some(email) = &self.config.email {
+                    request = request.header(reqwest::header::FROM, email);
+                }
+                request
+            }
+            OnCallProvider::Opsgenie => {
+                let action = if resolve { "close" } else { "acknowledge" };
+                self.opsgenie(reqwest::Method::POST, &format!("/v2/alerts/{id}/{action}"))
+                    .json(&serde_json::json!({
+                        "user": self.config.email,
+                        "source": "jb-shell",
+                    }))
+            }
+        };
+        fetch(request).await.map(|_| ())
+    }
+
+    fn pagerduty_auth(&self) -> String {
+        format!("Token token={}", self.token)
+    }
+
+    fn pagerduty(&self, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .get(format!("{PAGERDUTY_API}{path}"))
+            .header(reqwest::header::AUTHORIZATION, self.pagerduty_auth())
+            .header(
+                reqwest::header::ACCEPT,
+                "application/vnd.pagerduty+json;version=2",
+            )
+    }
+
+    fn opsgenie(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .request(
+                method,
+                format!("https://{}{path}", self.config.opsgenie_host),
+            )
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("GenieKey {}", self.token),
+            )
+    }
+}
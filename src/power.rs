@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::widgets::notifications::{
+    ActionCallback, NotificationAction, NotificationId, NotificationInput, NotificationKind,
+    NotificationRequest, NotificationSource,
+};
+
+const LOW_BATTERY_ID: NotificationId = u64::MAX - 0x220;
+
+// Degraded mode: on battery below `power.degraded_below` percent, pollers slow
+// down, workspace previews are skipped and the summary thread stops refreshing.
+static DEGRADED: AtomicBool = AtomicBool::new(false);
+/// Last battery reading; `u32::MAX` until there is one.
+static BATTERY_PERCENT: AtomicU32 = AtomicU32::new(u32::MAX);
+/// The `warn_below` threshold last warned about since the charger was
+/// unplugged. Every bar's battery widget reports the same readings.
+static WARNED: Mutex<Option<u32>> = Mutex::new(None);
+static NOTIFIER: OnceLock<relm4::Sender<NotificationInput>> = OnceLock::new();
+
+/// Where low-battery warnings go, registered once by `Shell`.
+pub fn set_notifier(sender: relm4::Sender<NotificationInput>) {
+    let _ = NOTIFIER.set(sender);
+}
+
+pub fn is_degraded() -> bool {
+    DEGRADED.load(Ordering::Relaxed)
+}
+
+/// Feed a battery reading; returns whether degraded mode is now active.
+pub fn update(discharging: bool, pct: u32) -> bool {
+    BATTERY_PERCENT.store(pct, Ordering::Relaxed);
+    let config = &crate::config::get().power;
+    let degraded = config.degraded_mode && discharging && pct <= config.degraded_below;
+    if DEGRADED.swap(degraded, Ordering::Relaxed) != degraded {
+        eprintln!(
+            "jb-shell: [power] degraded mode {}",
+            if degraded { "on" } else { "off" }
+        );
+    }
+    warn(discharging, pct);
+    degraded
+}
+
+/// Toast each `warn_below` threshold once as the battery runs down, and take
+/// the warning back when the charger is plugged in.
+fn warn(discharging: bool, pct: u32) {
+    let Some(notifier) = NOTIFIER.get() else {
+        return;
+    };
+    let mut warned = WARNED.lock().unwrap_or_else(|e| e.into_inner());
+    if !discharging {
+        if warned.take().is_some() {
+            notifier.emit(NotificationInput::Dismiss(LOW_BATTERY_ID));
+        }
+        return;
+    }
+    let thresholds = &crate::config::get().power.warn_below;
+    if let Some(threshold) = crate::core::power::warning_due(thresholds, pct, *warned) {
+        *warned = Some(threshold);
+        eprintln!("jb-shell: [power] battery at {pct}%, warning");
+        notifier.emit(NotificationInput::Show(low_battery_toast(pct)));
+    }
+}
+
+fn low_battery_toast(pct: u32) -> NotificationRequest {
+    let suspend = crate::config::get().power.suspend_command.clone();
+    let mut actions = Vec::new();
+    if !suspend.is_empty() {
+        actions.push(NotificationAction {
+            label: "Suspend now".to_string(),
+            css_class: "notif-default-action".to_string(),
+            callback: ActionCallback::RunCommand(suspend),
+        });
+    }
+    actions.push(NotificationAction {
+        label: "Dismiss".to_string(),
+        css_class: "notif-action".to_string(),
+        callback: ActionCallback::Dismiss,
+    });
+    NotificationRequest {
+        id: LOW_BATTERY_ID,
+        kind: NotificationKind::Toast,
+        icon: Some(if pct <= 5 {
+            "\u{f244}".to_string()
+        } else {
+            "\u{f243}".to_string()
+        }),
+        title: format!("Battery low: {pct}%"),
+        body: Some("Plug in the charger or suspend to keep your work".to_string()),
+        subtitle: None,
+        countdown_target: None,
+        actions,
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: Some("urgency-critical".to_string()),
+        // Stays up until dismissed or the charger goes in
+        timeout_ms: None,
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    }
+}
+
+/// The last battery reading, `None` on machines without one.
+pub fn battery_percent() -> Option<u32> {
+    let pct = BATTERY_PERCENT.load(Ordering::Relaxed);
+    (pct != u32::MAX).then_some(pct)
+}
+
+/// Stretch a poller's active interval while degraded mode is on.
+pub fn interval(active: Duration) -> Duration {
+    if is_degraded() {
+        active.max(Duration::from_secs(
+            crate::config::get().power.poll_interval_secs,
+        ))
+    } else {
+        active
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use std::sync::Mutex;
+
+use crate::config::MeetingConfig;
+
+/// Slack status from before the meeting, restored when it ends.
+#[derive(Debug, Default, Clone, Deserialize)]
+struct SlackStatus {
+    #[serde(default)]
+    status_text: String,
+    #[serde(default)]
+    status_emoji: String,
+    #[serde(default)]
+    status_expiration: i64,
+}
+
+#[derive(Deserialize)]
+struct ProfileResponse {
+    ok: bool,
+    error: Option<String>,
+    profile: Option<SlackStatus>,
+}
+
+static SAVED_STATUS: Mutex<Option<SlackStatus>> = Mutex::new(None);
+
+/// Called by the calendar alert scheduler when an accepted meeting starts
+/// (`Some(end)`) or ends (`None`). Sets or restores the Slack status if
+/// `[meetings] slack_token` is configured. Called again with the new end
+/// when a back-to-back meeting takes over, so the status doesn't expire early.
+pub fn meeting_changed(meeting_end: Option<DateTime<Local>>) {
+    let config = &crate::config::get().meetings;
+    let Some(token) = config.slack_token.clone() else {
+        return;
+    };
+    crate::runtime::handle().spawn(async move {
+        let config = &crate::config::get().meetings;
+        let client = reqwest::Client::new();
+        let result = match meeting_end {
+            Some(end) => enter_meeting(&client, &token, config, end).await,
+            None => leave_meeting(&client, &token).await,
+        };
+        if let Err(e) = result {
+            eprintln!("jb-shell: [presence] slack status update failed: {e}");
+        }
+    });
+}
+
+async fn enter_meeting(
+    client: &reqwest::Client,
+    token: &str,
+    config: &MeetingConfig,
+    end: DateTime<Local>,
+) -> Result<(), String> {
+    // Back-to-back or overlapping meetings only move the expiration: keep the
+    // status from before the first one rather than saving our own
+    if SAVED_STATUS.lock().is_ok_and(|s| s.is_none()) {
+        let previous = get_status(client, token).await?;
+        if let Ok(mut saved) = SAVED_STATUS.lock() {
+            saved.get_or_insert(previous);
+        }
+    }
+    set_status(
+        client,
+        token,
+        &SlackStatus {
+            status_text: config.slack_status_text.clone(),
+            status_emoji: config.slack_status_emoji.clone(),
+            // Slack clears it by itself if the shell isn't around to restore
+            status_expiration: end.timestamp(),
+        },
+    )
+    .await?;
+    eprintln!("jb-shell: [presence] slack status set for meeting");
+    Ok(())
+}
+
+async fn leave_meeting(client: &reqwest::Client, token: &str) -> Result<(), String> {
+    let Some(previous) = SAVED_STATUS.lock().ok().and_then(|mut s| s.take()) else {
+        return Ok(());
+    };
+    set_status(client, token, &previous).await?;
+    eprintln!("jb-shell: [presence] slack status restored");
+    Ok(())
+}
+
+async fn get_status(client: &reqwest::Client, token: &str) -> Result<SlackStatus, String> {
+    let response: ProfileResponse = client
+        .get("https://slack.com/api/users.profile.get")
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.ok {
+        return Err(response.error.unwrap_or_else(|| "unknown error".into()));
+    }
+    Ok(response.profile.unwrap_or_default())
+}
+
+async fn set_status(
+    client: &reqwest::Client,
+    token: &str,
+    status: &SlackStatus,
+) -> Result<(), String> {
+    let body = serde_json::json!({
+        "profile": {
+            "status_text": status.status_text,
+            "status_emoji": status.status_emoji,
+            "status_expiration": status.status_expiration,
+        }
+    });
+    let response: ProfileResponse = client
+        .post("https://slack.com/api/users.profile.set")
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.ok {
+        return Err(response.error.unwrap_or_else(|| "unknown error".into()));
+    }
+    Ok(())
+}
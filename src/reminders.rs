@@ -0,0 +1,109 @@
+use chrono::{Local, Timelike};
+use std::time::Duration;
+
+use crate::core::schedule::CronSchedule;
+use crate::widgets::notifications::{
+    ActionCallback, NotificationAction, NotificationId, NotificationInput, NotificationKind,
+    NotificationRequest, NotificationSource,
+};
+
+/// Reminder notifications count down from here, one id per `[[reminders]]`
+/// entry so a repeat replaces the last one still showing. The range sits
+/// below every other fixed id (those are all above `u64::MAX - 0x300`).
+const REMINDER_NOTIFICATION_ID: NotificationId = u64::MAX - 0x1000;
+/// Entries past this many are ignored so ids stay inside the range.
+const MAX_REMINDERS: usize = 0x1000;
+
+/// Checks every `[[reminders]]` schedule at the top of each minute and posts
+/// the ones that match. Entries with a bad schedule are logged and skipped.
+pub fn spawn_reminder_thread(notif_sender: relm4::Sender<NotificationInput>) {
+    let reminders: Vec<(usize, CronSchedule)> = crate::config::get()
+        .reminders
+        .iter()
+        .enumerate()
+        .take(MAX_REMINDERS)
+        .filter_map(
+            |(i, reminder)| match CronSchedule::parse(&reminder.schedule) {
+                Ok(schedule) => Some((i, schedule)),
+                Err(e) => {
+                    eprintln!(
+                        "jb-shell: [reminders] ignoring \"{}\": bad schedule `{}`: {e}",
+                        reminder.title, reminder.schedule
+                    );
+                    None
+                }
+            },
+        )
+        .collect();
+    if reminders.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut last_checked = None;
+        loop {
+            // Not idle-aware: a reminder is due at its minute either way.
+            // Minutes slept through in suspend are skipped, not caught up.
+            let now = Local::now();
+            let into_minute = Duration::from_secs(now.second() as u64)
+                + Duration::from_nanos(now.nanosecond() as u64 % 1_000_000_000);
+            std::thread::sleep(Duration::from_secs(60).saturating_sub(into_minute));
+
+            let now = Local::now().naive_local();
+            let minute = now.with_second(0).and_then(|t| t.with_nanosecond(0));
+            if minute == last_checked {
+                continue;
+            }
+            last_checked = minute;
+
+            for (i, schedule) in &reminders {
+                if schedule.matches(now) {
+                    let reminder = &crate::config::get().reminders[*i];
+                    eprintln!("jb-shell: [reminders] {}", reminder.title);
+                    notif_sender.emit(NotificationInput::Show(reminder_notification(
+                        REMINDER_NOTIFICATION_ID - *i as NotificationId,
+                        reminder,
+                    )));
+                }
+            }
+        }
+    });
+}
+
+fn reminder_notification(
+    id: NotificationId,
+    reminder: &crate::config::ReminderConfig,
+) -> NotificationRequest {
+    let mut request = NotificationRequest {
+        id,
+        kind: NotificationKind::Toast,
+        icon: Some("\u{f0f3}".to_string()),
+        title: reminder.title.clone(),
+        body: reminder.body.clone(),
+        subtitle: None,
+        countdown_target: None,
+        actions: Vec::new(),
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: None,
+        // Stays until dismissed, like a meeting alert
+        timeout_ms: None,
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    };
+    if reminder.fullscreen {
+        request.kind = NotificationKind::Fullscreen;
+        request.subtitle = Some(Local::now().format("%H:%M").to_string());
+        request.actions.push(NotificationAction {
+            label: "Dismiss".to_string(),
+            css_class: "dismiss-btn".to_string(),
+            callback: ActionCallback::Dismiss,
+        });
+        request.css_window_name = Some("reminder-fullscreen".to_string());
+        request.css_box_name = None;
+        request.css_card_class = Some("fullscreen-card".to_string());
+    }
+    request
+}
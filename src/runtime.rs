@@ -0,0 +1,19 @@
+use std::sync::OnceLock;
+use tokio::runtime::{Handle, Runtime};
+
+/// Handle to the process-wide tokio runtime that runs every async worker
+/// (calendar, summary, mpris, D-Bus interfaces). Built on first access.
+pub fn handle() -> Handle {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(2)
+                .thread_name("jb-shell-rt")
+                .enable_all()
+                .build()
+                .expect("failed to create tokio runtime")
+        })
+        .handle()
+        .clone()
+}
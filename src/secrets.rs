@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+const SERVICE: &str = "org.freedesktop.secrets";
+const SERVICE_PATH: &str = "/org/freedesktop/secrets";
+const DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+
+pub const CEREBRAS_API_KEY: &str = "cerebras-api-key";
+pub const GITHUB_TOKEN: &str = "github-token";
+pub const GOOGLE_TOKENS: &str = "google-tokens";
+
+/// Session path, parameters, value and content type, as the Secret Service
+/// passes a secret around.
+type Secret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+/// A "plain" transfer session with the Secret Service (gnome-keyring,
+/// KeePassXC, ...). Secrets travel unencrypted over the session bus, which
+/// only our own user can read.
+struct Session {
+    conn: zbus::blocking::Connection,
+    path: OwnedObjectPath,
+}
+
+impl Session {
+    fn open() -> zbus::Result<Self> {
+        let conn = crate::dbus::session_blocking()?;
+        let reply = conn.call_method(
+            Some(SERVICE),
+            SERVICE_PATH,
+            Some("org.freedesktop.Secret.Service"),
+            "OpenSession",
+            &("plain", Value::from("")),
+        )?;
+        let (_, path): (OwnedValue, OwnedObjectPath) = reply.body().deserialize()?;
+        Ok(Session { conn, path })
+    }
+
+    fn call<B>(
+        &self,
+        path: &str,
+        interface: &str,
+        method: &str,
+        body: &B,
+    ) -> zbus::Result<zbus::Message>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        self.conn
+            .call_method(Some(SERVICE), path, Some(interface), method, body)
+    }
+
+    /// Unlocked and locked items stored under `name`.
+    fn search(&self, name: &str) -> zbus::Result<(Vec<OwnedObjectPath>, Vec<OwnedObjectPath>)> {
+        self.call(
+            SERVICE_PATH,
+            "org.freedesktop.Secret.Service",
+            "SearchItems",
+            &(attributes(name),),
+        )?
+        .body()
+        .deserialize()
+    }
+
+    /// Unlock items or collections, asking the user through the keyring's
+    /// own prompt if needed. Without `prompt`, a locked keyring is an error.
+    fn unlock(&self, objects: &[&str], prompt: bool) -> zbus::Result<()> {
+        let objects: Vec<zbus::zvariant::ObjectPath> = objects
+            .iter()
+            .map(|o| zbus::zvariant::ObjectPath::try_from(*o))
+            .collect::<Result<_, _>>()?;
+        let (_, prompt_path): (Vec<OwnedObjectPath>, OwnedObjectPath) = self
+            .call(
+                SERVICE_PATH,
+                "org.freedesktop.Secret.Service",
+                "Unlock",
+                &(objects,),
+            )?
+            .body()
+            .deserialize()?;
+        if prompt_path.as_str() != "/" && !prompt {
+            return Err(zbus::Error::Failure("the keyring is locked".to_string()));
+        }
+        self.prompt(&prompt_path)
+    }
+
+    /// Run a prompt the service handed back ("/" means none was needed) and
+    /// wait for the user to answer it.
+    fn prompt(&self, path: &OwnedObjectPath) -> zbus::Result<()> {
+        if path.as_str() == "/" {
+            return Ok(());
+        }
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface("org.freedesktop.Secret.Prompt")?
+            .member("Completed")?
+            .path(path.as_str())?
+            .build();
+        // Subscribed before prompting so the answer can't be missed
+        let mut completed =
+            zbus::blocking::MessageIterator::for_match_rule(rule, &self.conn, Some(1))?;
+        self.call(
+            path.as_str(),
+            "org.freedesktop.Secret.Prompt",
+            "Prompt",
+            &("",),
+        )?;
+        let message = completed
+            .next()
+            .ok_or_else(|| zbus::Error::Failure("the prompt went away".to_string()))??;
+        let (dismissed, _): (bool, OwnedValue) = message.body().deserialize()?;
+        if dismissed {
+            return Err(zbus::Error::Failure("the prompt was dismissed".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.call(
+            self.path.as_str(),
+            "org.freedesktop.Secret.Session",
+            "Close",
+            &(),
+        );
+    }
+}
+
+fn attributes(name: &str) -> HashMap<&str, &str> {
+    HashMap::from([("application", "jb-shell"), ("name", name)])
+}
+
+/// Whether a Secret Service answers on the session bus (activating it if
+/// it's installed but not running).
+pub fn available() -> bool {
+    Session::open().is_ok()
+}
+
+/// The secret stored under `name`, or `None` if there's none or no Secret
+/// Service to ask; callers fall back to their config files. Blocks, and may
+/// show the keyring's unlock prompt.
+pub fn get(name: &str) -> Option<String> {
+    match lookup(name) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("jb-shell: [secrets] can't read {name} from the Secret Service: {e}");
+            None
+        }
+    }
+}
+
+/// [`get`] from async code, off the runtime's worker threads.
+pub async fn get_async(name: &'static str) -> Option<String> {
+    tokio::task::spawn_blocking(move || get(name))
+        .await
+        .ok()
+        .flatten()
+}
+
+fn lookup(name: &str) -> zbus::Result<Option<String>> {
+    let session = Session::open()?;
+    let (unlocked, locked) = session.search(name)?;
+    let item = match (unlocked.first(), locked.first()) {
+        (Some(item), _) => item,
+        (None, Some(item)) => {
+            session.unlock(&[item.as_str()], true)?;
+            item
+        }
+        (None, None) => return Ok(None),
+    };
+    let (_, _, value, _): Secret = session
+        .call(
+            item.as_str(),
+            "org.freedesktop.Secret.Item",
+            "GetSecret",
+            &(&session.path,),
+        )?
+        .body()
+        .deserialize()?;
+    String::from_utf8(value)
+        .map(Some)
+        .map_err(|_| zbus::Error::Failure(format!("{name} isn't UTF-8")))
+}
+
+/// Store `value` under `name` in the default collection, replacing what was
+/// there. `prompt` allows the keyring's unlock prompt; without it a locked
+/// keyring fails instead of blocking on the user.
+pub fn set(name: &str, value: &str, prompt: bool) -> zbus::Result<()> {
+    let session = Session::open()?;
+    session.unlock(&[DEFAULT_COLLECTION], prompt)?;
+    let label = format!("jb-shell {name}");
+    let properties: HashMap<&str, Value> = HashMap::from([
+        (
+            "org.freedesktop.Secret.Item.Label",
+            Value::from(label.as_str()),
+        ),
+        (
+            "org.freedesktop.Secret.Item.Attributes",
+            Value::from(attributes(name)),
+        ),
+    ]);
+    let secret = (
+        &session.path,
+        Vec::<u8>::new(),
+        value.as_bytes(),
+        "text/plain",
+    );
+    let (_, prompt_path): (OwnedObjectPath, OwnedObjectPath) = session
+        .call(
+            DEFAULT_COLLECTION,
+            "org.freedesktop.Secret.Collection",
+            "CreateItem",
+            &(properties, secret, true),
+        )?
+        .body()
+        .deserialize()?;
+    session.prompt(&prompt_path)
+}
+
+/// Remove every item stored under `name`; `false` if there was none.
+pub fn delete(name: &str) -> zbus::Result<bool> {
+    let session = Session::open()?;
+    let (unlocked, locked) = session.search(name)?;
+    if !locked.is_empty() {
+        let paths: Vec<&str> = locked.iter().map(|p| p.as_str()).collect();
+        session.unlock(&paths, true)?;
+    }
+    for item in unlocked.iter().chain(&locked) {
+        let prompt_path: OwnedObjectPath = session
+            .call(item.as_str(), "org.freedesktop.Secret.Item", "Delete", &())?
+            .body()
+            .deserialize()?;
+        session.prompt(&prompt_path)?;
+    }
+    Ok(!unlocked.is_empty() || !locked.is_empty())
+}
@@ -0,0 +1,577 @@
+use gdk4::Monitor;
+use relm4::{Component, ComponentController, Controller};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::caffeine::CaffeineCommand;
+use crate::display_profiles::DisplayCommand;
+use crate::focus_mode::FocusCommand;
+use crate::google_calendar::{self, CalendarResult, CalendarThreadMsg};
+use crate::i3bar::I3barClick;
+use crate::night_light::NightLightCommand;
+use crate::oncall::OnCallCommand;
+use crate::summary_thread::SummaryThreadMsg;
+use crate::widgets::caffeine::CaffeineInput;
+use crate::widgets::calendar::{CalendarAlertsModel, CalendarInput};
+use crate::widgets::ci::CiInput;
+use crate::widgets::clock::ClockInput;
+use crate::widgets::color_picker::{ColorPickerInit, ColorPickerInput, ColorPickerModel};
+use crate::widgets::custom::CustomInput;
+use crate::widgets::display_profiles::DisplayInput;
+use crate::widgets::focus_mode::FocusInput;
+use crate::widgets::gcloud_config::GcloudProvider;
+use crate::widgets::git::GitInput;
+use crate::widgets::i3bar::I3barInput;
+use crate::widgets::keybinds::{KeybindsInput, KeybindsModel};
+use crate::widgets::kube_context::KubeProvider;
+use crate::widgets::launcher::{LauncherInit, LauncherInput, LauncherModel};
+use crate::widgets::metrics::MetricsInput;
+use crate::widgets::mpris::MprisInput;
+use crate::widgets::network::NetworkInput;
+use crate::widgets::night_light::NightLightInput;
+use crate::widgets::notification_center::NotificationCenterInput;
+use crate::widgets::notifications::{NotificationInput, NotificationModel};
+use crate::widgets::oncall::OnCallInput;
+use crate::widgets::osd::{OsdInput, OsdModel};
+use crate::widgets::setup_wizard::SetupWizardModel;
+use crate::widgets::switcher::{spawn_switcher_thread, SwitcherInput, SwitcherProvider};
+use crate::widgets::toolchains::{
+    NodeProvider, RustupProvider, TerraformWorkspaceProvider, TfenvProvider,
+};
+use crate::widgets::volume::VolumeInput;
+use crate::widgets::wallpaper::{WallpaperInput, WallpaperModel};
+use crate::widgets::window_switcher::{WindowSwitcherInput, WindowSwitcherModel};
+
+/// Thread-safe fan-out from a singleton service to per-bar components.
+///
+/// The last published message is retained and replayed to late subscribers, so a
+/// bar added on hotplug starts with current state. Senders whose component has
+/// been dropped (bar removed) are pruned on the next publish.
+pub struct Subscribers<T> {
+    inner: Arc<Mutex<SubscribersInner<T>>>,
+}
+
+struct SubscribersInner<T> {
+    senders: Vec<relm4::Sender<T>>,
+    last: Option<T>,
+}
+
+impl<T> Clone for Subscribers<T> {
+    fn clone(&self) -> Self {
+        Subscribers {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Subscribers {
+            inner: Arc::new(Mutex::new(SubscribersInner {
+                senders: Vec::new(),
+                last: None,
+            })),
+        }
+    }
+}
+
+impl<T: Clone> Subscribers<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self, sender: relm4::Sender<T>) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if let Some(last) = &inner.last {
+            if sender.send(last.clone()).is_err() {
+                return;
+            }
+        }
+        inner.senders.push(sender);
+    }
+
+    pub fn publish(&self, msg: T) {
+        crate::metrics::widget_update(std::any::type_name::<T>());
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.senders.retain(|tx| tx.send(msg.clone()).is_ok());
+        inner.last = Some(msg);
+    }
+}
+
+/// Google Calendar: one API thread, one alert scheduler, many bar indicators.
+#[derive(Clone)]
+pub struct CalendarHandle {
+    pub thread_tx: tokio::sync::mpsc::Sender<CalendarThreadMsg>,
+    pub subscribers: Subscribers<CalendarInput>,
+}
+
+/// AI summary thread shared by every bar's notification center.
+#[derive(Clone)]
+pub struct SummaryHandle {
+    pub thread_tx: tokio::sync::mpsc::Sender<SummaryThreadMsg>,
+    pub subscribers: Subscribers<NotificationCenterInput>,
+}
+
+/// Night light: one thread owning the gamma backend, a toggle on every bar.
+#[derive(Clone)]
+pub struct NightLightHandle {
+    pub thread_tx: std::sync::mpsc::Sender<NightLightCommand>,
+    pub subscribers: Subscribers<NightLightInput>,
+}
+
+/// Idle inhibitor: one thread holding the D-Bus inhibit, a toggle on every bar.
+#[derive(Clone)]
+pub struct CaffeineHandle {
+    pub thread_tx: std::sync::mpsc::Sender<CaffeineCommand>,
+    pub subscribers: Subscribers<CaffeineInput>,
+}
+
+/// Focus mode: one thread parking windows and holding DND, a toggle on every bar.
+#[derive(Clone)]
+pub struct FocusHandle {
+    pub thread_tx: std::sync::mpsc::Sender<FocusCommand>,
+    pub subscribers: Subscribers<FocusInput>,
+}
+
+/// Display profiles: one thread driving `hyprctl keyword monitor`, a picker on
+/// every bar.
+#[derive(Clone)]
+pub struct DisplayHandle {
+    pub thread_tx: std::sync::mpsc::Sender<DisplayCommand>,
+    pub subscribers: Subscribers<DisplayInput>,
+}
+
+/// i3bar bridge: one status generator process, its blocks on every bar.
+#[derive(Clone)]
+pub struct I3barHandle {
+    pub thread_tx: std::sync::mpsc::Sender<I3barClick>,
+    pub subscribers: Subscribers<I3barInput>,
+}
+
+/// PagerDuty/Opsgenie: one API poller, an incident list on every bar.
+#[derive(Clone)]
+pub struct OnCallHandle {
+    pub thread_tx: tokio::sync::mpsc::Sender<OnCallCommand>,
+    pub subscribers: Subscribers<OnCallInput>,
+}
+
+/// kube/gcloud/toolchain switchers: one CLI poller per provider, a picker on
+/// every bar.
+/// `thread_tx` takes the item to switch to.
+#[derive(Clone)]
+pub struct SwitcherHandle {
+    pub thread_tx: std::sync::mpsc::Sender<String>,
+    pub subscribers: Subscribers<SwitcherInput>,
+}
+
+/// App-level coordinator owning the process-wide singletons. Per-monitor
+/// `StatusBar`s only hold views that subscribe to these.
+pub struct Shell {
+    notifications: Controller<NotificationModel>,
+    _calendar_alerts: Controller<CalendarAlertsModel>,
+    launcher: Controller<LauncherModel>,
+    keybinds: Controller<KeybindsModel>,
+    color_picker: Controller<ColorPickerModel>,
+    window_switcher: Controller<WindowSwitcherModel>,
+    /// Volume and brightness popup for the media keys.
+    osd: Controller<OsdModel>,
+    /// First-run wizard, only while there's no `config.toml`.
+    _setup_wizard: Option<Controller<SetupWizardModel>>,
+    wallpaper: Controller<WallpaperModel>,
+    /// Monitor the launcher/keybinds overlays live on.
+    overlay_monitor: RefCell<Monitor>,
+    pub calendar: CalendarHandle,
+    pub timezone: Subscribers<ClockInput>,
+    pub summary: SummaryHandle,
+    pub mpris: Subscribers<MprisInput>,
+    pub night_light: NightLightHandle,
+    pub caffeine: CaffeineHandle,
+    pub focus: FocusHandle,
+    pub display: DisplayHandle,
+    pub net_usage: Subscribers<NetworkInput>,
+    pub net_latency: Subscribers<NetworkInput>,
+    pub audio_output: Subscribers<VolumeInput>,
+    /// `[custom.<name>]` script feeds, keyed by name.
+    pub custom: HashMap<String, Subscribers<CustomInput>>,
+    pub i3bar: I3barHandle,
+    pub kube: SwitcherHandle,
+    pub gcloud: SwitcherHandle,
+    pub terraform: SwitcherHandle,
+    pub terraform_workspace: SwitcherHandle,
+    pub node: SwitcherHandle,
+    pub rustup: SwitcherHandle,
+    pub git: Subscribers<GitInput>,
+    pub ci: Subscribers<CiInput>,
+    pub oncall: OnCallHandle,
+    pub metrics: Subscribers<MetricsInput>,
+}
+
+impl Shell {
+    pub fn new(primary_monitor: &Monitor) -> Self {
+        let notifications = NotificationModel::builder()
+            .launch(primary_monitor.clone())
+            .detach();
+        let notif_sender = notifications.sender().clone();
+        let rt = crate::runtime::handle();
+        crate::power::set_notifier(notif_sender.clone());
+
+        // Notification daemon
+        let daemon_tx = crate::notification_daemon::spawn_notification_daemon(notif_sender.clone());
+        notif_sender.emit(NotificationInput::SetDaemonChannel(daemon_tx));
+
+        // Calendar thread → alert scheduler + bar indicators + meetings gauge
+        let calendar_subscribers = Subscribers::<CalendarInput>::new();
+        let publish = calendar_subscribers.clone();
+        let calendar_tx = google_calendar::spawn_calendar_thread(&rt, move |result| {
+            if let CalendarResult::EventsUpdated(events) = &result {
+                crate::metrics::set_calendar_events(events.clone());
+            }
+            publish.publish(match result {
+                CalendarResult::EventsUpdated(e) => CalendarInput::EventsUpdated(e),
+                CalendarResult::AuthComplete => CalendarInput::AuthComplete,
+                CalendarResult::AuthFailed(s) => CalendarInput::AuthFailed(s),
+                CalendarResult::AuthRevoked => CalendarInput::AuthRevoked,
+                CalendarResult::NeedsAuth => CalendarInput::NeedsAuth,
+                CalendarResult::NoCredentials => CalendarInput::NoCredentials,
+            });
+        });
+
+        // Timezone changes → bar clocks, calendar times, a banner toast
+        let timezone = Subscribers::<ClockInput>::new();
+        let publish = timezone.clone();
+        let zone_calendar_tx = calendar_tx.clone();
+        crate::timezone::spawn_timezone_thread(notif_sender.clone(), move |zone| {
+            let _ = zone_calendar_tx.try_send(CalendarThreadMsg::TimezoneChanged);
+            publish.publish(ClockInput::TimezoneChanged(zone));
+        });
+
+        let calendar_alerts = CalendarAlertsModel::builder()
+            .launch(notif_sender.clone())
+            .detach();
+        calendar_subscribers.subscribe(calendar_alerts.sender().clone());
+
+        // Summary thread → notification centers
+        let summary_subscribers = Subscribers::<NotificationCenterInput>::new();
+        let publish = summary_subscribers.clone();
+        let summary_tx = crate::summary_thread::spawn_summary_thread(&rt, move |result| {
+            publish.publish(NotificationCenterInput::SummaryResult(result));
+        });
+
+        // MPRIS poller → bar players
+        let mpris = Subscribers::<MprisInput>::new();
+        crate::widgets::mpris::spawn_mpris_thread(&rt, mpris.clone());
+
+        // dev.jb.shell.State ← MPRIS (other fields are set where they change)
+        crate::state_dbus::spawn_state_dbus(&mpris);
+
+        // Caffeine thread ← focus + MPRIS video, → bar toggles
+        let caffeine_subscribers = Subscribers::<CaffeineInput>::new();
+        let publish = caffeine_subscribers.clone();
+        let caffeine_tx = crate::caffeine::spawn_caffeine_thread(move |reason| {
+            publish.publish(CaffeineInput::State(reason));
+        });
+        let (mpris_tx, mpris_rx) = relm4::channel::<MprisInput>();
+        mpris.subscribe(mpris_tx);
+        let video_tx = caffeine_tx.clone();
+        rt.spawn(async move {
+            while let Some(msg) = mpris_rx.recv().await {
+                let player = match msg {
+                    MprisInput::Update {
+                        video: true,
+                        focus_hints,
+                        ..
+                    } => Some(focus_hints.last().cloned().unwrap_or_default()),
+                    _ => None,
+                };
+                if video_tx.send(CaffeineCommand::Video(player)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Focus mode thread ← calendar, → bar toggles + DND
+        let focus_subscribers = Subscribers::<FocusInput>::new();
+        let publish = focus_subscribers.clone();
+        let focus_tx = crate::focus_mode::spawn_focus_thread(notif_sender.clone(), move |state| {
+            publish.publish(FocusInput::State(state));
+        });
+        let (calendar_feed, calendar_rx) = relm4::channel::<CalendarInput>();
+        calendar_subscribers.subscribe(calendar_feed);
+        let events_tx = focus_tx.clone();
+        rt.spawn(async move {
+            while let Some(msg) = calendar_rx.recv().await {
+                let CalendarInput::EventsUpdated(events) = msg else {
+                    continue;
+                };
+                if events_tx.send(FocusCommand::Events(events)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Night light thread → bar toggles
+        let night_light_subscribers = Subscribers::<NightLightInput>::new();
+        let publish = night_light_subscribers.clone();
+        let night_light_tx = crate::night_light::spawn_night_light_thread(move |state| {
+            publish.publish(NightLightInput::State(state));
+        });
+
+        // Location ([location] or GeoClue) → night light schedule, commute
+        // weather
+        let location_tx = night_light_tx.clone();
+        let weather_tx = crate::weather::spawn_weather_thread(&rt);
+        crate::location::spawn_location_thread(move |location| {
+            let _ = location_tx.send(NightLightCommand::Location(location));
+            let _ = weather_tx.try_send(location);
+        });
+
+        // Display profile thread ← hotplug, → bar pickers
+        let display_subscribers = Subscribers::<DisplayInput>::new();
+        let publish = display_subscribers.clone();
+        let display_tx = crate::display_profiles::spawn_display_thread(move |state| {
+            publish.publish(DisplayInput::State(state));
+        });
+
+        // Data usage sampler → bar network popups, cap warnings → toasts
+        let net_usage = Subscribers::<NetworkInput>::new();
+        let publish = net_usage.clone();
+        crate::net_usage::spawn_usage_thread(notif_sender.clone(), move |state| {
+            publish.publish(NetworkInput::Usage(state));
+        });
+
+        // Optional ping monitor → bar network health
+        let net_latency = Subscribers::<NetworkInput>::new();
+        let publish = net_latency.clone();
+        crate::latency::spawn_latency_thread(move |state| {
+            publish.publish(NetworkInput::Latency(state));
+        });
+
+        // Audio port watcher → bar volume tooltips, switches → undo toasts
+        let audio_output = Subscribers::<VolumeInput>::new();
+        let publish = audio_output.clone();
+        crate::audio_ports::spawn_audio_port_thread(notif_sender.clone(), move |output| {
+            publish.publish(VolumeInput::Output(output));
+        });
+
+        // Custom module scripts → bar `custom/<name>` slots
+        let custom = crate::custom_modules::spawn_custom_modules();
+
+        // `[[reminders]]` schedules → toasts or fullscreen alerts
+        crate::reminders::spawn_reminder_thread(notif_sender.clone());
+
+        // i3bar-protocol generator → bar `i3bar` slots, clicks back to it
+        let i3bar_subscribers = Subscribers::<I3barInput>::new();
+        let publish = i3bar_subscribers.clone();
+        let i3bar_tx = crate::i3bar::spawn_i3bar_thread(move |blocks| {
+            publish.publish(I3barInput::Blocks(blocks));
+        });
+
+        // Switcher CLI pollers → bar switchers, one each however many bars
+        let kube = switcher_handle::<KubeProvider>();
+        let gcloud = switcher_handle::<GcloudProvider>();
+        let terraform = toolchain_handle::<TfenvProvider>("terraform");
+        let terraform_workspace =
+            toolchain_handle::<TerraformWorkspaceProvider>("terraform-workspace");
+        let node = toolchain_handle::<NodeProvider>("node");
+        let rustup = toolchain_handle::<RustupProvider>("rustup");
+
+        // `[git] repos` poller → bar git widgets
+        let git = Subscribers::<GitInput>::new();
+        let publish = git.clone();
+        crate::git_status::spawn_git_thread(move |repos| {
+            publish.publish(GitInput::Repos(repos));
+        });
+
+        // `[[ci.pipelines]]` poller → bar CI widgets, failure toasts
+        let ci = Subscribers::<CiInput>::new();
+        let publish = ci.clone();
+        crate::ci::spawn_ci_thread(&rt, notif_sender.clone(), move |pipelines| {
+            publish.publish(CiInput::Pipelines(pipelines));
+        });
+
+        // `[oncall]` incident poller → bar on-call widgets
+        let oncall_subscribers = Subscribers::<OnCallInput>::new();
+        let publish = oncall_subscribers.clone();
+        let oncall_tx = crate::oncall::spawn_oncall_thread(&rt, move |state| {
+            publish.publish(OnCallInput::State(state));
+        });
+
+        // `[metrics]` audit sampler → bar metrics readouts
+        let metrics = Subscribers::<MetricsInput>::new();
+        let publish = metrics.clone();
+        crate::metrics::spawn_metrics_thread(move |sample| {
+            publish.publish(MetricsInput::Sample(sample));
+        });
+
+        // Per-monitor wallpapers; the launcher's `wp` provider picks them
+        let wallpaper = WallpaperModel::builder().launch(()).detach();
+
+        // Global application launcher and keybind cheat-sheet (D-Bus activated)
+        let launcher = LauncherModel::builder()
+            .launch(LauncherInit {
+                monitor: primary_monitor.clone(),
+                wallpaper: wallpaper.sender().clone(),
+            })
+            .detach();
+        let keybinds = KeybindsModel::builder()
+            .launch(primary_monitor.clone())
+            .detach();
+        let color_picker = ColorPickerModel::builder()
+            .launch(ColorPickerInit {
+                monitor: primary_monitor.clone(),
+                notif_sender: notif_sender.clone(),
+            })
+            .detach();
+        let window_switcher = WindowSwitcherModel::builder()
+            .launch(primary_monitor.clone())
+            .detach();
+
+        // Media keys (`jb-shell key`) → volume, brightness, players + OSD
+        let osd = OsdModel::builder().launch(primary_monitor.clone()).detach();
+        crate::media_keys::spawn_media_keys(osd.sender().clone());
+
+        let setup_wizard = crate::widgets::setup_wizard::should_show().then(|| {
+            SetupWizardModel::builder()
+                .launch(primary_monitor.clone())
+                .detach()
+        });
+
+        crate::actions::set_targets(crate::actions::ActionTargets {
+            launcher: launcher.sender().clone(),
+            keybinds: keybinds.sender().clone(),
+            color_picker: color_picker.sender().clone(),
+            notifications: notif_sender.clone(),
+        });
+
+        Shell {
+            notifications,
+            _calendar_alerts: calendar_alerts,
+            launcher,
+            keybinds,
+            color_picker,
+            window_switcher,
+            osd,
+            _setup_wizard: setup_wizard,
+            wallpaper,
+            overlay_monitor: RefCell::new(primary_monitor.clone()),
+            calendar: CalendarHandle {
+                thread_tx: calendar_tx,
+                subscribers: calendar_subscribers,
+            },
+            timezone,
+            summary: SummaryHandle {
+                thread_tx: summary_tx,
+                subscribers: summary_subscribers,
+            },
+            mpris,
+            night_light: NightLightHandle {
+                thread_tx: night_light_tx,
+                subscribers: night_light_subscribers,
+            },
+            caffeine: CaffeineHandle {
+                thread_tx: caffeine_tx,
+                subscribers: caffeine_subscribers,
+            },
+            focus: FocusHandle {
+                thread_tx: focus_tx,
+                subscribers: focus_subscribers,
+            },
+            display: DisplayHandle {
+                thread_tx: display_tx,
+                subscribers: display_subscribers,
+            },
+            net_usage,
+            net_latency,
+            audio_output,
+            custom,
+            i3bar: I3barHandle {
+                thread_tx: i3bar_tx,
+                subscribers: i3bar_subscribers,
+            },
+            kube,
+            gcloud,
+            terraform,
+            terraform_workspace,
+            node,
+            rustup,
+            git,
+            ci,
+            oncall: OnCallHandle {
+                thread_tx: oncall_tx,
+                subscribers: oncall_subscribers,
+            },
+            metrics,
+        }
+    }
+
+    pub fn notification_sender(&self) -> &relm4::Sender<NotificationInput> {
+        self.notifications.sender()
+    }
+
+    /// Hyprland focus changed — feeds the caffeine app rules.
+    pub fn set_focused_class(&self, class: &str) {
+        let _ = self
+            .caffeine
+            .thread_tx
+            .send(CaffeineCommand::Focused(class.to_string()));
+    }
+
+    /// Hyprland added or removed a monitor — may switch display profiles.
+    pub fn monitors_changed(&self) {
+        let _ = self.display.thread_tx.send(DisplayCommand::Hotplug);
+    }
+
+    /// Called whenever the set of bars changes (startup, hotplug). Moves toasts
+    /// and overlays off monitors that disappeared.
+    pub fn set_monitors(&self, monitors: Vec<Monitor>) {
+        if let Some(first) = monitors.first() {
+            let mut overlay_monitor = self.overlay_monitor.borrow_mut();
+            if !monitors.contains(&overlay_monitor) {
+                eprintln!(
+                    "jb-shell: [monitor] moving launcher/keybinds overlays to surviving monitor"
+                );
+                *overlay_monitor = first.clone();
+                self.launcher.emit(LauncherInput::SetMonitor(first.clone()));
+                self.keybinds.emit(KeybindsInput::SetMonitor(first.clone()));
+                self.color_picker
+                    .emit(ColorPickerInput::SetMonitor(first.clone()));
+                self.window_switcher
+                    .emit(WindowSwitcherInput::SetMonitor(first.clone()));
+                self.osd.emit(OsdInput::SetMonitor(first.clone()));
+            }
+        }
+        self.wallpaper
+            .emit(WallpaperInput::SetMonitors(monitors.clone()));
+        self.notification_sender()
+            .emit(NotificationInput::SetMonitors(monitors));
+    }
+}
+
+/// A toolchain switcher's poller, started only if the bar layout places
+/// `layout_name`; otherwise its bars get a feed that never publishes.
+fn toolchain_handle<P: SwitcherProvider>(layout_name: &str) -> SwitcherHandle {
+    if !crate::config::get().bar.places(layout_name) {
+        return SwitcherHandle {
+            thread_tx: std::sync::mpsc::channel().0,
+            subscribers: Subscribers::new(),
+        };
+    }
+    switcher_handle::<P>()
+}
+
+fn switcher_handle<P: SwitcherProvider>() -> SwitcherHandle {
+    let subscribers = Subscribers::<SwitcherInput>::new();
+    let publish = subscribers.clone();
+    let thread_tx = spawn_switcher_thread::<P>(move |current, items| {
+        publish.publish(SwitcherInput::PollResult { current, items });
+    });
+    SwitcherHandle {
+        thread_tx,
+        subscribers,
+    }
+}
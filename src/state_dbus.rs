@@ -0,0 +1,183 @@
+use hyprland::data::{Client, Workspace};
+use hyprland::shared::{HyprDataActive, HyprDataActiveOptional};
+use std::sync::OnceLock;
+use tokio::sync::mpsc;
+
+use crate::shell::Subscribers;
+use crate::widgets::mpris::MprisInput;
+
+const PATH: &str = "/dev/jb/shell/State";
+const NAME: &str = "dev.jb.shell.State";
+
+/// A new value for one `dev.jb.shell.State` property.
+#[derive(Debug)]
+pub enum StateChange {
+    FocusedWorkspace(i32),
+    ActiveWindow {
+        title: String,
+        class: String,
+    },
+    Dnd(bool),
+    UnreadCount(u32),
+    /// "Artist — Title", empty when nothing plays.
+    CurrentTrack(String),
+    InMeeting(bool),
+}
+
+static UPDATES: OnceLock<mpsc::UnboundedSender<StateChange>> = OnceLock::new();
+
+/// Report a change from anywhere; a no-op before the service starts.
+/// Repeats of the current value don't signal.
+pub fn set(change: StateChange) {
+    if let Some(tx) = UPDATES.get() {
+        let _ = tx.send(change);
+    }
+}
+
+#[derive(Default)]
+struct StateDbus {
+    focused_workspace: i32,
+    active_window_title: String,
+    active_window_class: String,
+    dnd: bool,
+    unread_count: u32,
+    current_track: String,
+    in_meeting: bool,
+}
+
+#[zbus::interface(name = "dev.jb.shell.State")]
+impl StateDbus {
+    #[zbus(property)]
+    fn focused_workspace(&self) -> i32 {
+        self.focused_workspace
+    }
+
+    #[zbus(property)]
+    fn active_window_title(&self) -> String {
+        self.active_window_title.clone()
+    }
+
+    #[zbus(property)]
+    fn active_window_class(&self) -> String {
+        self.active_window_class.clone()
+    }
+
+    #[zbus(property)]
+    fn dnd(&self) -> bool {
+        self.dnd
+    }
+
+    #[zbus(property)]
+    fn unread_count(&self) -> u32 {
+        self.unread_count
+    }
+
+    #[zbus(property)]
+    fn current_track(&self) -> String {
+        self.current_track.clone()
+    }
+
+    #[zbus(property)]
+    fn in_meeting(&self) -> bool {
+        self.in_meeting
+    }
+}
+
+/// Export `dev.jb.shell.State`, seeded from Hyprland, and emit
+/// `PropertiesChanged` for every [`set`] that changes a value. The current
+/// track comes straight from the shell's MPRIS feed.
+pub fn spawn_state_dbus(mpris: &Subscribers<MprisInput>) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    if UPDATES.set(tx).is_err() {
+        return;
+    }
+
+    let mut initial = StateDbus::default();
+    if let Ok(workspace) = Workspace::get_active() {
+        initial.focused_workspace = workspace.id;
+    }
+    if let Ok(Some(client)) = Client::get_active() {
+        initial.active_window_title = client.title;
+        initial.active_window_class = client.class;
+    }
+
+    let rt = crate::runtime::handle();
+    let (mpris_tx, mpris_rx) = relm4::channel::<MprisInput>();
+    mpris.subscribe(mpris_tx);
+    rt.spawn(async move {
+        while let Some(msg) = mpris_rx.recv().await {
+            match msg {
+                MprisInput::Update { artist, title, .. } if artist.is_empty() => {
+                    set(StateChange::CurrentTrack(title));
+                }
+                MprisInput::Update { artist, title, .. } => {
+                    set(StateChange::CurrentTrack(format!("{artist} — {title}")));
+                }
+                MprisInput::Inactive => set(StateChange::CurrentTrack(String::new())),
+                MprisInput::Raise => {}
+            }
+        }
+    });
+
+    rt.spawn(async move {
+        if let Err(e) = crate::dbus::register(PATH, NAME, initial).await {
+            eprintln!("jb-shell: [dbus] failed to serve {NAME}: {e}");
+            return;
+        }
+        eprintln!("jb-shell: [dbus] serving {NAME} at {PATH}");
+        let state = match crate::dbus::session().await {
+            Ok(conn) => conn.object_server().interface::<_, StateDbus>(PATH).await,
+            Err(e) => Err(e),
+        };
+        let state = match state {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("jb-shell: [dbus] lost {NAME} after registering: {e}");
+                return;
+            }
+        };
+
+        while let Some(change) = rx.recv().await {
+            let emitter = state.signal_emitter();
+            let mut iface = state.get_mut().await;
+            let result = match change {
+                StateChange::FocusedWorkspace(id) if iface.focused_workspace != id => {
+                    iface.focused_workspace = id;
+                    iface.focused_workspace_changed(emitter).await
+                }
+                StateChange::ActiveWindow { title, class } => {
+                    let mut result = Ok(());
+                    if iface.active_window_title != title {
+                        iface.active_window_title = title;
+                        result = iface.active_window_title_changed(emitter).await;
+                    }
+                    if iface.active_window_class != class {
+                        iface.active_window_class = class;
+                        result = result.and(iface.active_window_class_changed(emitter).await);
+                    }
+                    result
+                }
+                StateChange::Dnd(on) if iface.dnd != on => {
+                    iface.dnd = on;
+                    iface.dnd_changed(emitter).await
+                }
+                StateChange::UnreadCount(count) if iface.unread_count != count => {
+                    iface.unread_count = count;
+                    iface.unread_count_changed(emitter).await
+                }
+                StateChange::CurrentTrack(track) if iface.current_track != track => {
+                    iface.current_track = track;
+                    iface.current_track_changed(emitter).await
+                }
+                StateChange::InMeeting(on) if iface.in_meeting != on => {
+                    iface.in_meeting = on;
+                    iface.in_meeting_changed(emitter).await
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                eprintln!("jb-shell: [dbus] failed to signal {NAME} change: {e}");
+            }
+        }
+    });
+}
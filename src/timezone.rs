@@ -0,0 +1,94 @@
+use chrono::Local;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use zbus::zvariant::OwnedValue;
+
+use crate::widgets::notifications::{
+    NotificationId, NotificationInput, NotificationKind, NotificationRequest, NotificationSource,
+};
+
+const TIMEDATED: &str = "org.freedesktop.timedate1";
+const ZONE_NOTIFICATION_ID: NotificationId = u64::MAX - 0xf0;
+
+/// Calls `publish` with the new zone name (e.g. "Asia/Tokyo") whenever
+/// systemd-timedated changes the system timezone, as `timedatectl
+/// set-timezone` and automatic-timezone tools do, and toasts the change.
+pub fn spawn_timezone_thread(
+    notif_sender: relm4::Sender<NotificationInput>,
+    publish: impl Fn(String) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let changed = |zone: String| {
+            notif_sender.emit(NotificationInput::Show(banner(&zone)));
+            publish(zone);
+        };
+        if let Err(e) = follow_timedated(&changed) {
+            eprintln!("jb-shell: [timezone] not watching timedated: {e}");
+        }
+    });
+}
+
+fn follow_timedated(publish: &impl Fn(String)) -> zbus::Result<()> {
+    let conn = zbus::blocking::Connection::system()?;
+    let rule = zbus::MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .sender(TIMEDATED)?
+        .path("/org/freedesktop/timedate1")?
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .build();
+    for message in zbus::blocking::MessageIterator::for_match_rule(rule, &conn, None)? {
+        let (interface, changed, invalidated): (String, HashMap<String, OwnedValue>, Vec<String>) =
+            message?.body().deserialize()?;
+        if interface != TIMEDATED {
+            continue;
+        }
+        let zone = match changed.get("Timezone") {
+            Some(value) => String::try_from(value.try_clone()?).ok(),
+            None if invalidated.iter().any(|p| p == "Timezone") => localtime_zone(),
+            None => continue,
+        };
+        // chrono re-reads /etc/localtime at most once a second, so give
+        // `Local` time to notice before anyone re-renders with it
+        std::thread::sleep(Duration::from_millis(1100));
+        let zone = zone.unwrap_or_else(|| "local time".to_string());
+        eprintln!("jb-shell: [timezone] now {zone}");
+        publish(zone);
+    }
+    Ok(())
+}
+
+fn banner(zone: &str) -> NotificationRequest {
+    let now = Local::now();
+    NotificationRequest {
+        id: ZONE_NOTIFICATION_ID,
+        kind: NotificationKind::Toast,
+        icon: Some("\u{f0ac}".to_string()),
+        title: format!("Timezone changed to {zone}"),
+        body: Some(format!(
+            "It's {} (UTC{}); the clock and calendar follow",
+            now.format("%-I:%M %p"),
+            now.format("%:z")
+        )),
+        subtitle: None,
+        countdown_target: None,
+        actions: Vec::new(),
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: None,
+        timeout_ms: Some(6000),
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    }
+}
+
+/// "Europe/Berlin" from the `/etc/localtime` symlink.
+fn localtime_zone() -> Option<String> {
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    let (_, zone) = target.split_once("zoneinfo/")?;
+    Some(zone.to_string())
+}
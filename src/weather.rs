@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::core::commute::{parse_open_meteo, Forecast};
+use crate::location::Location;
+
+const OPEN_METEO: &str = "https://api.open-meteo.com/v1/forecast";
+const REFRESH: Duration = Duration::from_secs(30 * 60);
+
+static FORECAST: Mutex<Forecast> = Mutex::new(Vec::new());
+
+/// The last hourly forecast fetched; empty until one arrives, or when
+/// `[commute]` doesn't ask for weather.
+pub fn forecast() -> Forecast {
+    FORECAST.lock().map(|f| f.clone()).unwrap_or_default()
+}
+
+/// Fetches the hourly forecast from Open-Meteo for each location sent in,
+/// and again every half hour, for commute hints. Nothing runs unless
+/// `[commute] buffer_minutes` is set and `weather` is on.
+pub fn spawn_weather_thread(rt: &tokio::runtime::Handle) -> mpsc::Sender<Location> {
+    let (tx, rx) = mpsc::channel(4);
+    let config = &crate::config::get().commute;
+    if config.buffer_minutes > 0 && config.weather {
+        rt.spawn(weather_thread_main(rx));
+    }
+    tx
+}
+
+async fn weather_thread_main(mut rx: mpsc::Receiver<Location>) {
+    let http = reqwest::Client::new();
+    let Some(mut location) = rx.recv().await else {
+        return;
+    };
+    loop {
+        if !crate::widgets::network::is_offline() {
+            match fetch(&http, location).await {
+                Ok(forecast) => {
+                    if let Ok(mut f) = FORECAST.lock() {
+                        *f = forecast;
+                    }
+                }
+                Err(e) => eprintln!("jb-shell: [weather] forecast failed: {e}"),
+            }
+        }
+        match tokio::time::timeout(crate::idle::interval(REFRESH), rx.recv()).await {
+            Err(_) => {}
+            Ok(None) => return,
+            Ok(Some(moved)) => location = moved,
+        }
+    }
+}
+
+async fn fetch(http: &reqwest::Client, location: Location) -> Result<Forecast, String> {
+    let response = http
+        .get(OPEN_METEO)
+        .query(&[
+            ("latitude", format!("{:.2}", location.latitude)),
+            ("longitude", format!("{:.2}", location.longitude)),
+            ("hourly", "weather_code".to_string()),
+            ("forecast_days", "2".to_string()),
+            ("timeformat", "unixtime".to_string()),
+        ])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    let body = response.text().await.map_err(|e| e.to_string())?;
+    parse_open_meteo(&body).map_err(|e| e.to_string())
+}
@@ -0,0 +1,22 @@
+use gtk4::accessible::Property;
+use gtk4::prelude::*;
+
+/// Name a control or popup for screen readers. Bar triggers need one: their
+/// visible label is usually an icon-font glyph, which gets read out as a
+/// codepoint or not at all.
+pub fn name(widget: &impl IsA<gtk4::Accessible>, name: &str) {
+    widget.update_property(&[Property::Label(name)]);
+}
+
+/// Longer text read after the name, e.g. a rich tooltip as plain text.
+pub fn describe(widget: &impl IsA<gtk4::Accessible>, description: &str) {
+    widget.update_property(&[Property::Description(description)]);
+}
+
+/// The system asked for less motion: `gtk-enable-animations` is off, as set
+/// by GNOME's "Reduce animation", the settings portal or `settings.ini`. GTK
+/// already skips its own transitions then; this covers the shell's hand-made
+/// fades. Read each time so a change applies to the next animation.
+pub fn reduce_motion() -> bool {
+    gtk4::Settings::default().is_some_and(|s| !s.is_gtk_enable_animations())
+}
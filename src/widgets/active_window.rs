@@ -1,35 +1,385 @@
+use chrono::Local;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation};
+use gtk4::{Box as GtkBox, Label, Orientation, PolicyType, ScrolledWindow, TickCallbackId};
+use hyprland::data::{Client, Clients, FullscreenMode, Monitors, Workspaces};
+use hyprland::dispatch::{Dispatch, DispatchType, FullscreenType, WindowIdentifier};
+use hyprland::keyword::Keyword;
+use hyprland::shared::{Address, HyprData, HyprDataVec};
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::path::PathBuf;
+use std::rc::Rc;
 
+use crate::core::window_rules::{self, WindowState};
+use crate::widgets::context_menu::MenuItem;
+use crate::widgets::tooltip::Tooltip;
+
+/// How fast a marquee title moves, in pixels per second.
+const MARQUEE_SPEED: f64 = 30.0;
+/// Rest at the start of each pass, so the title's beginning can be read.
+const MARQUEE_PAUSE_SECS: f64 = 1.5;
+/// Between the end of a scrolling title and its repeat.
+const MARQUEE_GAP: &str = "      ";
+
+/// Title of the focused window on this bar's monitor. Dimmed (`.unfocused`)
+/// while another monitor has focus, showing what was last focused here.
+/// Titles past `[active_window] max_chars` are cut short or, with `marquee`,
+/// scroll; either way the full title is in the tooltip. Right-click for
+/// quick window rules on that window.
 pub struct ActiveWindowWidget {
     pub container: GtkBox,
     label: Label,
+    scroller: ScrolledWindow,
+    tooltip: Tooltip,
+    marquee: Rc<Marquee>,
+}
+
+/// Scroll state for a title too wide for the widget. The title is shown
+/// twice with a gap, and the scroll wraps after one title-and-gap, so the
+/// text loops without a jump.
+#[derive(Default)]
+struct Marquee {
+    /// Width of the title and gap in pixels; 0 while the title fits.
+    period: Cell<f64>,
+    offset: Cell<f64>,
+    pause_left: Cell<f64>,
+    /// Frame time (µs) of the last tick, for the step size.
+    last_frame: Cell<Option<i64>>,
+    hovered: Cell<bool>,
+    tick: RefCell<Option<TickCallbackId>>,
 }
 
 impl ActiveWindowWidget {
-    pub fn new() -> Self {
+    pub fn new(monitor_name: &str) -> Self {
         let container = GtkBox::new(Orientation::Horizontal, 0);
         container.set_widget_name("active-window");
 
         let label = Label::new(Some("Desktop"));
-        container.append(&label);
+        let scroller = ScrolledWindow::new();
+        // Scrolled by the marquee only, never by the user
+        scroller.set_policy(PolicyType::External, PolicyType::Never);
+        scroller.set_propagate_natural_width(true);
+        scroller.set_child(Some(&label));
+        container.append(&scroller);
+
+        let monitor = monitor_name.to_string();
+        crate::widgets::context_menu::attach(&container, move || window_menu(&monitor));
+
+        let marquee = Rc::new(Marquee::default());
+        let motion = gtk4::EventControllerMotion::new();
+        let enter_marquee = marquee.clone();
+        motion.connect_enter(move |_, _, _| {
+            enter_marquee.hovered.set(true);
+            enter_marquee.stop();
+        });
+        let (leave_marquee, leave_scroller) = (marquee.clone(), scroller.clone());
+        motion.connect_leave(move |_| {
+            leave_marquee.hovered.set(false);
+            leave_marquee.start(&leave_scroller);
+        });
+        container.add_controller(motion);
 
-        Self { container, label }
+        let widget = Self {
+            tooltip: Tooltip::attach(&container),
+            container,
+            label,
+            scroller,
+            marquee,
+        };
+        if let Some((title, focused)) = initial_state(monitor_name) {
+            widget.set_title(&title);
+            widget.set_focused(focused);
+        }
+        widget
     }
 
     pub fn set_title(&self, title: &str) {
-        let display = if title.is_empty() {
-            "Desktop".to_string()
-        } else if title.chars().count() > 60 {
+        let config = &crate::config::get().active_window;
+        self.marquee.stop();
+        self.marquee.period.set(0.0);
+        self.marquee.offset.set(0.0);
+        self.scroller.hadjustment().set_value(0.0);
+        self.scroller.set_max_content_width(-1);
+
+        let long = title.chars().count() > config.max_chars;
+        self.tooltip.set_markup(&if long {
+            glib::markup_escape_text(title).to_string()
+        } else {
+            String::new()
+        });
+
+        if title.is_empty() {
+            self.label.set_label("Desktop");
+        } else if !long {
+            self.label.set_label(title);
+        } else if config.marquee && !crate::widgets::a11y::reduce_motion() {
+            let pass = format!("{title}{MARQUEE_GAP}");
+            let width = self.char_width() * config.max_chars as i32;
+            self.scroller.set_max_content_width(width);
+            self.label.set_label(&format!("{pass}{title}"));
+            let period = self.label.create_pango_layout(Some(&pass)).pixel_size().0;
+            self.marquee.period.set(period as f64);
+            self.marquee.pause_left.set(MARQUEE_PAUSE_SECS);
+            self.marquee.start(&self.scroller);
+        } else {
             let end: usize = title
                 .char_indices()
-                .nth(57)
+                .nth(config.max_chars.saturating_sub(3))
                 .map(|(i, _)| i)
                 .unwrap_or(title.len());
-            format!("{}...", &title[..end])
+            self.label.set_label(&format!("{}...", &title[..end]));
+        }
+        // Screen readers get the whole title rather than a moving slice
+        let name = if title.is_empty() { "Desktop" } else { title };
+        crate::widgets::a11y::name(&self.container, name);
+    }
+
+    pub fn set_focused(&self, focused: bool) {
+        if focused {
+            self.container.remove_css_class("unfocused");
+        } else {
+            self.container.add_css_class("unfocused");
+        }
+    }
+
+    /// The label font's average character width, in pixels.
+    fn char_width(&self) -> i32 {
+        let metrics = self.label.pango_context().metrics(None, None);
+        (metrics.approximate_char_width() / gtk4::pango::SCALE).max(1)
+    }
+}
+
+impl Marquee {
+    /// Scroll `scroller` every frame while the title is too wide and the
+    /// pointer is elsewhere.
+    fn start(self: &Rc<Self>, scroller: &ScrolledWindow) {
+        if self.period.get() <= 0.0 || self.hovered.get() || self.tick.borrow().is_some() {
+            return;
+        }
+        self.last_frame.set(None);
+        let marquee = self.clone();
+        let id = scroller.add_tick_callback(move |scroller, clock| {
+            let now = clock.frame_time();
+            let elapsed = marquee
+                .last_frame
+                .replace(Some(now))
+                .map_or(0.0, |last| (now - last) as f64 / 1_000_000.0);
+            if marquee.pause_left.get() > 0.0 {
+                marquee.pause_left.set(marquee.pause_left.get() - elapsed);
+            } else {
+                let mut offset = marquee.offset.get() + elapsed * MARQUEE_SPEED;
+                if offset >= marquee.period.get() {
+                    offset = 0.0;
+                    marquee.pause_left.set(MARQUEE_PAUSE_SECS);
+                }
+                marquee.offset.set(offset);
+            }
+            scroller.hadjustment().set_value(marquee.offset.get());
+            glib::ControlFlow::Continue
+        });
+        *self.tick.borrow_mut() = Some(id);
+    }
+
+    /// Hold the title where it is.
+    fn stop(&self) {
+        if let Some(id) = self.tick.borrow_mut().take() {
+            id.remove();
+        }
+    }
+}
+
+/// The monitor's active workspace's last window title, and whether the
+/// monitor has focus.
+fn initial_state(monitor_name: &str) -> Option<(String, bool)> {
+    let monitor = Monitors::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .find(|m| m.name == monitor_name)?;
+    let title = Workspaces::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .find(|ws| ws.id == monitor.active_workspace.id)
+        .map(|ws| ws.last_window_title)
+        .unwrap_or_default();
+    Some((title, monitor.focused))
+}
+
+/// The window the widget shows: the last one focused on the monitor's
+/// active workspace.
+fn shown_client(monitor_name: &str) -> Option<Client> {
+    let monitor = Monitors::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .find(|m| m.name == monitor_name)?;
+    let workspace = Workspaces::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .find(|ws| ws.id == monitor.active_workspace.id)?;
+    Clients::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .find(|c| c.address == workspace.last_window)
+}
+
+/// Float, pin, fullscreen and opacity toggles for the shown window, and
+/// saving how it's set up now as window rules for its class.
+fn window_menu(monitor_name: &str) -> Vec<MenuItem> {
+    let Some(client) = shown_client(monitor_name) else {
+        return Vec::new();
+    };
+    let address = client.address.clone();
+    let fullscreen = matches!(
+        client.fullscreen,
+        FullscreenMode::Fullscreen | FullscreenMode::MaximizedFullscreen
+    );
+
+    let mut items = vec![MenuItem::Heading(client.class.clone())];
+    let float_address = address.clone();
+    items.push(MenuItem::action(
+        if client.floating { "Tile" } else { "Float" },
+        move || {
+            dispatch(DispatchType::ToggleFloating(Some(
+                WindowIdentifier::Address(float_address.clone()),
+            )))
+        },
+    ));
+    // Hyprland only pins floating windows
+    if client.floating {
+        let pin_address = address.clone();
+        items.push(MenuItem::action(
+            if client.pinned {
+                "Unpin"
+            } else {
+                "Pin to every workspace"
+            },
+            move || {
+                dispatch(DispatchType::TogglePinWindow(WindowIdentifier::Address(
+                    pin_address.clone(),
+                )))
+            },
+        ));
+    }
+    let fullscreen_address = address.clone();
+    items.push(MenuItem::action(
+        if fullscreen {
+            "Leave fullscreen"
         } else {
-            title.to_string()
+            "Fullscreen"
+        },
+        move || {
+            on_window(
+                &fullscreen_address,
+                DispatchType::ToggleFullscreen(FullscreenType::Real),
+            )
+        },
+    ));
+    items.push(MenuItem::action("Toggle opaque", move || {
+        on_window(&address, DispatchType::ToggleOpaque)
+    }));
+
+    if !client.class.is_empty() {
+        let state = WindowState {
+            floating: client.floating,
+            pinned: client.pinned,
+            fullscreen,
+            size: (client.size.0 as i32, client.size.1 as i32),
         };
-        self.label.set_label(&display);
+        let class = client.class;
+        items.push(MenuItem::Separator);
+        items.push(MenuItem::action("Save as window rules", move || {
+            save_rules(&class, &state)
+        }));
+    }
+    items
+}
+
+/// Focus `address`, then run a dispatcher that only acts on the focused
+/// window.
+fn on_window(address: &Address, then: DispatchType) {
+    dispatch(DispatchType::FocusWindow(WindowIdentifier::Address(
+        address.clone(),
+    )));
+    dispatch(then);
+}
+
+/// Run a menu's dispatcher, logging what Hyprland rejects.
+fn dispatch(request: DispatchType) {
+    let name = format!("{request:?}");
+    if let Err(e) = Dispatch::call(request) {
+        eprintln!("jb-shell: [window-rules] {name} failed: {e}");
+    }
+}
+
+/// `[context_menu] window_rules`, else `windowrules.conf` in the config
+/// directory.
+fn rules_path() -> PathBuf {
+    match &crate::config::get().context_menu.window_rules {
+        Some(path) => crate::config::expand_home(path),
+        None => crate::config::config_dir().join("windowrules.conf"),
+    }
+}
+
+/// Append the rules that would recreate `state` for `class` to the snippets
+/// file, skipping ones already there, and add them to the running session
+/// with `hyprctl keyword` so new windows follow them straight away.
+fn save_rules(class: &str, state: &WindowState) {
+    let path = rules_path();
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    let rules = window_rules::missing(&existing, &window_rules::suggest(class, state));
+    if rules.is_empty() {
+        eprintln!(
+            "jb-shell: [window-rules] {class} rules already in {}",
+            path.display()
+        );
+        return;
+    }
+
+    let mut text = String::new();
+    if !existing.is_empty() {
+        text.push_str(if existing.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        });
+    }
+    text.push_str(&format!(
+        "# {class}, saved by jb-shell on {}\n",
+        Local::now().format("%Y-%m-%d")
+    ));
+    for rule in &rules {
+        text.push_str(&window_rules::line(rule));
+        text.push('\n');
+    }
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+        })
+        .and_then(|mut file| file.write_all(text.as_bytes()));
+    if let Err(e) = written {
+        eprintln!(
+            "jb-shell: [window-rules] can't write {}: {e}",
+            path.display()
+        );
+        return;
+    }
+    eprintln!(
+        "jb-shell: [window-rules] saved {} rule(s) for {class} to {}",
+        rules.len(),
+        path.display()
+    );
+    for rule in &rules {
+        if let Err(e) = Keyword::set("windowrulev2", rule.as_str()) {
+            eprintln!("jb-shell: [window-rules] windowrulev2 {rule} failed: {e}");
+        }
     }
 }
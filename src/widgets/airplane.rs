@@ -0,0 +1,203 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Image, Orientation};
+use relm4::prelude::*;
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::widgets::tooltip::Tooltip;
+
+/// Radios covered by the toggle, as named in rfkill's `type` file.
+const RADIOS: &[(&str, &str)] = &[("wlan", "Wi-Fi"), ("bluetooth", "Bluetooth")];
+
+/// Bar toggle that blocks Wi-Fi and Bluetooth together through rfkill. Lit
+/// while every radio is off, whether by the toggle or a hardware switch.
+pub struct AirplaneModel {
+    radios: Vec<Radio>,
+}
+
+/// Every rfkill device of one type, folded together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Radio {
+    pub kind: &'static str,
+    pub label: &'static str,
+    pub soft_blocked: bool,
+    /// A hardware switch (or firmware) holds it off; rfkill can't undo that.
+    pub hard_blocked: bool,
+}
+
+impl Radio {
+    fn blocked(&self) -> bool {
+        self.soft_blocked || self.hard_blocked
+    }
+}
+
+#[derive(Debug)]
+pub enum AirplaneInput {
+    State(Vec<Radio>),
+    Toggle,
+}
+
+pub struct AirplaneWidgets {
+    button: Button,
+    icon: Image,
+    tooltip: Tooltip,
+}
+
+impl SimpleComponent for AirplaneModel {
+    type Init = ();
+    type Input = AirplaneInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = AirplaneWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("airplane");
+        b.set_valign(gtk4::Align::Center);
+        b
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let icon = Image::from_icon_name("airplane-mode-disabled-symbolic");
+        icon.set_pixel_size(16);
+        let button = Button::new();
+        crate::widgets::a11y::name(&button, "Airplane mode");
+        button.set_child(Some(&icon));
+        root.append(&button);
+
+        let toggle_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            toggle_sender.emit(AirplaneInput::Toggle);
+        });
+
+        // Background polling thread; also catches hardware switch flips
+        let input_sender = sender.input_sender().clone();
+        std::thread::spawn(move || loop {
+            input_sender.emit(AirplaneInput::State(read_radios()));
+            crate::idle::sleep(Duration::from_secs(5));
+        });
+
+        let tooltip = Tooltip::attach(&button);
+        let model = AirplaneModel { radios: Vec::new() };
+        let widgets = AirplaneWidgets {
+            button,
+            icon,
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            AirplaneInput::State(radios) => self.radios = radios,
+            AirplaneInput::Toggle => {
+                let command = if self.airplane_mode() {
+                    "unblock"
+                } else {
+                    "block"
+                };
+                let input_sender = sender.input_sender().clone();
+                std::thread::spawn(move || {
+                    set_blocked(command);
+                    input_sender.emit(AirplaneInput::State(read_radios()));
+                });
+            }
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        if self.airplane_mode() {
+            widgets.button.add_css_class("active");
+            widgets.icon.set_icon_name(Some("airplane-mode-symbolic"));
+        } else {
+            widgets.button.remove_css_class("active");
+            widgets
+                .icon
+                .set_icon_name(Some("airplane-mode-disabled-symbolic"));
+        }
+
+        let mut markup = if self.airplane_mode() {
+            "<b>Airplane mode on</b>".to_string()
+        } else {
+            "<b>Airplane mode off</b>".to_string()
+        };
+        for radio in &self.radios {
+            let state = if radio.hard_blocked {
+                "off (hardware switch)"
+            } else if radio.soft_blocked {
+                "off"
+            } else {
+                "on"
+            };
+            markup.push_str(&format!("\n{}: {state}", radio.label));
+        }
+        if self.radios.is_empty() {
+            markup.push_str("\nNo Wi-Fi or Bluetooth radios");
+        } else if self.airplane_mode() && self.radios.iter().any(|r| r.hard_blocked) {
+            markup.push_str("\nFlip the hardware switch to turn radios back on");
+        }
+        widgets.tooltip.set_markup(&markup);
+    }
+}
+
+impl AirplaneModel {
+    /// Every radio present is off. No radios at all doesn't count.
+    fn airplane_mode(&self) -> bool {
+        !self.radios.is_empty() && self.radios.iter().all(Radio::blocked)
+    }
+}
+
+/// Current rfkill state from sysfs, one entry per radio type present.
+fn read_radios() -> Vec<Radio> {
+    let mut radios: Vec<Radio> = Vec::new();
+    let Ok(entries) = fs::read_dir("/sys/class/rfkill") else {
+        return radios;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |file: &str| {
+            fs::read_to_string(path.join(file))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        let kind = read("type");
+        let Some(&(kind, label)) = RADIOS.iter().find(|(k, _)| *k == kind) else {
+            continue;
+        };
+        let soft = read("soft") == "1";
+        let hard = read("hard") == "1";
+        match radios.iter_mut().find(|r| r.kind == kind) {
+            Some(radio) => {
+                radio.soft_blocked &= soft;
+                radio.hard_blocked |= hard;
+            }
+            None => radios.push(Radio {
+                kind,
+                label,
+                soft_blocked: soft,
+                hard_blocked: hard,
+            }),
+        }
+    }
+    radios.sort_by_key(|r| RADIOS.iter().position(|(k, _)| *k == r.kind));
+    radios
+}
+
+fn set_blocked(command: &str) {
+    let kinds = RADIOS.iter().map(|(kind, _)| *kind);
+    match Command::new("rfkill").arg(command).args(kinds).output() {
+        Ok(out) if !out.status.success() => {
+            eprintln!(
+                "jb-shell: [airplane] rfkill {command} failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Err(e) => eprintln!("jb-shell: [airplane] failed to run rfkill: {e}"),
+        _ => {}
+    }
+}
@@ -1,49 +1,144 @@
+use gdk4::Monitor;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Image, Label, Orientation};
+use gtk4::{Box as GtkBox, Button, Image, Label, Orientation, Switch};
 use relm4::prelude::*;
 use std::time::Duration;
 
+use crate::core::charge::ChargeLimit;
+use crate::widgets::switcher::BarPopup;
+use crate::widgets::tooltip::Tooltip;
+
+/// Battery level in the bar; click opens a popup with the status and, on
+/// laptops with charge thresholds or conservation mode, a "Limit charging"
+/// toggle.
 pub struct BatteryModel {
     pct: u32,
     icon_name: String,
     visible: bool,
+    degraded: bool,
+    status: String,
+    /// `None` on machines without a charge limit.
+    charge_limit: Option<ChargeLimit>,
+    /// Why the last toggle didn't take, until the next one.
+    charge_error: Option<String>,
+    popup_visible: bool,
+}
+
+pub struct BatteryInit {
+    pub monitor: Monitor,
 }
 
 #[derive(Debug)]
 pub enum BatteryInput {
-    PollResult { pct: u32, icon_name: String },
+    PollResult {
+        pct: u32,
+        icon_name: String,
+        degraded: bool,
+        status: String,
+        charge_limit: Option<ChargeLimit>,
+    },
     NoBattery,
+    SetChargeLimit(bool),
+    ChargeLimitSet(Result<ChargeLimit, String>),
+    TogglePopup,
+    HidePopup,
 }
 
 pub struct BatteryWidgets {
+    trigger: Button,
     icon: Image,
     label: Label,
+    limit_label: Label,
+    popup: BarPopup,
+    popup_title: Label,
+    popup_status: Label,
+    limit_row: GtkBox,
+    limit_switch: Switch,
+    limit_error: Label,
+    tooltip: Tooltip,
 }
 
-impl SimpleComponent for BatteryModel {
-    type Init = ();
+impl Component for BatteryModel {
+    type Init = BatteryInit;
     type Input = BatteryInput;
     type Output = ();
+    type CommandOutput = ();
     type Root = GtkBox;
     type Widgets = BatteryWidgets;
 
     fn init_root() -> Self::Root {
         let b = GtkBox::new(Orientation::Horizontal, 4);
         b.set_widget_name("battery");
+        crate::widgets::a11y::name(&b, "Battery");
         b
     }
 
     fn init(
-        _init: Self::Init,
+        init: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let icon = Image::from_icon_name("battery-full-symbolic");
         icon.set_pixel_size(16);
         let label = Label::new(Some(""));
+        let limit_label = Label::new(None);
+        limit_label.set_widget_name("battery-limit");
+        limit_label.set_visible(false);
+
+        let trigger_box = GtkBox::new(Orientation::Horizontal, 4);
+        trigger_box.append(&icon);
+        trigger_box.append(&label);
+        trigger_box.append(&limit_label);
+        let trigger = Button::new();
+        trigger.set_widget_name("battery-trigger");
+        trigger.set_child(Some(&trigger_box));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(BatteryInput::TogglePopup);
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 8);
+        let popup_title = Label::new(None);
+        popup_title.set_halign(gtk4::Align::Start);
+        popup_box.append(&popup_title);
+        let popup_status = Label::new(None);
+        popup_status.set_halign(gtk4::Align::Start);
+        popup_status.add_css_class("dim");
+        popup_box.append(&popup_status);
+
+        let limit_row = GtkBox::new(Orientation::Horizontal, 8);
+        let limit_row_label = Label::new(Some("Limit charging"));
+        limit_row_label.set_hexpand(true);
+        limit_row_label.set_halign(gtk4::Align::Start);
+        limit_row.append(&limit_row_label);
+        let limit_switch = Switch::new();
+        crate::widgets::a11y::name(&limit_switch, "Limit charging");
+        let limit_sender = sender.input_sender().clone();
+        limit_switch.connect_state_set(move |_, on| {
+            limit_sender.emit(BatteryInput::SetChargeLimit(on));
+            glib::Propagation::Proceed
+        });
+        limit_row.append(&limit_switch);
+        limit_row.set_visible(false);
+        popup_box.append(&limit_row);
+        let limit_error = Label::new(None);
+        limit_error.set_halign(gtk4::Align::Start);
+        limit_error.set_wrap(true);
+        limit_error.set_max_width_chars(36);
+        limit_error.add_css_class("dim");
+        limit_error.set_visible(false);
+        popup_box.append(&limit_error);
 
-        root.append(&icon);
-        root.append(&label);
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "battery-popup",
+            "Battery",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(BatteryInput::HidePopup),
+        );
 
         // Battery crate types are !Send, so init on a dedicated thread that owns them
         let input_sender = sender.input_sender().clone();
@@ -73,6 +168,7 @@ impl SimpleComponent for BatteryModel {
             };
 
             loop {
+                crate::metrics::wakeup();
                 let _ = manager.refresh(&mut bat);
                 let pct = (bat.state_of_charge().value * 100.0).round() as u32;
                 let icon_name = match bat.state() {
@@ -83,9 +179,26 @@ impl SimpleComponent for BatteryModel {
                     _ if pct <= 90 => "battery-good-symbolic",
                     _ => "battery-full-symbolic",
                 };
+                let degraded =
+                    crate::power::update(bat.state() == battery::State::Discharging, pct);
+                let status = match (bat.state(), bat.time_to_empty(), bat.time_to_full()) {
+                    (battery::State::Discharging, Some(t), _) => {
+                        format!("Discharging · {} remaining", format_duration(t.value))
+                    }
+                    (battery::State::Charging, _, Some(t)) => {
+                        format!("Charging · {} until full", format_duration(t.value))
+                    }
+                    (battery::State::Charging, _, _) => "Charging".to_string(),
+                    (battery::State::Discharging, _, _) => "Discharging".to_string(),
+                    (battery::State::Full, _, _) => "Fully charged".to_string(),
+                    _ => "Not charging".to_string(),
+                };
                 input_sender.emit(BatteryInput::PollResult {
                     pct,
                     icon_name: icon_name.to_string(),
+                    degraded,
+                    status,
+                    charge_limit: crate::charge_limit::read(),
                 });
                 std::thread::sleep(Duration::from_secs(30));
             }
@@ -95,31 +208,148 @@ impl SimpleComponent for BatteryModel {
             pct: 0,
             icon_name: "battery-full-symbolic".to_string(),
             visible: true,
+            degraded: false,
+            status: String::new(),
+            charge_limit: None,
+            charge_error: None,
+            popup_visible: false,
+        };
+        let tooltip = Tooltip::attach(&trigger);
+        let widgets = BatteryWidgets {
+            trigger,
+            icon,
+            label,
+            limit_label,
+            popup,
+            popup_title,
+            popup_status,
+            limit_row,
+            limit_switch,
+            limit_error,
+            tooltip,
         };
-        let widgets = BatteryWidgets { icon, label };
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
-        match msg {
-            BatteryInput::PollResult { pct, icon_name } => {
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            BatteryInput::PollResult {
+                pct,
+                icon_name,
+                degraded,
+                status,
+                charge_limit,
+            } => {
                 self.pct = pct;
                 self.icon_name = icon_name;
                 self.visible = true;
+                self.degraded = degraded;
+                self.status = status;
+                self.charge_limit = charge_limit;
             }
             BatteryInput::NoBattery => {
                 self.visible = false;
             }
+            BatteryInput::SetChargeLimit(on) => {
+                // Echo of our own update_view sync
+                if self.charge_limit.is_none_or(|l| l.is_limited() == on) {
+                    return;
+                }
+                let done_sender = sender.input_sender().clone();
+                std::thread::spawn(move || {
+                    let result = crate::charge_limit::set_limited(on);
+                    if let Err(e) = &result {
+                        eprintln!("jb-shell: [battery] charge limit: {e}");
+                    }
+                    done_sender.emit(BatteryInput::ChargeLimitSet(result));
+                });
+                return;
+            }
+            BatteryInput::ChargeLimitSet(result) => match result {
+                Ok(limit) => {
+                    self.charge_limit = Some(limit);
+                    self.charge_error = None;
+                }
+                Err(e) => self.charge_error = Some(e),
+            },
+            BatteryInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            BatteryInput::HidePopup => {
+                self.popup_visible = false;
+            }
         }
+
+        self.update_view(widgets, sender);
+        root.set_visible(self.visible);
     }
 
     fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
-        if let Some(parent) = widgets.icon.parent() {
-            parent.set_visible(self.visible);
+        if !self.visible {
+            widgets.popup.hide();
+            return;
         }
-        if self.visible {
-            widgets.icon.set_icon_name(Some(&self.icon_name));
-            widgets.label.set_label(&format!("{}%", self.pct));
+        widgets.icon.set_icon_name(Some(&self.icon_name));
+        widgets.label.set_label(&format!("{}%", self.pct));
+        let limit = self.charge_limit.and_then(ChargeLimit::describe);
+        widgets
+            .limit_label
+            .set_label(limit.as_deref().unwrap_or(""));
+        widgets.limit_label.set_visible(limit.is_some());
+
+        let mut tooltip = format!(
+            "<b>{}%</b>  {}",
+            self.pct,
+            glib::markup_escape_text(&self.status)
+        );
+        if let Some(limit) = &limit {
+            tooltip.push_str(&format!("\nCharge limit: {limit}"));
+        }
+        if let Some(parent) = widgets.trigger.parent() {
+            if self.degraded {
+                parent.add_css_class("degraded");
+                tooltip.push_str("\nPower saver: slower polling, no previews or summaries");
+            } else {
+                parent.remove_css_class("degraded");
+            }
         }
+        widgets.tooltip.set_markup(&tooltip);
+
+        let title = match &limit {
+            Some(limit) => format!("<b>{}%</b> \u{b7} {limit}", self.pct),
+            None => format!("<b>{}%</b>", self.pct),
+        };
+        widgets.popup_title.set_markup(&title);
+        widgets.popup_status.set_label(&self.status);
+        widgets.limit_row.set_visible(self.charge_limit.is_some());
+        if let Some(limit) = self.charge_limit {
+            widgets.limit_switch.set_active(limit.is_limited());
+        }
+        widgets
+            .limit_error
+            .set_label(self.charge_error.as_deref().unwrap_or(""));
+        widgets.limit_error.set_visible(self.charge_error.is_some());
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+fn format_duration(secs: f32) -> String {
+    let minutes = (secs / 60.0).round() as u64;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
     }
 }
@@ -0,0 +1,92 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::caffeine::{CaffeineCommand, CaffeineReason};
+use crate::shell::CaffeineHandle;
+use crate::widgets::tooltip::Tooltip;
+
+/// Bar toggle for the shared idle inhibitor. Lit while any rule holds it; the
+/// tooltip names the rule.
+pub struct CaffeineModel {
+    reason: Option<CaffeineReason>,
+    handle: CaffeineHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum CaffeineInput {
+    State(Option<CaffeineReason>),
+    Toggle,
+}
+
+pub struct CaffeineWidgets {
+    button: Button,
+    tooltip: Tooltip,
+}
+
+impl SimpleComponent for CaffeineModel {
+    type Init = CaffeineHandle;
+    type Input = CaffeineInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = CaffeineWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("caffeine");
+        b.set_valign(gtk4::Align::Center);
+        b
+    }
+
+    fn init(
+        handle: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let button = Button::new();
+        crate::widgets::a11y::name(&button, "Keep awake");
+        button.set_child(Some(&Label::new(Some("\u{f0f4}"))));
+        root.append(&button);
+
+        let toggle_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            toggle_sender.emit(CaffeineInput::Toggle);
+        });
+
+        handle.subscribers.subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&button);
+        let model = CaffeineModel {
+            reason: None,
+            handle,
+        };
+        let widgets = CaffeineWidgets { button, tooltip };
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            CaffeineInput::State(reason) => self.reason = reason,
+            CaffeineInput::Toggle => {
+                let _ = self.handle.thread_tx.send(CaffeineCommand::Toggle);
+            }
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        let markup = match &self.reason {
+            Some(reason) => {
+                widgets.button.add_css_class("active");
+                format!(
+                    "<b>Staying awake</b>\n{}",
+                    glib::markup_escape_text(&reason.describe())
+                )
+            }
+            None => {
+                widgets.button.remove_css_class("active");
+                "<b>Idle allowed</b>\nClick to keep the screen on".to_string()
+            }
+        };
+        widgets.tooltip.set_markup(&markup);
+    }
+}
@@ -1,7 +1,10 @@
-use crate::google_calendar::{self, CalendarEvent, CalendarResult, CalendarThreadMsg};
+use crate::core::commute::{self, Weather};
+use crate::core::time::format_countdown;
+use crate::google_calendar::{CalendarEvent, CalendarThreadMsg};
+use crate::shell::CalendarHandle;
 use crate::widgets::notifications::{
-    format_countdown, hash_event_id, ActionCallback, NotificationAction, NotificationInput,
-    NotificationKind, NotificationRequest, NotificationSource,
+    hash_event_id, ActionCallback, NotificationAction, NotificationInput, NotificationKind,
+    NotificationRequest, NotificationSource,
 };
 use chrono::Local;
 use gdk4::Monitor;
@@ -18,6 +21,7 @@ use tokio::sync::mpsc;
 pub struct CalendarInit {
     pub monitor: Monitor,
     pub notif_sender: relm4::Sender<NotificationInput>,
+    pub calendar: CalendarHandle,
 }
 
 pub struct CalendarModel {
@@ -25,13 +29,27 @@ pub struct CalendarModel {
     authenticated: bool,
     auth_in_progress: bool,
     has_credentials: bool,
+    popup_visible: bool,
+    /// Event whose details are expanded in the popup.
+    expanded_event: Option<String>,
+    notif_sender: relm4::Sender<NotificationInput>,
+}
+
+/// Fires the commute reminder, 5-minute toast and 1-minute fullscreen meeting
+/// alerts. There is one per process (owned by `Shell`), fed by the same
+/// calendar stream as the bar views.
+pub struct CalendarAlertsModel {
+    events: Vec<CalendarEvent>,
+    notified_commute: HashSet<String>,
     notified_5min: HashSet<String>,
     notified_1min: HashSet<String>,
-    popup_visible: bool,
+    /// When the accepted meetings in progress end, as last told to the toast
+    /// model and Slack.
+    meeting_end: Option<chrono::DateTime<Local>>,
     notif_sender: relm4::Sender<NotificationInput>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum CalendarInput {
     EventsUpdated(Vec<CalendarEvent>),
     AuthComplete,
@@ -44,6 +62,10 @@ pub enum CalendarInput {
     FocusLeave,
     FocusEnter,
     CheckNotifications,
+    /// Open the link of the nearest meeting (bar button or D-Bus).
+    JoinNextMeeting,
+    /// Expand or collapse an event row in the popup.
+    ToggleEventDetails(String),
 }
 
 pub struct CalendarWidgets {
@@ -77,6 +99,7 @@ impl Component for CalendarModel {
     ) -> ComponentParts<Self> {
         let monitor = init.monitor;
         let notif_sender = init.notif_sender;
+        let calendar = init.calendar;
 
         // Trigger button
         let trigger_box = GtkBox::new(Orientation::Horizontal, 4);
@@ -86,6 +109,7 @@ impl Component for CalendarModel {
         trigger_box.append(&indicator_label);
 
         let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "Calendar");
         trigger.set_child(Some(&trigger_box));
         root.append(&trigger);
 
@@ -97,6 +121,7 @@ impl Component for CalendarModel {
 
         // Event list popup
         let popup = Window::new();
+        crate::widgets::a11y::name(&popup, "Calendar");
         popup.set_widget_name("calendar-popup-window");
         popup.init_layer_shell();
         popup.set_layer(Layer::Overlay);
@@ -123,32 +148,18 @@ impl Component for CalendarModel {
         });
         popup.add_controller(focus);
 
-        // Spawn calendar thread
-        let input_sender = sender.input_sender().clone();
-        let thread_tx = google_calendar::spawn_calendar_thread(move |result| match result {
-            CalendarResult::EventsUpdated(e) => input_sender.emit(CalendarInput::EventsUpdated(e)),
-            CalendarResult::AuthComplete => input_sender.emit(CalendarInput::AuthComplete),
-            CalendarResult::AuthFailed(s) => input_sender.emit(CalendarInput::AuthFailed(s)),
-            CalendarResult::AuthRevoked => input_sender.emit(CalendarInput::AuthRevoked),
-            CalendarResult::NeedsAuth => input_sender.emit(CalendarInput::NeedsAuth),
-            CalendarResult::NoCredentials => input_sender.emit(CalendarInput::NoCredentials),
-        });
-
-        // 1-second notification check timer
-        let check_sender = sender.input_sender().clone();
-        glib::timeout_add_local(Duration::from_secs(1), move || {
-            check_sender.emit(CalendarInput::CheckNotifications);
-            glib::ControlFlow::Continue
-        });
+        // Subscribe to the shared calendar thread
+        calendar
+            .subscribers
+            .subscribe(sender.input_sender().clone());
 
         let model = CalendarModel {
             events: Vec::new(),
             authenticated: false,
             auth_in_progress: false,
             has_credentials: true,
-            notified_5min: HashSet::new(),
-            notified_1min: HashSet::new(),
             popup_visible: false,
+            expanded_event: None,
             notif_sender,
         };
 
@@ -158,7 +169,7 @@ impl Component for CalendarModel {
             popup,
             popup_box,
             close_timer: Rc::new(RefCell::new(None)),
-            thread_tx,
+            thread_tx: calendar.thread_tx,
         };
 
         ComponentParts { model, widgets }
@@ -187,23 +198,8 @@ impl Component for CalendarModel {
                 cancel_timer(&widgets.close_timer);
                 return;
             }
-            CalendarInput::CheckNotifications => {
-                self.check_notifications();
-                return;
-            }
+            CalendarInput::CheckNotifications | CalendarInput::JoinNextMeeting => return,
             CalendarInput::EventsUpdated(events) => {
-                // Clear notifications for removed or rescheduled events
-                let old_times: std::collections::HashMap<&str, _> = self
-                    .events
-                    .iter()
-                    .map(|e| (e.id.as_str(), e.start))
-                    .collect();
-                let new_times: std::collections::HashMap<&str, _> =
-                    events.iter().map(|e| (e.id.as_str(), e.start)).collect();
-                self.notified_5min
-                    .retain(|id| new_times.get(id.as_str()) == old_times.get(id.as_str()));
-                self.notified_1min
-                    .retain(|id| new_times.get(id.as_str()) == old_times.get(id.as_str()));
                 self.events = events;
                 self.authenticated = true;
             }
@@ -230,7 +226,7 @@ impl Component for CalendarModel {
                     self.popup_visible = !self.popup_visible;
                     if self.popup_visible {
                         show_setup_instructions(widgets);
-                        position_popup(&widgets.popup, &widgets.trigger);
+                        crate::widgets::switcher::position_popup(&widgets.popup, &widgets.trigger);
                         widgets.popup.set_visible(true);
                     } else {
                         widgets.popup.set_visible(false);
@@ -247,6 +243,14 @@ impl Component for CalendarModel {
             }
             CalendarInput::HidePopup => {
                 self.popup_visible = false;
+                self.expanded_event = None;
+            }
+            CalendarInput::ToggleEventDetails(id) => {
+                if self.expanded_event.as_deref() == Some(id.as_str()) {
+                    self.expanded_event = None;
+                } else {
+                    self.expanded_event = Some(id);
+                }
             }
         }
 
@@ -297,7 +301,7 @@ impl Component for CalendarModel {
         // Update popup
         if self.popup_visible {
             self.rebuild_popup(widgets, &sender);
-            position_popup(&widgets.popup, &widgets.trigger);
+            crate::widgets::switcher::position_popup(&widgets.popup, &widgets.trigger);
             widgets.popup.set_visible(true);
         } else {
             cancel_timer(&widgets.close_timer);
@@ -307,7 +311,75 @@ impl Component for CalendarModel {
 }
 
 impl CalendarModel {
-    fn rebuild_popup(&self, widgets: &CalendarWidgets, _sender: &ComponentSender<Self>) {
+    /// Description, location, attendees and meeting link for an expanded row.
+    fn build_event_details(&self, event: &CalendarEvent) -> GtkBox {
+        let details = GtkBox::new(Orientation::Vertical, 4);
+        details.set_widget_name("calendar-event-details");
+
+        let title = Label::new(Some(&event.title));
+        title.set_halign(gtk4::Align::Start);
+        title.set_wrap(true);
+        title.add_css_class("title");
+        details.append(&title);
+
+        if let Some(location) = &event.location {
+            details.append(&detail_row("\u{f041}", location));
+        }
+
+        if let Some(url) = &event.meeting_link {
+            let row = detail_row("\u{f03d}", url);
+            let join_btn = Button::with_label("Join");
+            join_btn.set_widget_name("calendar-detail-button");
+            let notif_sender = self.notif_sender.clone();
+            let notif_id = hash_event_id(&event.id, "popup-join");
+            let url = url.clone();
+            join_btn.connect_clicked(move |_| {
+                notif_sender.emit(NotificationInput::ActionTriggered(
+                    notif_id,
+                    ActionCallback::OpenUrl(url.clone()),
+                ));
+            });
+            row.append(&join_btn);
+            details.append(&row);
+        }
+
+        if !event.attendees.is_empty() {
+            let accepted = event
+                .attendees
+                .iter()
+                .filter(|a| a.response == "accepted")
+                .count();
+            let header = Label::new(Some(&format!(
+                "{} attendees \u{b7} {accepted} accepted",
+                event.attendees.len()
+            )));
+            header.set_halign(gtk4::Align::Start);
+            header.add_css_class("dim");
+            details.append(&header);
+
+            for attendee in &event.attendees {
+                let (mark, class) = match attendee.response.as_str() {
+                    "accepted" => ("\u{2713}", "accepted"),
+                    "declined" => ("\u{2717}", "declined"),
+                    "tentative" => ("?", "tentative"),
+                    _ => ("\u{b7}", "pending"),
+                };
+                let label = Label::new(Some(&format!("{mark} {}", attendee.name)));
+                label.set_halign(gtk4::Align::Start);
+                label.add_css_class("attendee");
+                label.add_css_class(class);
+                details.append(&label);
+            }
+        }
+
+        if let Some(description) = &event.description {
+            details.append(&detail_row("\u{f15c}", description));
+        }
+
+        details
+    }
+
+    fn rebuild_popup(&self, widgets: &CalendarWidgets, sender: &ComponentSender<Self>) {
         while let Some(child) = widgets.popup_box.first_child() {
             widgets.popup_box.remove(&child);
         }
@@ -372,18 +444,28 @@ impl CalendarModel {
                 btn.add_css_class("current");
             }
 
-            if let Some(url) = event.meeting_link.clone() {
-                let notif_sender = self.notif_sender.clone();
-                let notif_id = hash_event_id(&event.id, "popup-join");
-                btn.connect_clicked(move |_| {
-                    notif_sender.emit(NotificationInput::ActionTriggered(
-                        notif_id,
-                        ActionCallback::OpenUrl(url.clone()),
-                    ));
-                });
-            }
+            let toggle_sender = sender.input_sender().clone();
+            let event_id = event.id.clone();
+            btn.connect_clicked(move |_| {
+                toggle_sender.emit(CalendarInput::ToggleEventDetails(event_id.clone()));
+            });
 
             widgets.popup_box.append(&btn);
+
+            if let Some((leave, weather)) = leave_by(event).filter(|_| event.start > now) {
+                let hint = Label::new(Some(&leave_by_text(leave, weather)));
+                hint.set_widget_name("calendar-leave-by");
+                hint.set_halign(gtk4::Align::Start);
+                if leave <= now {
+                    hint.add_css_class("due");
+                }
+                widgets.popup_box.append(&hint);
+            }
+
+            if self.expanded_event.as_deref() == Some(event.id.as_str()) {
+                btn.add_css_class("expanded");
+                widgets.popup_box.append(&self.build_event_details(event));
+            }
         }
 
         let footer = Label::new(Some(&format!("{upcoming_count} upcoming")));
@@ -391,10 +473,158 @@ impl CalendarModel {
         footer.set_halign(gtk4::Align::Start);
         widgets.popup_box.append(&footer);
     }
+}
+
+/// The meeting with a link to join at `now`: one in progress (the latest to
+/// start, if they overlap), else the next to start. A running meeting wins
+/// over one about to start, so Join doesn't disappear mid-call.
+pub fn nearest_meeting(
+    events: &[CalendarEvent],
+    now: chrono::DateTime<Local>,
+) -> Option<&CalendarEvent> {
+    let meetings = || {
+        events
+            .iter()
+            .filter(|e| !e.is_all_day && e.end > now && e.meeting_link.is_some())
+    };
+    meetings()
+        .filter(|e| e.start <= now)
+        .max_by_key(|e| e.start)
+        .or_else(|| meetings().min_by_key(|e| e.start))
+}
+
+/// When to leave for `event` under `[commute]`, and the weather that allowed
+/// for; `None` for all-day events, video calls, or with commute hints off.
+fn leave_by(event: &CalendarEvent) -> Option<(chrono::DateTime<Local>, Weather)> {
+    let config = &crate::config::get().commute;
+    let location = event.location.as_deref()?;
+    if config.buffer_minutes == 0 || event.is_all_day || !commute::is_physical(location) {
+        return None;
+    }
+    Some(commute::leave_by(
+        event.start,
+        config.buffer_minutes,
+        config.bad_weather_minutes,
+        &crate::weather::forecast(),
+    ))
+}
 
+fn leave_by_text(leave: chrono::DateTime<Local>, weather: Weather) -> String {
+    match weather.describe() {
+        Some(weather) => format!("Leave by {} \u{b7} {weather}", leave.format("%H:%M")),
+        None => format!("Leave by {}", leave.format("%H:%M")),
+    }
+}
+
+pub fn open_meeting_link(url: &str) {
+    eprintln!("jb-shell: [calendar] joining {url}");
+    crate::activation::open_uri(url);
+}
+
+struct CalendarDbus {
+    sender: relm4::Sender<CalendarInput>,
+}
+
+#[zbus::interface(name = "dev.jb.shell.Calendar")]
+impl CalendarDbus {
+    fn join_next_meeting(&self) {
+        self.sender.emit(CalendarInput::JoinNextMeeting);
+    }
+}
+
+impl Component for CalendarAlertsModel {
+    type Init = relm4::Sender<NotificationInput>;
+    type Input = CalendarInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = ();
+
+    fn init_root() -> Self::Root {
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        notif_sender: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        crate::dbus::serve(
+            "/dev/jb/shell/Calendar",
+            "dev.jb.shell.Calendar",
+            CalendarDbus {
+                sender: sender.input_sender().clone(),
+            },
+        );
+
+        // 1-second notification check timer
+        let check_sender = sender.input_sender().clone();
+        glib::timeout_add_local(Duration::from_secs(1), move || {
+            check_sender.emit(CalendarInput::CheckNotifications);
+            glib::ControlFlow::Continue
+        });
+
+        let model = CalendarAlertsModel {
+            events: Vec::new(),
+            notified_commute: HashSet::new(),
+            notified_5min: HashSet::new(),
+            notified_1min: HashSet::new(),
+            meeting_end: None,
+            notif_sender,
+        };
+        ComponentParts { model, widgets: () }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>, _root: &Self::Root) {
+        match message {
+            CalendarInput::CheckNotifications => self.check_notifications(),
+            CalendarInput::JoinNextMeeting => {
+                match nearest_meeting(&self.events, Local::now())
+                    .and_then(|e| e.meeting_link.as_deref())
+                {
+                    Some(url) => open_meeting_link(url),
+                    None => eprintln!("jb-shell: [calendar] no meeting to join"),
+                }
+            }
+            CalendarInput::EventsUpdated(events) => {
+                // Clear notifications for removed or rescheduled events
+                let old_times: std::collections::HashMap<&str, _> = self
+                    .events
+                    .iter()
+                    .map(|e| (e.id.as_str(), e.start))
+                    .collect();
+                let new_times: std::collections::HashMap<&str, _> =
+                    events.iter().map(|e| (e.id.as_str(), e.start)).collect();
+                self.notified_commute
+                    .retain(|id| new_times.get(id.as_str()) == old_times.get(id.as_str()));
+                self.notified_5min
+                    .retain(|id| new_times.get(id.as_str()) == old_times.get(id.as_str()));
+                self.notified_1min
+                    .retain(|id| new_times.get(id.as_str()) == old_times.get(id.as_str()));
+                self.events = events;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl CalendarAlertsModel {
     fn check_notifications(&mut self) {
         let now = Local::now();
 
+        // Drives "DND during meetings" and the Slack meeting status
+        let meeting_end = meeting_end(&self.events, now);
+        if meeting_end != self.meeting_end {
+            // A back-to-back meeting only moves the end; Slack needs the new
+            // expiration but DND is already on
+            if meeting_end.is_some() != self.meeting_end.is_some() {
+                self.notif_sender
+                    .emit(NotificationInput::MeetingChanged(meeting_end.is_some()));
+            }
+            self.meeting_end = meeting_end;
+            crate::presence::meeting_changed(meeting_end);
+        }
+
         for event in &self.events {
             if event.is_all_day || event.start <= now {
                 continue;
@@ -412,9 +642,23 @@ impl CalendarModel {
                 );
             }
 
+            // The 5-minute toast takes over from a late commute reminder
+            if let Some((leave, weather)) = leave_by(event) {
+                if now >= leave && secs_until > 300 && !self.notified_commute.contains(&event.id) {
+                    eprintln!("jb-shell: firing commute notification for {}", event.title);
+                    self.notified_commute.insert(event.id.clone());
+                    self.notif_sender.emit(NotificationInput::Show(
+                        self.build_commute_notification(event, weather),
+                    ));
+                }
+            }
+
             if secs_until <= 300 && !self.notified_5min.contains(&event.id) {
                 eprintln!("jb-shell: firing 5min notification for {}", event.title);
                 self.notified_5min.insert(event.id.clone());
+                let commute_id = hash_event_id(&event.id, "commute");
+                self.notif_sender
+                    .emit(NotificationInput::Dismiss(commute_id));
                 self.notif_sender
                     .emit(NotificationInput::Show(self.build_5min_notification(event)));
             }
@@ -433,6 +677,55 @@ impl CalendarModel {
         }
     }
 
+    fn build_commute_notification(
+        &self,
+        event: &CalendarEvent,
+        weather: Weather,
+    ) -> NotificationRequest {
+        let id = hash_event_id(&event.id, "commute");
+        let location = event.location.as_deref().unwrap_or_default();
+        let mut body = format!("Starts {} \u{b7} {location}", event.start.format("%H:%M"));
+        if let Some(weather) = weather.describe() {
+            body.push_str(&format!(" \u{b7} {weather}"));
+        }
+
+        let mut actions = Vec::new();
+        if let Ok(url) = reqwest::Url::parse_with_params(
+            "https://www.openstreetmap.org/search",
+            &[("query", location)],
+        ) {
+            actions.push(NotificationAction {
+                label: "Directions".to_string(),
+                css_class: "join-btn".to_string(),
+                callback: ActionCallback::OpenUrl(url.into()),
+            });
+        }
+        actions.push(NotificationAction {
+            label: "Dismiss".to_string(),
+            css_class: "dismiss-btn".to_string(),
+            callback: ActionCallback::Dismiss,
+        });
+
+        NotificationRequest {
+            id,
+            kind: NotificationKind::Toast,
+            icon: None,
+            title: format!("Time to leave for {}", event.title),
+            body: Some(body),
+            subtitle: None,
+            countdown_target: None,
+            actions,
+            css_window_name: None,
+            css_box_name: Some("calendar-notif".to_string()),
+            css_card_class: None,
+            timeout_ms: None,
+            repeat_count: 1,
+            swatch: None,
+            progress: None,
+            source: NotificationSource::Internal,
+        }
+    }
+
     fn build_5min_notification(&self, event: &CalendarEvent) -> NotificationRequest {
         let id = hash_event_id(&event.id, "5min");
         let now = Local::now();
@@ -471,6 +764,9 @@ impl CalendarModel {
             css_box_name: Some("calendar-notif".to_string()),
             css_card_class: None,
             timeout_ms: None,
+            repeat_count: 1,
+            swatch: None,
+            progress: None,
             source: NotificationSource::Internal,
         }
     }
@@ -512,6 +808,9 @@ impl CalendarModel {
             css_box_name: None,
             css_card_class: Some("fullscreen-card".to_string()),
             timeout_ms: None,
+            repeat_count: 1,
+            swatch: None,
+            progress: None,
             source: NotificationSource::Internal,
         }
     }
@@ -575,6 +874,37 @@ fn set_trigger_class(trigger: &Button, class: &str) {
     }
 }
 
+/// Icon + wrapped text + a button copying the text to the clipboard.
+fn detail_row(icon: &str, text: &str) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 6);
+    let icon_label = Label::new(Some(icon));
+    icon_label.set_valign(gtk4::Align::Start);
+    icon_label.add_css_class("dim");
+    row.append(&icon_label);
+
+    let text_label = Label::new(Some(text));
+    text_label.set_halign(gtk4::Align::Start);
+    text_label.set_hexpand(true);
+    text_label.set_xalign(0.0);
+    text_label.set_wrap(true);
+    text_label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+    text_label.set_max_width_chars(40);
+    text_label.set_selectable(true);
+    row.append(&text_label);
+
+    let copy_btn = Button::with_label("\u{f0c5}");
+    crate::widgets::a11y::name(&copy_btn, "Copy");
+    copy_btn.set_widget_name("calendar-detail-button");
+    copy_btn.set_valign(gtk4::Align::Start);
+    copy_btn.set_tooltip_text(Some("Copy"));
+    let text = text.to_string();
+    copy_btn.connect_clicked(move |btn| {
+        btn.clipboard().set_text(&text);
+    });
+    row.append(&copy_btn);
+    row
+}
+
 fn truncate_title(title: &str, max_len: usize) -> String {
     let char_count = title.chars().count();
     if char_count <= max_len {
@@ -588,23 +918,19 @@ fn truncate_title(title: &str, max_len: usize) -> String {
     format!("{}...", &title[..end])
 }
 
-fn position_popup(popup: &Window, trigger: &Button) {
-    let Some(root) = trigger.root() else {
-        popup.set_margin(Edge::Top, 32);
-        return;
-    };
-    if let Some(bounds) = trigger.compute_bounds(root.upcast_ref::<gtk4::Widget>()) {
-        popup.set_margin(Edge::Top, (bounds.y() + bounds.height()) as i32);
-
-        let screen_w = root.width();
-        let (_, popup_natural, _, _) = popup.measure(gtk4::Orientation::Horizontal, -1);
-        let popup_w = popup_natural.max(200);
-        let left = (bounds.x() as i32).min(screen_w - popup_w).max(0);
-        popup.set_margin(Edge::Left, left);
-    } else {
-        popup.set_margin(Edge::Top, 32);
-        popup.set_margin(Edge::Left, 0);
-    }
+/// When the accepted meetings in progress at `now` end, or None if there are
+/// none.
+pub fn meeting_end(
+    events: &[CalendarEvent],
+    now: chrono::DateTime<Local>,
+) -> Option<chrono::DateTime<Local>> {
+    crate::core::time::meeting_end(
+        events
+            .iter()
+            .filter(|e| !e.is_all_day && e.accepted)
+            .map(|e| (e.start, e.end)),
+        now,
+    )
 }
 
 fn is_meeting_focused() -> bool {
@@ -0,0 +1,242 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::ci::CiPipelineState;
+use crate::core::ci::PipelineStatus;
+use crate::shell::Subscribers;
+use crate::widgets::switcher::BarPopup;
+use crate::widgets::tooltip::Tooltip;
+
+/// Latest run of each `[[ci.pipelines]]` entry: the worst status in the bar
+/// with a count of failures; click lists every pipeline, each row opening its
+/// run in the browser.
+pub struct CiModel {
+    pipelines: Vec<CiPipelineState>,
+    popup_visible: bool,
+}
+
+pub struct CiInit {
+    pub monitor: Monitor,
+    pub feed: Subscribers<CiInput>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CiInput {
+    Pipelines(Vec<CiPipelineState>),
+    TogglePopup,
+    HidePopup,
+}
+
+pub struct CiWidgets {
+    icon: Label,
+    label: Label,
+    trigger: Button,
+    popup: BarPopup,
+    popup_box: GtkBox,
+    tooltip: Tooltip,
+}
+
+const STATUS_CLASSES: [PipelineStatus; 4] = [
+    PipelineStatus::Passed,
+    PipelineStatus::Failed,
+    PipelineStatus::Running,
+    PipelineStatus::Other,
+];
+
+impl Component for CiModel {
+    type Init = CiInit;
+    type Input = CiInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = CiWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("ci");
+        b.set_valign(gtk4::Align::Center);
+        b.set_visible(false);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let trigger_box = GtkBox::new(Orientation::Horizontal, 4);
+        let icon = Label::new(None);
+        icon.add_css_class("ci-icon");
+        let label = Label::new(None);
+        trigger_box.append(&icon);
+        trigger_box.append(&label);
+
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "CI pipelines");
+        trigger.set_widget_name("ci-trigger");
+        trigger.set_child(Some(&trigger_box));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(CiInput::TogglePopup);
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 2);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "ci-popup",
+            "CI pipelines",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(CiInput::HidePopup),
+        );
+
+        init.feed.subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&trigger);
+        let model = CiModel {
+            pipelines: Vec::new(),
+            popup_visible: false,
+        };
+        let widgets = CiWidgets {
+            icon,
+            label,
+            trigger,
+            popup,
+            popup_box,
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            CiInput::Pipelines(pipelines) => {
+                root.set_visible(!pipelines.is_empty());
+                self.pipelines = pipelines;
+            }
+            CiInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            CiInput::HidePopup => {
+                self.popup_visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
+        let statuses: Vec<PipelineStatus> = self
+            .pipelines
+            .iter()
+            .filter_map(|p| p.run.as_ref().map(|r| r.status))
+            .collect();
+        let failed = statuses
+            .iter()
+            .filter(|&&s| s == PipelineStatus::Failed)
+            .count();
+        let overall = [
+            PipelineStatus::Failed,
+            PipelineStatus::Running,
+            PipelineStatus::Passed,
+        ]
+        .into_iter()
+        .find(|s| statuses.contains(s))
+        .unwrap_or(PipelineStatus::Other);
+
+        widgets.icon.set_label(overall.icon());
+        widgets.label.set_label(&failed.to_string());
+        widgets.label.set_visible(failed > 0);
+        for status in STATUS_CLASSES {
+            widgets.trigger.remove_css_class(status.css_class());
+        }
+        widgets.trigger.add_css_class(overall.css_class());
+
+        let tooltip: Vec<String> = self
+            .pipelines
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} {}",
+                    status_of(p).icon(),
+                    glib::markup_escape_text(&p.name)
+                )
+            })
+            .collect();
+        widgets.tooltip.set_markup(&tooltip.join("\n"));
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            while let Some(child) = widgets.popup_box.first_child() {
+                widgets.popup_box.remove(&child);
+            }
+            for pipeline in &self.pipelines {
+                widgets
+                    .popup_box
+                    .append(&build_pipeline_row(pipeline, &sender));
+            }
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+fn status_of(pipeline: &CiPipelineState) -> PipelineStatus {
+    pipeline
+        .run
+        .as_ref()
+        .map_or(PipelineStatus::Other, |r| r.status)
+}
+
+/// Icon, name and run title (or the poll error); click opens the run.
+fn build_pipeline_row(pipeline: &CiPipelineState, sender: &ComponentSender<CiModel>) -> Button {
+    let status = status_of(pipeline);
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    let icon = Label::new(Some(status.icon()));
+    icon.add_css_class("ci-icon");
+    let text = GtkBox::new(Orientation::Vertical, 0);
+    let name = Label::new(Some(&pipeline.name));
+    name.set_halign(gtk4::Align::Start);
+    let detail_text = match (&pipeline.error, &pipeline.run) {
+        (Some(error), _) => error.clone(),
+        (None, Some(run)) => run.title.clone(),
+        (None, None) => "no runs".to_string(),
+    };
+    let detail = Label::new(Some(&detail_text));
+    detail.add_css_class("dim");
+    detail.set_halign(gtk4::Align::Start);
+    detail.set_max_width_chars(50);
+    detail.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    text.append(&name);
+    text.append(&detail);
+    row.append(&icon);
+    row.append(&text);
+
+    let button = Button::new();
+    button.set_widget_name("ci-run");
+    button.add_css_class(status.css_class());
+    button.set_child(Some(&row));
+    match pipeline.run.as_ref().map(|r| r.url.clone()) {
+        Some(url) => {
+            let hide_sender = sender.input_sender().clone();
+            button.connect_clicked(move |_| {
+                crate::activation::open_uri(&url);
+                hide_sender.emit(CiInput::HidePopup);
+            });
+        }
+        None => button.set_sensitive(false),
+    }
+    button
+}
@@ -1,25 +1,49 @@
-use chrono::Local;
+use chrono::{DateTime, Datelike, Local, Timelike};
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Label, Orientation};
+use gtk4::{Box as GtkBox, GestureClick, Label, Orientation};
 use relm4::prelude::*;
+use std::time::Instant;
+
+use crate::core::sysinfo::{format_duration, parse_loadavg, parse_uptime, resumed_between};
+use crate::shell::Subscribers;
+use crate::widgets::context_menu::MenuItem;
+use crate::widgets::tooltip::Tooltip;
+
+/// A tick gap this much longer on the boot clock than the monotonic one is a
+/// suspend, not scheduling jitter.
+const SUSPEND_SLACK_SECS: f64 = 5.0;
 
 pub struct ClockModel {
     date: String,
     time: String,
+    details: String,
+    /// `/proc/sys/kernel/osrelease`, read once.
+    kernel: String,
+    /// Monotonic time and uptime at the last tick, to notice resumes.
+    last_tick: (Instant, Option<f64>),
+    /// When the last resume was noticed; None until one happens while running.
+    resumed_at: Option<DateTime<Local>>,
+    /// Showing `[clock] alt_date_format`/`alt_time_format`, toggled by a click.
+    alternate: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ClockInput {
     Tick,
+    ToggleFormat,
+    /// From the shell's timezone thread: re-render now rather than on the
+    /// next tick.
+    TimezoneChanged(String),
 }
 
 pub struct ClockWidgets {
     date_label: Label,
     time_label: Label,
+    tooltip: Tooltip,
 }
 
 impl SimpleComponent for ClockModel {
-    type Init = ();
+    type Init = Subscribers<ClockInput>;
     type Input = ClockInput;
     type Output = ();
     type Root = GtkBox;
@@ -32,7 +56,7 @@ impl SimpleComponent for ClockModel {
     }
 
     fn init(
-        _init: Self::Init,
+        timezone: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -45,37 +69,160 @@ impl SimpleComponent for ClockModel {
         root.append(&time_label);
 
         let now = Local::now();
-        let model = ClockModel {
-            date: now.format("%a, %b %-d").to_string(),
-            time: now.format("%-I:%M %p").to_string(),
+        let kernel = std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|k| k.trim().to_string())
+            .unwrap_or_default();
+        let uptime = read_uptime();
+        let mut model = ClockModel {
+            date: String::new(),
+            time: String::new(),
+            details: clock_details(&now, &kernel, uptime, None),
+            kernel,
+            last_tick: (Instant::now(), uptime),
+            resumed_at: None,
+            alternate: false,
         };
+        model.format_labels(&now);
 
-        // Clock doesn't do blocking I/O, so a main-thread timer is fine
+        // Clock only reads /proc, which doesn't block, so a main-thread timer is fine
         let input_sender = sender.input_sender().clone();
         glib::timeout_add_seconds_local(1, move || {
             input_sender.emit(ClockInput::Tick);
             glib::ControlFlow::Continue
         });
+        timezone.subscribe(sender.input_sender().clone());
+
+        // A left click in `[actions.clock]` claims the click first
+        let click = GestureClick::new();
+        let click_sender = sender.input_sender().clone();
+        click.connect_released(move |_, _, _, _| {
+            click_sender.emit(ClockInput::ToggleFormat);
+        });
+        root.add_controller(click);
+        crate::widgets::context_menu::attach(&root, || {
+            vec![
+                MenuItem::action("Copy date", || {
+                    copy(&Local::now().format("%A, %B %-d, %Y").to_string());
+                }),
+                MenuItem::action("Copy ISO 8601 timestamp", || {
+                    copy(&Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false));
+                }),
+            ]
+        });
 
         let widgets = ClockWidgets {
             date_label,
             time_label,
+            tooltip: Tooltip::attach(&root),
         };
         ComponentParts { model, widgets }
     }
 
     fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
         match msg {
-            ClockInput::Tick => {
+            ClockInput::Tick | ClockInput::TimezoneChanged(_) => {
                 let now = Local::now();
-                self.date = now.format("%a, %b %-d").to_string();
-                self.time = now.format("%-I:%M %p").to_string();
+                let uptime = read_uptime();
+                if let (Some(previous), Some(current)) = (self.last_tick.1, uptime) {
+                    let monotonic = self.last_tick.0.elapsed().as_secs_f64();
+                    if resumed_between(current - previous, monotonic, SUSPEND_SLACK_SECS) {
+                        self.resumed_at = Some(now);
+                    }
+                }
+                self.last_tick = (Instant::now(), uptime);
+                self.format_labels(&now);
+                self.details = clock_details(&now, &self.kernel, uptime, self.resumed_at);
+            }
+            ClockInput::ToggleFormat => {
+                self.alternate = !self.alternate;
+                self.format_labels(&Local::now());
             }
         }
     }
 
     fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
         widgets.date_label.set_label(&self.date);
+        widgets.date_label.set_visible(!self.date.is_empty());
         widgets.time_label.set_label(&self.time);
+        widgets.time_label.set_visible(!self.time.is_empty());
+        widgets.tooltip.set_markup(&self.details);
+    }
+}
+
+impl ClockModel {
+    fn format_labels(&mut self, now: &DateTime<Local>) {
+        let config = &crate::config::get().clock;
+        let (date, time) = if self.alternate {
+            (
+                config.alt_date_format.as_str(),
+                config.alt_time_format.as_str(),
+            )
+        } else {
+            (config.date_format.as_str(), config.time_format())
+        };
+        self.date = format_localized(now, date);
+        self.time = format_localized(now, time);
+    }
+}
+
+/// `now` through GLib's strftime rather than chrono's, for month and day
+/// names and AM/PM in the user's locale. The offset comes from chrono so a
+/// timezone change shows right away. A format GLib rejects is shown as is.
+fn format_localized(now: &DateTime<Local>, format: &str) -> String {
+    if format.is_empty() {
+        return String::new();
+    }
+    let zone = glib::TimeZone::from_offset(now.offset().local_minus_utc());
+    glib::DateTime::new(
+        &zone,
+        now.year(),
+        now.month() as i32,
+        now.day() as i32,
+        now.hour() as i32,
+        now.minute() as i32,
+        now.second() as f64,
+    )
+    .and_then(|local| local.format(format))
+    .map(|text| text.to_string())
+    .unwrap_or_else(|_| format.to_string())
+}
+
+fn copy(text: &str) {
+    if let Some(display) = gdk4::Display::default() {
+        display.clipboard().set_text(text);
+    }
+}
+
+fn read_uptime() -> Option<f64> {
+    parse_uptime(&std::fs::read_to_string("/proc/uptime").ok()?)
+}
+
+/// Date and week, then uptime, load, kernel and time since the last resume.
+fn clock_details(
+    now: &DateTime<Local>,
+    kernel: &str,
+    uptime: Option<f64>,
+    resumed_at: Option<DateTime<Local>>,
+) -> String {
+    let mut details = now
+        .format("<b>%Y-%m-%d</b>  %H:%M:%S\nWeek %V · day %j · UTC%:z")
+        .to_string();
+    details.push('\n');
+    if let Some(uptime) = uptime {
+        details.push_str(&format!("\nUp {}", format_duration(uptime as u64)));
+    }
+    if let Some([one, five, fifteen]) = std::fs::read_to_string("/proc/loadavg")
+        .ok()
+        .and_then(|l| parse_loadavg(&l))
+    {
+        details.push_str(&format!("\nLoad {one:.2} {five:.2} {fifteen:.2}"));
+    }
+    if !kernel.is_empty() {
+        details.push_str(&format!("\nLinux {}", glib::markup_escape_text(kernel)));
+    }
+    if let Some(resumed_at) = resumed_at {
+        let awake = (*now - resumed_at).num_seconds().max(0) as u64;
+        details.push_str(&format!("\nAwake {} since resume", format_duration(awake)));
     }
+    details
 }
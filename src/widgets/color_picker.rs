@@ -0,0 +1,231 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, EventControllerKey, GestureClick, Label, Orientation, Window};
+use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use hyprland::data::Monitor as HyprMonitor;
+use hyprland::shared::HyprDataActive;
+use relm4::prelude::*;
+
+use crate::widgets::notifications::{
+    NotificationId, NotificationInput, NotificationKind, NotificationRequest, NotificationSource,
+};
+
+/// Each pick replaces the previous result toast.
+const PICK_NOTIFICATION_ID: NotificationId = u64::MAX - 0xc0;
+
+// ── D-Bus activation ─────────────────────────────────────────────────
+
+struct ColorPickerDbus {
+    sender: relm4::Sender<ColorPickerInput>,
+}
+
+#[zbus::interface(name = "dev.jb.shell.ColorPicker")]
+impl ColorPickerDbus {
+    fn pick(&self) {
+        self.sender.emit(ColorPickerInput::Start);
+    }
+}
+
+fn spawn_color_picker_dbus(sender: relm4::Sender<ColorPickerInput>) {
+    crate::dbus::serve(
+        "/dev/jb/shell/ColorPicker",
+        "dev.jb.shell.ColorPicker",
+        ColorPickerDbus { sender },
+    );
+}
+
+// ── relm4 Component ──────────────────────────────────────────────────
+
+/// Eyedropper: a transparent crosshair overlay on the focused monitor. A click
+/// samples the window under the pointer through `workspace_capture`, copies
+/// the hex value and toasts a swatch.
+pub struct ColorPickerModel {
+    visible: bool,
+    /// A click was taken and the capture thread hasn't answered yet.
+    sampling: bool,
+    monitor: Monitor,
+    notif_sender: relm4::Sender<NotificationInput>,
+}
+
+pub struct ColorPickerInit {
+    pub monitor: Monitor,
+    pub notif_sender: relm4::Sender<NotificationInput>,
+}
+
+#[derive(Debug)]
+pub enum ColorPickerInput {
+    Start,
+    Cancel,
+    Sample,
+    Sampled(Option<[u8; 3]>),
+    /// The fallback monitor went away (hotplug) — use another one.
+    SetMonitor(Monitor),
+}
+
+pub struct ColorPickerWidgets {
+    overlay: Window,
+}
+
+impl Component for ColorPickerModel {
+    type Init = ColorPickerInit;
+    type Input = ColorPickerInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = ColorPickerWidgets;
+
+    fn init_root() -> Self::Root {
+        // Invisible root — the real UI is the overlay window
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        init: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let overlay = Window::new();
+        crate::widgets::a11y::name(&overlay, "Color picker");
+        overlay.set_widget_name("color-picker-overlay");
+        overlay.init_layer_shell();
+        overlay.set_namespace(Some("jb-shell-color-picker"));
+        overlay.set_layer(Layer::Overlay);
+        overlay.set_exclusive_zone(-1);
+        overlay.set_anchor(Edge::Top, true);
+        overlay.set_anchor(Edge::Bottom, true);
+        overlay.set_anchor(Edge::Left, true);
+        overlay.set_anchor(Edge::Right, true);
+        overlay.set_keyboard_mode(KeyboardMode::Exclusive);
+        overlay.set_monitor(Some(&init.monitor));
+        overlay.set_cursor_from_name(Some("crosshair"));
+
+        let hint = Label::new(Some("Click to pick a color \u{b7} Esc to cancel"));
+        hint.set_widget_name("color-picker-hint");
+        hint.set_halign(gtk4::Align::Center);
+        hint.set_valign(gtk4::Align::Start);
+        hint.set_margin_top(48);
+        overlay.set_child(Some(&hint));
+        overlay.set_visible(false);
+
+        let click = GestureClick::new();
+        click.set_button(gdk4::BUTTON_PRIMARY);
+        let click_sender = sender.input_sender().clone();
+        click.connect_pressed(move |_, _, _, _| {
+            click_sender.emit(ColorPickerInput::Sample);
+        });
+        overlay.add_controller(click);
+
+        let key_ctl = EventControllerKey::new();
+        let key_sender = sender.input_sender().clone();
+        key_ctl.connect_key_pressed(move |_, keyval, _keycode, _state| {
+            if keyval == gdk4::Key::Escape {
+                key_sender.emit(ColorPickerInput::Cancel);
+                glib::Propagation::Stop
+            } else {
+                glib::Propagation::Proceed
+            }
+        });
+        overlay.add_controller(key_ctl);
+
+        spawn_color_picker_dbus(sender.input_sender().clone());
+
+        let model = ColorPickerModel {
+            visible: false,
+            sampling: false,
+            monitor: init.monitor,
+            notif_sender: init.notif_sender,
+        };
+        let widgets = ColorPickerWidgets { overlay };
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            ColorPickerInput::Start => {
+                if !self.visible {
+                    let monitor = focused_monitor().unwrap_or_else(|| self.monitor.clone());
+                    widgets.overlay.set_monitor(Some(&monitor));
+                    self.sampling = false;
+                    self.visible = true;
+                }
+            }
+            ColorPickerInput::Cancel => {
+                self.visible = false;
+            }
+            ColorPickerInput::Sample => {
+                if self.visible && !self.sampling {
+                    self.sampling = true;
+                    let result_sender = sender.input_sender().clone();
+                    std::thread::spawn(move || {
+                        let color = crate::workspace_capture::sample_cursor_color();
+                        result_sender.emit(ColorPickerInput::Sampled(color));
+                    });
+                }
+            }
+            ColorPickerInput::Sampled(color) => {
+                self.sampling = false;
+                // Set the clipboard while the overlay still holds focus
+                let request = match color {
+                    Some(rgb) => {
+                        let hex = format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2]);
+                        widgets.overlay.clipboard().set_text(&hex);
+                        eprintln!("jb-shell: [color-picker] picked {hex}");
+                        pick_notification(&hex, Some(rgb))
+                    }
+                    None => pick_notification("No color", None),
+                };
+                self.notif_sender.emit(NotificationInput::Show(request));
+                self.visible = false;
+            }
+            ColorPickerInput::SetMonitor(monitor) => {
+                self.monitor = monitor;
+                self.visible = false;
+            }
+        }
+
+        widgets.overlay.set_visible(self.visible);
+    }
+}
+
+/// The GDK monitor Hyprland currently has focused.
+pub fn focused_monitor() -> Option<Monitor> {
+    let name = HyprMonitor::get_active().ok()?.name;
+    let monitors = gdk4::Display::default()?.monitors();
+    (0..monitors.n_items())
+        .filter_map(|i| monitors.item(i)?.downcast::<Monitor>().ok())
+        .find(|m| m.connector().is_some_and(|c| c == name))
+}
+
+fn pick_notification(title: &str, rgb: Option<[u8; 3]>) -> NotificationRequest {
+    let body = match rgb {
+        Some([r, g, b]) => format!("rgb({r}, {g}, {b}) \u{b7} copied to clipboard"),
+        None => "Nothing to sample there \u{2014} only windows can be captured".to_string(),
+    };
+    NotificationRequest {
+        id: PICK_NOTIFICATION_ID,
+        kind: NotificationKind::Toast,
+        icon: None,
+        title: title.to_string(),
+        body: Some(body),
+        subtitle: None,
+        countdown_target: None,
+        actions: Vec::new(),
+        css_window_name: None,
+        css_box_name: Some("color-picker-notif".to_string()),
+        css_card_class: None,
+        timeout_ms: Some(5000),
+        repeat_count: 1,
+        swatch: rgb.map(|[r, g, b]| {
+            gdk4::RGBA::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0)
+        }),
+        progress: None,
+        source: NotificationSource::Internal,
+    }
+}
@@ -0,0 +1,160 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Entry, GestureClick, Label, Orientation, Popover, Separator};
+use gtk4_layer_shell::{KeyboardMode, LayerShell};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// One row of a right-click menu.
+pub enum MenuItem {
+    Action {
+        label: String,
+        action: Box<dyn Fn()>,
+    },
+    /// Opens an entry in place of the menu; Enter hands its text over.
+    Prompt {
+        label: String,
+        initial: String,
+        submit: Rc<dyn Fn(String)>,
+    },
+    /// A dim, unclickable line, e.g. what a group of items applies to.
+    Heading(String),
+    Separator,
+}
+
+impl MenuItem {
+    pub fn action(label: impl Into<String>, action: impl Fn() + 'static) -> Self {
+        MenuItem::Action {
+            label: label.into(),
+            action: Box::new(action),
+        }
+    }
+
+    pub fn prompt(
+        label: impl Into<String>,
+        initial: impl Into<String>,
+        submit: impl Fn(String) + 'static,
+    ) -> Self {
+        MenuItem::Prompt {
+            label: label.into(),
+            initial: initial.into(),
+            submit: Rc::new(submit),
+        }
+    }
+}
+
+/// Give a bar widget a right-click menu. `items` runs on every right-click,
+/// so labels can follow the widget's current state; an empty list shows
+/// nothing. A `right-click` in the widget's `[actions]` still wins, since
+/// those claim the click first.
+pub fn attach(widget: &impl IsA<gtk4::Widget>, items: impl Fn() -> Vec<MenuItem> + 'static) {
+    let widget = widget.as_ref().clone();
+    let popover = Popover::new();
+    popover.set_has_arrow(false);
+    popover.set_position(gtk4::PositionType::Bottom);
+    popover.add_css_class("context-menu");
+    popover.set_parent(&widget);
+    crate::widgets::a11y::name(&popover, "Context menu");
+
+    // Bars take no keyboard input; lend them some while the menu is open so
+    // it can be driven by keys and prompts can be typed into
+    let lent_keyboard = Rc::new(Cell::new(false));
+    let lent = lent_keyboard.clone();
+    popover.connect_closed(move |popover| {
+        if lent.replace(false) {
+            if let Some(window) = popover.root().and_downcast::<gtk4::Window>() {
+                window.set_keyboard_mode(KeyboardMode::None);
+            }
+        }
+    });
+
+    let click = GestureClick::new();
+    click.set_button(gdk4::BUTTON_SECONDARY);
+    let popover_ref = popover.clone();
+    click.connect_pressed(move |gesture, _, x, y| {
+        let items = items();
+        if items.is_empty() {
+            return;
+        }
+        gesture.set_state(gtk4::EventSequenceState::Claimed);
+        popover_ref.set_child(Some(&build_menu(&popover_ref, items)));
+        popover_ref.set_pointing_to(Some(&gdk4::Rectangle::new(x as i32, y as i32, 1, 1)));
+        if let Some(window) = popover_ref.root().and_downcast::<gtk4::Window>() {
+            if window.keyboard_mode() == KeyboardMode::None {
+                window.set_keyboard_mode(KeyboardMode::OnDemand);
+                lent_keyboard.set(true);
+            }
+        }
+        popover_ref.popup();
+    });
+    widget.add_controller(click);
+
+    // A popover is a child of its widget; let go before the widget does
+    widget.connect_destroy(move |_| popover.unparent());
+}
+
+fn build_menu(popover: &Popover, items: Vec<MenuItem>) -> GtkBox {
+    let menu = GtkBox::new(Orientation::Vertical, 2);
+    menu.add_css_class("context-menu-items");
+    for item in items {
+        match item {
+            MenuItem::Action { label, action } => {
+                let btn = menu_button(&label);
+                let popover = popover.clone();
+                btn.connect_clicked(move |_| {
+                    popover.popdown();
+                    action();
+                });
+                menu.append(&btn);
+            }
+            MenuItem::Prompt {
+                label,
+                initial,
+                submit,
+            } => {
+                let btn = menu_button(&format!("{label}\u{2026}"));
+                let popover = popover.clone();
+                btn.connect_clicked(move |_| {
+                    let entry = prompt(&popover, &label, &initial, submit.clone());
+                    popover.set_child(Some(&entry));
+                    entry.grab_focus();
+                    entry.select_region(0, -1);
+                });
+                menu.append(&btn);
+            }
+            MenuItem::Heading(text) => {
+                let heading = Label::new(Some(&text));
+                heading.set_xalign(0.0);
+                heading.add_css_class("context-menu-heading");
+                heading.add_css_class("dim");
+                menu.append(&heading);
+            }
+            MenuItem::Separator => menu.append(&Separator::new(Orientation::Horizontal)),
+        }
+    }
+    menu
+}
+
+fn menu_button(label: &str) -> Button {
+    let btn = Button::with_label(label);
+    btn.add_css_class("flat");
+    btn.add_css_class("context-menu-item");
+    if let Some(label) = btn.child().and_downcast::<Label>() {
+        label.set_xalign(0.0);
+    }
+    btn
+}
+
+/// The entry a `Prompt` item swaps the menu for.
+fn prompt(popover: &Popover, label: &str, initial: &str, submit: Rc<dyn Fn(String)>) -> Entry {
+    let entry = Entry::new();
+    entry.add_css_class("context-menu-entry");
+    entry.set_text(initial);
+    entry.set_placeholder_text(Some(label));
+    crate::widgets::a11y::name(&entry, label);
+    let popover_ref = popover.clone();
+    entry.connect_activate(move |entry| {
+        popover_ref.popdown();
+        submit(entry.text().trim().to_string());
+    });
+    entry
+}
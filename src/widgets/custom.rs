@@ -0,0 +1,97 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::config::CustomModuleConfig;
+use crate::core::waybar::{format_output, ModuleOutput};
+use crate::shell::Subscribers;
+use crate::widgets::tooltip::Tooltip;
+
+/// Bar view of a `[custom.<name>]` script: formatted text, the script's
+/// classes on the box and its tooltip. Hidden while the text is empty, as in
+/// waybar.
+pub struct CustomModel {
+    config: &'static CustomModuleConfig,
+    output: ModuleOutput,
+}
+
+pub struct CustomInit {
+    pub name: String,
+    pub config: &'static CustomModuleConfig,
+    pub feed: Subscribers<CustomInput>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CustomInput {
+    Update(ModuleOutput),
+}
+
+pub struct CustomWidgets {
+    root: GtkBox,
+    label: Label,
+    /// Classes from the last update, removed before the next one's are added.
+    classes: Vec<String>,
+    tooltip: Tooltip,
+}
+
+impl SimpleComponent for CustomModel {
+    type Init = CustomInit;
+    type Input = CustomInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = CustomWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.add_css_class("custom");
+        b.set_visible(false);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        root.set_widget_name(&format!("custom-{}", init.name));
+        let label = Label::new(None);
+        root.append(&label);
+
+        init.feed.subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&root);
+        let model = CustomModel {
+            config: init.config,
+            output: ModuleOutput::default(),
+        };
+        let widgets = CustomWidgets {
+            root,
+            label,
+            classes: Vec::new(),
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            CustomInput::Update(output) => self.output = output,
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        let text = format_output(&self.config.format, &self.output, &self.config.format_icons);
+        // Waybar modules may use Pango markup in their text
+        widgets.label.set_markup(&text);
+        widgets.root.set_visible(!self.output.text.is_empty());
+        widgets.tooltip.set_markup(&self.output.tooltip);
+
+        for class in widgets.classes.drain(..) {
+            widgets.root.remove_css_class(&class);
+        }
+        for class in &self.output.class {
+            widgets.root.add_css_class(class);
+        }
+        widgets.classes = self.output.class.clone();
+    }
+}
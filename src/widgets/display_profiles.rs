@@ -0,0 +1,194 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Image, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::display_profiles::{DisplayCommand, DisplayState};
+use crate::shell::DisplayHandle;
+use crate::widgets::switcher::BarPopup;
+use crate::widgets::tooltip::Tooltip;
+
+/// Bar picker for the `[display]` profiles: click opens a popup with one row
+/// per profile, the applied one highlighted.
+pub struct DisplayModel {
+    state: Option<DisplayState>,
+    popup_visible: bool,
+    handle: DisplayHandle,
+}
+
+pub struct DisplayInit {
+    pub monitor: Monitor,
+    pub handle: DisplayHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum DisplayInput {
+    State(DisplayState),
+    Apply(String),
+    TogglePopup,
+    HidePopup,
+}
+
+pub struct DisplayWidgets {
+    trigger: Button,
+    popup: BarPopup,
+    rows_box: GtkBox,
+    /// Profile buttons by name, built from the first state.
+    rows: Vec<(String, Button)>,
+    tooltip: Tooltip,
+}
+
+impl Component for DisplayModel {
+    type Init = DisplayInit;
+    type Input = DisplayInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = DisplayWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("display");
+        b.set_valign(gtk4::Align::Center);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let icon = Image::from_icon_name("video-display-symbolic");
+        icon.set_pixel_size(16);
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "Display profiles");
+        trigger.set_widget_name("display-trigger");
+        trigger.set_child(Some(&icon));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(DisplayInput::TogglePopup);
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 8);
+        let title = Label::new(Some("Display profile"));
+        title.set_halign(gtk4::Align::Start);
+        popup_box.append(&title);
+        let rows_box = GtkBox::new(Orientation::Vertical, 2);
+        popup_box.append(&rows_box);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "display-popup",
+            "Display profiles",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(DisplayInput::HidePopup),
+        );
+
+        init.handle
+            .subscribers
+            .subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&trigger);
+        let model = DisplayModel {
+            state: None,
+            popup_visible: false,
+            handle: init.handle,
+        };
+        let widgets = DisplayWidgets {
+            trigger,
+            popup,
+            rows_box,
+            rows: Vec::new(),
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            DisplayInput::State(state) => {
+                if widgets.rows_box.first_child().is_none() {
+                    build_rows(widgets, &state.profiles, &sender);
+                }
+                self.state = Some(state);
+            }
+            DisplayInput::Apply(name) => {
+                let _ = self.handle.thread_tx.send(DisplayCommand::Apply(name));
+                self.popup_visible = false;
+            }
+            DisplayInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            DisplayInput::HidePopup => {
+                self.popup_visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        if let Some(state) = &self.state {
+            for (name, button) in &widgets.rows {
+                if state.active.as_ref() == Some(name) {
+                    button.add_css_class("active");
+                } else {
+                    button.remove_css_class("active");
+                }
+            }
+
+            let title = match &state.active {
+                Some(name) => format!("<b>Display: {}</b>", glib::markup_escape_text(name)),
+                None => "<b>Display</b>".to_string(),
+            };
+            let outputs = if state.outputs.is_empty() {
+                "No outputs reported".to_string()
+            } else {
+                state.outputs.join(", ")
+            };
+            widgets
+                .tooltip
+                .set_markup(&format!("{title}\n{}", glib::markup_escape_text(&outputs)));
+        }
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+fn build_rows(
+    widgets: &mut DisplayWidgets,
+    profiles: &[String],
+    sender: &ComponentSender<DisplayModel>,
+) {
+    if profiles.is_empty() {
+        let empty = Label::new(Some("No [display] profiles configured"));
+        empty.add_css_class("dim");
+        widgets.rows_box.append(&empty);
+        return;
+    }
+    for name in profiles {
+        let button = Button::with_label(name);
+        button.set_widget_name("display-profile");
+        let apply_sender = sender.input_sender().clone();
+        let profile = name.clone();
+        button.connect_clicked(move |_| {
+            apply_sender.emit(DisplayInput::Apply(profile.clone()));
+        });
+        widgets.rows_box.append(&button);
+        widgets.rows.push((name.clone(), button));
+    }
+}
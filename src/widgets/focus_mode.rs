@@ -0,0 +1,97 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::focus_mode::{FocusCommand, FocusState};
+use crate::shell::FocusHandle;
+use crate::widgets::tooltip::Tooltip;
+
+/// Bar toggle for focus mode. Lit while it's on; the tooltip lists the parked
+/// apps.
+pub struct FocusModel {
+    state: FocusState,
+    handle: FocusHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum FocusInput {
+    State(FocusState),
+    Toggle,
+}
+
+pub struct FocusWidgets {
+    button: Button,
+    tooltip: Tooltip,
+}
+
+impl SimpleComponent for FocusModel {
+    type Init = FocusHandle;
+    type Input = FocusInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = FocusWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("focus");
+        b.set_valign(gtk4::Align::Center);
+        b
+    }
+
+    fn init(
+        handle: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let button = Button::new();
+        crate::widgets::a11y::name(&button, "Focus mode");
+        button.set_child(Some(&Label::new(Some("\u{f140}"))));
+        root.append(&button);
+
+        let toggle_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            toggle_sender.emit(FocusInput::Toggle);
+        });
+
+        handle.subscribers.subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&button);
+        let model = FocusModel {
+            state: FocusState::default(),
+            handle,
+        };
+        let widgets = FocusWidgets { button, tooltip };
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            FocusInput::State(state) => self.state = state,
+            FocusInput::Toggle => {
+                let _ = self.handle.thread_tx.send(FocusCommand::Toggle);
+            }
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        let markup = if self.state.active {
+            widgets.button.add_css_class("active");
+            let parked = if self.state.parked.is_empty() {
+                "Nothing to park".to_string()
+            } else {
+                let mut classes = self.state.parked.clone();
+                classes.sort();
+                classes.dedup();
+                format!("Parked: {}", classes.join(", "))
+            };
+            format!(
+                "<b>Focus mode</b>\nDo not disturb is on\n{}",
+                glib::markup_escape_text(&parked)
+            )
+        } else {
+            widgets.button.remove_css_class("active");
+            "<b>Focus mode off</b>\nClick to hide distracting apps and silence toasts".to_string()
+        };
+        widgets.tooltip.set_markup(&markup);
+    }
+}
@@ -7,6 +7,7 @@ pub struct GcloudProvider;
 
 impl SwitcherProvider for GcloudProvider {
     const WIDGET_NAME: &'static str = "gcloud-config";
+    const DESCRIPTION: &'static str = "gcloud configuration";
     const TRIGGER_NAME: &'static str = "gcloud-trigger";
     const POPUP_NAME: &'static str = "gcloud-popup";
     const MENU_ITEM_NAME: &'static str = "gcloud-menu-item";
@@ -0,0 +1,270 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+
+use crate::core::exec::shell_quote;
+use crate::git_status::GitRepoState;
+use crate::shell::Subscribers;
+use crate::widgets::switcher::BarPopup;
+use crate::widgets::tooltip::Tooltip;
+
+/// Branch and dirty/ahead/behind state of the `[git] repos`; click opens a
+/// popup with each repo's recent commits and terminal/editor buttons.
+pub struct GitModel {
+    repos: Vec<GitRepoState>,
+    popup_visible: bool,
+}
+
+pub struct GitInit {
+    pub monitor: Monitor,
+    pub feed: Subscribers<GitInput>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GitInput {
+    Repos(Vec<GitRepoState>),
+    TogglePopup,
+    HidePopup,
+}
+
+pub struct GitWidgets {
+    label: Label,
+    trigger: Button,
+    popup: BarPopup,
+    popup_box: GtkBox,
+    tooltip: Tooltip,
+}
+
+impl Component for GitModel {
+    type Init = GitInit;
+    type Input = GitInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = GitWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("git");
+        b.set_valign(gtk4::Align::Center);
+        b.set_visible(false);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let trigger_box = GtkBox::new(Orientation::Horizontal, 4);
+        let icon = Label::new(Some("\u{e0a0}"));
+        icon.add_css_class("git-icon");
+        let label = Label::new(None);
+        trigger_box.append(&icon);
+        trigger_box.append(&label);
+
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "Git repositories");
+        trigger.set_widget_name("git-trigger");
+        trigger.set_child(Some(&trigger_box));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(GitInput::TogglePopup);
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 8);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "git-popup",
+            "Git repositories",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(GitInput::HidePopup),
+        );
+
+        init.feed.subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&trigger);
+        let model = GitModel {
+            repos: Vec::new(),
+            popup_visible: false,
+        };
+        let widgets = GitWidgets {
+            label,
+            trigger,
+            popup,
+            popup_box,
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            GitInput::Repos(repos) => {
+                root.set_visible(!repos.is_empty());
+                self.repos = repos;
+            }
+            GitInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            GitInput::HidePopup => {
+                self.popup_visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
+        // One repo shows its name only in the tooltip; several are prefixed
+        let named = self.repos.len() > 1;
+        let parts: Vec<String> = self
+            .repos
+            .iter()
+            .map(|repo| {
+                let state = repo_summary(repo);
+                if named {
+                    format!("{} {state}", repo.name)
+                } else {
+                    state
+                }
+            })
+            .collect();
+        widgets.label.set_label(&parts.join("  "));
+
+        let dirty = self
+            .repos
+            .iter()
+            .any(|r| r.status.as_ref().is_some_and(|s| s.is_dirty()));
+        if dirty {
+            widgets.trigger.add_css_class("dirty");
+        } else {
+            widgets.trigger.remove_css_class("dirty");
+        }
+
+        let tooltip: Vec<String> = self
+            .repos
+            .iter()
+            .map(|repo| {
+                let upstream = repo
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.upstream.as_deref())
+                    .map(|u| format!(" \u{2192} {u}"))
+                    .unwrap_or_default();
+                format!(
+                    "<b>{}</b>{}\n{}",
+                    glib::markup_escape_text(&repo.name),
+                    glib::markup_escape_text(&upstream),
+                    glib::markup_escape_text(&repo.path.display().to_string())
+                )
+            })
+            .collect();
+        widgets.tooltip.set_markup(&tooltip.join("\n"));
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            while let Some(child) = widgets.popup_box.first_child() {
+                widgets.popup_box.remove(&child);
+            }
+            for repo in &self.repos {
+                widgets.popup_box.append(&build_repo_section(repo, &sender));
+            }
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+fn repo_summary(repo: &GitRepoState) -> String {
+    match &repo.status {
+        Some(status) => status.summary(),
+        None => "not a repo".to_string(),
+    }
+}
+
+fn build_repo_section(repo: &GitRepoState, sender: &ComponentSender<GitModel>) -> GtkBox {
+    let section = GtkBox::new(Orientation::Vertical, 2);
+    section.add_css_class("git-repo");
+
+    let header = Label::new(None);
+    header.set_halign(gtk4::Align::Start);
+    header.set_markup(&format!(
+        "<b>{}</b>  {}",
+        glib::markup_escape_text(&repo.name),
+        glib::markup_escape_text(&repo_summary(repo))
+    ));
+    section.append(&header);
+
+    for commit in &repo.commits {
+        let row = GtkBox::new(Orientation::Horizontal, 6);
+        row.add_css_class("git-commit");
+        let hash = Label::new(Some(&commit.hash));
+        hash.add_css_class("dim");
+        let subject = Label::new(Some(&commit.subject));
+        subject.set_halign(gtk4::Align::Start);
+        subject.set_hexpand(true);
+        subject.set_max_width_chars(50);
+        subject.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        let age = Label::new(Some(&commit.age));
+        age.add_css_class("dim");
+        row.append(&hash);
+        row.append(&subject);
+        row.append(&age);
+        section.append(&row);
+    }
+
+    let buttons = GtkBox::new(Orientation::Horizontal, 4);
+    buttons.set_halign(gtk4::Align::End);
+    let terminal = Button::with_label("Terminal");
+    let editor = Button::with_label("Editor");
+    for (button, in_terminal) in [(&terminal, true), (&editor, false)] {
+        button.set_widget_name("git-open");
+        let path = repo.path.clone();
+        let hide_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            open_repo(&path, in_terminal);
+            hide_sender.emit(GitInput::HidePopup);
+        });
+        buttons.append(button);
+    }
+    section.append(&buttons);
+    section
+}
+
+/// Start a terminal in the repository, or `[git] editor` on it.
+fn open_repo(path: &Path, in_terminal: bool) {
+    let quoted = shell_quote(&path.display().to_string());
+    let command = if in_terminal {
+        format!(
+            "cd {quoted} && exec {}",
+            crate::widgets::launcher::terminal_command()
+        )
+    } else {
+        format!("{} {quoted}", crate::config::get().git.editor)
+    };
+    if let Err(e) = std::process::Command::new("sh")
+        .args(["-c", &command])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()
+    {
+        eprintln!("jb-shell: [git] failed to run `{command}`: {e}");
+    }
+}
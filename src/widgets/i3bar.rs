@@ -0,0 +1,151 @@
+use gtk4::prelude::*;
+use gtk4::{
+    glib, Box as GtkBox, EventControllerScroll, EventControllerScrollFlags, GestureClick, Label,
+    Orientation, Separator,
+};
+use relm4::prelude::*;
+
+use crate::core::i3bar::Block;
+use crate::i3bar::I3barClick;
+use crate::shell::I3barHandle;
+
+/// Bar view of an i3bar-protocol generator (i3status-rust, bumblebee-status):
+/// one label per block, colored as the generator asks, clicks sent back.
+pub struct I3barModel {
+    blocks: Vec<Block>,
+    handle: I3barHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum I3barInput {
+    Blocks(Vec<Block>),
+}
+
+pub struct I3barWidgets {
+    root: GtkBox,
+}
+
+impl SimpleComponent for I3barModel {
+    type Init = I3barHandle;
+    type Input = I3barInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = I3barWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 8);
+        b.set_widget_name("i3bar");
+        b.set_visible(false);
+        b
+    }
+
+    fn init(
+        handle: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        handle.subscribers.subscribe(sender.input_sender().clone());
+        let model = I3barModel {
+            blocks: Vec::new(),
+            handle,
+        };
+        let widgets = I3barWidgets { root };
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            I3barInput::Blocks(blocks) => self.blocks = blocks,
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        let root = &widgets.root;
+        while let Some(child) = root.first_child() {
+            root.remove(&child);
+        }
+        let shown: Vec<&Block> = self
+            .blocks
+            .iter()
+            .filter(|block| !block.full_text.is_empty())
+            .collect();
+        for (i, block) in shown.iter().enumerate() {
+            root.append(&self.block_label(block));
+            if block.separator && i + 1 < shown.len() {
+                let separator = Separator::new(Orientation::Vertical);
+                separator.add_css_class("bar-separator");
+                root.append(&separator);
+            }
+        }
+        root.set_visible(!shown.is_empty());
+    }
+}
+
+impl I3barModel {
+    fn block_label(&self, block: &Block) -> Label {
+        let label = Label::new(None);
+        label.add_css_class("i3bar-block");
+        if let Some(name) = &block.name {
+            label.add_css_class(&format!("block-{name}"));
+        }
+        if block.urgent {
+            label.add_css_class("urgent");
+        }
+        label.set_markup(&block_markup(block));
+
+        // Clicks go back to the generator by block name/instance
+        let click = GestureClick::new();
+        click.set_button(0);
+        let tx = self.handle.thread_tx.clone();
+        let (name, instance) = (block.name.clone(), block.instance.clone());
+        click.connect_pressed(move |gesture, _, _, _| {
+            let _ = tx.send(I3barClick {
+                name: name.clone(),
+                instance: instance.clone(),
+                button: gesture.current_button(),
+            });
+        });
+        label.add_controller(click);
+
+        let scroll = EventControllerScroll::new(EventControllerScrollFlags::VERTICAL);
+        let tx = self.handle.thread_tx.clone();
+        let (name, instance) = (block.name.clone(), block.instance.clone());
+        scroll.connect_scroll(move |_, _, dy| {
+            let _ = tx.send(I3barClick {
+                name: name.clone(),
+                instance: instance.clone(),
+                button: if dy < 0.0 { 4 } else { 5 },
+            });
+            glib::Propagation::Stop
+        });
+        label.add_controller(scroll);
+        label
+    }
+}
+
+/// `full_text` as Pango markup, wrapped in a span for the block's colors.
+fn block_markup(block: &Block) -> String {
+    let text = if block.is_pango() {
+        block.full_text.clone()
+    } else {
+        glib::markup_escape_text(&block.full_text).to_string()
+    };
+    let mut attrs = String::new();
+    if let Some(color) = &block.color {
+        attrs.push_str(&format!(
+            " foreground=\"{}\"",
+            glib::markup_escape_text(color)
+        ));
+    }
+    if let Some(background) = &block.background {
+        attrs.push_str(&format!(
+            " background=\"{}\"",
+            glib::markup_escape_text(background)
+        ));
+    }
+    if attrs.is_empty() {
+        text
+    } else {
+        format!("<span{attrs}>{text}</span>")
+    }
+}
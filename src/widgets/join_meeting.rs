@@ -0,0 +1,128 @@
+use chrono::Local;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+use std::time::Duration;
+
+use crate::google_calendar::CalendarEvent;
+use crate::shell::CalendarHandle;
+use crate::widgets::calendar::{nearest_meeting, open_meeting_link, CalendarInput};
+
+#[derive(Debug)]
+pub enum JoinMeetingInput {
+    EventsUpdated(Vec<CalendarEvent>),
+    /// Signed out of Google Calendar: nothing left to join.
+    NeedsAuth,
+    Join,
+    /// Re-evaluates the join window between event updates.
+    Tick,
+}
+
+/// "Join" button that appears when a meeting with a link starts within
+/// `[meetings] join_button_minutes` (or is already running).
+pub struct JoinMeetingModel {
+    events: Vec<CalendarEvent>,
+}
+
+pub struct JoinMeetingWidgets {
+    button: Button,
+    label: Label,
+}
+
+impl SimpleComponent for JoinMeetingModel {
+    type Init = CalendarHandle;
+    type Input = JoinMeetingInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = JoinMeetingWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("join-meeting");
+        b.set_valign(gtk4::Align::Center);
+        b.set_visible(false);
+        b
+    }
+
+    fn init(
+        calendar: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let label = Label::new(None);
+        let button = Button::new();
+        crate::widgets::a11y::name(&button, "Join next meeting");
+        button.set_child(Some(&label));
+        root.append(&button);
+
+        let click_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            click_sender.emit(JoinMeetingInput::Join);
+        });
+
+        // Re-evaluate the window every few seconds, not just on event updates
+        let check_sender = sender.input_sender().clone();
+        glib::timeout_add_local(Duration::from_secs(5), move || {
+            check_sender.emit(JoinMeetingInput::Tick);
+            glib::ControlFlow::Continue
+        });
+
+        let (calendar_feed, calendar_rx) = relm4::channel::<CalendarInput>();
+        calendar.subscribers.subscribe(calendar_feed);
+        let input = sender.input_sender().clone();
+        crate::runtime::handle().spawn(async move {
+            while let Some(msg) = calendar_rx.recv().await {
+                let msg = match msg {
+                    CalendarInput::EventsUpdated(events) => JoinMeetingInput::EventsUpdated(events),
+                    CalendarInput::AuthRevoked | CalendarInput::NeedsAuth => {
+                        JoinMeetingInput::NeedsAuth
+                    }
+                    _ => continue,
+                };
+                if input.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let model = JoinMeetingModel { events: Vec::new() };
+        let widgets = JoinMeetingWidgets { button, label };
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            JoinMeetingInput::EventsUpdated(events) => self.events = events,
+            JoinMeetingInput::NeedsAuth => self.events.clear(),
+            JoinMeetingInput::Join => {
+                if let Some(url) = nearest_meeting(&self.events, Local::now())
+                    .and_then(|e| e.meeting_link.as_deref())
+                {
+                    open_meeting_link(url);
+                }
+            }
+            JoinMeetingInput::Tick => {}
+        }
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        let now = Local::now();
+        let window =
+            chrono::TimeDelta::minutes(crate::config::get().meetings.join_button_minutes as i64);
+        let meeting = nearest_meeting(&self.events, now).filter(|e| e.start - now <= window);
+
+        let Some(root) = widgets.button.parent() else {
+            return;
+        };
+        root.set_visible(meeting.is_some());
+        if let Some(event) = meeting {
+            let mins = (event.start - now).num_minutes();
+            widgets.label.set_label(&if mins > 0 {
+                format!("\u{f03d} Join in {mins}m")
+            } else {
+                "\u{f03d} Join".to_string()
+            });
+            widgets.button.set_tooltip_text(Some(&event.title));
+        }
+    }
+}
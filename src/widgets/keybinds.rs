@@ -0,0 +1,450 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, EventControllerKey, FlowBox, Label, Orientation, ScrolledWindow, SearchEntry,
+    SelectionMode, Window,
+};
+use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use relm4::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+// ── Keybind sources ──────────────────────────────────────────────────
+
+#[derive(Clone, Debug)]
+struct Keybind {
+    keys: String,
+    action: String,
+    category: String,
+}
+
+/// One entry of `hyprctl binds -j`.
+#[derive(Deserialize)]
+struct HyprBind {
+    #[serde(default)]
+    modmask: u32,
+    #[serde(default)]
+    submap: String,
+    #[serde(default)]
+    key: String,
+    #[serde(default)]
+    has_description: bool,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    dispatcher: String,
+    #[serde(default)]
+    arg: String,
+}
+
+const MOD_NAMES: &[(u32, &str)] = &[
+    (64, "SUPER"),
+    (4, "CTRL"),
+    (8, "ALT"),
+    (1, "SHIFT"),
+    (2, "CAPS"),
+    (16, "MOD2"),
+    (32, "MOD3"),
+    (128, "MOD5"),
+];
+
+fn format_modmask(modmask: u32) -> Vec<&'static str> {
+    MOD_NAMES
+        .iter()
+        .filter(|(bit, _)| modmask & bit != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn category_for(dispatcher: &str, submap: &str) -> String {
+    if !submap.is_empty() {
+        return format!("Submap: {submap}");
+    }
+    let category = match dispatcher.to_lowercase().as_str() {
+        "workspace"
+        | "movetoworkspace"
+        | "movetoworkspacesilent"
+        | "togglespecialworkspace"
+        | "focusworkspaceoncurrentmonitor"
+        | "movecurrentworkspacetomonitor"
+        | "focusmonitor" => "Workspaces",
+        "movefocus" | "movewindow" | "swapwindow" | "resizeactive" | "moveactive"
+        | "killactive" | "closewindow" | "togglefloating" | "setfloating" | "fullscreen"
+        | "fakefullscreen" | "pin" | "pseudo" | "togglesplit" | "layoutmsg" | "centerwindow"
+        | "cyclenext" | "focuswindow" | "togglegroup" | "changegroupactive" => "Windows",
+        "exec" | "execr" => "Launch",
+        "submap" => "Submaps",
+        "exit" | "dpms" | "forcerendererreload" => "Session",
+        _ => "Other",
+    };
+    category.to_string()
+}
+
+fn describe(dispatcher: &str, arg: &str, description: Option<&str>) -> String {
+    if let Some(desc) = description.filter(|d| !d.is_empty()) {
+        return desc.to_string();
+    }
+    if arg.is_empty() {
+        dispatcher.to_string()
+    } else {
+        format!("{dispatcher} {arg}")
+    }
+}
+
+fn load_from_hyprctl() -> Option<Vec<Keybind>> {
+    let output = Command::new("hyprctl").args(["binds", "-j"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let binds: Vec<HyprBind> = serde_json::from_slice(&output.stdout).ok()?;
+
+    Some(
+        binds
+            .into_iter()
+            .filter(|b| !b.key.is_empty())
+            .map(|b| {
+                let mut parts: Vec<&str> = format_modmask(b.modmask);
+                parts.push(b.key.as_str());
+                let description = b.has_description.then_some(b.description.as_str());
+                Keybind {
+                    keys: parts.join(" + "),
+                    action: describe(&b.dispatcher, &b.arg, description),
+                    category: category_for(&b.dispatcher, &b.submap),
+                }
+            })
+            .collect(),
+    )
+}
+
+fn hyprland_conf_path() -> PathBuf {
+    std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".into())).join(".config")
+        })
+        .join("hypr/hyprland.conf")
+}
+
+/// Fallback parser for `bind* = MODS, KEY, dispatcher, arg` lines when hyprctl is unavailable.
+/// Expands `$variables` defined earlier in the file; `source =` includes are not followed.
+fn load_from_conf() -> Vec<Keybind> {
+    let Ok(content) = std::fs::read_to_string(hyprland_conf_path()) else {
+        return Vec::new();
+    };
+
+    let mut vars: Vec<(String, String)> = Vec::new();
+    let mut submap = String::new();
+    let mut binds = Vec::new();
+
+    for line in content.lines() {
+        let line = crate::core::hyprland_conf::strip_comment(line);
+        let line = line.trim();
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let mut value = value.trim().to_string();
+
+        if let Some(name) = key.strip_prefix('$') {
+            vars.push((format!("${name}"), value));
+            // Longest names first so `$mainModShift` wins over `$mainMod`
+            vars.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+            continue;
+        }
+        for (name, replacement) in &vars {
+            value = value.replace(name.as_str(), replacement);
+        }
+
+        if key == "submap" {
+            submap = if value == "reset" { String::new() } else { value };
+            continue;
+        }
+        if !key.starts_with("bind") {
+            continue;
+        }
+
+        // `bindd` carries a description as the third field
+        let has_description = key.trim_start_matches("bind").contains('d');
+        let fields: Vec<&str> = value.splitn(5, ',').map(|f| f.trim()).collect();
+        let (mods, bind_key, rest) = match fields.as_slice() {
+            [mods, bind_key, rest @ ..] => (*mods, *bind_key, rest),
+            _ => continue,
+        };
+        let (description, dispatcher, arg) = if has_description {
+            (
+                rest.first().copied(),
+                rest.get(1).copied().unwrap_or(""),
+                rest.get(2).copied().unwrap_or(""),
+            )
+        } else {
+            (
+                None,
+                rest.first().copied().unwrap_or(""),
+                rest.get(1).copied().unwrap_or(""),
+            )
+        };
+
+        let mut parts: Vec<String> = mods
+            .split(|c: char| c == ' ' || c == '_')
+            .filter(|m| !m.is_empty())
+            .map(|m| m.to_uppercase())
+            .collect();
+        parts.push(bind_key.to_string());
+
+        binds.push(Keybind {
+            keys: parts.join(" + "),
+            action: describe(dispatcher, arg, description),
+            category: category_for(dispatcher, &submap),
+        });
+    }
+
+    binds
+}
+
+fn load_keybinds() -> Vec<Keybind> {
+    load_from_hyprctl().unwrap_or_else(load_from_conf)
+}
+
+fn matches_query(bind: &Keybind, query: &str) -> bool {
+    query.is_empty()
+        || bind.keys.to_lowercase().contains(query)
+        || bind.action.to_lowercase().contains(query)
+        || bind.category.to_lowercase().contains(query)
+}
+
+// ── D-Bus activation ─────────────────────────────────────────────────
+
+struct KeybindsDbus {
+    sender: relm4::Sender<KeybindsInput>,
+}
+
+#[zbus::interface(name = "dev.jb.shell.Keybinds")]
+impl KeybindsDbus {
+    fn show_keybinds(&self) {
+        self.sender.emit(KeybindsInput::Show);
+    }
+}
+
+fn spawn_keybinds_dbus(sender: relm4::Sender<KeybindsInput>) {
+    crate::dbus::serve(
+        "/dev/jb/shell/Keybinds",
+        "dev.jb.shell.Keybinds",
+        KeybindsDbus { sender },
+    );
+}
+
+// ── relm4 Component ──────────────────────────────────────────────────
+
+pub struct KeybindsModel {
+    visible: bool,
+    search_text: String,
+    binds: Vec<Keybind>,
+}
+
+#[derive(Debug)]
+pub enum KeybindsInput {
+    Show,
+    Hide,
+    SearchChanged(String),
+    /// The overlay's monitor went away (hotplug) — move to another one.
+    SetMonitor(Monitor),
+}
+
+pub struct KeybindsWidgets {
+    overlay: Window,
+    search_entry: SearchEntry,
+    groups_box: FlowBox,
+}
+
+impl Component for KeybindsModel {
+    type Init = Monitor;
+    type Input = KeybindsInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = KeybindsWidgets;
+
+    fn init_root() -> Self::Root {
+        // Invisible root — the real UI is the overlay window
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        init: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let monitor = init;
+
+        let overlay = Window::new();
+        crate::widgets::a11y::name(&overlay, "Keyboard shortcuts");
+        overlay.set_widget_name("keybinds-overlay");
+        overlay.init_layer_shell();
+        overlay.set_layer(Layer::Overlay);
+        overlay.set_exclusive_zone(-1);
+        overlay.set_anchor(Edge::Top, true);
+        overlay.set_anchor(Edge::Bottom, true);
+        overlay.set_anchor(Edge::Left, true);
+        overlay.set_anchor(Edge::Right, true);
+        overlay.set_keyboard_mode(KeyboardMode::Exclusive);
+        overlay.set_monitor(Some(&monitor));
+
+        let card = GtkBox::new(Orientation::Vertical, 8);
+        card.set_widget_name("keybinds-card");
+        card.set_margin_top(48);
+        card.set_margin_bottom(48);
+        card.set_margin_start(48);
+        card.set_margin_end(48);
+
+        let search_entry = SearchEntry::new();
+        search_entry.set_widget_name("keybinds-search");
+        search_entry.set_placeholder_text(Some("Search keybinds..."));
+        card.append(&search_entry);
+
+        let search_sender = sender.input_sender().clone();
+        search_entry.connect_search_changed(move |entry| {
+            search_sender.emit(KeybindsInput::SearchChanged(entry.text().to_string()));
+        });
+
+        let groups_box = FlowBox::new();
+        groups_box.set_selection_mode(SelectionMode::None);
+        groups_box.set_max_children_per_line(4);
+        groups_box.set_valign(gtk4::Align::Start);
+
+        let scroll = ScrolledWindow::new();
+        scroll.set_vexpand(true);
+        scroll.set_child(Some(&groups_box));
+        card.append(&scroll);
+
+        overlay.set_child(Some(&card));
+        overlay.set_visible(false);
+
+        let key_ctl = EventControllerKey::new();
+        key_ctl.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        let key_sender = sender.input_sender().clone();
+        key_ctl.connect_key_pressed(move |_, keyval, _keycode, _state| {
+            if keyval == gdk4::Key::Escape {
+                key_sender.emit(KeybindsInput::Hide);
+                glib::Propagation::Stop
+            } else {
+                glib::Propagation::Proceed
+            }
+        });
+        overlay.add_controller(key_ctl);
+
+        spawn_keybinds_dbus(sender.input_sender().clone());
+
+        let model = KeybindsModel {
+            visible: false,
+            search_text: String::new(),
+            binds: Vec::new(),
+        };
+
+        let widgets = KeybindsWidgets {
+            overlay,
+            search_entry,
+            groups_box,
+        };
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            KeybindsInput::Show => {
+                // Reload on every show so config reloads are picked up
+                self.binds = load_keybinds();
+                self.search_text.clear();
+                self.visible = true;
+                widgets.search_entry.set_text("");
+            }
+            KeybindsInput::Hide => {
+                self.visible = false;
+            }
+            KeybindsInput::SearchChanged(text) => {
+                self.search_text = text.to_lowercase();
+            }
+            KeybindsInput::SetMonitor(monitor) => {
+                widgets.overlay.set_monitor(Some(&monitor));
+                self.visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        if self.visible {
+            self.rebuild_groups(&widgets.groups_box);
+            widgets.overlay.set_visible(true);
+            widgets.search_entry.grab_focus();
+        } else {
+            widgets.overlay.set_visible(false);
+        }
+    }
+}
+
+impl KeybindsModel {
+    fn rebuild_groups(&self, groups_box: &FlowBox) {
+        while let Some(child) = groups_box.first_child() {
+            groups_box.remove(&child);
+        }
+
+        let mut groups: BTreeMap<&str, Vec<&Keybind>> = BTreeMap::new();
+        for bind in self
+            .binds
+            .iter()
+            .filter(|b| matches_query(b, &self.search_text))
+        {
+            groups.entry(bind.category.as_str()).or_default().push(bind);
+        }
+
+        if groups.is_empty() {
+            let empty = Label::new(Some("No matching keybinds"));
+            empty.add_css_class("keybinds-empty");
+            groups_box.insert(&empty, -1);
+            return;
+        }
+
+        for (category, binds) in groups {
+            let group = GtkBox::new(Orientation::Vertical, 2);
+            group.add_css_class("keybinds-group");
+            group.set_valign(gtk4::Align::Start);
+
+            let title = Label::new(Some(category));
+            title.add_css_class("keybinds-group-title");
+            title.set_halign(gtk4::Align::Start);
+            group.append(&title);
+
+            for bind in binds {
+                let row = GtkBox::new(Orientation::Horizontal, 12);
+
+                let keys = Label::new(Some(&bind.keys));
+                keys.add_css_class("keybind-keys");
+                keys.set_halign(gtk4::Align::Start);
+                keys.set_width_chars(22);
+                keys.set_xalign(0.0);
+                row.append(&keys);
+
+                let action = Label::new(Some(&bind.action));
+                action.add_css_class("keybind-action");
+                action.set_halign(gtk4::Align::Start);
+                action.set_max_width_chars(40);
+                action.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+                row.append(&action);
+
+                group.append(&row);
+            }
+
+            groups_box.insert(&group, -1);
+        }
+    }
+}
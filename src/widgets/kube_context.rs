@@ -7,6 +7,7 @@ pub struct KubeProvider;
 
 impl SwitcherProvider for KubeProvider {
     const WIDGET_NAME: &'static str = "kube-context";
+    const DESCRIPTION: &'static str = "Kubernetes context";
     const TRIGGER_NAME: &'static str = "kube-trigger";
     const POPUP_NAME: &'static str = "kube-popup";
     const MENU_ITEM_NAME: &'static str = "kube-menu-item";
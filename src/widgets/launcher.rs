@@ -2,35 +2,26 @@ use gdk4::Monitor;
 use gtk4::prelude::*;
 use gtk4::{Box as GtkBox, EventControllerKey, Image, Label, Orientation, SearchEntry, Window};
 use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use hyprland::data::{Client, Clients, Workspace};
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial};
+use hyprland::shared::{HyprData, HyprDataActive, HyprDataVec};
+use inotify::{Inotify, WatchMask};
+
+use crate::config::LauncherPlacement;
+use crate::core::calc;
+use crate::core::exec::{process_exec, shell_quote};
+use crate::core::launcher::{self as ranking, DesktopApp, FrecencyEntry};
+use crate::widgets::wallpaper::WallpaperInput;
 use relm4::prelude::*;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::os::unix::process::CommandExt;
-use std::path::PathBuf;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
-
-// ── Desktop app ──────────────────────────────────────────────────────
-
-#[derive(Clone, Debug)]
-#[allow(dead_code)]
-struct DesktopApp {
-    id: String, // e.g. "firefox.desktop"
-    name: String,
-    exec: String,
-    icon: Option<String>,
-    comment: Option<String>,
-    categories: Vec<String>,
-    keywords: Vec<String>,
-}
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // ── Frecency ─────────────────────────────────────────────────────────
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct FrecencyEntry {
-    count: u32,
-    last_used: u64,
-}
-
 fn frecency_path() -> PathBuf {
     let data_dir = std::env::var("XDG_DATA_HOME")
         .map(PathBuf::from)
@@ -56,27 +47,9 @@ fn save_frecency(frecency: &HashMap<String, FrecencyEntry>) {
     }
 }
 
-fn frecency_score(entry: &FrecencyEntry) -> f64 {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    let age_secs = now.saturating_sub(entry.last_used);
-    let recency_weight = if age_secs < 3600 {
-        1.0
-    } else if age_secs < 86400 {
-        0.8
-    } else if age_secs < 604800 {
-        0.5
-    } else {
-        0.2
-    };
-    entry.count as f64 * recency_weight
-}
-
 // ── .desktop file parsing ────────────────────────────────────────────
 
-fn xdg_app_dirs() -> Vec<PathBuf> {
+pub(crate) fn xdg_app_dirs() -> Vec<PathBuf> {
     let mut dirs = Vec::new();
 
     if let Ok(data_home) = std::env::var("XDG_DATA_HOME") {
@@ -142,6 +115,9 @@ fn parse_desktop_file(path: &std::path::Path, id: &str) -> Option<DesktopApp> {
     let mut app_type = None;
     let mut no_display = false;
     let mut hidden = false;
+    let mut try_exec = None;
+    let mut only_show_in = Vec::new();
+    let mut not_show_in = Vec::new();
 
     for line in content.lines() {
         let line = line.trim();
@@ -165,22 +141,17 @@ fn parse_desktop_file(path: &std::path::Path, id: &str) -> Option<DesktopApp> {
                 "Icon" => icon = Some(value.to_string()),
                 "Comment" => comment = Some(value.to_string()),
                 "Categories" => {
-                    categories = value
-                        .split(';')
-                        .filter(|s| !s.is_empty())
-                        .map(|s| s.to_string())
-                        .collect();
+                    categories = split_list(value);
                 }
                 "Keywords" => {
-                    keywords = value
-                        .split(';')
-                        .filter(|s| !s.is_empty())
-                        .map(|s| s.to_string())
-                        .collect();
+                    keywords = split_list(value);
                 }
                 "Type" => app_type = Some(value.to_string()),
                 "NoDisplay" => no_display = value.eq_ignore_ascii_case("true"),
                 "Hidden" => hidden = value.eq_ignore_ascii_case("true"),
+                "TryExec" => try_exec = Some(value.to_string()),
+                "OnlyShowIn" => only_show_in = split_list(value),
+                "NotShowIn" => not_show_in = split_list(value),
                 _ => {}
             }
         }
@@ -189,9 +160,22 @@ fn parse_desktop_file(path: &std::path::Path, id: &str) -> Option<DesktopApp> {
     if app_type.as_deref() != Some("Application") || no_display || hidden {
         return None;
     }
+    // Orphaned entries whose binary was uninstalled
+    if try_exec.is_some_and(|bin| !binary_exists(&bin)) {
+        return None;
+    }
+    // Entries meant for another desktop environment
+    let desktops = current_desktops();
+    if !only_show_in.is_empty() && !only_show_in.iter().any(|d| desktops.contains(d)) {
+        return None;
+    }
+    if not_show_in.iter().any(|d| desktops.contains(d)) {
+        return None;
+    }
 
     Some(DesktopApp {
         id: id.to_string(),
+        path: path.to_path_buf(),
         name: name?,
         exec: exec?,
         icon,
@@ -201,136 +185,177 @@ fn parse_desktop_file(path: &std::path::Path, id: &str) -> Option<DesktopApp> {
     })
 }
 
-// ── Search / ranking ─────────────────────────────────────────────────
-
-const MAX_RESULTS: usize = 8;
+pub(crate) fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
 
-fn filter_and_rank(
-    apps: &[DesktopApp],
-    query: &str,
-    frecency: &HashMap<String, FrecencyEntry>,
-) -> Vec<usize> {
-    if query.is_empty() {
-        // Return top frecent apps
-        let mut indices: Vec<usize> = (0..apps.len()).collect();
-        indices.sort_by(|&a, &b| {
-            let sa = frecency
-                .get(&apps[a].id)
-                .map(frecency_score)
-                .unwrap_or(0.0);
-            let sb = frecency
-                .get(&apps[b].id)
-                .map(frecency_score)
-                .unwrap_or(0.0);
-            sb.partial_cmp(&sa).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        indices.truncate(MAX_RESULTS);
-        return indices;
-    }
-
-    let q = query.to_lowercase();
-
-    // Tier 1: exact prefix match on name
-    let mut tier1 = Vec::new();
-    // Tier 2: word-boundary match on name
-    let mut tier2 = Vec::new();
-    // Tier 3: substring match on name
-    let mut tier3 = Vec::new();
-    // Tier 4: match on keywords/categories
-    let mut tier4 = Vec::new();
-
-    for (i, app) in apps.iter().enumerate() {
-        let name_lower = app.name.to_lowercase();
-        if name_lower.starts_with(&q) {
-            tier1.push(i);
-        } else if word_boundary_match(&name_lower, &q) {
-            tier2.push(i);
-        } else if name_lower.contains(&q) {
-            tier3.push(i);
-        } else if app
-            .keywords
-            .iter()
-            .any(|k| k.to_lowercase().contains(&q))
-            || app
-                .categories
-                .iter()
-                .any(|c| c.to_lowercase().contains(&q))
-        {
-            tier4.push(i);
-        }
-    }
-
-    let sort_by_frecency = |indices: &mut Vec<usize>| {
-        indices.sort_by(|&a, &b| {
-            let sa = frecency
-                .get(&apps[a].id)
-                .map(frecency_score)
-                .unwrap_or(0.0);
-            let sb = frecency
-                .get(&apps[b].id)
-                .map(frecency_score)
-                .unwrap_or(0.0);
-            sb.partial_cmp(&sa).unwrap_or(std::cmp::Ordering::Equal)
-        });
+/// `TryExec` check: an absolute path, or a name found on `$PATH`.
+pub(crate) fn binary_exists(bin: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let is_executable = |path: &std::path::Path| {
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
     };
+    if bin.contains('/') {
+        return is_executable(std::path::Path::new(bin));
+    }
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| is_executable(&dir.join(bin))))
+}
 
-    sort_by_frecency(&mut tier1);
-    sort_by_frecency(&mut tier2);
-    sort_by_frecency(&mut tier3);
-    sort_by_frecency(&mut tier4);
+/// Desktop names for `OnlyShowIn`/`NotShowIn`, from `[launcher] desktop` or
+/// `$XDG_CURRENT_DESKTOP` (both may be colon-separated lists).
+pub(crate) fn current_desktops() -> Vec<String> {
+    crate::config::get()
+        .launcher
+        .desktop
+        .clone()
+        .or_else(|| std::env::var("XDG_CURRENT_DESKTOP").ok())
+        .unwrap_or_else(|| "Hyprland".to_string())
+        .split(':')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
 
-    let mut result = Vec::new();
-    for tier in [tier1, tier2, tier3, tier4] {
-        for idx in tier {
-            if result.len() >= MAX_RESULTS {
-                break;
-            }
-            result.push(idx);
-        }
-        if result.len() >= MAX_RESULTS {
-            break;
-        }
+// ── Desktop file cache ───────────────────────────────────────────────
+
+/// Parsed desktop files, replaced wholesale by the watcher thread. The
+/// generation lets the launcher skip re-ranking when nothing changed.
+struct DesktopCache {
+    apps: Mutex<Arc<Vec<DesktopApp>>>,
+    generation: AtomicU64,
+}
+
+static DESKTOP_CACHE: OnceLock<DesktopCache> = OnceLock::new();
+
+/// Scan once, then keep the cache fresh from inotify events on every XDG
+/// applications dir.
+fn start_desktop_cache() {
+    let cache = DesktopCache {
+        apps: Mutex::new(Arc::new(scan_desktop_files())),
+        generation: AtomicU64::new(0),
+    };
+    if DESKTOP_CACHE.set(cache).is_ok() {
+        std::thread::spawn(watch_desktop_dirs);
     }
-    result
 }
 
-fn word_boundary_match(name: &str, query: &str) -> bool {
-    // Check if query matches starting at any word boundary in name
-    for (i, _) in name.char_indices() {
-        if i == 0 {
-            continue; // Skip — prefix match is tier 1
+fn desktop_snapshot() -> (u64, Arc<Vec<DesktopApp>>) {
+    let Some(cache) = DESKTOP_CACHE.get() else {
+        return (0, Arc::new(Vec::new()));
+    };
+    let apps = cache
+        .apps
+        .lock()
+        .map(|apps| apps.clone())
+        .unwrap_or_default();
+    (cache.generation.load(Ordering::Acquire), apps)
+}
+
+fn watch_desktop_dirs() {
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            eprintln!("jb-shell: [launcher] inotify unavailable, desktop files won't refresh: {e}");
+            return;
         }
-        let before = name.as_bytes().get(i.wrapping_sub(1)).copied().unwrap_or(0);
-        if before == b' ' || before == b'-' || before == b'_' {
-            if name[i..].starts_with(query) {
-                return true;
+    };
+    let mask = WatchMask::CREATE
+        | WatchMask::DELETE
+        | WatchMask::CLOSE_WRITE
+        | WatchMask::MOVED_TO
+        | WatchMask::MOVED_FROM;
+    let watched = xdg_app_dirs()
+        .iter()
+        .filter(|dir| inotify.watches().add(dir, mask).is_ok())
+        .count();
+    if watched == 0 {
+        return;
+    }
+
+    let mut buffer = [0u8; 4096];
+    loop {
+        let relevant = match inotify.read_events_blocking(&mut buffer) {
+            Ok(mut events) => events.any(|event| {
+                event
+                    .name
+                    .is_some_and(|name| name.to_string_lossy().ends_with(".desktop"))
+            }),
+            Err(e) => {
+                eprintln!("jb-shell: [launcher] inotify read failed: {e}");
+                return;
             }
+        };
+        if !relevant {
+            continue;
         }
+
+        // Package managers write files in bursts — let it settle, then drain
+        std::thread::sleep(Duration::from_millis(250));
+        while inotify
+            .read_events(&mut buffer)
+            .is_ok_and(|mut events| events.next().is_some())
+        {}
+
+        let apps = scan_desktop_files();
+        eprintln!(
+            "jb-shell: [launcher] desktop files changed, {} apps",
+            apps.len()
+        );
+        let Some(cache) = DESKTOP_CACHE.get() else {
+            return;
+        };
+        if let Ok(mut cached) = cache.apps.lock() {
+            *cached = Arc::new(apps);
+        }
+        cache.generation.fetch_add(1, Ordering::Release);
     }
-    false
 }
 
-// ── Exec field processing ────────────────────────────────────────────
+// ── Search / ranking ─────────────────────────────────────────────────
 
-fn process_exec(exec: &str) -> String {
-    // Strip field codes, keep everything else (including env VAR=val)
-    exec.split_whitespace()
-        .filter(|tok| {
-            !matches!(
-                *tok,
-                "%f" | "%F" | "%u" | "%U" | "%i" | "%c" | "%k" | "%d" | "%D" | "%n" | "%N"
-                    | "%v" | "%m"
-            )
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
+fn filter_and_rank(
+    apps: &[DesktopApp],
+    query: &str,
+    frecency: &HashMap<String, FrecencyEntry>,
+) -> Vec<usize> {
+    let max_results = crate::config::get().launcher.max_results;
+    ranking::filter_and_rank(apps, query, frecency, unix_now(), max_results)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub(crate) fn terminal_command() -> String {
+    crate::config::get()
+        .launcher
+        .terminal
+        .clone()
+        .or_else(|| std::env::var("TERMINAL").ok())
+        .unwrap_or_else(|| "foot".to_string())
 }
 
-fn launch_app(app: &DesktopApp, frecency: &mut HashMap<String, FrecencyEntry>) {
-    let exec_line = process_exec(&app.exec);
+fn launch_app(app: &DesktopApp, in_terminal: bool, frecency: &mut HashMap<String, FrecencyEntry>) {
+    let mut exec_line = process_exec(app);
     if exec_line.is_empty() {
         return;
     }
+    if in_terminal {
+        exec_line = format!(
+            "{} -e sh -c {}",
+            terminal_command(),
+            shell_quote(&exec_line)
+        );
+    }
 
     // Run through sh -c so env VAR=val prefixes, shell wrapper scripts,
     // and process substitutions all work correctly.
@@ -344,6 +369,7 @@ fn launch_app(app: &DesktopApp, frecency: &mut HashMap<String, FrecencyEntry>) {
     {
         Ok(_) => {
             eprintln!("jb-shell: [launcher] launched {}", app.id);
+            crate::metrics::app_launched();
         }
         Err(e) => {
             eprintln!("jb-shell: [launcher] failed to launch {}: {e}", app.id);
@@ -351,10 +377,7 @@ fn launch_app(app: &DesktopApp, frecency: &mut HashMap<String, FrecencyEntry>) {
     }
 
     // Bump frecency
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
+    let now = unix_now();
     let entry = frecency.entry(app.id.clone()).or_insert(FrecencyEntry {
         count: 0,
         last_used: now,
@@ -364,6 +387,85 @@ fn launch_app(app: &DesktopApp, frecency: &mut HashMap<String, FrecencyEntry>) {
     save_frecency(frecency);
 }
 
+// ── File search ──────────────────────────────────────────────────────
+
+/// Paths under `[launcher] file_roots`, walked on a thread the first time an
+/// `f ` query is typed after the launcher opens.
+static FILE_INDEX: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+static INDEXING: AtomicBool = AtomicBool::new(false);
+
+/// Stop walking here, so a huge home directory can't stall the index.
+const MAX_INDEXED_FILES: usize = 50_000;
+
+fn reindex_files(done: relm4::Sender<LauncherInput>) {
+    if INDEXING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let config = &crate::config::get().launcher;
+        let mut paths = Vec::new();
+        for root in &config.file_roots {
+            let root = crate::config::expand_home(root);
+            walk_files(&root, config.file_depth, &mut paths);
+        }
+        if let Ok(mut index) = FILE_INDEX.lock() {
+            *index = paths;
+        }
+        INDEXING.store(false, Ordering::Release);
+        done.emit(LauncherInput::FilesIndexed);
+    });
+}
+
+/// Everything under `dir` down to `depth` more levels, hidden entries skipped.
+fn walk_files(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if paths.len() >= MAX_INDEXED_FILES {
+            return;
+        }
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        paths.push(path.clone());
+        if is_dir && depth > 0 {
+            walk_files(&path, depth - 1, paths);
+        }
+    }
+}
+
+/// `f <query>`: indexed paths whose name contains the query, names starting
+/// with it first, then shallower paths.
+fn filter_files(query: &str) -> Vec<PathBuf> {
+    let q = query.trim().to_lowercase();
+    if q.is_empty() {
+        return Vec::new();
+    }
+    let Ok(index) = FILE_INDEX.lock() else {
+        return Vec::new();
+    };
+    let mut hits: Vec<(bool, usize, &PathBuf)> = index
+        .iter()
+        .filter_map(|path| {
+            let name = file_name(path).to_lowercase();
+            name.contains(&q)
+                .then(|| (!name.starts_with(&q), path.components().count(), path))
+        })
+        .collect();
+    hits.sort();
+    hits.into_iter()
+        .take(crate::config::get().launcher.max_results)
+        .map(|(_, _, path)| path.clone())
+        .collect()
+}
+
+fn file_uri(path: &Path) -> String {
+    gio::File::for_path(path).uri().to_string()
+}
+
 // ── D-Bus activation ─────────────────────────────────────────────────
 
 struct LauncherDbus {
@@ -378,30 +480,11 @@ impl LauncherDbus {
 }
 
 fn spawn_launcher_dbus(sender: relm4::Sender<LauncherInput>) {
-    std::thread::spawn(move || {
-        let server = LauncherDbus { sender };
-        let _conn = match zbus::blocking::connection::Builder::session()
-            .expect("failed to create session bus builder")
-            .serve_at("/dev/jb/shell/Launcher", server)
-            .expect("failed to register launcher interface")
-            .name("dev.jb.shell.Launcher")
-            .expect("failed to set launcher bus name")
-            .build()
-        {
-            Ok(conn) => conn,
-            Err(e) => {
-                eprintln!("jb-shell: [launcher] failed to acquire bus name: {e}");
-                return;
-            }
-        };
-
-        eprintln!("jb-shell: [launcher] D-Bus interface listening");
-
-        // Block forever — zbus dispatches on its own executor
-        loop {
-            std::thread::park();
-        }
-    });
+    crate::dbus::serve(
+        "/dev/jb/shell/Launcher",
+        "dev.jb.shell.Launcher",
+        LauncherDbus { sender },
+    );
 }
 
 // ── relm4 Component ──────────────────────────────────────────────────
@@ -409,11 +492,37 @@ fn spawn_launcher_dbus(sender: relm4::Sender<LauncherInput>) {
 pub struct LauncherModel {
     visible: bool,
     search_text: String,
-    apps: Vec<DesktopApp>,
+    apps: Arc<Vec<DesktopApp>>,
+    apps_generation: u64,
     filtered: Vec<usize>,
+    /// Set while the query has a provider prefix; its results replace the apps.
+    provider: Option<Provider>,
+    /// Whether this opening has started a file index yet.
+    files_indexed: bool,
+    /// Hyprland clients, fetched once per opening by the first `w ` query so
+    /// typing filters a snapshot instead of asking hyprctl per keystroke.
+    windows: Option<Vec<Client>>,
     selected_index: usize,
     frecency: HashMap<String, FrecencyEntry>,
-    last_scan: Instant,
+    monitor: Monitor,
+    wallpaper: relm4::Sender<WallpaperInput>,
+}
+
+/// Results of a prefixed query, listed instead of apps.
+enum Provider {
+    /// `wp <name>`: images in the wallpaper folder.
+    Wallpapers(Vec<PathBuf>),
+    /// `f <name>`: files and folders under `[launcher] file_roots`.
+    Files(Vec<PathBuf>),
+    /// `= <expression>`: the expression and its value, or why it has none.
+    Calc(String, Result<f64, String>),
+    /// `w <title>`: open windows by title or class.
+    Windows(Vec<Client>),
+}
+
+pub struct LauncherInit {
+    pub monitor: Monitor,
+    pub wallpaper: relm4::Sender<WallpaperInput>,
 }
 
 #[derive(Debug)]
@@ -421,19 +530,32 @@ pub enum LauncherInput {
     Toggle,
     SearchChanged(String),
     Activate,
+    /// Shift+Enter or right-click: the result's alternate action. `None` means
+    /// the highlighted row. Apps launch in a terminal, files open their
+    /// folder, windows move to the current workspace, and wallpapers are set
+    /// on the launcher's monitor only.
+    ActivateSecondary(Option<usize>),
+    /// Ctrl+Enter on a file: copy its path.
+    CopyPath,
     MoveUp,
     MoveDown,
     Hide,
+    /// The overlay's monitor went away (hotplug) — move to another one.
+    SetMonitor(Monitor),
+    /// The file index thread finished; re-run an `f ` query.
+    FilesIndexed,
 }
 
 pub struct LauncherWidgets {
     overlay: Window,
+    outer: GtkBox,
     search_entry: SearchEntry,
     results_box: GtkBox,
+    preview_box: GtkBox,
 }
 
 impl Component for LauncherModel {
-    type Init = Monitor;
+    type Init = LauncherInit;
     type Input = LauncherInput;
     type Output = ();
     type CommandOutput = ();
@@ -450,10 +572,11 @@ impl Component for LauncherModel {
         _root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let monitor = init;
+        let LauncherInit { monitor, wallpaper } = init;
 
         // ── Build overlay window ──
         let overlay = Window::new();
+        crate::widgets::a11y::name(&overlay, "Launcher");
         overlay.set_widget_name("launcher-overlay");
         overlay.init_layer_shell();
         overlay.set_layer(Layer::Overlay);
@@ -465,16 +588,17 @@ impl Component for LauncherModel {
         overlay.set_keyboard_mode(KeyboardMode::Exclusive);
         overlay.set_monitor(Some(&monitor));
 
-        // Outer container — centers the card
+        // Outer container — positions the card per `[launcher] placement`
         let outer = GtkBox::new(Orientation::Vertical, 0);
-        outer.set_valign(gtk4::Align::Center);
         outer.set_halign(gtk4::Align::Center);
         outer.set_vexpand(true);
         outer.set_hexpand(true);
+        apply_placement(&overlay, &outer, &monitor);
 
         // Card
         let card = GtkBox::new(Orientation::Vertical, 8);
         card.set_widget_name("launcher-card");
+        card.set_size_request(crate::config::get().launcher.width, -1);
 
         // Search entry
         let search_entry = SearchEntry::new();
@@ -487,10 +611,19 @@ impl Component for LauncherModel {
             search_sender.emit(LauncherInput::SearchChanged(entry.text().to_string()));
         });
 
-        // Results list
+        // Results list, with the optional preview pane beside it
+        let body = GtkBox::new(Orientation::Horizontal, 12);
         let results_box = GtkBox::new(Orientation::Vertical, 0);
         results_box.set_widget_name("launcher-results");
-        card.append(&results_box);
+        results_box.set_hexpand(true);
+        body.append(&results_box);
+
+        let preview_box = GtkBox::new(Orientation::Vertical, 6);
+        preview_box.set_widget_name("launcher-preview");
+        preview_box.set_size_request(220, -1);
+        preview_box.set_visible(crate::config::get().launcher.preview);
+        body.append(&preview_box);
+        card.append(&body);
 
         outer.append(&card);
         overlay.set_child(Some(&outer));
@@ -509,6 +642,16 @@ impl Component for LauncherModel {
                     key_sender.emit(LauncherInput::Hide);
                     glib::Propagation::Stop
                 }
+                gdk4::Key::Return | gdk4::Key::KP_Enter
+                    if state.contains(gdk4::ModifierType::SHIFT_MASK) =>
+                {
+                    key_sender.emit(LauncherInput::ActivateSecondary(None));
+                    glib::Propagation::Stop
+                }
+                gdk4::Key::Return | gdk4::Key::KP_Enter if ctrl => {
+                    key_sender.emit(LauncherInput::CopyPath);
+                    glib::Propagation::Stop
+                }
                 gdk4::Key::Return | gdk4::Key::KP_Enter => {
                     key_sender.emit(LauncherInput::Activate);
                     glib::Propagation::Stop
@@ -535,7 +678,8 @@ impl Component for LauncherModel {
         search_entry.add_controller(key_ctl);
 
         // ── Scan apps + load frecency ──
-        let apps = scan_desktop_files();
+        start_desktop_cache();
+        let (apps_generation, apps) = desktop_snapshot();
         let frecency = load_frecency();
         let filtered = filter_and_rank(&apps, "", &frecency);
 
@@ -551,16 +695,23 @@ impl Component for LauncherModel {
             visible: false,
             search_text: String::new(),
             apps,
+            apps_generation,
             filtered,
+            provider: None,
+            files_indexed: false,
+            windows: None,
             selected_index: 0,
             frecency,
-            last_scan: Instant::now(),
+            monitor,
+            wallpaper,
         };
 
         let widgets = LauncherWidgets {
             overlay,
+            outer,
             search_entry,
             results_box,
+            preview_box,
         };
 
         ComponentParts { model, widgets }
@@ -570,7 +721,7 @@ impl Component for LauncherModel {
         &mut self,
         widgets: &mut Self::Widgets,
         message: Self::Input,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
         _root: &Self::Root,
     ) {
         match message {
@@ -578,13 +729,17 @@ impl Component for LauncherModel {
                 if self.visible {
                     self.visible = false;
                 } else {
-                    // Re-scan if >30s since last
-                    if self.last_scan.elapsed().as_secs() > 30 {
-                        self.apps = scan_desktop_files();
-                        self.last_scan = Instant::now();
+                    // Pick up whatever the watcher thread has seen since
+                    let (generation, apps) = desktop_snapshot();
+                    if generation != self.apps_generation {
+                        self.apps = apps;
+                        self.apps_generation = generation;
                     }
                     self.search_text.clear();
                     self.filtered = filter_and_rank(&self.apps, "", &self.frecency);
+                    self.provider = None;
+                    self.files_indexed = false;
+                    self.windows = None;
                     self.selected_index = 0;
                     self.visible = true;
                     widgets.search_entry.set_text("");
@@ -592,11 +747,20 @@ impl Component for LauncherModel {
             }
             LauncherInput::SearchChanged(text) => {
                 self.search_text = text;
+                if self.search_text.starts_with("f ") && !self.files_indexed {
+                    self.files_indexed = true;
+                    reindex_files(sender.input_sender().clone());
+                }
+                if self.search_text.starts_with("w ") && self.windows.is_none() {
+                    self.windows = Some(Clients::get().map(|c| c.to_vec()).unwrap_or_default());
+                }
+                let windows = self.windows.as_deref().unwrap_or_default();
+                self.provider = provider_for(&self.search_text, windows);
                 self.filtered = filter_and_rank(&self.apps, &self.search_text, &self.frecency);
                 self.selected_index = 0;
             }
             LauncherInput::MoveDown => {
-                if !self.filtered.is_empty() && self.selected_index + 1 < self.filtered.len() {
+                if self.selected_index + 1 < self.result_count() {
                     self.selected_index += 1;
                 }
             }
@@ -606,23 +770,106 @@ impl Component for LauncherModel {
                 }
             }
             LauncherInput::Activate => {
-                if let Some(&app_idx) = self.filtered.get(self.selected_index) {
+                if let Some(provider) = &self.provider {
+                    match provider {
+                        Provider::Wallpapers(wallpapers) => {
+                            if let Some(path) = wallpapers.get(self.selected_index) {
+                                self.wallpaper.emit(WallpaperInput::Set(None, path.clone()));
+                                self.visible = false;
+                            }
+                        }
+                        Provider::Files(files) => {
+                            if let Some(path) = files.get(self.selected_index) {
+                                crate::activation::open_uri(&file_uri(path));
+                                self.visible = false;
+                            }
+                        }
+                        Provider::Calc(_, Ok(value)) => {
+                            if let Some(display) = gdk4::Display::default() {
+                                display.clipboard().set_text(&calc::format_value(*value));
+                            }
+                            self.visible = false;
+                        }
+                        Provider::Calc(_, Err(_)) => {}
+                        Provider::Windows(windows) => {
+                            if let Some(window) = windows.get(self.selected_index) {
+                                let _ = Dispatch::call(DispatchType::FocusWindow(
+                                    WindowIdentifier::Address(window.address.clone()),
+                                ));
+                                self.visible = false;
+                            }
+                        }
+                    }
+                } else if let Some(&app_idx) = self.filtered.get(self.selected_index) {
                     let app = self.apps[app_idx].clone();
-                    launch_app(&app, &mut self.frecency);
+                    launch_app(&app, false, &mut self.frecency);
                     self.visible = false;
                 }
             }
+            LauncherInput::ActivateSecondary(index) => {
+                let index = index.unwrap_or(self.selected_index);
+                if let Some(Provider::Wallpapers(wallpapers)) = &self.provider {
+                    if let Some(path) = wallpapers.get(index) {
+                        let connector = self.monitor.connector().map(|c| c.to_string());
+                        self.wallpaper
+                            .emit(WallpaperInput::Set(connector, path.clone()));
+                        self.visible = false;
+                    }
+                } else if let Some(Provider::Files(files)) = &self.provider {
+                    if let Some(folder) = files.get(index).and_then(|p| p.parent()) {
+                        crate::activation::open_uri(&file_uri(folder));
+                        self.visible = false;
+                    }
+                } else if let Some(Provider::Windows(windows)) = &self.provider {
+                    if let Some(window) = windows.get(index) {
+                        move_here(window);
+                        self.visible = false;
+                    }
+                } else if self.provider.is_some() {
+                    // No alternate action for calculations
+                } else if let Some(&app_idx) = self.filtered.get(index) {
+                    let app = self.apps[app_idx].clone();
+                    launch_app(&app, true, &mut self.frecency);
+                    self.visible = false;
+                }
+            }
+            LauncherInput::CopyPath => {
+                if let Some(Provider::Files(files)) = &self.provider {
+                    if let Some(path) = files.get(self.selected_index) {
+                        if let Some(display) = gdk4::Display::default() {
+                            display.clipboard().set_text(&path.to_string_lossy());
+                        }
+                        self.visible = false;
+                    }
+                }
+            }
             LauncherInput::Hide => {
                 self.visible = false;
             }
+            LauncherInput::SetMonitor(monitor) => {
+                widgets.overlay.set_monitor(Some(&monitor));
+                apply_placement(&widgets.overlay, &widgets.outer, &monitor);
+                self.monitor = monitor;
+                self.visible = false;
+            }
+            LauncherInput::FilesIndexed => {
+                if let Some(Provider::Files(_)) = self.provider {
+                    let windows = self.windows.as_deref().unwrap_or_default();
+                    self.provider = provider_for(&self.search_text, windows);
+                    self.selected_index = 0;
+                }
+            }
         }
 
-        self.update_view(widgets, _sender);
+        self.update_view(widgets, sender);
     }
 
-    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
         if self.visible {
-            self.rebuild_results(&widgets.results_box);
+            self.rebuild_results(&widgets.results_box, &sender);
+            if crate::config::get().launcher.preview {
+                self.rebuild_preview(&widgets.preview_box);
+            }
             widgets.overlay.set_visible(true);
             widgets.search_entry.grab_focus();
         } else {
@@ -632,13 +879,22 @@ impl Component for LauncherModel {
 }
 
 impl LauncherModel {
-    fn rebuild_results(&self, results_box: &GtkBox) {
+    fn result_count(&self) -> usize {
+        match &self.provider {
+            Some(Provider::Wallpapers(paths) | Provider::Files(paths)) => paths.len(),
+            Some(Provider::Calc(..)) => 1,
+            Some(Provider::Windows(windows)) => windows.len(),
+            None => self.filtered.len(),
+        }
+    }
+
+    fn rebuild_results(&self, results_box: &GtkBox, sender: &ComponentSender<Self>) {
         // Clear existing children
         while let Some(child) = results_box.first_child() {
             results_box.remove(&child);
         }
 
-        if self.filtered.is_empty() {
+        if self.result_count() == 0 {
             let empty = Label::new(Some("No matches"));
             empty.add_css_class("launcher-empty");
             empty.set_halign(gtk4::Align::Start);
@@ -646,10 +902,34 @@ impl LauncherModel {
             return;
         }
 
+        match &self.provider {
+            Some(Provider::Wallpapers(wallpapers)) => {
+                self.rebuild_wallpaper_results(wallpapers, results_box, sender);
+                return;
+            }
+            Some(Provider::Files(files)) => {
+                self.rebuild_file_results(files, results_box, sender);
+                return;
+            }
+            Some(Provider::Windows(windows)) => {
+                self.rebuild_window_results(windows, results_box, sender);
+                return;
+            }
+            Some(Provider::Calc(expr, value)) => {
+                results_box.append(&calc_row(expr, value));
+                return;
+            }
+            None => {}
+        }
+
         for (i, &app_idx) in self.filtered.iter().enumerate() {
             let app = &self.apps[app_idx];
             let row = GtkBox::new(Orientation::Horizontal, 8);
             row.add_css_class("launcher-item");
+            // Per-provider hook for themes
+            row.add_css_class("kind-app");
+            row.set_tooltip_text(Some("Shift+Enter or right-click: launch in terminal"));
+            add_secondary_click(&row, i, sender);
             if i == self.selected_index {
                 row.add_css_class("selected");
             }
@@ -664,7 +944,7 @@ impl LauncherModel {
             } else {
                 Image::from_icon_name("application-x-executable")
             };
-            icon.set_pixel_size(24);
+            icon.set_pixel_size(crate::config::get().launcher.icon_size);
             icon.add_css_class("app-icon");
             row.append(&icon);
 
@@ -693,6 +973,397 @@ impl LauncherModel {
             results_box.append(&row);
         }
     }
+
+    fn rebuild_wallpaper_results(
+        &self,
+        wallpapers: &[PathBuf],
+        results_box: &GtkBox,
+        sender: &ComponentSender<Self>,
+    ) {
+        let icon_size = crate::config::get().launcher.icon_size;
+        for (i, path) in wallpapers.iter().enumerate() {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            row.add_css_class("launcher-item");
+            row.add_css_class("kind-wallpaper");
+            row.set_tooltip_text(Some("Shift+Enter or right-click: this monitor only"));
+            add_secondary_click(&row, i, sender);
+            if i == self.selected_index {
+                row.add_css_class("selected");
+            }
+
+            row.append(&wallpaper_thumbnail(path, icon_size * 2));
+
+            let name_label = Label::new(Some(&file_name(path)));
+            name_label.add_css_class("app-name");
+            name_label.set_halign(gtk4::Align::Start);
+            name_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+            row.append(&name_label);
+            results_box.append(&row);
+        }
+    }
+
+    fn rebuild_file_results(
+        &self,
+        files: &[PathBuf],
+        results_box: &GtkBox,
+        sender: &ComponentSender<Self>,
+    ) {
+        for (i, path) in files.iter().enumerate() {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            row.add_css_class("launcher-item");
+            row.add_css_class("kind-file");
+            row.set_tooltip_text(Some(
+                "Shift+Enter or right-click: open containing folder\nCtrl+Enter: copy path",
+            ));
+            add_secondary_click(&row, i, sender);
+            if i == self.selected_index {
+                row.add_css_class("selected");
+            }
+
+            let icon = file_icon(path);
+            icon.set_pixel_size(crate::config::get().launcher.icon_size);
+            icon.add_css_class("app-icon");
+            row.append(&icon);
+
+            let text_box = GtkBox::new(Orientation::Vertical, 0);
+            let name_label = Label::new(Some(&file_name(path)));
+            name_label.add_css_class("app-name");
+            name_label.set_halign(gtk4::Align::Start);
+            text_box.append(&name_label);
+            if let Some(parent) = path.parent() {
+                let dir_label = Label::new(Some(&parent.to_string_lossy()));
+                dir_label.add_css_class("app-comment");
+                dir_label.set_halign(gtk4::Align::Start);
+                dir_label.set_ellipsize(gtk4::pango::EllipsizeMode::Start);
+                text_box.append(&dir_label);
+            }
+            row.append(&text_box);
+            results_box.append(&row);
+        }
+    }
+
+    fn rebuild_window_results(
+        &self,
+        windows: &[Client],
+        results_box: &GtkBox,
+        sender: &ComponentSender<Self>,
+    ) {
+        let icon_size = crate::config::get().launcher.icon_size;
+        for (i, window) in windows.iter().enumerate() {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            row.add_css_class("launcher-item");
+            row.add_css_class("kind-window");
+            row.set_tooltip_text(Some("Shift+Enter or right-click: move to this workspace"));
+            add_secondary_click(&row, i, sender);
+            if i == self.selected_index {
+                row.add_css_class("selected");
+            }
+
+            let icon = crate::app_icons::image(None, &window.class, icon_size)
+                .unwrap_or_else(|| Image::from_icon_name("application-x-executable"));
+            icon.set_pixel_size(icon_size);
+            icon.add_css_class("app-icon");
+            row.append(&icon);
+
+            let text_box = GtkBox::new(Orientation::Vertical, 0);
+            let title_label = Label::new(Some(&truncate_str(&window.title, 60)));
+            title_label.add_css_class("app-name");
+            title_label.set_halign(gtk4::Align::Start);
+            text_box.append(&title_label);
+            let where_label = Label::new(Some(&format!(
+                "{} \u{b7} workspace {}",
+                window.class, window.workspace.name
+            )));
+            where_label.add_css_class("app-comment");
+            where_label.set_halign(gtk4::Align::Start);
+            text_box.append(&where_label);
+            row.append(&text_box);
+            results_box.append(&row);
+        }
+    }
+
+    /// Details for the highlighted result: an app's full comment, categories
+    /// and command; a file's thumbnail, type, size and modification time; a
+    /// calculation's expression and value; a window's title, class and
+    /// workspace. Wallpapers get a large thumbnail.
+    fn rebuild_preview(&self, preview_box: &GtkBox) {
+        while let Some(child) = preview_box.first_child() {
+            preview_box.remove(&child);
+        }
+        match &self.provider {
+            Some(Provider::Wallpapers(wallpapers)) => {
+                if let Some(path) = wallpapers.get(self.selected_index) {
+                    preview_box.append(&wallpaper_thumbnail(path, 220));
+                    preview_box.append(&preview_title(&file_name(path)));
+                }
+                return;
+            }
+            Some(Provider::Files(files)) => {
+                if let Some(path) = files.get(self.selected_index) {
+                    file_preview(path, preview_box);
+                }
+                return;
+            }
+            Some(Provider::Calc(expr, value)) => {
+                let (title, detail) = match value {
+                    Ok(value) => (
+                        format!("= {}", calc::format_value(*value)),
+                        "Enter copies the result",
+                    ),
+                    Err(e) => (e.clone(), "Numbers, + - * / % ^ and parentheses"),
+                };
+                preview_box.append(&preview_title(&title));
+                append_details(
+                    preview_box,
+                    vec![
+                        ("preview-exec", expr.clone()),
+                        ("preview-comment", detail.to_string()),
+                    ],
+                );
+                return;
+            }
+            Some(Provider::Windows(windows)) => {
+                if let Some(window) = windows.get(self.selected_index) {
+                    preview_box.append(&preview_title(&window.title));
+                    append_details(
+                        preview_box,
+                        vec![
+                            ("preview-comment", window.class.clone()),
+                            ("preview-exec", format!("workspace {}", window.workspace.name)),
+                        ],
+                    );
+                }
+                return;
+            }
+            None => {}
+        }
+        let Some(app) = self
+            .filtered
+            .get(self.selected_index)
+            .map(|&idx| &self.apps[idx])
+        else {
+            return;
+        };
+
+        preview_box.append(&preview_title(&app.name));
+
+        let mut details = Vec::new();
+        if let Some(comment) = &app.comment {
+            details.push(("preview-comment", comment.clone()));
+        }
+        if !app.categories.is_empty() {
+            details.push(("preview-categories", app.categories.join(", ")));
+        }
+        details.push(("preview-exec", process_exec(app)));
+        details.push(("preview-id", app.id.clone()));
+        append_details(preview_box, details);
+    }
+}
+
+/// Right-click on a result row runs its alternate action.
+fn add_secondary_click(row: &GtkBox, index: usize, sender: &ComponentSender<LauncherModel>) {
+    let right_click = gtk4::GestureClick::new();
+    right_click.set_button(gdk4::BUTTON_SECONDARY);
+    let click_sender = sender.input_sender().clone();
+    right_click.connect_pressed(move |_, _, _, _| {
+        click_sender.emit(LauncherInput::ActivateSecondary(Some(index)));
+    });
+    row.add_controller(right_click);
+}
+
+/// `w <query>`: open windows whose title or class contains the query.
+fn filter_windows(clients: &[Client], query: &str) -> Vec<Client> {
+    let q = query.trim().to_lowercase();
+    clients
+        .iter()
+        .filter(|c| c.mapped && !c.title.is_empty())
+        .filter(|c| c.title.to_lowercase().contains(&q) || c.class.to_lowercase().contains(&q))
+        .take(crate::config::get().launcher.max_results)
+        .cloned()
+        .collect()
+}
+
+/// Bring `window` to the focused workspace and focus it.
+fn move_here(window: &Client) {
+    let Ok(workspace) = Workspace::get_active() else {
+        return;
+    };
+    let address = WindowIdentifier::Address(window.address.clone());
+    if let Err(e) = Dispatch::call(DispatchType::MoveToWorkspace(
+        WorkspaceIdentifierWithSpecial::Id(workspace.id),
+        Some(address),
+    )) {
+        eprintln!("jb-shell: [launcher] failed to move {}: {e}", window.class);
+    }
+}
+
+fn preview_title(text: &str) -> Label {
+    let name = Label::new(Some(text));
+    name.add_css_class("preview-title");
+    name.set_halign(gtk4::Align::Start);
+    name.set_wrap(true);
+    name.set_xalign(0.0);
+    name
+}
+
+fn append_details(preview_box: &GtkBox, details: Vec<(&str, String)>) {
+    for (class, text) in details {
+        let label = Label::new(Some(&text));
+        label.add_css_class(class);
+        label.set_halign(gtk4::Align::Start);
+        label.set_xalign(0.0);
+        label.set_wrap(true);
+        label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+        label.set_max_width_chars(30);
+        preview_box.append(&label);
+    }
+}
+
+/// Thumbnail (images) or type icon, then type, size, modification time and
+/// folder.
+fn file_preview(path: &Path, preview_box: &GtkBox) {
+    if crate::widgets::wallpaper::is_image(path) {
+        preview_box.append(&wallpaper_thumbnail(path, 220));
+    } else {
+        let icon = file_icon(path);
+        icon.set_pixel_size(64);
+        icon.set_halign(gtk4::Align::Start);
+        preview_box.append(&icon);
+    }
+    preview_box.append(&preview_title(&file_name(path)));
+
+    let mut details = Vec::new();
+    let metadata = std::fs::metadata(path).ok();
+    if metadata.as_ref().is_some_and(|m| m.is_dir()) {
+        details.push(("preview-categories", "Folder".to_string()));
+    } else {
+        let (content_type, _) = gio::content_type_guess(Some(path), &[]);
+        details.push((
+            "preview-categories",
+            gio::content_type_get_description(&content_type).to_string(),
+        ));
+        if let Some(metadata) = &metadata {
+            details.push((
+                "preview-comment",
+                crate::core::usage::format_bytes(metadata.len()),
+            ));
+        }
+    }
+    if let Some(modified) = metadata.and_then(|m| m.modified().ok()) {
+        let modified: chrono::DateTime<chrono::Local> = modified.into();
+        details.push((
+            "preview-comment",
+            format!("Modified {}", modified.format("%Y-%m-%d %H:%M")),
+        ));
+    }
+    if let Some(parent) = path.parent() {
+        details.push(("preview-exec", parent.to_string_lossy().into_owned()));
+    }
+    append_details(preview_box, details);
+}
+
+fn file_icon(path: &Path) -> Image {
+    if path.is_dir() {
+        return Image::from_icon_name("folder");
+    }
+    let (content_type, _) = gio::content_type_guess(Some(path), &[]);
+    Image::from_gicon(&gio::content_type_get_icon(&content_type))
+}
+
+fn calc_row(expr: &str, value: &Result<f64, String>) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class("launcher-item");
+    row.add_css_class("kind-calc");
+    row.add_css_class("selected");
+
+    let icon = Image::from_icon_name("accessories-calculator");
+    icon.set_pixel_size(crate::config::get().launcher.icon_size);
+    icon.add_css_class("app-icon");
+    row.append(&icon);
+
+    let text_box = GtkBox::new(Orientation::Vertical, 0);
+    let result = match value {
+        Ok(value) => format!("= {}", calc::format_value(*value)),
+        Err(_) => "\u{2026}".to_string(),
+    };
+    let result_label = Label::new(Some(&result));
+    result_label.add_css_class("app-name");
+    result_label.set_halign(gtk4::Align::Start);
+    text_box.append(&result_label);
+    let expr_label = Label::new(Some(&truncate_str(expr, 60)));
+    expr_label.add_css_class("app-comment");
+    expr_label.set_halign(gtk4::Align::Start);
+    text_box.append(&expr_label);
+    row.append(&text_box);
+    row
+}
+
+/// The provider a query's prefix asks for, with its results. `windows` is the
+/// opening's client snapshot for `w `.
+fn provider_for(query: &str, windows: &[Client]) -> Option<Provider> {
+    if let Some(name) = query.strip_prefix("wp ") {
+        Some(Provider::Wallpapers(filter_wallpapers(name)))
+    } else if let Some(name) = query.strip_prefix("f ") {
+        Some(Provider::Files(filter_files(name)))
+    } else if let Some(name) = query.strip_prefix("w ") {
+        Some(Provider::Windows(filter_windows(windows, name)))
+    } else {
+        let expr = query.strip_prefix('=')?.trim();
+        Some(Provider::Calc(expr.to_string(), calc::evaluate(expr)))
+    }
+}
+
+/// Center the card, or pin its top edge for `top-third`. The overlay gets a
+/// `placement-*` class so themes can tell them apart.
+fn apply_placement(overlay: &Window, outer: &GtkBox, monitor: &Monitor) {
+    overlay.remove_css_class("placement-center");
+    overlay.remove_css_class("placement-top-third");
+    match crate::config::get().launcher.placement {
+        LauncherPlacement::Center => {
+            outer.set_valign(gtk4::Align::Center);
+            outer.set_margin_top(0);
+            overlay.add_css_class("placement-center");
+        }
+        LauncherPlacement::TopThird => {
+            outer.set_valign(gtk4::Align::Start);
+            outer.set_margin_top(monitor.geometry().height() / 6);
+            overlay.add_css_class("placement-top-third");
+        }
+    }
+}
+
+/// `wp <query>`: images in the wallpaper folder whose file name contains the query.
+fn filter_wallpapers(query: &str) -> Vec<PathBuf> {
+    let q = query.trim().to_lowercase();
+    crate::widgets::wallpaper::list_images()
+        .into_iter()
+        .filter(|path| file_name(path).to_lowercase().contains(&q))
+        .take(crate::config::get().launcher.max_results)
+        .collect()
+}
+
+fn wallpaper_thumbnail(path: &Path, width: i32) -> gtk4::Widget {
+    match crate::widgets::wallpaper::thumbnail(path, width) {
+        Some(texture) => {
+            let picture = gtk4::Picture::for_paintable(&texture);
+            picture.set_content_fit(gtk4::ContentFit::Cover);
+            picture.set_size_request(width, width * 9 / 16);
+            picture.add_css_class("wallpaper-thumb");
+            picture.upcast()
+        }
+        None => {
+            let icon = Image::from_icon_name("image-x-generic");
+            icon.set_pixel_size(crate::config::get().launcher.icon_size);
+            icon.add_css_class("app-icon");
+            icon.upcast()
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
 }
 
 fn truncate_str(s: &str, max_len: usize) -> String {
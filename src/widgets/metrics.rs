@@ -0,0 +1,202 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::metrics::MetricsSample;
+use crate::shell::Subscribers;
+use crate::widgets::switcher::BarPopup;
+
+/// Rows shown per counter family; the rest are summed into one line.
+const TOP_ROWS: usize = 8;
+
+/// Bar readout of the shell's own CPU use in `[metrics]` audit mode. Click
+/// opens a popup ranking what woke up most over the last sample. Hidden
+/// unless audit mode is on.
+pub struct MetricsModel {
+    sample: Option<MetricsSample>,
+    popup_visible: bool,
+}
+
+pub struct MetricsInit {
+    pub monitor: Monitor,
+    pub feed: Subscribers<MetricsInput>,
+}
+
+#[derive(Debug, Clone)]
+pub enum MetricsInput {
+    Sample(MetricsSample),
+    TogglePopup,
+    HidePopup,
+}
+
+pub struct MetricsWidgets {
+    trigger: Button,
+    label: Label,
+    popup: BarPopup,
+    summary: Label,
+    sections: GtkBox,
+}
+
+impl Component for MetricsModel {
+    type Init = MetricsInit;
+    type Input = MetricsInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = MetricsWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("metrics");
+        b.set_valign(gtk4::Align::Center);
+        b.set_visible(crate::config::get().metrics.enabled);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let label = Label::new(Some("\u{f035b} \u{2014}"));
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "Shell resource use");
+        trigger.set_widget_name("metrics-trigger");
+        trigger.set_child(Some(&label));
+        trigger.set_tooltip_text(Some("Shell CPU use (audit mode)"));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(MetricsInput::TogglePopup);
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 8);
+        let title = Label::new(Some("Shell resource use"));
+        title.set_halign(gtk4::Align::Start);
+        title.add_css_class("metrics-title");
+        popup_box.append(&title);
+        let summary = Label::new(Some("Waiting for the first sample\u{2026}"));
+        summary.set_halign(gtk4::Align::Start);
+        popup_box.append(&summary);
+        let sections = GtkBox::new(Orientation::Vertical, 8);
+        popup_box.append(&sections);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "metrics-popup",
+            "Shell resource use",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(MetricsInput::HidePopup),
+        );
+
+        init.feed.subscribe(sender.input_sender().clone());
+
+        let model = MetricsModel {
+            sample: None,
+            popup_visible: false,
+        };
+        let widgets = MetricsWidgets {
+            trigger,
+            label,
+            popup,
+            summary,
+            sections,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            MetricsInput::Sample(sample) => {
+                self.sample = Some(sample);
+            }
+            MetricsInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            MetricsInput::HidePopup => {
+                self.popup_visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        if let Some(sample) = &self.sample {
+            widgets
+                .label
+                .set_text(&format!("\u{f035b} {:.1}%", sample.cpu_percent));
+            widgets.summary.set_text(&format!(
+                "CPU {:.1}% \u{b7} {} MiB resident \u{b7} {} threads",
+                sample.cpu_percent,
+                sample.rss_bytes / (1024 * 1024),
+                sample.threads
+            ));
+            if self.popup_visible {
+                build_sections(&widgets.sections, sample);
+            }
+        }
+
+        if self.popup_visible {
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+/// One ranked list per counter family, as rates over the sample interval.
+fn build_sections(sections: &GtkBox, sample: &MetricsSample) {
+    while let Some(child) = sections.first_child() {
+        sections.remove(&child);
+    }
+    let families = [
+        ("Widget updates", &sample.widget_updates),
+        ("Thread wakeups", &sample.thread_wakeups),
+        ("D-Bus calls", &sample.dbus_calls),
+    ];
+    for (heading, counts) in families {
+        let section = GtkBox::new(Orientation::Vertical, 2);
+        let title = Label::new(Some(heading));
+        title.set_halign(gtk4::Align::Start);
+        title.add_css_class("metrics-heading");
+        section.append(&title);
+        if counts.is_empty() {
+            let idle = Label::new(Some("None this interval"));
+            idle.set_halign(gtk4::Align::Start);
+            idle.add_css_class("dim");
+            section.append(&idle);
+        }
+        for (name, count) in counts.iter().take(TOP_ROWS) {
+            section.append(&rate_row(name, *count, sample.interval_secs));
+        }
+        if counts.len() > TOP_ROWS {
+            let rest: u64 = counts[TOP_ROWS..].iter().map(|(_, c)| c).sum();
+            let others = format!("{} others", counts.len() - TOP_ROWS);
+            section.append(&rate_row(&others, rest, sample.interval_secs));
+        }
+        sections.append(&section);
+    }
+}
+
+fn rate_row(name: &str, count: u64, secs: f64) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 12);
+    row.add_css_class("metrics-row");
+    let name = Label::new(Some(name));
+    name.set_halign(gtk4::Align::Start);
+    name.set_hexpand(true);
+    row.append(&name);
+    let rate = Label::new(Some(&format!("{:.1}/s", count as f64 / secs.max(1.0))));
+    rate.add_css_class("metrics-rate");
+    row.append(&rate);
+    row
+}
@@ -1,14 +1,39 @@
+pub mod a11y;
 pub mod active_window;
+pub mod airplane;
 pub mod battery;
+pub mod caffeine;
 pub mod calendar;
+pub mod ci;
 pub mod clock;
+pub mod color_picker;
+pub mod context_menu;
+pub mod custom;
+pub mod display_profiles;
+pub mod focus_mode;
 pub mod gcloud_config;
+pub mod git;
+pub mod i3bar;
+pub mod join_meeting;
+pub mod keybinds;
 pub mod kube_context;
 pub mod launcher;
+pub mod metrics;
 pub mod mpris;
 pub mod network;
+pub mod night_light;
 pub mod notification_center;
 pub mod notifications;
+pub mod oncall;
+pub mod osd;
+pub mod preview_canvas;
+pub mod scale;
+pub mod setup_wizard;
 pub mod switcher;
+pub mod taskbar;
+pub mod toolchains;
+pub mod tooltip;
 pub mod volume;
+pub mod wallpaper;
+pub mod window_switcher;
 pub mod workspaces;
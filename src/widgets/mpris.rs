@@ -1,4 +1,5 @@
-use crate::widgets::notifications::focus_app_window;
+use crate::core::window_focus::FocusQuery;
+use crate::shell::Subscribers;
 use gtk4::prelude::*;
 use gtk4::{Box as GtkBox, Label, Orientation};
 use relm4::prelude::*;
@@ -14,13 +15,15 @@ pub struct MprisModel {
     title_keywords: Vec<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum MprisInput {
     Update {
         artist: String,
         title: String,
         focus_hints: Vec<String>,
         title_keywords: Vec<String>,
+        /// Per `[caffeine] video_players` or a video file URL.
+        video: bool,
     },
     Inactive,
     Raise,
@@ -32,7 +35,8 @@ pub struct MprisWidgets {
 }
 
 impl SimpleComponent for MprisModel {
-    type Init = ();
+    /// The shell's MPRIS feed — one poller shared by every bar.
+    type Init = Subscribers<MprisInput>;
     type Input = MprisInput;
     type Output = ();
     type Root = GtkBox;
@@ -41,13 +45,14 @@ impl SimpleComponent for MprisModel {
     fn init_root() -> Self::Root {
         let b = GtkBox::new(Orientation::Horizontal, 8);
         b.set_widget_name("mpris-player");
+        crate::widgets::a11y::name(&b, "Media player");
         b.set_valign(gtk4::Align::Center);
         b.set_visible(false);
         b
     }
 
     fn init(
-        _init: Self::Init,
+        feed: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -64,14 +69,7 @@ impl SimpleComponent for MprisModel {
         });
         root.add_controller(click);
 
-        let input_sender = sender.input_sender().clone();
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .expect("mpris tokio runtime");
-            rt.block_on(mpris_poll_loop(input_sender));
-        });
+        feed.subscribe(sender.input_sender().clone());
 
         let model = MprisModel {
             playing: false,
@@ -94,6 +92,7 @@ impl SimpleComponent for MprisModel {
                 title,
                 focus_hints,
                 title_keywords,
+                ..
             } => {
                 // Only mutate if something actually changed — avoids unnecessary
                 // widget invalidation that leaks Vulkan dmabuf/sync fds.
@@ -115,11 +114,11 @@ impl SimpleComponent for MprisModel {
             }
             MprisInput::Raise => {
                 if !self.focus_hints.is_empty() {
-                    let class_refs: Vec<&str> =
-                        self.focus_hints.iter().map(|s| s.as_str()).collect();
-                    let title_refs: Vec<&str> =
-                        self.title_keywords.iter().map(|s| s.as_str()).collect();
-                    focus_app_window(&class_refs, &title_refs, None);
+                    crate::window_focus::focus(FocusQuery {
+                        class_hints: self.focus_hints.clone(),
+                        title_keywords: self.title_keywords.clone(),
+                        ..FocusQuery::default()
+                    });
                 }
             }
         }
@@ -164,41 +163,62 @@ struct MprisInfo {
     title: String,
     focus_hints: Vec<String>,
     title_keywords: Vec<String>,
+    video: bool,
+}
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "webm", "avi", "mov", "m4v", "wmv", "flv"];
+
+fn is_video(focus_hints: &[String], url: &str) -> bool {
+    let players = &crate::config::get().caffeine.video_players;
+    let known_player = focus_hints
+        .iter()
+        .any(|hint| players.iter().any(|p| p.eq_ignore_ascii_case(hint)));
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let video_file = path
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    known_player || video_file
 }
 
-async fn mpris_poll_loop(input_sender: relm4::Sender<MprisInput>) {
+/// Start the process-wide MPRIS poller, publishing player state to `feed`.
+pub fn spawn_mpris_thread(rt: &tokio::runtime::Handle, feed: Subscribers<MprisInput>) {
+    rt.spawn(mpris_poll_loop(feed));
+}
+
+async fn mpris_poll_loop(feed: Subscribers<MprisInput>) {
     let mut conn: Option<zbus::Connection> = None;
     // Cache focus hints per player bus name (these don't change)
     let mut cached_hints: HashMap<String, Vec<String>> = HashMap::new();
 
     loop {
         if conn.is_none() {
-            conn = zbus::Connection::session().await.ok();
+            conn = crate::dbus::session().await.ok();
         }
 
         if let Some(ref c) = conn {
             match poll_mpris(c, &mut cached_hints).await {
                 Ok(Some(info)) => {
-                    input_sender.emit(MprisInput::Update {
+                    feed.publish(MprisInput::Update {
                         artist: info.artist,
                         title: info.title,
                         focus_hints: info.focus_hints,
                         title_keywords: info.title_keywords,
+                        video: info.video,
                     });
                 }
                 Ok(None) => {
-                    input_sender.emit(MprisInput::Inactive);
+                    feed.publish(MprisInput::Inactive);
                 }
                 Err(_) => {
                     conn = None;
-                    input_sender.emit(MprisInput::Inactive);
+                    feed.publish(MprisInput::Inactive);
                 }
             }
         } else {
-            input_sender.emit(MprisInput::Inactive);
+            feed.publish(MprisInput::Inactive);
         }
 
-        tokio::time::sleep(Duration::from_secs(3)).await;
+        crate::idle::sleep_async(Duration::from_secs(3)).await;
     }
 }
 
@@ -289,6 +309,11 @@ async fn poll_mpris(
         return Ok(None);
     }
 
+    let url = meta_dict
+        .get("xesam:url")
+        .and_then(|v| String::try_from(v.clone()).ok())
+        .unwrap_or_default();
+
     // Get or compute focus hints (cached per player bus name)
     let focus_hints = cached_hints
         .entry(mpris_name.clone())
@@ -336,10 +361,13 @@ async fn poll_mpris(
         title_keywords.push(artist.clone());
     }
 
+    let video = is_video(&focus_hints, &url);
+
     Ok(Some(MprisInfo {
         artist,
         title,
         focus_hints,
         title_keywords,
+        video,
     }))
 }
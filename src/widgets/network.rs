@@ -1,35 +1,107 @@
+use gdk4::Monitor;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Image, Label, Orientation};
+use gtk4::{Box as GtkBox, Button, DrawingArea, Image, Label, Orientation, Switch};
 use relm4::prelude::*;
+use std::cell::RefCell;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+use crate::actions::Action;
+use crate::core::latency::Health;
+use crate::core::usage::{format_bytes, format_rate};
+use crate::latency::LatencyState;
+use crate::net_usage::{UsageState, HISTORY_LEN};
+use crate::shell::Subscribers;
+use crate::widgets::context_menu::MenuItem;
+use crate::widgets::switcher::BarPopup;
+use crate::widgets::tooltip::Tooltip;
+
 const SKIP_PREFIXES: &[&str] = &["lo", "docker", "br-", "veth", "tailscale", "virbr"];
 
+/// NetworkManager connection profile the hotspot switch creates and removes.
+const HOTSPOT_CONNECTION: &str = "jb-shell-hotspot";
+
+// Set by the poll below when no interface is up; the summary thread skips its
+// API calls meanwhile instead of failing them.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Connection indicator, colored by ping health when `[network.latency]` is
+/// set; click opens a popup with the hotspot switch, data usage and latency.
 pub struct NetworkModel {
     icon_name: String,
     label_text: String,
+    details: String,
+    usage: Option<UsageState>,
+    latency: Option<LatencyState>,
+    hotspot: Option<HotspotState>,
+    /// A hotspot start/stop is running; the switch waits for it.
+    hotspot_busy: bool,
+    popup_visible: bool,
+}
+
+/// The running hotspot, as NetworkManager reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotspotState {
+    pub interface: String,
+    pub clients: usize,
+}
+
+pub struct NetworkInit {
+    pub monitor: Monitor,
+    pub usage: Subscribers<NetworkInput>,
+    pub latency: Subscribers<NetworkInput>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum NetworkInput {
     PollResult {
         icon_name: String,
         label_text: String,
+        details: String,
+        hotspot: Option<HotspotState>,
     },
+    Usage(UsageState),
+    Latency(LatencyState),
+    SetHotspot(bool),
+    HotspotDone(Option<HotspotState>),
+    TogglePopup,
+    HidePopup,
 }
 
 pub struct NetworkWidgets {
     icon: Image,
     label: Label,
+    trigger: Button,
+    popup: BarPopup,
+    hotspot_switch: Switch,
+    hotspot_label: Label,
+    sparkline: DrawingArea,
+    /// Throughput history the sparkline draws; replaced on each sample.
+    history: Rc<RefCell<Vec<f64>>>,
+    rate_label: Label,
+    totals_label: Label,
+    /// Latency section; hidden until the first ping.
+    latency_box: GtkBox,
+    latency_title: Label,
+    latency_sparkline: DrawingArea,
+    latency_history: Rc<RefCell<Vec<Option<f64>>>>,
+    latency_label: Label,
+    tooltip: Tooltip,
 }
 
-impl SimpleComponent for NetworkModel {
-    type Init = ();
+impl Component for NetworkModel {
+    type Init = NetworkInit;
     type Input = NetworkInput;
     type Output = ();
+    type CommandOutput = ();
     type Root = GtkBox;
     type Widgets = NetworkWidgets;
 
@@ -40,7 +112,7 @@ impl SimpleComponent for NetworkModel {
     }
 
     fn init(
-        _init: Self::Init,
+        init: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -48,55 +120,392 @@ impl SimpleComponent for NetworkModel {
         icon.set_pixel_size(16);
         let label = Label::new(Some("Offline"));
 
-        root.append(&icon);
-        root.append(&label);
+        let content = GtkBox::new(Orientation::Horizontal, 4);
+        content.append(&icon);
+        content.append(&label);
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "Network");
+        trigger.set_widget_name("network-trigger");
+        trigger.set_child(Some(&content));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(NetworkInput::TogglePopup);
+        });
+        crate::widgets::context_menu::attach(&trigger, || {
+            vec![MenuItem::action("Connection settings", || {
+                let editor = &crate::config::get().context_menu.network_editor;
+                crate::actions::run(&Action::Command(editor.clone()));
+            })]
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 8);
+        let hotspot_row = GtkBox::new(Orientation::Horizontal, 8);
+        let hotspot_title = Label::new(Some("Hotspot"));
+        hotspot_title.set_hexpand(true);
+        hotspot_title.set_halign(gtk4::Align::Start);
+        hotspot_row.append(&hotspot_title);
+        let hotspot_switch = Switch::new();
+        let hotspot_sender = sender.input_sender().clone();
+        hotspot_switch.connect_state_set(move |_, on| {
+            hotspot_sender.emit(NetworkInput::SetHotspot(on));
+            glib::Propagation::Proceed
+        });
+        hotspot_row.append(&hotspot_switch);
+        popup_box.append(&hotspot_row);
+        let hotspot_label = Label::new(None);
+        hotspot_label.set_halign(gtk4::Align::Start);
+        hotspot_label.add_css_class("dim");
+        popup_box.append(&hotspot_label);
+
+        let usage_title = Label::new(Some("Throughput"));
+        usage_title.set_halign(gtk4::Align::Start);
+        popup_box.append(&usage_title);
+        let history = Rc::new(RefCell::new(Vec::new()));
+        let sparkline = DrawingArea::new();
+        sparkline.set_widget_name("network-sparkline");
+        sparkline.set_content_width(220);
+        sparkline.set_content_height(40);
+        let draw_history = history.clone();
+        sparkline.set_draw_func(move |_, cr, width, height| {
+            draw_sparkline(cr, &draw_history.borrow(), width as f64, height as f64);
+        });
+        popup_box.append(&sparkline);
+        let rate_label = Label::new(None);
+        rate_label.set_halign(gtk4::Align::Start);
+        popup_box.append(&rate_label);
+        let totals_label = Label::new(None);
+        totals_label.set_halign(gtk4::Align::Start);
+        totals_label.add_css_class("dim");
+        popup_box.append(&totals_label);
+
+        let latency_box = GtkBox::new(Orientation::Vertical, 8);
+        latency_box.set_visible(false);
+        let latency_title = Label::new(Some("Latency"));
+        latency_title.set_halign(gtk4::Align::Start);
+        latency_box.append(&latency_title);
+        let latency_history = Rc::new(RefCell::new(Vec::new()));
+        let latency_sparkline = DrawingArea::new();
+        latency_sparkline.set_widget_name("network-sparkline");
+        latency_sparkline.set_content_width(220);
+        latency_sparkline.set_content_height(40);
+        let draw_history = latency_history.clone();
+        latency_sparkline.set_draw_func(move |_, cr, width, height| {
+            draw_latency(cr, &draw_history.borrow(), width as f64, height as f64);
+        });
+        latency_box.append(&latency_sparkline);
+        let latency_label = Label::new(None);
+        latency_label.set_halign(gtk4::Align::Start);
+        latency_label.add_css_class("dim");
+        latency_box.append(&latency_label);
+        popup_box.append(&latency_box);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "network-popup",
+            "Network",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(NetworkInput::HidePopup),
+        );
+
+        init.usage.subscribe(sender.input_sender().clone());
+        init.latency.subscribe(sender.input_sender().clone());
 
         // Background polling thread
         let input_sender = sender.input_sender().clone();
         std::thread::spawn(move || loop {
-            let (icon_name, label_text) = detect_network();
+            let (icon_name, label_text, details) = detect_network();
+            OFFLINE.store(icon_name == "network-offline-symbolic", Ordering::Relaxed);
             input_sender.emit(NetworkInput::PollResult {
                 icon_name,
                 label_text,
+                details,
+                hotspot: hotspot_state(),
             });
-            std::thread::sleep(Duration::from_secs(5));
+            crate::idle::sleep(Duration::from_secs(5));
         });
 
         let model = NetworkModel {
             icon_name: "network-offline-symbolic".to_string(),
             label_text: "Offline".to_string(),
+            details: String::new(),
+            usage: None,
+            latency: None,
+            hotspot: None,
+            hotspot_busy: false,
+            popup_visible: false,
+        };
+        let tooltip = Tooltip::attach(&root);
+        let widgets = NetworkWidgets {
+            icon,
+            label,
+            trigger,
+            popup,
+            hotspot_switch,
+            hotspot_label,
+            sparkline,
+            history,
+            rate_label,
+            totals_label,
+            latency_box,
+            latency_title,
+            latency_sparkline,
+            latency_history,
+            latency_label,
+            tooltip,
         };
-        let widgets = NetworkWidgets { icon, label };
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
-        match msg {
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
             NetworkInput::PollResult {
                 icon_name,
                 label_text,
+                details,
+                hotspot,
             } => {
                 self.icon_name = icon_name;
                 self.label_text = label_text;
+                self.details = details;
+                if !self.hotspot_busy {
+                    self.hotspot = hotspot;
+                }
+            }
+            NetworkInput::Usage(usage) => {
+                *widgets.history.borrow_mut() = usage.history.clone();
+                widgets.sparkline.queue_draw();
+                self.usage = Some(usage);
+            }
+            NetworkInput::Latency(latency) => {
+                *widgets.latency_history.borrow_mut() = latency.history.clone();
+                widgets.latency_sparkline.queue_draw();
+                self.latency = Some(latency);
+            }
+            NetworkInput::SetHotspot(on) => {
+                // Echo of our own update_view sync
+                if self.hotspot_busy || self.hotspot.is_some() == on {
+                    return;
+                }
+                self.hotspot_busy = true;
+                let done_sender = sender.input_sender().clone();
+                std::thread::spawn(move || {
+                    if on {
+                        start_hotspot();
+                    } else {
+                        stop_hotspot();
+                    }
+                    done_sender.emit(NetworkInput::HotspotDone(hotspot_state()));
+                });
+            }
+            NetworkInput::HotspotDone(hotspot) => {
+                self.hotspot_busy = false;
+                self.hotspot = hotspot;
+            }
+            NetworkInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            NetworkInput::HidePopup => {
+                self.popup_visible = false;
             }
         }
+
+        self.update_view(widgets, sender);
     }
 
     fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
         widgets.icon.set_icon_name(Some(&self.icon_name));
         widgets.label.set_label(&self.label_text);
+
+        let ssid = &crate::config::get().network.hotspot.ssid;
+        let mut details = self.details.clone();
+        if let Some(hotspot) = &self.hotspot {
+            details.push_str(&format!(
+                "\nHotspot <b>{}</b>: {}",
+                glib::markup_escape_text(ssid),
+                clients_text(hotspot.clients)
+            ));
+        }
+        if let Some(usage) = &self.usage {
+            details.push_str(&format!(
+                "\n\u{2193} {}  \u{2191} {}",
+                format_rate(usage.rx_rate),
+                format_rate(usage.tx_rate)
+            ));
+        }
+        if let Some(latency) = &self.latency {
+            details.push_str(&format!(
+                "\nPing {}: {}",
+                glib::markup_escape_text(&latency.host),
+                latency_text(latency)
+            ));
+        }
+        widgets.tooltip.set_markup(&details);
+
+        widgets.hotspot_switch.set_active(self.hotspot.is_some());
+        widgets.hotspot_switch.set_sensitive(!self.hotspot_busy);
+        let status = match (&self.hotspot, self.hotspot_busy) {
+            (_, true) => "Working…".to_string(),
+            (Some(hotspot), false) => format!(
+                "{ssid} on {} \u{b7} {}",
+                hotspot.interface,
+                clients_text(hotspot.clients)
+            ),
+            (None, false) => format!("Share this connection as \"{ssid}\""),
+        };
+        widgets.hotspot_label.set_label(&status);
+        if self.hotspot.is_some() {
+            widgets.trigger.add_css_class("hotspot");
+        } else {
+            widgets.trigger.remove_css_class("hotspot");
+        }
+
+        if let Some(usage) = &self.usage {
+            widgets.rate_label.set_label(&format!(
+                "\u{2193} {}  \u{2191} {}",
+                format_rate(usage.rx_rate),
+                format_rate(usage.tx_rate)
+            ));
+            let month = match usage.cap {
+                Some(cap) => format!("{} of {}", format_bytes(usage.month), format_bytes(cap)),
+                None => format_bytes(usage.month),
+            };
+            widgets.totals_label.set_label(&format!(
+                "Today {} \u{b7} This month {month}",
+                format_bytes(usage.today)
+            ));
+            let over_cap = usage.cap.is_some_and(|cap| usage.month >= cap);
+            if over_cap {
+                widgets.totals_label.add_css_class("over-cap");
+            } else {
+                widgets.totals_label.remove_css_class("over-cap");
+            }
+        }
+
+        if let Some(latency) = &self.latency {
+            widgets.latency_box.set_visible(true);
+            widgets
+                .latency_title
+                .set_label(&format!("Latency \u{b7} {}", latency.host));
+            widgets.latency_label.set_label(&latency_text(latency));
+        }
+        let health = self.latency.as_ref().map(|l| l.health);
+        for (class, state) in [
+            ("latency-degraded", Health::Degraded),
+            ("latency-bad", Health::Bad),
+        ] {
+            if health == Some(state) {
+                widgets.trigger.add_css_class(class);
+            } else {
+                widgets.trigger.remove_css_class(class);
+            }
+        }
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+/// Throughput line scaled to the busiest sample, newest at the right edge.
+fn draw_sparkline(cr: &gtk4::cairo::Context, history: &[f64], width: f64, height: f64) {
+    if history.len() < 2 {
+        return;
+    }
+    let max = history.iter().copied().fold(0.0, f64::max).max(1.0);
+    let step = width / (HISTORY_LEN - 1) as f64;
+    let x0 = width - (history.len() - 1) as f64 * step;
+    let y = |rate: f64| height - 1.0 - (rate / max) * (height - 2.0);
+
+    cr.move_to(x0, height);
+    for (i, &rate) in history.iter().enumerate() {
+        cr.line_to(x0 + i as f64 * step, y(rate));
+    }
+    cr.line_to(width, height);
+    cr.close_path();
+    // @accent from style.css — cairo doesn't see CSS colors
+    cr.set_source_rgba(0.2, 0.8, 1.0, 0.25);
+    let _ = cr.fill();
+
+    cr.move_to(x0, y(history[0]));
+    for (i, &rate) in history.iter().enumerate().skip(1) {
+        cr.line_to(x0 + i as f64 * step, y(rate));
     }
+    cr.set_source_rgba(0.2, 0.8, 1.0, 0.8);
+    cr.set_line_width(1.5);
+    let _ = cr.stroke();
 }
 
-fn detect_network() -> (String, String) {
+/// Round-trip line with lost pings as full-height red ticks; the scale
+/// starts at 100 ms so a quiet link doesn't look jittery.
+fn draw_latency(cr: &gtk4::cairo::Context, history: &[Option<f64>], width: f64, height: f64) {
+    let max = history.iter().flatten().copied().fold(100.0, f64::max);
+    let step = width / (crate::latency::HISTORY_LEN - 1) as f64;
+    let x0 = width - history.len().saturating_sub(1) as f64 * step;
+    let y = |ms: f64| height - 1.0 - (ms / max) * (height - 2.0);
+
+    // @accent from style.css — cairo doesn't see CSS colors
+    cr.set_source_rgba(0.2, 0.8, 1.0, 0.8);
+    cr.set_line_width(1.5);
+    let mut drawing = false;
+    for (i, sample) in history.iter().enumerate() {
+        let x = x0 + i as f64 * step;
+        match (sample, drawing) {
+            (Some(ms), false) => cr.move_to(x, y(*ms)),
+            (Some(ms), true) => cr.line_to(x, y(*ms)),
+            (None, _) => {}
+        }
+        drawing = sample.is_some();
+    }
+    let _ = cr.stroke();
+
+    // #f38ba8 (red) from style.css
+    cr.set_source_rgba(0.95, 0.55, 0.66, 0.8);
+    cr.set_line_width(1.0);
+    for (i, _) in history.iter().enumerate().filter(|(_, s)| s.is_none()) {
+        let x = x0 + i as f64 * step;
+        cr.move_to(x, 0.0);
+        cr.line_to(x, height);
+    }
+    let _ = cr.stroke();
+}
+
+/// "23 ms · 0% loss", or "no replies" when every recent ping was lost.
+fn latency_text(latency: &LatencyState) -> String {
+    match latency.mean_ms {
+        Some(ms) => format!("{ms:.0} ms \u{b7} {:.0}% loss", latency.loss_percent),
+        None => "no replies".to_string(),
+    }
+}
+
+fn clients_text(clients: usize) -> String {
+    match clients {
+        1 => "1 client".to_string(),
+        n => format!("{n} clients"),
+    }
+}
+
+/// Returns (icon name, label, tooltip markup).
+fn detect_network() -> (String, String, String) {
     let net_dir = Path::new("/sys/class/net");
     if !net_dir.is_dir() {
-        return ("network-offline-symbolic".into(), "Offline".into());
+        return offline();
     }
 
     let entries = match fs::read_dir(net_dir) {
         Ok(e) => e,
-        Err(_) => return ("network-offline-symbolic".into(), "Offline".into()),
+        Err(_) => return offline(),
     };
 
     let mut wired_up: Option<String> = None;
@@ -127,8 +536,9 @@ fn detect_network() -> (String, String) {
         }
     }
 
-    if wired_up.is_some() {
-        return ("network-wired-symbolic".into(), "Wired".into());
+    if let Some(iface) = wired_up {
+        let details = network_details("Wired", &iface, None);
+        return ("network-wired-symbolic".into(), "Wired".into(), details);
     }
 
     if let Some(iface) = wireless_up {
@@ -142,10 +552,49 @@ fn detect_network() -> (String, String) {
         } else {
             "network-wireless-signal-none-symbolic"
         };
-        return (icon.into(), ssid);
+        let details = network_details(&ssid, &iface, Some(rssi));
+        return (icon.into(), ssid, details);
     }
 
-    ("network-offline-symbolic".into(), "Offline".into())
+    offline()
+}
+
+fn offline() -> (String, String, String) {
+    (
+        "network-offline-symbolic".into(),
+        "Offline".into(),
+        "<b>Offline</b>".into(),
+    )
+}
+
+/// Tooltip markup: connection name, interface, signal (wireless) and IPv4 address.
+fn network_details(name: &str, iface: &str, rssi: Option<i32>) -> String {
+    let mut details = format!(
+        "<b>{}</b>  {}",
+        glib::markup_escape_text(name),
+        glib::markup_escape_text(iface)
+    );
+    if let Some(rssi) = rssi {
+        details.push_str(&format!("\nSignal {rssi} dBm"));
+    }
+    if let Some(addr) = ipv4_address(iface) {
+        details.push_str(&format!("\n{addr}"));
+    }
+    details
+}
+
+fn ipv4_address(iface: &str) -> Option<String> {
+    let output = Command::new("ip")
+        .args(["-4", "-o", "addr", "show", "dev", iface])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // "2: wlan0    inet 192.168.1.20/24 brd ..." — the 4th field is the address
+    text.lines()
+        .next()?
+        .split_whitespace()
+        .nth(3)
+        .map(str::to_string)
 }
 
 fn get_wireless_info(iface: &str) -> (String, i32) {
@@ -181,3 +630,59 @@ fn get_wireless_info(iface: &str) -> (String, i32) {
         Err(_) => (iface.to_string(), -100),
     }
 }
+
+/// The hotspot connection if NetworkManager has it up, with its station count.
+fn hotspot_state() -> Option<HotspotState> {
+    let output = Command::new("nmcli")
+        .args(["-t", "-f", "NAME,DEVICE", "connection", "show", "--active"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let interface = text
+        .lines()
+        .find_map(|line| line.strip_prefix(HOTSPOT_CONNECTION)?.strip_prefix(':'))?
+        .to_string();
+    let clients = Command::new("iw")
+        .args(["dev", &interface, "station", "dump"])
+        .output()
+        .map(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .filter(|line| line.starts_with("Station "))
+                .count()
+        })
+        .unwrap_or(0);
+    Some(HotspotState { interface, clients })
+}
+
+fn start_hotspot() {
+    let config = &crate::config::get().network.hotspot;
+    let mut args = vec!["device", "wifi", "hotspot", "con-name", HOTSPOT_CONNECTION];
+    args.extend(["ssid", config.ssid.as_str()]);
+    if let Some(password) = &config.password {
+        args.extend(["password", password.as_str()]);
+    }
+    if let Some(interface) = &config.interface {
+        args.extend(["ifname", interface.as_str()]);
+    }
+    nmcli(&args);
+}
+
+fn stop_hotspot() {
+    // Delete rather than just deactivate so a later start picks up config changes
+    nmcli(&["connection", "delete", HOTSPOT_CONNECTION]);
+}
+
+fn nmcli(args: &[&str]) {
+    match Command::new("nmcli").args(args).output() {
+        Ok(out) if !out.status.success() => {
+            eprintln!(
+                "jb-shell: [network] nmcli {} failed: {}",
+                args[..args.len().min(3)].join(" "),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Err(e) => eprintln!("jb-shell: [network] failed to run nmcli: {e}"),
+        _ => {}
+    }
+}
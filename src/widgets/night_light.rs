@@ -0,0 +1,265 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Image, Label, Orientation, Scale, Switch};
+use relm4::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::night_light::{NightLightCommand, NightLightState, MAX_TEMPERATURE, MIN_TEMPERATURE};
+use crate::shell::NightLightHandle;
+use crate::widgets::switcher::{cancel_close_timer, BarPopup};
+use crate::widgets::tooltip::Tooltip;
+
+/// Bar toggle for the shared night light: click flips it, right-click opens a
+/// popup with the temperature slider and the sunset-to-sunrise switch.
+pub struct NightLightModel {
+    state: Option<NightLightState>,
+    popup_visible: bool,
+    handle: NightLightHandle,
+}
+
+pub struct NightLightInit {
+    pub monitor: Monitor,
+    pub handle: NightLightHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum NightLightInput {
+    State(NightLightState),
+    Toggle,
+    SetTemperature(u32),
+    SetAuto(bool),
+    TogglePopup,
+    HidePopup,
+}
+
+pub struct NightLightWidgets {
+    trigger: Button,
+    icon: Image,
+    popup: BarPopup,
+    scale: Scale,
+    scale_label: Label,
+    auto_switch: Switch,
+    /// Dragging the slider restarts the backend once it settles, not per step.
+    temperature_timer: Rc<RefCell<Option<glib::SourceId>>>,
+    tooltip: Tooltip,
+}
+
+impl Component for NightLightModel {
+    type Init = NightLightInit;
+    type Input = NightLightInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = NightLightWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("night-light");
+        b.set_valign(gtk4::Align::Center);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let icon = Image::from_icon_name("night-light-symbolic");
+        icon.set_pixel_size(16);
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "Night light");
+        trigger.set_widget_name("night-light-trigger");
+        trigger.set_child(Some(&icon));
+        root.append(&trigger);
+
+        let toggle_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            toggle_sender.emit(NightLightInput::Toggle);
+        });
+        let right_click = gtk4::GestureClick::new();
+        right_click.set_button(gdk4::BUTTON_SECONDARY);
+        let popup_sender = sender.input_sender().clone();
+        right_click.connect_pressed(move |_, _, _, _| {
+            popup_sender.emit(NightLightInput::TogglePopup);
+        });
+        trigger.add_controller(right_click);
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 8);
+        let scale_label = Label::new(None);
+        scale_label.set_halign(gtk4::Align::Start);
+        popup_box.append(&scale_label);
+
+        let scale = Scale::with_range(
+            Orientation::Horizontal,
+            MIN_TEMPERATURE as f64,
+            MAX_TEMPERATURE as f64,
+            100.0,
+        );
+        scale.set_size_request(220, -1);
+        scale.set_draw_value(false);
+        let scale_sender = sender.input_sender().clone();
+        scale.connect_value_changed(move |scale| {
+            scale_sender.emit(NightLightInput::SetTemperature(scale.value() as u32));
+        });
+        popup_box.append(&scale);
+
+        let auto_row = GtkBox::new(Orientation::Horizontal, 8);
+        let auto_label = Label::new(Some("Sunset to sunrise"));
+        auto_label.set_hexpand(true);
+        auto_label.set_halign(gtk4::Align::Start);
+        auto_row.append(&auto_label);
+        let auto_switch = Switch::new();
+        let auto_sender = sender.input_sender().clone();
+        auto_switch.connect_state_set(move |_, on| {
+            auto_sender.emit(NightLightInput::SetAuto(on));
+            glib::Propagation::Proceed
+        });
+        auto_row.append(&auto_switch);
+        popup_box.append(&auto_row);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "night-light-popup",
+            "Night light",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(NightLightInput::HidePopup),
+        );
+
+        init.handle
+            .subscribers
+            .subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&trigger);
+        let model = NightLightModel {
+            state: None,
+            popup_visible: false,
+            handle: init.handle,
+        };
+        let widgets = NightLightWidgets {
+            trigger,
+            icon,
+            popup,
+            scale,
+            scale_label,
+            auto_switch,
+            temperature_timer: Rc::new(RefCell::new(None)),
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            NightLightInput::State(state) => {
+                self.state = Some(state);
+            }
+            NightLightInput::Toggle => self.send(NightLightCommand::Toggle),
+            NightLightInput::SetTemperature(temp) => {
+                cancel_close_timer(&widgets.temperature_timer);
+                // Echo of our own update_view sync, or dragged back to where it was
+                if self.state.as_ref().is_some_and(|s| s.temperature == temp) {
+                    return;
+                }
+                let thread_tx = self.handle.thread_tx.clone();
+                let timer_ref = widgets.temperature_timer.clone();
+                let id = glib::timeout_add_local_once(Duration::from_millis(250), move || {
+                    let _ = thread_tx.send(NightLightCommand::SetTemperature(temp));
+                    *timer_ref.borrow_mut() = None;
+                });
+                *widgets.temperature_timer.borrow_mut() = Some(id);
+                widgets
+                    .scale_label
+                    .set_label(&format!("Temperature: {temp}K"));
+                return;
+            }
+            NightLightInput::SetAuto(on) => {
+                if self.state.as_ref().is_some_and(|s| s.auto == on) {
+                    return;
+                }
+                self.send(NightLightCommand::SetAuto(on));
+                return;
+            }
+            NightLightInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            NightLightInput::HidePopup => {
+                self.popup_visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        let Some(state) = &self.state else {
+            return;
+        };
+
+        if state.active {
+            widgets.trigger.add_css_class("active");
+            widgets.icon.set_icon_name(Some("night-light-symbolic"));
+        } else {
+            widgets.trigger.remove_css_class("active");
+            widgets
+                .icon
+                .set_icon_name(Some("night-light-disabled-symbolic"));
+        }
+
+        let mut markup = if state.active {
+            format!("<b>Night light on</b> \u{b7} {}K", state.temperature)
+        } else {
+            "<b>Night light off</b>".to_string()
+        };
+        if let Some(next) = state.next_change {
+            let what = if state.active {
+                "Off at sunrise"
+            } else {
+                "On at sunset"
+            };
+            markup.push_str(&format!(
+                "\n{what} {} (in {})",
+                next.format("%H:%M"),
+                crate::night_light::format_until(next)
+            ));
+        }
+        markup.push_str("\nRight-click for temperature");
+        widgets.tooltip.set_markup(&markup);
+
+        widgets
+            .scale_label
+            .set_label(&format!("Temperature: {}K", state.temperature));
+        widgets.scale.set_value(state.temperature as f64);
+        widgets.auto_switch.set_active(state.auto);
+        widgets.auto_switch.set_sensitive(state.auto_available);
+        let hint = if state.auto_available {
+            None
+        } else if crate::location::configured() {
+            Some("Waiting for the location")
+        } else {
+            Some("Set [location] latitude and longitude, or geoclue = true")
+        };
+        widgets.auto_switch.set_tooltip_text(hint);
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+impl NightLightModel {
+    fn send(&self, command: NightLightCommand) {
+        let _ = self.handle.thread_tx.send(command);
+    }
+}
@@ -1,13 +1,32 @@
 use chrono::{DateTime, Local};
 use gdk4::Monitor;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Button, Label, Orientation, Window};
+use gtk4::{
+    Box as GtkBox, Button, EventControllerMotion, GestureClick, Label, MenuButton, Orientation,
+    Overlay, PolicyType, Popover, ProgressBar, Revealer, RevealerTransitionType, ScrolledWindow,
+    Window,
+};
 use gtk4_layer_shell::{Edge, Layer, LayerShell};
 use relm4::prelude::*;
+use std::cell::Cell;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+use crate::config::{BodyMarkup, PrivacyToasts, ToastMonitor, ToastOverflow};
+use crate::core::cli::{parse_notify_args, NotifyArgs};
+use crate::core::markup;
+use crate::core::time::format_countdown;
+use crate::core::window_focus::FocusQuery;
+use crate::notification_daemon::{
+    store_thumbnail, DaemonCommand, MuteDuration, CLOSE_REASON_DISMISSED, CLOSE_REASON_EXPIRED,
+    CLOSE_REASON_UNDEFINED,
+};
+use crate::state_dbus::StateChange;
+use crate::widgets::notification_center::NotificationCenterInput;
+
 pub type NotificationId = u64;
 
 #[derive(Clone, Debug)]
@@ -24,6 +43,8 @@ pub enum NotificationSource {
         app_name: String,
         desktop_entry: Option<String>,
         sender_pid: Option<u32>,
+        /// Spec `resident` hint: keep the toast after an action is invoked.
+        resident: bool,
     },
 }
 
@@ -31,7 +52,15 @@ pub enum NotificationSource {
 pub enum ActionCallback {
     Dismiss,
     OpenUrl(String),
-    FdAction { fd_id: u32, action_key: String },
+    FdAction {
+        fd_id: u32,
+        action_key: String,
+    },
+    /// Put this text on the clipboard ("Copy code").
+    CopyText(String),
+    /// Run this argv (no shell), for the shell's own toasts: an undo,
+    /// "Suspend now". A command that fails gets a toast of its own.
+    RunCommand(Vec<String>),
 }
 
 #[derive(Clone, Debug)]
@@ -55,39 +84,284 @@ pub struct NotificationRequest {
     pub css_box_name: Option<String>,
     pub css_card_class: Option<String>,
     pub timeout_ms: Option<u32>,
+    /// Identical notifications collapsed into this one; shown as "×N" above 1.
+    pub repeat_count: u32,
+    /// Color chip shown before the title (color picker results).
+    pub swatch: Option<gdk4::RGBA>,
+    /// Spec `value` hint (0–100), drawn as a progress bar.
+    pub progress: Option<u8>,
     pub source: NotificationSource,
 }
 
 #[derive(Debug)]
 pub enum NotificationInput {
     Show(NotificationRequest),
+    /// Stored in history without a toast (silent low urgency).
+    Silent(u32),
     Dismiss(NotificationId),
+    /// Tear down a toast without reporting a close reason (the daemon already did).
+    Remove(NotificationId),
     Tick,
     ActionTriggered(NotificationId, ActionCallback),
-    SetDaemonChannel(std::sync::mpsc::Sender<crate::notification_daemon::DaemonCommand>),
-    SetCenterOpen(bool),
-    SetCenterSender(relm4::Sender<crate::widgets::notification_center::NotificationCenterInput>),
+    /// Mute the toast's app via the daemon's rules table, then dismiss it.
+    MuteApp(NotificationId, MuteDuration),
+    /// Pointer entered (true) or left (false) a toast — freezes its expiry.
+    Hover(NotificationId, bool),
+    /// A toast's long body was expanded (true) or collapsed (false) with its
+    /// chevron; expanding buys reading time.
+    ExpandBody(NotificationId, bool),
+    SetDaemonChannel(std::sync::mpsc::Sender<DaemonCommand>),
+    /// A bar's notification center (identified by its monitor) opened or closed.
+    SetCenterOpen(Monitor, bool),
+    /// A center marked read, cleared or deleted rows; every badge must refresh.
+    CenterChanged,
+    /// A center's history write for the daemon, which answers with
+    /// `CenterChanged` once it's done.
+    ToDaemon(DaemonCommand),
+    /// Register a (per-bar) notification center living on the given monitor.
+    AddCenter(Monitor, relm4::Sender<NotificationCenterInput>),
+    /// Monitors toasts may be shown on (all bars). Windows on monitors that went
+    /// away are re-presented on a surviving one.
+    SetMonitors(Vec<Monitor>),
+    /// Hyprland focus moved — new toasts go to this monitor.
+    FocusMonitor(Monitor),
+    /// The "+N more" overflow pill was clicked.
+    OpenCenter,
+    /// A do-not-disturb preset was picked in a notification center.
+    SetDnd(DndPreset),
+    /// The calendar saw an accepted meeting start (true) or end (false).
+    MeetingChanged(bool),
+    /// Focus mode was turned on (true) or off (false).
+    SetFocus(bool),
+    /// Hyprland reported a screencast starting (true) or stopping (false).
+    SetScreencast(bool),
+}
+
+/// Do-not-disturb presets offered in the notification center header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DndPreset {
+    Off,
+    Minutes(i64),
+    UntilTomorrow,
+    /// Toggle automatic DND while an accepted meeting is in progress.
+    DuringMeetings,
+}
+
+/// While active, freedesktop toasts go straight to the notification center.
+/// Calendar alerts still show.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DndStatus {
+    pub until: Option<DateTime<Local>>,
+    pub during_meetings: bool,
+    pub in_meeting: bool,
+    /// Focus mode is on; it holds DND until turned off.
+    pub focus: bool,
+}
+
+impl DndStatus {
+    pub fn is_active(&self) -> bool {
+        self.until.is_some_and(|until| until > Local::now())
+            || (self.during_meetings && self.in_meeting)
+            || self.focus
+    }
+}
+
+// ── D-Bus activation ─────────────────────────────────────────────────
+
+/// `jb-shell notify` toasts get IDs above any freedesktop (`u32`) one.
+const CLI_ID_BASE: NotificationId = 1 << 40;
+
+struct NotifyDbus {
+    sender: relm4::Sender<NotificationInput>,
+    next_id: AtomicU64,
+}
+
+#[zbus::interface(name = "dev.jb.shell.Notify")]
+impl NotifyDbus {
+    /// Takes the `jb-shell notify` arguments as given; returns the toast's ID.
+    fn post(&self, args: Vec<String>) -> zbus::fdo::Result<u64> {
+        let parsed =
+            parse_notify_args(&args, Local::now()).map_err(zbus::fdo::Error::InvalidArgs)?;
+        let id = CLI_ID_BASE + self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.sender
+            .emit(NotificationInput::Show(cli_request(id, parsed)));
+        Ok(id)
+    }
+}
+
+fn spawn_notify_dbus(sender: relm4::Sender<NotificationInput>) {
+    crate::dbus::serve(
+        "/dev/jb/shell/Notify",
+        "dev.jb.shell.Notify",
+        NotifyDbus {
+            sender,
+            next_id: AtomicU64::new(0),
+        },
+    );
 }
 
+/// A toast styled like the daemon's; actions run their command with `sh -c`.
+/// With a countdown the title line ticks and the title moves to the body.
+fn cli_request(id: NotificationId, args: NotifyArgs) -> NotificationRequest {
+    let timeout_ms = match args.timeout_ms {
+        Some(0) => None,
+        Some(ms) => Some(ms),
+        None if args.urgency == 2 => None,
+        None if !args.actions.is_empty() => Some(15000),
+        None => Some(5000),
+    };
+    let mut actions: Vec<NotificationAction> = args
+        .actions
+        .into_iter()
+        .map(|(label, command)| NotificationAction {
+            label,
+            css_class: "notif-action".to_string(),
+            callback: ActionCallback::RunCommand(vec!["sh".into(), "-c".into(), command]),
+        })
+        .collect();
+    actions.push(NotificationAction {
+        label: "Dismiss".to_string(),
+        css_class: "notif-action".to_string(),
+        callback: ActionCallback::Dismiss,
+    });
+    let (title, body) = match args.countdown {
+        Some(target) => (
+            format_countdown(target, Local::now()),
+            Some(match args.body {
+                Some(body) => format!("{} \u{b7} {body}", args.title),
+                None => args.title,
+            }),
+        ),
+        None => (args.title, args.body),
+    };
+
+    NotificationRequest {
+        id,
+        kind: NotificationKind::Toast,
+        icon: args.icon,
+        title,
+        body,
+        subtitle: None,
+        countdown_target: args.countdown,
+        actions,
+        css_window_name: None,
+        css_box_name: Some("fd-notification".to_string()),
+        css_card_class: match args.urgency {
+            0 => Some("urgency-low".to_string()),
+            2 => Some("urgency-critical".to_string()),
+            _ => None,
+        },
+        timeout_ms,
+        repeat_count: 1,
+        swatch: None,
+        progress: None,
+        source: NotificationSource::Internal,
+    }
+}
+
+// ── relm4 Component ──────────────────────────────────────────────────
+
 pub struct NotificationModel {
     active: Vec<ActiveNotification>,
-    daemon_tx: Option<std::sync::mpsc::Sender<crate::notification_daemon::DaemonCommand>>,
-    center_open: bool,
-    center_sender:
-        Option<relm4::Sender<crate::widgets::notification_center::NotificationCenterInput>>,
+    daemon_tx: Option<std::sync::mpsc::Sender<DaemonCommand>>,
+    /// Center commands sent before the daemon thread was up; flushed when
+    /// its channel arrives.
+    pending_daemon: Vec<DaemonCommand>,
+    /// Monitors whose bar has its notification center open. Toasts are
+    /// suppressed everywhere while any is.
+    open_centers: Vec<Monitor>,
+    centers: Vec<(Monitor, relm4::Sender<NotificationCenterInput>)>,
+    monitors: Vec<Monitor>,
+    focused_monitor: Monitor,
+    overflow_pill: Window,
+    overflow_label: Label,
+    dnd: DndStatus,
+    /// A screencast is running and `[privacy]` is enabled.
+    screencast: bool,
 }
 
 struct ActiveNotification {
     request: NotificationRequest,
+    /// One window per monitor the notification is shown on.
+    views: Vec<NotificationView>,
+    expiry: Rc<Expiry>,
+    shown: bool,
+}
+
+struct NotificationView {
+    monitor: Monitor,
     window: Window,
     title_label: Label,
-    expires_at: Option<Instant>,
 }
 
-pub struct NotificationWidgets {
-    monitor: Monitor,
+/// Expiry bookkeeping, shared with the toast's progress bar tick callback.
+#[derive(Default)]
+struct Expiry {
+    /// Set once the toast is actually on screen; queued overflow toasts don't expire.
+    deadline: Cell<Option<Instant>>,
+    total: Cell<Duration>,
+    /// Time left when the pointer entered the toast; `deadline` is cleared meanwhile.
+    paused: Cell<Option<Duration>>,
 }
 
+impl Expiry {
+    fn start(&self, request: &NotificationRequest) {
+        if let Some(ms) = request.timeout_ms {
+            let total = Duration::from_millis(ms as u64);
+            self.total.set(total);
+            self.deadline.set(Some(Instant::now() + total));
+        }
+    }
+
+    fn pause(&self) {
+        if let Some(deadline) = self.deadline.take() {
+            self.paused
+                .set(Some(deadline.saturating_duration_since(Instant::now())));
+        }
+    }
+
+    fn resume(&self) {
+        if let Some(remaining) = self.paused.take() {
+            self.deadline.set(Some(Instant::now() + remaining));
+        }
+    }
+
+    /// Push the deadline back, paused or not. No-op without a timeout.
+    fn extend(&self, extra: Duration) {
+        if let Some(deadline) = self.deadline.get() {
+            self.deadline.set(Some(deadline + extra));
+        } else if let Some(remaining) = self.paused.get() {
+            self.paused.set(Some(remaining + extra));
+        } else {
+            return;
+        }
+        self.total.set(self.total.get() + extra);
+    }
+
+    /// Fraction of the timeout still remaining, 1.0 before the clock starts.
+    fn remaining_fraction(&self) -> f64 {
+        let remaining = match (self.deadline.get(), self.paused.get()) {
+            (Some(deadline), _) => deadline.saturating_duration_since(Instant::now()),
+            (None, Some(remaining)) => remaining,
+            (None, None) => return 1.0,
+        };
+        remaining.as_secs_f64() / self.total.get().as_secs_f64().max(0.001)
+    }
+}
+
+const TOAST_FADE_MS: u64 = 200;
+
+/// Added to a toast's timeout when its body is expanded.
+const EXPAND_EXTRA: Duration = Duration::from_secs(10);
+
+/// Characters per body line, as the toast labels' `max_width_chars`.
+const BODY_LINE_CHARS: usize = 50;
+
+/// A failed `RunCommand` replaces the previous failure's toast.
+const COMMAND_FAILED_ID: NotificationId = u64::MAX - 0x210;
+
+pub struct NotificationWidgets;
+
 impl Component for NotificationModel {
     type Init = Monitor;
     type Input = NotificationInput;
@@ -111,73 +385,171 @@ impl Component for NotificationModel {
             glib::ControlFlow::Continue
         });
 
+        let (overflow_pill, overflow_label) = build_overflow_pill(&monitor, &sender);
+        spawn_notify_dbus(sender.input_sender().clone());
+
         let model = NotificationModel {
             active: Vec::new(),
             daemon_tx: None,
-            center_open: false,
-            center_sender: None,
+            pending_daemon: Vec::new(),
+            open_centers: Vec::new(),
+            centers: Vec::new(),
+            monitors: vec![monitor.clone()],
+            focused_monitor: monitor,
+            overflow_pill,
+            overflow_label,
+            dnd: DndStatus {
+                during_meetings: crate::config::get().meetings.auto_dnd,
+                ..DndStatus::default()
+            },
+            screencast: false,
         };
-        let widgets = NotificationWidgets { monitor };
+        let widgets = NotificationWidgets;
         ComponentParts { model, widgets }
     }
 
     fn update_with_view(
         &mut self,
-        widgets: &mut Self::Widgets,
+        _widgets: &mut Self::Widgets,
         message: Self::Input,
         sender: ComponentSender<Self>,
         _root: &Self::Root,
     ) {
         match message {
+            NotificationInput::Silent(fd_id) => {
+                self.broadcast_to_centers(|| NotificationCenterInput::NewNotification(fd_id));
+                // Never displayed — let the sender know it's gone
+                if let Some(tx) = &self.daemon_tx {
+                    let _ = tx.send(DaemonCommand::NotificationClosed {
+                        id: fd_id,
+                        reason: CLOSE_REASON_UNDEFINED,
+                    });
+                }
+            }
             NotificationInput::Show(request) => {
-                // Suppress FD toast when center is open; forward to center instead
-                if self.center_open {
+                // Muted apps never get here; DND and screencasts keep the
+                // sender's window out of history too
+                if crate::config::get().toasts.thumbnails
+                    && !self.dnd.is_active()
+                    && !self.screencast
+                {
+                    if let NotificationSource::Freedesktop {
+                        fd_id,
+                        app_name,
+                        desktop_entry,
+                        sender_pid,
+                        ..
+                    } = &request.source
+                    {
+                        store_thumbnail(
+                            *fd_id,
+                            FocusQuery {
+                                pid: *sender_pid,
+                                desktop_entry: desktop_entry.clone(),
+                                class_hints: vec![app_name.clone()],
+                                title_keywords: vec![request.title.clone()],
+                                ..FocusQuery::default()
+                            },
+                            sender.input_sender().clone(),
+                        );
+                    }
+                }
+
+                // Suppress FD toast when center is open or DND is on; forward to
+                // center instead
+                if !self.open_centers.is_empty() || self.dnd.is_active() || self.hiding_toasts() {
                     if let NotificationSource::Freedesktop { fd_id, .. } = &request.source {
-                        if let Some(center_tx) = &self.center_sender {
-                            center_tx.emit(
-                                crate::widgets::notification_center::NotificationCenterInput::NewNotification(*fd_id),
-                            );
+                        self.broadcast_to_centers(|| {
+                            NotificationCenterInput::NewNotification(*fd_id)
+                        });
+                        // Never displayed as a toast — let the sender know it's gone
+                        if let Some(tx) = &self.daemon_tx {
+                            let _ = tx.send(DaemonCommand::NotificationClosed {
+                                id: *fd_id,
+                                reason: CLOSE_REASON_UNDEFINED,
+                            });
                         }
                         return;
                     }
                 }
 
+                // An app updating its toast (replaces_id) keeps the toast's
+                // place in the stack and what's left of its timeout
+                if matches!(request.kind, NotificationKind::Toast) {
+                    let screencast = self.screencast;
+                    if let Some(notif) = self.active.iter_mut().find(|n| {
+                        n.request.id == request.id
+                            && matches!(n.request.kind, NotificationKind::Toast)
+                    }) {
+                        replace_toast(notif, request, &sender, screencast);
+                        self.restack();
+                        return;
+                    }
+                }
+
                 // Dismiss existing notification with same ID
                 self.dismiss_by_id_with_reason(request.id, 0);
 
-                let expires_at = request
-                    .timeout_ms
-                    .map(|ms| Instant::now() + Duration::from_millis(ms as u64));
-
-                let window = build_notification_window(&widgets.monitor, &request, &sender);
-                let title_label = find_title_label(&window);
+                let expiry = Rc::new(Expiry::default());
+                let views = self
+                    .target_monitors()
+                    .into_iter()
+                    .map(|monitor| {
+                        let window =
+                            build_notification_window(&monitor, &request, &sender, &expiry);
+                        let title_label = find_title_label(&window);
+                        set_private(&window, self.screencast);
+                        NotificationView {
+                            monitor,
+                            window,
+                            title_label,
+                        }
+                    })
+                    .collect::<Vec<_>>();
 
-                window.set_visible(true);
+                // Toasts are revealed by restack() once there's a free slot
+                let shown = !matches!(request.kind, NotificationKind::Toast);
+                if shown {
+                    for view in &views {
+                        view.window.set_visible(true);
+                    }
+                    expiry.start(&request);
+                }
 
                 self.active.push(ActiveNotification {
                     request,
-                    window,
-                    title_label,
-                    expires_at,
+                    views,
+                    expiry,
+                    shown,
                 });
 
-                restack_toasts(&self.active);
+                self.restack();
             }
             NotificationInput::Dismiss(id) => {
-                self.dismiss_by_id_with_reason(id, 2);
-                restack_toasts(&self.active);
+                self.dismiss_by_id_with_reason(id, CLOSE_REASON_DISMISSED);
+                self.restack();
+            }
+            NotificationInput::Remove(id) => {
+                self.dismiss_by_id_with_reason(id, 0);
+                self.restack();
             }
             NotificationInput::Tick => {
                 let now_chrono = Local::now();
+                if self.dnd.until.is_some_and(|until| until <= now_chrono) {
+                    self.dnd.until = None;
+                    self.publish_dnd();
+                }
                 let now_instant = Instant::now();
                 let mut expired_ids = Vec::new();
                 for notif in &self.active {
                     if let Some(target) = notif.request.countdown_target {
-                        notif
-                            .title_label
-                            .set_label(&format_countdown(target, now_chrono));
+                        let countdown = format_countdown(target, now_chrono);
+                        for view in &notif.views {
+                            view.title_label.set_label(&countdown);
+                        }
                     }
-                    if let Some(exp) = notif.expires_at {
+                    // No deadline while queued or hovered
+                    if let Some(exp) = notif.expiry.deadline.get() {
                         if now_instant >= exp {
                             expired_ids.push(notif.request.id);
                         }
@@ -185,88 +557,361 @@ impl Component for NotificationModel {
                 }
                 if !expired_ids.is_empty() {
                     for id in expired_ids {
-                        self.dismiss_by_id_with_reason(id, 1);
+                        self.dismiss_by_id_with_reason(id, CLOSE_REASON_EXPIRED);
                     }
-                    restack_toasts(&self.active);
+                    self.restack();
                 }
             }
             NotificationInput::ActionTriggered(id, callback) => {
+                let mut keep_open = false;
                 match &callback {
                     ActionCallback::Dismiss => {}
                     ActionCallback::OpenUrl(url) => {
-                        let _ = std::process::Command::new("xdg-open").arg(url).spawn();
+                        crate::activation::open_uri(url);
+                    }
+                    ActionCallback::CopyText(text) => {
+                        // Right away, while the click's serial is the latest
+                        if let Some(display) = gdk4::Display::default() {
+                            display.clipboard().set_text(text);
+                        }
+                    }
+                    ActionCallback::RunCommand(argv) => {
+                        run_command(argv.clone(), sender.input_sender().clone());
                     }
                     ActionCallback::FdAction { fd_id, action_key } => {
                         // Focus the originating app's window
+                        let mut app = FocusQuery::default();
                         if let Some(notif) = self.active.iter().find(|n| n.request.id == id) {
                             if let NotificationSource::Freedesktop {
                                 app_name,
                                 desktop_entry,
                                 sender_pid,
+                                resident,
                                 ..
                             } = &notif.request.source
                             {
-                                let mut hints: Vec<&str> = Vec::new();
-                                if let Some(de) = desktop_entry {
-                                    hints.push(de);
-                                }
-                                if !app_name.is_empty() {
-                                    hints.push(app_name);
-                                }
-                                let title = &notif.request.title;
-                                focus_app_window(&hints, &[title], *sender_pid);
+                                keep_open = *resident;
+                                app = FocusQuery {
+                                    pid: *sender_pid,
+                                    desktop_entry: desktop_entry.clone(),
+                                    class_hints: vec![app_name.clone()],
+                                    title_keywords: vec![notif.request.title.clone()],
+                                    ..FocusQuery::default()
+                                };
+                                crate::window_focus::focus(app.clone());
                             }
                         }
                         if let Some(tx) = &self.daemon_tx {
-                            let _ =
-                                tx.send(crate::notification_daemon::DaemonCommand::ActionInvoked {
-                                    id: *fd_id,
-                                    action_key: action_key.clone(),
-                                });
+                            let _ = tx.send(DaemonCommand::ActionInvoked {
+                                id: *fd_id,
+                                action_key: action_key.clone(),
+                                activation_token: crate::activation::token(app),
+                            });
                         }
                     }
                 }
-                self.dismiss_by_id_with_reason(id, 2);
-                restack_toasts(&self.active);
+                if !keep_open {
+                    self.dismiss_by_id_with_reason(id, CLOSE_REASON_DISMISSED);
+                    self.restack();
+                }
+            }
+            NotificationInput::MuteApp(id, duration) => {
+                let app_name =
+                    self.active
+                        .iter()
+                        .find(|n| n.request.id == id)
+                        .and_then(|n| match &n.request.source {
+                            NotificationSource::Freedesktop { app_name, .. } => {
+                                Some(app_name.clone())
+                            }
+                            NotificationSource::Internal => None,
+                        });
+                if let (Some(app_name), Some(tx)) = (app_name, &self.daemon_tx) {
+                    let _ = tx.send(DaemonCommand::MuteApp {
+                        app_name,
+                        until: duration.until_utc(),
+                    });
+                }
+                self.dismiss_by_id_with_reason(id, CLOSE_REASON_DISMISSED);
+                self.restack();
+            }
+            NotificationInput::Hover(id, inside) => {
+                if let Some(notif) = self.active.iter().find(|n| n.request.id == id) {
+                    if inside {
+                        notif.expiry.pause();
+                    } else {
+                        notif.expiry.resume();
+                    }
+                }
+            }
+            NotificationInput::ExpandBody(id, expanded) => {
+                if let Some(notif) = self.active.iter().find(|n| n.request.id == id) {
+                    if expanded {
+                        notif.expiry.extend(EXPAND_EXTRA);
+                    }
+                }
+                self.restack();
             }
             NotificationInput::SetDaemonChannel(tx) => {
+                for command in self.pending_daemon.drain(..) {
+                    let _ = tx.send(command);
+                }
                 self.daemon_tx = Some(tx);
             }
-            NotificationInput::SetCenterOpen(open) => {
-                self.center_open = open;
+            NotificationInput::SetCenterOpen(monitor, open) => {
+                self.open_centers.retain(|m| *m != monitor);
+                if open {
+                    self.open_centers.push(monitor);
+                }
+            }
+            NotificationInput::CenterChanged => {
+                self.broadcast_to_centers(|| NotificationCenterInput::Refresh);
+            }
+            NotificationInput::ToDaemon(command) => match &self.daemon_tx {
+                Some(tx) => {
+                    let _ = tx.send(command);
+                }
+                None => self.pending_daemon.push(command),
+            },
+            NotificationInput::AddCenter(monitor, center_tx) => {
+                let _ = center_tx.send(NotificationCenterInput::DndChanged(self.dnd.clone()));
+                self.centers.push((monitor, center_tx));
+            }
+            NotificationInput::SetDnd(preset) => {
+                match preset {
+                    DndPreset::Off => {
+                        self.dnd.until = None;
+                        self.dnd.during_meetings = false;
+                    }
+                    DndPreset::Minutes(minutes) => {
+                        self.dnd.until = Some(Local::now() + chrono::TimeDelta::minutes(minutes));
+                    }
+                    DndPreset::UntilTomorrow => {
+                        let tomorrow = Local::now().date_naive() + chrono::TimeDelta::days(1);
+                        self.dnd.until = tomorrow
+                            .and_hms_opt(0, 0, 0)
+                            .and_then(|t| t.and_local_timezone(Local).earliest());
+                    }
+                    DndPreset::DuringMeetings => {
+                        self.dnd.during_meetings = !self.dnd.during_meetings;
+                    }
+                }
+                self.publish_dnd();
+            }
+            NotificationInput::MeetingChanged(in_meeting) => {
+                self.dnd.in_meeting = in_meeting;
+                self.publish_dnd();
+            }
+            NotificationInput::SetFocus(focus) => {
+                self.dnd.focus = focus;
+                self.publish_dnd();
+            }
+            NotificationInput::SetScreencast(active) => {
+                self.screencast = active && crate::config::get().privacy.enabled;
+                eprintln!(
+                    "jb-shell: [privacy] screencast {}",
+                    if active { "started" } else { "ended" }
+                );
+                for view in self.active.iter().flat_map(|n| &n.views) {
+                    set_private(&view.window, self.screencast);
+                }
+            }
+            NotificationInput::SetMonitors(monitors) => {
+                let Some(first) = monitors.first().cloned() else {
+                    // Everything is off (DPMS) — keep state until a monitor returns
+                    return;
+                };
+                if !monitors.contains(&self.focused_monitor) {
+                    self.focused_monitor = first;
+                }
+                self.monitors = monitors;
+
+                // Centers of removed bars are gone; if one of them was open we'd
+                // otherwise suppress toasts forever.
+                self.centers.retain(|(m, _)| self.monitors.contains(m));
+                self.open_centers.retain(|m| self.monitors.contains(m));
+
+                self.rehome_views(&sender);
+                self.restack();
+            }
+            NotificationInput::FocusMonitor(monitor) => {
+                self.focused_monitor = monitor;
             }
-            NotificationInput::SetCenterSender(sender) => {
-                self.center_sender = Some(sender);
+            NotificationInput::OpenCenter => {
+                // Prefer the center on the focused monitor
+                let center = self
+                    .centers
+                    .iter()
+                    .find(|(m, _)| *m == self.focused_monitor)
+                    .or_else(|| self.centers.first());
+                if let Some((_, center_tx)) = center {
+                    center_tx.emit(NotificationCenterInput::TogglePopup);
+                }
             }
         }
     }
 }
 
 impl NotificationModel {
+    /// `[privacy] toasts = "hide"` during a screencast: route new toasts to
+    /// the center as if DND were on.
+    fn hiding_toasts(&self) -> bool {
+        self.screencast && crate::config::get().privacy.toasts == PrivacyToasts::Hide
+    }
+
+    /// Monitors a new notification should get a window on.
+    fn target_monitors(&self) -> Vec<Monitor> {
+        match crate::config::get().toasts.monitor {
+            ToastMonitor::Focused => vec![self.focused_monitor.clone()],
+            ToastMonitor::All => self.monitors.clone(),
+        }
+    }
+
+    /// Drop windows on monitors that no longer exist and rebuild any notification
+    /// left without a window on the focused monitor.
+    fn rehome_views(&mut self, sender: &ComponentSender<Self>) {
+        for notif in &mut self.active {
+            let (kept, lost): (Vec<_>, Vec<_>) = notif
+                .views
+                .drain(..)
+                .partition(|v| self.monitors.contains(&v.monitor));
+            notif.views = kept;
+            if lost.is_empty() {
+                continue;
+            }
+            for view in lost {
+                view.window.destroy();
+            }
+            if notif.views.is_empty() {
+                eprintln!(
+                    "jb-shell: [monitor] re-presenting notification {} on surviving monitor",
+                    notif.request.id
+                );
+                let monitor = self.focused_monitor.clone();
+                let window =
+                    build_notification_window(&monitor, &notif.request, sender, &notif.expiry);
+                let title_label = find_title_label(&window);
+                set_private(&window, self.screencast);
+                if notif.shown {
+                    window.set_visible(true);
+                }
+                notif.views.push(NotificationView {
+                    monitor,
+                    window,
+                    title_label,
+                });
+            }
+        }
+    }
+
+    fn publish_dnd(&mut self) {
+        eprintln!(
+            "jb-shell: [dnd] {} (until {:?}, during meetings {}, in meeting {}, focus {})",
+            if self.dnd.is_active() { "on" } else { "off" },
+            self.dnd.until,
+            self.dnd.during_meetings,
+            self.dnd.in_meeting,
+            self.dnd.focus
+        );
+        crate::state_dbus::set(StateChange::Dnd(self.dnd.is_active()));
+        crate::state_dbus::set(StateChange::InMeeting(self.dnd.in_meeting));
+        let status = self.dnd.clone();
+        self.broadcast_to_centers(|| NotificationCenterInput::DndChanged(status.clone()));
+    }
+
+    fn broadcast_to_centers(&mut self, msg: impl Fn() -> NotificationCenterInput) {
+        // Centers of removed bars are gone — drop them on first failed send
+        self.centers.retain(|(_, tx)| tx.send(msg()).is_ok());
+    }
+
+    /// Lay out toasts from the configured corner, revealing queued ones as slots
+    /// free up and summarising the rest in the overflow pill. Each monitor has
+    /// its own stack; `max_visible` counts notifications, not windows.
+    fn restack(&mut self) {
+        let cfg = &crate::config::get().toasts;
+        let edge = if cfg.anchor.is_top() {
+            Edge::Top
+        } else {
+            Edge::Bottom
+        };
+        // Per monitor: its scale and where the next toast goes
+        let mut offsets: Vec<(Monitor, f64, i32)> = Vec::new();
+        let mut visible = 0;
+        let mut queued = 0;
+
+        for notif in &mut self.active {
+            if !matches!(notif.request.kind, NotificationKind::Toast) {
+                continue;
+            }
+            if cfg.max_visible > 0 && visible >= cfg.max_visible {
+                if !notif.shown {
+                    queued += 1;
+                }
+                continue;
+            }
+            visible += 1;
+            if !notif.shown {
+                notif.shown = true;
+                notif.expiry.start(&notif.request);
+                for view in &notif.views {
+                    view.window.set_visible(true);
+                }
+            }
+            for view in &notif.views {
+                let idx = match offsets.iter().position(|(m, _, _)| *m == view.monitor) {
+                    Some(idx) => idx,
+                    None => {
+                        let scale = crate::widgets::scale::monitor_scale(&view.monitor);
+                        offsets.push((view.monitor.clone(), scale, cfg.margin_y()));
+                        offsets.len() - 1
+                    }
+                };
+                let (_, scale, offset) = &mut offsets[idx];
+                // Each toast is its own surface; keep every one of them on
+                // whole device pixels
+                *offset = crate::core::scale::snap(*offset as f64, *scale);
+                view.window.set_margin(edge, *offset);
+                let (_, natural, _, _) = view.window.measure(gtk4::Orientation::Vertical, -1);
+                let height = natural.max(60);
+                *offset += height + cfg.spacing;
+            }
+        }
+
+        if queued > 0 && cfg.overflow == ToastOverflow::Collapse {
+            let (scale, offset) = offsets
+                .iter()
+                .find(|(m, _, _)| *m == self.focused_monitor)
+                .map(|(_, scale, offset)| (*scale, *offset))
+                .unwrap_or_else(|| {
+                    let scale = crate::widgets::scale::monitor_scale(&self.focused_monitor);
+                    (scale, cfg.margin_y())
+                });
+            self.overflow_label.set_label(&format!("+{queued} more"));
+            self.overflow_pill.set_monitor(Some(&self.focused_monitor));
+            crate::widgets::scale::set_margin(&self.overflow_pill, edge, offset as f64, scale);
+            self.overflow_pill.set_visible(true);
+        } else {
+            self.overflow_pill.set_visible(false);
+        }
+    }
+
     fn dismiss_by_id_with_reason(&mut self, id: NotificationId, reason: u32) {
         let mut i = 0;
         while i < self.active.len() {
             if self.active[i].request.id == id {
                 let notif = self.active.remove(i);
-                notif.window.destroy();
+                for view in notif.views {
+                    fade_out_and_destroy(view.window);
+                }
                 if reason > 0 {
                     if let NotificationSource::Freedesktop { fd_id, .. } = notif.request.source {
                         if let Some(tx) = &self.daemon_tx {
-                            let _ = tx.send(
-                                crate::notification_daemon::DaemonCommand::NotificationClosed {
-                                    id: fd_id,
-                                    reason,
-                                },
-                            );
+                            let _ = tx.send(DaemonCommand::NotificationClosed { id: fd_id, reason });
                         }
                     }
                 }
-                // Notify center to refresh count
-                if let Some(center_tx) = &self.center_sender {
-                    center_tx.emit(
-                        crate::widgets::notification_center::NotificationCenterInput::Refresh,
-                    );
-                }
+                // Notify centers to refresh count
+                self.broadcast_to_centers(|| NotificationCenterInput::Refresh);
             } else {
                 i += 1;
             }
@@ -274,10 +919,94 @@ impl NotificationModel {
     }
 }
 
+/// Mark a toast window for `[privacy] toasts = "hide-body"` — the stylesheet
+/// blurs its body while the class is set.
+fn set_private(window: &Window, screencast: bool) {
+    let hide_body = crate::config::get().privacy.toasts == PrivacyToasts::HideBody;
+    if screencast && hide_body {
+        window.add_css_class("screencast-private");
+    } else {
+        window.remove_css_class("screencast-private");
+    }
+}
+
+/// Swap a live toast's card for `request`'s. The expiry clock carries on;
+/// it only starts if the update adds a timeout, and stops if it drops one.
+fn replace_toast(
+    notif: &mut ActiveNotification,
+    request: NotificationRequest,
+    sender: &ComponentSender<NotificationModel>,
+    screencast: bool,
+) {
+    for view in &mut notif.views {
+        if let Some(revealer) = view.window.child().and_downcast::<Revealer>() {
+            revealer.set_child(Some(&build_toast_card(&request, sender, &notif.expiry)));
+        }
+        view.title_label = find_title_label(&view.window);
+        set_private(&view.window, screencast);
+    }
+
+    let expiry = &notif.expiry;
+    if request.timeout_ms.is_none() {
+        expiry.deadline.set(None);
+        expiry.paused.set(None);
+    } else if notif.shown && expiry.deadline.get().is_none() && expiry.paused.get().is_none() {
+        expiry.start(&request);
+    }
+    notif.request = request;
+}
+
+/// A toast's card: content, expiry bar, click-for-default and close glyph.
+/// Rebuilt in place when an app replaces the notification.
+fn build_toast_card(
+    request: &NotificationRequest,
+    sender: &ComponentSender<NotificationModel>,
+    expiry: &Rc<Expiry>,
+) -> Overlay {
+    let inner = GtkBox::new(Orientation::Vertical, 4);
+    if let Some(name) = &request.css_box_name {
+        inner.set_widget_name(name);
+    }
+    if let Some(class) = &request.css_card_class {
+        inner.add_css_class(class);
+    }
+
+    build_notification_content(&inner, request, sender);
+    // Keep the first row clear of the close glyph
+    if let Some(first) = inner.first_child() {
+        first.set_margin_end(20);
+    }
+
+    if request.timeout_ms.is_some() {
+        inner.append(&build_expiry_bar(expiry));
+    }
+
+    // Clicking the card runs the spec "default" action, like other
+    // daemons; buttons inside claim their own clicks first
+    if let Some(default) = request.actions.iter().find(|a| is_default_action(a)) {
+        inner.add_css_class("has-default-action");
+        inner.set_cursor_from_name(Some("pointer"));
+        let click = GestureClick::new();
+        let id = request.id;
+        let cb = default.callback.clone();
+        let click_sender = sender.input_sender().clone();
+        click.connect_released(move |_, _, _, _| {
+            click_sender.emit(NotificationInput::ActionTriggered(id, cb.clone()));
+        });
+        inner.add_controller(click);
+    }
+
+    let card = Overlay::new();
+    card.set_child(Some(&inner));
+    card.add_overlay(&build_close_glyph(request.id, sender));
+    card
+}
+
 fn build_notification_window(
     monitor: &Monitor,
     request: &NotificationRequest,
     sender: &ComponentSender<NotificationModel>,
+    expiry: &Rc<Expiry>,
 ) -> Window {
     let window = Window::new();
     window.init_layer_shell();
@@ -286,21 +1015,32 @@ fn build_notification_window(
     window.set_monitor(Some(monitor));
     match &request.kind {
         NotificationKind::Toast => {
-            window.set_anchor(Edge::Top, true);
-            window.set_anchor(Edge::Right, true);
-            window.set_margin(Edge::Top, 8);
-            window.set_margin(Edge::Right, 8);
+            anchor_toast_window(&window);
 
-            let inner = GtkBox::new(Orientation::Vertical, 4);
-            if let Some(name) = &request.css_box_name {
-                inner.set_widget_name(name);
-            }
-            if let Some(class) = &request.css_card_class {
-                inner.add_css_class(class);
-            }
+            // Slide in from the anchored screen edge once mapped
+            let revealer = Revealer::new();
+            revealer.set_transition_type(if crate::config::get().toasts.anchor.is_right() {
+                RevealerTransitionType::SlideLeft
+            } else {
+                RevealerTransitionType::SlideRight
+            });
+            revealer.set_transition_duration(250);
+            revealer.set_child(Some(&build_toast_card(request, sender, expiry)));
+            revealer.connect_map(|r| r.set_reveal_child(true));
+            window.set_child(Some(&revealer));
 
-            build_notification_content(&inner, request, sender);
-            window.set_child(Some(&inner));
+            // Always tracked: a replacement may add a timeout later
+            let motion = EventControllerMotion::new();
+            let id = request.id;
+            let enter_sender = sender.input_sender().clone();
+            motion.connect_enter(move |_, _, _| {
+                enter_sender.emit(NotificationInput::Hover(id, true));
+            });
+            let leave_sender = sender.input_sender().clone();
+            motion.connect_leave(move |_| {
+                leave_sender.emit(NotificationInput::Hover(id, false));
+            });
+            window.add_controller(motion);
         }
         NotificationKind::Fullscreen => {
             window.set_anchor(Edge::Top, true);
@@ -343,15 +1083,66 @@ fn build_notification_content(
     title_label.set_max_width_chars(50);
     title_label.set_wrap(true);
     title_label.set_xalign(0.0);
-    container.append(&title_label);
+    let app_icon = match &request.source {
+        NotificationSource::Freedesktop {
+            app_name,
+            desktop_entry,
+            ..
+        } => crate::app_icons::image(desktop_entry.as_deref(), app_name, 20),
+        NotificationSource::Internal => None,
+    };
+    if app_icon.is_some() || request.swatch.is_some() || request.repeat_count > 1 {
+        let title_row = GtkBox::new(Orientation::Horizontal, 6);
+        if let Some(color) = request.swatch {
+            let swatch = gtk4::DrawingArea::new();
+            swatch.set_content_width(20);
+            swatch.set_content_height(20);
+            swatch.add_css_class("notif-swatch");
+            swatch.set_draw_func(move |_, cr, width, height| {
+                cr.set_source_rgba(
+                    color.red() as f64,
+                    color.green() as f64,
+                    color.blue() as f64,
+                    color.alpha() as f64,
+                );
+                cr.rectangle(0.0, 0.0, width as f64, height as f64);
+                let _ = cr.fill();
+            });
+            title_row.append(&swatch);
+        }
+        if let Some(image) = &app_icon {
+            image.set_valign(gtk4::Align::Start);
+            title_row.append(image);
+        }
+        title_label.set_hexpand(true);
+        title_row.append(&title_label);
+        if request.repeat_count > 1 {
+            let count_label = Label::new(Some(&format!("\u{d7}{}", request.repeat_count)));
+            count_label.add_css_class("notif-repeat-count");
+            count_label.set_valign(gtk4::Align::Start);
+            title_row.append(&count_label);
+        }
+        container.append(&title_row);
+    } else {
+        container.append(&title_label);
+    }
 
+    let toast = matches!(request.kind, NotificationKind::Toast);
     if let Some(body) = &request.body {
-        let body_label = Label::new(Some(body));
+        let body_label = Label::new(None);
+        match request.source {
+            NotificationSource::Freedesktop { .. } => set_body_markup(&body_label, body),
+            NotificationSource::Internal => body_label.set_text(body),
+        }
         body_label.add_css_class("notif-event");
-        body_label.set_max_width_chars(50);
+        body_label.set_max_width_chars(BODY_LINE_CHARS as i32);
         body_label.set_wrap(true);
         body_label.set_xalign(0.0);
-        container.append(&body_label);
+        if toast && is_long_body(&plain_body(body)) {
+            container.append(&build_expandable_body(&body_label, request.id, sender));
+        } else {
+            container.append(&body_label);
+        }
     }
 
     if let Some(subtitle) = &request.subtitle {
@@ -360,13 +1151,46 @@ fn build_notification_content(
         container.append(&sub_label);
     }
 
-    if !request.actions.is_empty() {
+    if let Some(value) = request.progress {
+        let progress = ProgressBar::new();
+        progress.add_css_class("notif-progress");
+        progress.set_fraction(value as f64 / 100.0);
+        container.append(&progress);
+    }
+
+    let mute_app = match &request.source {
+        NotificationSource::Freedesktop { app_name, .. } if !app_name.is_empty() => {
+            Some(app_name.as_str())
+        }
+        _ => None,
+    };
+
+    // Toasts take the default action on a card click and dismiss with the
+    // close glyph, so neither gets a button there
+    let buttons: Vec<&NotificationAction> = request
+        .actions
+        .iter()
+        .filter(|a| {
+            !(toast && (is_default_action(a) || matches!(a.callback, ActionCallback::Dismiss)))
+        })
+        .collect();
+    let quick = match (&request.body, toast) {
+        (Some(body), true) => quick_actions(
+            &plain_body(body),
+            markup::first_link(body),
+            &request.actions,
+        ),
+        _ => Vec::new(),
+    };
+    let buttons: Vec<&NotificationAction> = buttons.into_iter().chain(&quick).collect();
+
+    if !buttons.is_empty() || mute_app.is_some() {
         let button_row = GtkBox::new(Orientation::Horizontal, 8);
-        if matches!(request.kind, NotificationKind::Fullscreen) {
+        if !toast {
             button_row.set_halign(gtk4::Align::Center);
         }
 
-        for action in &request.actions {
+        for action in buttons {
             let btn = Button::with_label(&action.label);
             btn.add_css_class(&action.css_class);
 
@@ -380,159 +1204,323 @@ fn build_notification_content(
             button_row.append(&btn);
         }
 
+        if let Some(app_name) = mute_app {
+            button_row.append(&build_mute_menu(request.id, app_name, sender));
+        }
+
         container.append(&button_row);
     }
 }
 
-fn find_title_label(window: &Window) -> Label {
-    // The title label is the one with css class "notif-title-label" inside the window's child box.
-    // We walk the children to find it.
-    if let Some(inner) = window.child().and_then(|c| c.downcast::<GtkBox>().ok()) {
-        let mut child = inner.first_child();
-        while let Some(widget) = child {
-            if let Ok(label) = widget.clone().downcast::<Label>() {
-                if label.has_css_class("notif-title-label") {
-                    return label;
-                }
-            }
-            child = widget.next_sibling();
-        }
+/// Show an app's notification body in `label` as `[toasts] markup` says.
+pub fn set_body_markup(label: &Label, body: &str) {
+    match crate::config::get().toasts.markup {
+        BodyMarkup::Strict => label.set_text(&markup::to_plain(body, true)),
+        BodyMarkup::Lenient => label.set_markup(&markup::to_pango(body)),
     }
-    // Fallback — should never happen
-    Label::new(None)
 }
 
-const BAR_HEIGHT_OFFSET: i32 = 40; // ~31px bar + 8px gap + 1px breathing room
+/// An app's notification body as plain text under `[toasts] markup`, for
+/// measuring, truncating and the summary prompt.
+pub fn plain_body(body: &str) -> String {
+    let strict = crate::config::get().toasts.markup == BodyMarkup::Strict;
+    markup::to_plain(body, strict)
+}
 
-fn restack_toasts(active: &[ActiveNotification]) {
-    let mut top_offset = BAR_HEIGHT_OFFSET;
-    for notif in active {
-        match &notif.request.kind {
-            NotificationKind::Toast => {
-                notif.window.set_margin(Edge::Top, top_offset);
-                let (_, natural, _, _) = notif.window.measure(gtk4::Orientation::Vertical, -1);
-                let height = natural.max(60);
-                top_offset += height + 8;
-            }
-            NotificationKind::Fullscreen => {}
+/// Whether a toast body runs past `[toasts] body_lines`, counting wrapped
+/// lines roughly by characters.
+fn is_long_body(body: &str) -> bool {
+    let max_lines = crate::config::get().toasts.body_lines.max(1) as usize;
+    let lines: usize = body
+        .lines()
+        .map(|line| line.chars().count().div_ceil(BODY_LINE_CHARS).max(1))
+        .sum();
+    lines > max_lines
+}
+
+/// A long toast body cut to `[toasts] body_lines`, with a chevron that
+/// shows all of it, scrolling past `expanded_max_height`.
+fn build_expandable_body(
+    body_label: &Label,
+    id: NotificationId,
+    sender: &ComponentSender<NotificationModel>,
+) -> GtkBox {
+    let cfg = &crate::config::get().toasts;
+    let collapse = |label: &Label| {
+        label.set_lines(cfg.body_lines.max(1));
+        label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    };
+    collapse(body_label);
+
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(PolicyType::Never, PolicyType::Automatic);
+    scroller.set_propagate_natural_height(true);
+    scroller.set_max_content_height(cfg.expanded_max_height);
+    scroller.set_hexpand(true);
+    scroller.set_child(Some(body_label));
+
+    let chevron = Button::from_icon_name("pan-down-symbolic");
+    chevron.add_css_class("notif-expand");
+    chevron.set_valign(gtk4::Align::End);
+    chevron.set_tooltip_text(Some("Show more"));
+    let label = body_label.clone();
+    let expand_sender = sender.input_sender().clone();
+    chevron.connect_clicked(move |chevron| {
+        let expanding = label.lines() > 0;
+        if expanding {
+            label.set_lines(-1);
+            label.set_ellipsize(gtk4::pango::EllipsizeMode::None);
+            chevron.set_icon_name("pan-up-symbolic");
+            chevron.set_tooltip_text(Some("Show less"));
+        } else {
+            collapse(&label);
+            chevron.set_icon_name("pan-down-symbolic");
+            chevron.set_tooltip_text(Some("Show more"));
         }
-    }
+        expand_sender.emit(NotificationInput::ExpandBody(id, expanding));
+    });
+
+    let row = GtkBox::new(Orientation::Horizontal, 4);
+    row.append(&scroller);
+    row.append(&chevron);
+    row
 }
 
-pub fn format_countdown(target: DateTime<Local>, now: DateTime<Local>) -> String {
-    let secs = (target - now).num_seconds().max(0);
-    let mins = secs / 60;
-    let remaining_secs = secs % 60;
-    if mins > 0 {
-        format!("Starting in {}m {}s", mins, remaining_secs)
-    } else {
-        format!("Starting in {}s", remaining_secs)
+/// "Open link" and "Copy code" for the first URL and one-time code in a
+/// notification body, unless `[toasts] quick_actions` is off. `body` is the
+/// `plain_body` text, so entities are decoded; `link` is the body's first
+/// `<a href>`, which plain text drops, and wins over a URL in the text. A
+/// link the sender already offers as a button isn't repeated.
+pub fn quick_actions(
+    body: &str,
+    link: Option<String>,
+    existing: &[NotificationAction],
+) -> Vec<NotificationAction> {
+    use crate::core::quick_actions::{find_otp, find_url};
+    if !crate::config::get().toasts.quick_actions {
+        return Vec::new();
     }
+    let mut actions = Vec::new();
+    if let Some(url) = link.or_else(|| find_url(body)) {
+        let offered = existing
+            .iter()
+            .any(|a| matches!(&a.callback, ActionCallback::OpenUrl(u) if *u == url));
+        if !offered {
+            actions.push(NotificationAction {
+                label: "Open link".to_string(),
+                css_class: "notif-action".to_string(),
+                callback: ActionCallback::OpenUrl(url),
+            });
+        }
+    }
+    if let Some(code) = find_otp(body) {
+        actions.push(NotificationAction {
+            label: "Copy code".to_string(),
+            css_class: "notif-action".to_string(),
+            callback: ActionCallback::CopyText(code),
+        });
+    }
+    actions
 }
 
-pub fn hash_event_id(event_id: &str, suffix: &str) -> NotificationId {
-    let mut hasher = DefaultHasher::new();
-    event_id.hash(&mut hasher);
-    suffix.hash(&mut hasher);
-    hasher.finish()
+/// Run a `RunCommand` action off the main thread, waiting it out so a
+/// command that can't start or exits non-zero is toasted rather than lost.
+fn run_command(argv: Vec<String>, sender: relm4::Sender<NotificationInput>) {
+    let Some((program, args)) = argv.split_first() else {
+        return;
+    };
+    let mut command = std::process::Command::new(program);
+    command.args(args).stdin(std::process::Stdio::null());
+    std::thread::spawn(move || {
+        let error = match command.status() {
+            Ok(status) if status.success() => return,
+            Ok(status) => format!("`{}` {status}", argv.join(" ")),
+            Err(e) => format!("Can't run {}: {e}", argv[0]),
+        };
+        eprintln!("jb-shell: [notifications] action failed: {error}");
+        sender.emit(NotificationInput::Show(NotificationRequest {
+            id: COMMAND_FAILED_ID,
+            kind: NotificationKind::Toast,
+            icon: Some("\u{f06a}".to_string()),
+            title: "Action failed".to_string(),
+            body: Some(error),
+            subtitle: None,
+            countdown_target: None,
+            actions: Vec::new(),
+            css_window_name: None,
+            css_box_name: Some("fd-notification".to_string()),
+            css_card_class: None,
+            timeout_ms: Some(8000),
+            repeat_count: 1,
+            swatch: None,
+            progress: None,
+            source: NotificationSource::Internal,
+        }));
+    });
 }
 
-/// Focus a Hyprland window matching the given hints.
-/// Tries PID-based matching first (walks process tree to find the window),
-/// then class hints with optional title keyword disambiguation.
-/// Switches workspace automatically.
-pub fn focus_app_window(hints: &[&str], title_keywords: &[&str], sender_pid: Option<u32>) {
-    use hyprland::data::Clients;
-    use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
-    use hyprland::shared::{HyprData, HyprDataVec};
+/// The freedesktop `default` action, invoked by clicking the notification.
+fn is_default_action(action: &NotificationAction) -> bool {
+    matches!(&action.callback, ActionCallback::FdAction { action_key, .. } if action_key == "default")
+}
 
-    let clients = match Clients::get() {
-        Ok(c) => c.to_vec(),
-        Err(_) => return,
-    };
+/// "×" in a toast's top corner.
+fn build_close_glyph(id: NotificationId, sender: &ComponentSender<NotificationModel>) -> Button {
+    let btn = Button::with_label("\u{d7}");
+    crate::widgets::a11y::name(&btn, "Dismiss");
+    btn.add_css_class("notif-close");
+    btn.set_halign(gtk4::Align::End);
+    btn.set_valign(gtk4::Align::Start);
+    btn.set_tooltip_text(Some("Dismiss"));
+    let close_sender = sender.input_sender().clone();
+    btn.connect_clicked(move |_| {
+        close_sender.emit(NotificationInput::ActionTriggered(
+            id,
+            ActionCallback::Dismiss,
+        ));
+    });
+    btn
+}
 
-    // Try PID-based matching: walk up the process tree to find a Hyprland window
-    if let Some(pid) = sender_pid {
-        let window_pids: std::collections::HashSet<i32> =
-            clients.iter().map(|c| c.pid).collect();
-        if let Some(window_pid) = walk_to_window_pid(pid, &window_pids) {
-            if let Some(client) = clients.iter().find(|c| c.pid == window_pid) {
-                let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
-                    client.address.clone(),
-                )));
-                return;
-            }
-        }
-    }
+/// Overflow "⋯" button offering to mute the toast's app for a while.
+fn build_mute_menu(
+    id: NotificationId,
+    app_name: &str,
+    sender: &ComponentSender<NotificationModel>,
+) -> MenuButton {
+    let menu_box = GtkBox::new(Orientation::Vertical, 2);
+    menu_box.add_css_class("notif-mute-menu");
 
-    let hints_lower: Vec<String> = hints
-        .iter()
-        .map(|h| h.to_lowercase())
-        .filter(|h| !h.is_empty())
-        .collect();
+    let popover = Popover::new();
+    popover.set_child(Some(&menu_box));
 
-    if hints_lower.is_empty() {
-        return;
+    for (label, duration) in [
+        (format!("Mute {app_name} for 1h"), MuteDuration::Hour),
+        (format!("Mute {app_name} today"), MuteDuration::Today),
+        (format!("Mute {app_name} forever"), MuteDuration::Forever),
+    ] {
+        let btn = Button::with_label(&label);
+        let mute_sender = sender.input_sender().clone();
+        let popover_ref = popover.clone();
+        btn.connect_clicked(move |_| {
+            popover_ref.popdown();
+            mute_sender.emit(NotificationInput::MuteApp(id, duration));
+        });
+        menu_box.append(&btn);
     }
 
-    let keywords_lower: Vec<String> = title_keywords
-        .iter()
-        .map(|k| k.to_lowercase())
-        .filter(|k| !k.is_empty())
-        .collect();
+    let menu_btn = MenuButton::new();
+    crate::widgets::a11y::name(&menu_btn, "More actions");
+    menu_btn.set_label("\u{22ef}");
+    menu_btn.add_css_class("notif-overflow");
+    menu_btn.set_hexpand(true);
+    menu_btn.set_halign(gtk4::Align::End);
+    menu_btn.set_popover(Some(&popover));
+    menu_btn
+}
 
-    // Collect all candidate windows that match any class hint
-    let mut candidates: Vec<_> = Vec::new();
-    for hint in &hints_lower {
-        for client in &clients {
-            let class = client.class.to_lowercase();
-            if class == *hint || class.contains(hint.as_str()) || hint.contains(class.as_str()) {
-                if !candidates
-                    .iter()
-                    .any(|c: &&hyprland::data::Client| c.address == client.address)
-                {
-                    candidates.push(client);
-                }
+fn find_title_label(window: &Window) -> Label {
+    // The title label is the one with css class "notif-title-label" somewhere under the
+    // window (toasts nest it inside a revealer). Walk the tree to find it.
+    fn walk(widget: &gtk4::Widget) -> Option<Label> {
+        if let Some(label) = widget.downcast_ref::<Label>() {
+            if label.has_css_class("notif-title-label") {
+                return Some(label.clone());
+            }
+        }
+        let mut child = widget.first_child();
+        while let Some(c) = child {
+            if let Some(found) = walk(&c) {
+                return Some(found);
             }
+            child = c.next_sibling();
         }
+        None
     }
+    window
+        .child()
+        .and_then(|c| walk(&c))
+        // Fallback — should never happen
+        .unwrap_or_else(|| Label::new(None))
+}
 
-    if candidates.is_empty() {
-        return;
-    }
+/// Thin bar along the bottom of a toast that shrinks as it approaches expiry.
+fn build_expiry_bar(expiry: &Rc<Expiry>) -> ProgressBar {
+    let bar = ProgressBar::new();
+    bar.add_css_class("notif-expiry-bar");
+    bar.set_fraction(1.0);
+    let expiry = expiry.clone();
+    bar.add_tick_callback(move |bar, _| {
+        bar.set_fraction(expiry.remaining_fraction());
+        glib::ControlFlow::Continue
+    });
+    bar
+}
 
-    // If we have title keywords, prefer a window whose title matches
-    if !keywords_lower.is_empty() {
-        if let Some(client) = candidates.iter().find(|c| {
-            let title = c.title.to_lowercase();
-            keywords_lower.iter().any(|k| title.contains(k.as_str()))
-        }) {
-            let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
-                client.address.clone(),
-            )));
-            return;
+/// Fade the window's content out, then destroy it; at once with reduced
+/// motion, where GTK skips the CSS transition anyway.
+fn fade_out_and_destroy(window: Window) {
+    match window.child() {
+        Some(child) if window.is_visible() && !crate::widgets::a11y::reduce_motion() => {
+            child.add_css_class("notif-leaving");
+            glib::timeout_add_local_once(Duration::from_millis(TOAST_FADE_MS), move || {
+                window.destroy();
+            });
         }
+        _ => window.destroy(),
     }
+}
 
-    // Otherwise just focus the first candidate
-    let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
-        candidates[0].address.clone(),
-    )));
+/// Anchor a toast-stack window to the configured corner. The stacking edge's
+/// margin is set later by `restack()`.
+fn anchor_toast_window(window: &Window) {
+    let cfg = &crate::config::get().toasts;
+    let (v_edge, h_edge) = (
+        if cfg.anchor.is_top() {
+            Edge::Top
+        } else {
+            Edge::Bottom
+        },
+        if cfg.anchor.is_right() {
+            Edge::Right
+        } else {
+            Edge::Left
+        },
+    );
+    window.set_anchor(v_edge, true);
+    window.set_anchor(h_edge, true);
+    window.set_margin(v_edge, cfg.margin_y());
+    window.set_margin(h_edge, cfg.margin_x);
 }
 
-/// Walk up the process tree from `start_pid` until we find a PID that owns a Hyprland window.
-fn walk_to_window_pid(start_pid: u32, window_pids: &std::collections::HashSet<i32>) -> Option<i32> {
-    let mut pid = start_pid as i32;
-    while pid > 1 {
-        if window_pids.contains(&pid) {
-            return Some(pid);
-        }
-        let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
-        pid = status
-            .lines()
-            .find(|l| l.starts_with("PPid:"))
-            .and_then(|l| l.split_whitespace().nth(1))
-            .and_then(|s| s.parse().ok())?;
-    }
-    None
+fn build_overflow_pill(
+    monitor: &Monitor,
+    sender: &ComponentSender<NotificationModel>,
+) -> (Window, Label) {
+    let window = Window::new();
+    window.init_layer_shell();
+    window.set_layer(Layer::Overlay);
+    window.set_exclusive_zone(-1);
+    window.set_monitor(Some(monitor));
+    window.set_widget_name("notif-overflow-pill");
+    anchor_toast_window(&window);
+
+    let label = Label::new(None);
+    let btn = Button::new();
+    btn.set_child(Some(&label));
+    let pill_sender = sender.input_sender().clone();
+    btn.connect_clicked(move |_| {
+        pill_sender.emit(NotificationInput::OpenCenter);
+    });
+    window.set_child(Some(&btn));
+    window.set_visible(false);
+
+    (window, label)
+}
+
+pub fn hash_event_id(event_id: &str, suffix: &str) -> NotificationId {
+    let mut hasher = DefaultHasher::new();
+    event_id.hash(&mut hasher);
+    suffix.hash(&mut hasher);
+    hasher.finish()
 }
@@ -0,0 +1,259 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use relm4::prelude::*;
+
+use crate::core::oncall::{Incident, IncidentStatus};
+use crate::oncall::{OnCallCommand, OnCallState};
+use crate::shell::OnCallHandle;
+use crate::widgets::switcher::BarPopup;
+use crate::widgets::tooltip::Tooltip;
+
+/// PagerDuty/Opsgenie status: highlighted while on call, red with a count
+/// while incidents are open; click lists them with acknowledge/resolve.
+pub struct OnCallModel {
+    state: OnCallState,
+    popup_visible: bool,
+    handle: OnCallHandle,
+}
+
+pub struct OnCallInit {
+    pub monitor: Monitor,
+    pub handle: OnCallHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum OnCallInput {
+    State(OnCallState),
+    Command(OnCallCommand),
+    TogglePopup,
+    HidePopup,
+}
+
+pub struct OnCallWidgets {
+    label: Label,
+    trigger: Button,
+    popup: BarPopup,
+    popup_box: GtkBox,
+    tooltip: Tooltip,
+}
+
+impl Component for OnCallModel {
+    type Init = OnCallInit;
+    type Input = OnCallInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = OnCallWidgets;
+
+    fn init_root() -> Self::Root {
+        let b = GtkBox::new(Orientation::Horizontal, 0);
+        b.set_widget_name("oncall");
+        b.set_valign(gtk4::Align::Center);
+        b.set_visible(false);
+        b
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let trigger_box = GtkBox::new(Orientation::Horizontal, 4);
+        let icon = Label::new(Some("\u{f0a1}"));
+        icon.add_css_class("oncall-icon");
+        let label = Label::new(None);
+        trigger_box.append(&icon);
+        trigger_box.append(&label);
+
+        let trigger = Button::new();
+        crate::widgets::a11y::name(&trigger, "On-call incidents");
+        trigger.set_widget_name("oncall-trigger");
+        trigger.set_child(Some(&trigger_box));
+        root.append(&trigger);
+
+        let popup_sender = sender.input_sender().clone();
+        trigger.connect_clicked(move |_| {
+            popup_sender.emit(OnCallInput::TogglePopup);
+        });
+
+        let popup_box = GtkBox::new(Orientation::Vertical, 6);
+
+        let hide_sender = sender.input_sender().clone();
+        let popup = BarPopup::new(
+            "oncall-popup",
+            "On-call incidents",
+            &init.monitor,
+            &popup_box,
+            move || hide_sender.emit(OnCallInput::HidePopup),
+        );
+
+        init.handle
+            .subscribers
+            .subscribe(sender.input_sender().clone());
+
+        let tooltip = Tooltip::attach(&trigger);
+        let model = OnCallModel {
+            state: OnCallState::default(),
+            popup_visible: false,
+            handle: init.handle,
+        };
+        let widgets = OnCallWidgets {
+            label,
+            trigger,
+            popup,
+            popup_box,
+            tooltip,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            OnCallInput::State(state) => {
+                root.set_visible(true);
+                self.state = state;
+            }
+            OnCallInput::Command(command) => {
+                let _ = self.handle.thread_tx.try_send(command);
+                return;
+            }
+            OnCallInput::TogglePopup => {
+                self.popup_visible = !self.popup_visible;
+            }
+            OnCallInput::HidePopup => {
+                self.popup_visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
+        let count = self.state.incidents.len();
+        widgets.label.set_label(&count.to_string());
+        widgets.label.set_visible(count > 0);
+        for (class, on) in [
+            ("on-call", self.state.on_call),
+            ("incident", count > 0),
+            ("error", self.state.error.is_some()),
+        ] {
+            if on {
+                widgets.trigger.add_css_class(class);
+            } else {
+                widgets.trigger.remove_css_class(class);
+            }
+        }
+
+        let mut tooltip = status_line(&self.state);
+        if let Some(error) = &self.state.error {
+            tooltip.push_str(&format!("\n{}", glib::markup_escape_text(error)));
+        }
+        widgets.tooltip.set_markup(&tooltip);
+
+        if self.popup_visible {
+            widgets.tooltip.hide();
+            while let Some(child) = widgets.popup_box.first_child() {
+                widgets.popup_box.remove(&child);
+            }
+            let header = Label::new(None);
+            header.set_halign(gtk4::Align::Start);
+            header.set_markup(&format!("<b>{}</b>", status_line(&self.state)));
+            widgets.popup_box.append(&header);
+            if let Some(error) = &self.state.error {
+                let error = Label::new(Some(error));
+                error.add_css_class("dim");
+                error.set_halign(gtk4::Align::Start);
+                widgets.popup_box.append(&error);
+            }
+            for incident in &self.state.incidents {
+                widgets
+                    .popup_box
+                    .append(&build_incident_row(incident, &sender));
+            }
+            widgets.popup.show(&widgets.trigger);
+        } else {
+            widgets.popup.hide();
+        }
+    }
+}
+
+fn status_line(state: &OnCallState) -> String {
+    let duty = if state.on_call {
+        "On call"
+    } else {
+        "Not on call"
+    };
+    match state.incidents.len() {
+        0 => format!("{duty} \u{b7} no open incidents"),
+        1 => format!("{duty} \u{b7} 1 open incident"),
+        n => format!("{duty} \u{b7} {n} open incidents"),
+    }
+}
+
+/// Title and service, then Acknowledge (until acknowledged), Resolve and, when
+/// the provider gives a page, Open.
+fn build_incident_row(incident: &Incident, sender: &ComponentSender<OnCallModel>) -> GtkBox {
+    let row = GtkBox::new(Orientation::Vertical, 2);
+    row.add_css_class("oncall-incident");
+    if incident.status == IncidentStatus::Triggered {
+        row.add_css_class("triggered");
+    }
+
+    let title = Label::new(Some(&incident.title));
+    title.set_halign(gtk4::Align::Start);
+    title.set_max_width_chars(50);
+    title.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    row.append(&title);
+    let status = match incident.status {
+        IncidentStatus::Triggered => "triggered",
+        IncidentStatus::Acknowledged => "acknowledged",
+    };
+    let detail = if incident.source.is_empty() {
+        status.to_string()
+    } else {
+        format!("{} \u{b7} {status}", incident.source)
+    };
+    let detail = Label::new(Some(&detail));
+    detail.add_css_class("dim");
+    detail.set_halign(gtk4::Align::Start);
+    row.append(&detail);
+
+    let buttons = GtkBox::new(Orientation::Horizontal, 4);
+    buttons.set_halign(gtk4::Align::End);
+    let mut actions = Vec::new();
+    if incident.status == IncidentStatus::Triggered {
+        actions.push((
+            "Acknowledge",
+            OnCallCommand::Acknowledge(incident.id.clone()),
+        ));
+    }
+    actions.push(("Resolve", OnCallCommand::Resolve(incident.id.clone())));
+    for (label, command) in actions {
+        let button = Button::with_label(label);
+        button.set_widget_name("oncall-action");
+        let command_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            command_sender.emit(OnCallInput::Command(command.clone()));
+        });
+        buttons.append(&button);
+    }
+    if let Some(url) = incident.url.clone() {
+        let button = Button::with_label("Open");
+        button.set_widget_name("oncall-action");
+        let hide_sender = sender.input_sender().clone();
+        button.connect_clicked(move |_| {
+            crate::activation::open_uri(&url);
+            hide_sender.emit(OnCallInput::HidePopup);
+        });
+        buttons.append(&button);
+    }
+    row.append(&buttons);
+    row
+}
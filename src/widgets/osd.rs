@@ -0,0 +1,183 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Image, Label, LevelBar, Orientation, Window};
+use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use relm4::prelude::*;
+use std::time::Duration;
+
+use crate::core::audio::is_boosted;
+
+/// How long the OSD stays up after the last key press.
+const HIDE_AFTER: Duration = Duration::from_millis(1500);
+
+/// Gap between the OSD and the bottom of the screen, in logical pixels.
+const BOTTOM_MARGIN: f64 = 96.0;
+
+/// What a key press changed, for the OSD to show.
+#[derive(Debug, Clone)]
+pub struct OsdLevel {
+    pub icon: &'static str,
+    /// Read out with the percentage, e.g. "Volume".
+    pub name: &'static str,
+    pub percent: u32,
+    /// Muted output or microphone: the bar is dimmed.
+    pub muted: bool,
+    /// The full bar: 100, or `[volume] max_percent` when volume may boost.
+    pub max: u32,
+}
+
+/// On-screen display for the media keys: an icon, a level bar and the
+/// percentage, bottom center on the focused monitor. Every press restarts
+/// its timeout, so holding a key keeps it up.
+pub struct OsdModel {
+    visible: bool,
+    /// Fallback when Hyprland's focused monitor can't be matched.
+    monitor: Monitor,
+    level: Option<OsdLevel>,
+    /// Bumped on every `Show`; only the latest timeout hides.
+    generation: u64,
+}
+
+#[derive(Debug)]
+pub enum OsdInput {
+    Show(OsdLevel),
+    Hide(u64),
+    /// The fallback monitor went away (hotplug) — use another one.
+    SetMonitor(Monitor),
+}
+
+pub struct OsdWidgets {
+    overlay: Window,
+    card: GtkBox,
+    icon: Image,
+    bar: LevelBar,
+    label: Label,
+}
+
+impl Component for OsdModel {
+    type Init = Monitor;
+    type Input = OsdInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = OsdWidgets;
+
+    fn init_root() -> Self::Root {
+        // Invisible root — the real UI is the overlay window
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        monitor: Self::Init,
+        _root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let overlay = Window::new();
+        overlay.set_widget_name("osd");
+        overlay.init_layer_shell();
+        overlay.set_namespace(Some("jb-shell-osd"));
+        overlay.set_layer(Layer::Overlay);
+        overlay.set_exclusive_zone(-1);
+        overlay.set_anchor(Edge::Bottom, true);
+        overlay.set_keyboard_mode(KeyboardMode::None);
+        overlay.set_monitor(Some(&monitor));
+
+        let card = GtkBox::new(Orientation::Horizontal, 12);
+        card.set_widget_name("osd-card");
+        let icon = Image::new();
+        icon.set_pixel_size(24);
+        let bar = LevelBar::for_interval(0.0, 100.0);
+        bar.set_widget_name("osd-level");
+        bar.set_hexpand(true);
+        bar.set_valign(gtk4::Align::Center);
+        let label = Label::new(None);
+        label.set_widget_name("osd-label");
+        label.set_width_chars(4);
+        label.set_xalign(1.0);
+        card.append(&icon);
+        card.append(&bar);
+        card.append(&label);
+        overlay.set_child(Some(&card));
+        overlay.set_visible(false);
+
+        let model = OsdModel {
+            visible: false,
+            monitor,
+            level: None,
+            generation: 0,
+        };
+        let widgets = OsdWidgets {
+            overlay,
+            card,
+            icon,
+            bar,
+            label,
+        };
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            OsdInput::Show(level) => {
+                if !self.visible {
+                    let monitor = crate::widgets::color_picker::focused_monitor()
+                        .unwrap_or_else(|| self.monitor.clone());
+                    let scale = crate::widgets::scale::monitor_scale(&monitor);
+                    widgets.overlay.set_monitor(Some(&monitor));
+                    crate::widgets::scale::set_margin(
+                        &widgets.overlay,
+                        Edge::Bottom,
+                        BOTTOM_MARGIN,
+                        scale,
+                    );
+                    self.visible = true;
+                }
+                self.level = Some(level);
+                self.generation += 1;
+                let generation = self.generation;
+                let hide_sender = sender.input_sender().clone();
+                glib::timeout_add_local_once(HIDE_AFTER, move || {
+                    hide_sender.emit(OsdInput::Hide(generation));
+                });
+            }
+            OsdInput::Hide(generation) => {
+                if generation == self.generation {
+                    self.visible = false;
+                }
+            }
+            OsdInput::SetMonitor(monitor) => {
+                self.monitor = monitor;
+                self.visible = false;
+            }
+        }
+
+        if let Some(level) = &self.level {
+            widgets.icon.set_icon_name(Some(level.icon));
+            widgets.bar.set_max_value(level.max.max(1) as f64);
+            widgets.bar.set_value(level.percent.min(level.max) as f64);
+            widgets.label.set_label(&format!("{}%", level.percent));
+            if level.muted {
+                widgets.card.add_css_class("osd-muted");
+            } else {
+                widgets.card.remove_css_class("osd-muted");
+            }
+            if is_boosted(level.percent) {
+                widgets.card.add_css_class("osd-boosted");
+            } else {
+                widgets.card.remove_css_class("osd-boosted");
+            }
+            let state = if level.muted { " (muted)" } else { "" };
+            crate::widgets::a11y::name(
+                &widgets.overlay,
+                &format!("{} {}%{state}", level.name, level.percent),
+            );
+        }
+        widgets.overlay.set_visible(self.visible);
+    }
+}
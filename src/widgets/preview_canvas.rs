@@ -0,0 +1,262 @@
+use gdk4::{MemoryFormat, MemoryTexture, RGBA};
+use gtk4::graphene::Rect;
+use gtk4::gsk::RoundedRect;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use hyprland::shared::Address;
+
+use crate::core::preview::{downscale_bilinear, Frame};
+
+/// Thumbnails fade in over this long once a capture arrives.
+const FADE_IN_US: i64 = 150_000;
+
+/// One window of the workspace preview: its own texture, drawn at `bounds`
+/// (preview coordinates, see `PreviewCanvas::set_windows`).
+pub struct PreviewWindow {
+    /// `None` for a window that couldn't be captured: a labelled tile.
+    pub texture: Option<gdk4::Texture>,
+    pub label: String,
+    pub bounds: Rect,
+    pub address: Address,
+}
+
+impl PreviewWindow {
+    /// Upload a capture, pre-scaled to `bounds` at the widget's `scale`
+    /// factor so the GPU only ever does a small final resample.
+    pub fn from_frame(frame: &Frame, bounds: Rect, scale: i32, address: Address) -> Self {
+        let w = ((bounds.width() * scale as f32) as u32).max(1);
+        let h = ((bounds.height() * scale as f32) as u32).max(1);
+        let pixels = downscale_bilinear(frame, w, h);
+        let texture = MemoryTexture::new(
+            w as i32,
+            h as i32,
+            MemoryFormat::B8g8r8a8Premultiplied,
+            &glib::Bytes::from_owned(pixels),
+            w as usize * 4,
+        );
+        PreviewWindow {
+            texture: Some(texture.upcast()),
+            label: String::new(),
+            bounds,
+            address,
+        }
+    }
+
+    /// Stand-in for a window the compositor wouldn't export, so it isn't
+    /// silently missing from the preview.
+    pub fn placeholder(bounds: Rect, address: Address, label: String) -> Self {
+        PreviewWindow {
+            texture: None,
+            label,
+            bounds,
+            address,
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct PreviewCanvas {
+        /// Size of the preview coordinate space the window bounds are in.
+        pub(super) size: Cell<(f32, f32)>,
+        pub(super) windows: RefCell<Vec<PreviewWindow>>,
+        pub(super) hovered: RefCell<Option<Address>>,
+        /// Frame clock time (µs) the current windows started fading in.
+        pub(super) fade_start: Cell<Option<i64>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PreviewCanvas {
+        const NAME: &'static str = "JbPreviewCanvas";
+        type Type = super::PreviewCanvas;
+        type ParentType = gtk4::Widget;
+    }
+
+    impl ObjectImpl for PreviewCanvas {}
+
+    impl WidgetImpl for PreviewCanvas {
+        fn snapshot(&self, snapshot: &gtk4::Snapshot) {
+            let widget = self.obj();
+            let (width, height) = self.size.get();
+            if width <= 0.0 || height <= 0.0 {
+                return;
+            }
+            let opacity = match (self.fade_start.get(), widget.frame_clock()) {
+                (Some(start), Some(clock)) => {
+                    ((clock.frame_time() - start) as f64 / FADE_IN_US as f64).clamp(0.0, 1.0)
+                }
+                _ => 1.0,
+            };
+
+            snapshot.save();
+            snapshot.scale(
+                widget.width() as f32 / width,
+                widget.height() as f32 / height,
+            );
+            snapshot.push_opacity(opacity);
+            // Floating windows can hang off the monitor
+            snapshot.push_clip(&Rect::new(0.0, 0.0, width, height));
+            let hovered = self.hovered.borrow();
+            let accent = accent_color(widget.upcast_ref());
+            for window in self.windows.borrow().iter() {
+                match &window.texture {
+                    Some(texture) => snapshot.append_texture(texture, &window.bounds),
+                    None => draw_placeholder(&widget, snapshot, window, accent),
+                }
+                if hovered.as_ref() == Some(&window.address) {
+                    let tint = RGBA::new(accent.red(), accent.green(), accent.blue(), 0.2);
+                    snapshot.append_color(&tint, &window.bounds);
+                    snapshot.append_border(
+                        &RoundedRect::from_rect(window.bounds, 0.0),
+                        &[2.0; 4],
+                        &[accent; 4],
+                    );
+                }
+            }
+            snapshot.pop();
+            snapshot.pop();
+            snapshot.restore();
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Workspace preview drawn from per-window textures with render nodes, so
+    /// hover highlights and the fade-in are redraws, not re-uploads.
+    pub struct PreviewCanvas(ObjectSubclass<imp::PreviewCanvas>)
+        @extends gtk4::Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget;
+}
+
+impl Default for PreviewCanvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PreviewCanvas {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Replace the windows (topmost last) and size the widget to `width` x
+    /// `height` preview pixels.
+    pub fn set_windows(&self, width: f32, height: f32, windows: Vec<PreviewWindow>) {
+        let imp = self.imp();
+        imp.size.set((width, height));
+        *imp.windows.borrow_mut() = windows;
+        self.set_size_request(width as i32, height as i32);
+        self.start_fade_in();
+        self.queue_draw();
+    }
+
+    pub fn clear(&self) {
+        let imp = self.imp();
+        imp.windows.borrow_mut().clear();
+        *imp.hovered.borrow_mut() = None;
+        imp.fade_start.set(None);
+        self.queue_draw();
+    }
+
+    /// Drop one window's thumbnail, e.g. after it was closed from the popup.
+    pub fn remove_window(&self, address: &Address) {
+        let imp = self.imp();
+        imp.windows.borrow_mut().retain(|w| &w.address != address);
+        if imp.hovered.borrow().as_ref() == Some(address) {
+            *imp.hovered.borrow_mut() = None;
+        }
+        self.queue_draw();
+    }
+
+    /// The topmost window under widget coordinates `(x, y)`.
+    pub fn window_at(&self, x: f64, y: f64) -> Option<Address> {
+        let imp = self.imp();
+        let (width, height) = imp.size.get();
+        if self.width() == 0 || self.height() == 0 {
+            return None;
+        }
+        let px = (x * width as f64 / self.width() as f64) as f32;
+        let py = (y * height as f64 / self.height() as f64) as f32;
+        imp.windows
+            .borrow()
+            .iter()
+            .rev()
+            .find(|w| w.bounds.contains_point(&gtk4::graphene::Point::new(px, py)))
+            .map(|w| w.address.clone())
+    }
+
+    /// Highlight one window's thumbnail, or none.
+    pub fn set_hovered(&self, address: Option<&Address>) {
+        let mut hovered = self.imp().hovered.borrow_mut();
+        if hovered.as_ref() != address {
+            *hovered = address.cloned();
+            drop(hovered);
+            self.queue_draw();
+        }
+    }
+
+    fn start_fade_in(&self) {
+        if crate::widgets::a11y::reduce_motion() {
+            return;
+        }
+        let Some(clock) = self.frame_clock() else {
+            return;
+        };
+        self.imp().fade_start.set(Some(clock.frame_time()));
+        self.add_tick_callback(|canvas, clock| {
+            canvas.queue_draw();
+            let started = canvas.imp().fade_start.get().unwrap_or(0);
+            if clock.frame_time() - started < FADE_IN_US {
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+    }
+}
+
+/// A dim tile with an outline and the window's label centered, shrunk to fit.
+fn draw_placeholder(
+    widget: &PreviewCanvas,
+    snapshot: &gtk4::Snapshot,
+    window: &PreviewWindow,
+    accent: RGBA,
+) {
+    let bounds = window.bounds;
+    snapshot.append_color(&RGBA::new(0.12, 0.12, 0.18, 0.9), &bounds);
+    let outline = RGBA::new(accent.red(), accent.green(), accent.blue(), 0.4);
+    snapshot.append_border(
+        &RoundedRect::from_rect(bounds, 0.0),
+        &[1.0; 4],
+        &[outline; 4],
+    );
+
+    let layout = widget.create_pango_layout(Some(&window.label));
+    let (text_w, text_h) = layout.pixel_size();
+    if text_w <= 0 || text_h <= 0 {
+        return;
+    }
+    let fit = (bounds.width() * 0.9 / text_w as f32)
+        .min(bounds.height() * 0.9 / text_h as f32)
+        .min(2.0);
+    snapshot.save();
+    snapshot.translate(&gtk4::graphene::Point::new(
+        bounds.x() + (bounds.width() - text_w as f32 * fit) / 2.0,
+        bounds.y() + (bounds.height() - text_h as f32 * fit) / 2.0,
+    ));
+    snapshot.scale(fit, fit);
+    snapshot.append_layout(&layout, &RGBA::new(0.8, 0.82, 0.9, 1.0));
+    snapshot.restore();
+}
+
+/// The stylesheet's `@accent`, for the hover outline.
+#[allow(deprecated)]
+fn accent_color(widget: &gtk4::Widget) -> RGBA {
+    widget
+        .style_context()
+        .lookup_color("accent")
+        .unwrap_or(RGBA::new(0.2, 0.8, 1.0, 1.0))
+}
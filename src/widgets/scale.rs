@@ -0,0 +1,36 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::Window;
+use gtk4_layer_shell::{Edge, LayerShell};
+use hyprland::data::Monitors;
+use hyprland::shared::HyprData;
+
+/// The scale Hyprland draws `monitor` at, fractional ones included. GDK
+/// before 4.14 only knows whole scales, rounded up, so it's the fallback for
+/// an output Hyprland doesn't list.
+pub fn monitor_scale(monitor: &Monitor) -> f64 {
+    let fractional = monitor.connector().and_then(|connector| {
+        Monitors::get()
+            .ok()?
+            .to_vec()
+            .into_iter()
+            .find(|m| m.name == connector.as_str())
+            .map(|m| m.scale as f64)
+    });
+    fractional.unwrap_or_else(|| monitor.scale_factor() as f64)
+}
+
+/// Set a layer-shell margin of `logical` pixels, moved ahead to the next one
+/// that sits on a whole device pixel of `scale` (see
+/// [`crate::core::scale::snap`]).
+pub fn set_margin(window: &Window, edge: Edge, logical: f64, scale: f64) {
+    window.set_margin(edge, crate::core::scale::snap(logical, scale));
+}
+
+/// [`monitor_scale`] of the monitor a layer-shell window is pinned to.
+pub fn window_scale(window: &Window) -> f64 {
+    window
+        .monitor()
+        .map(|monitor| monitor_scale(&monitor))
+        .unwrap_or_else(|| window.scale_factor() as f64)
+}
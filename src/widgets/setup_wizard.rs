@@ -0,0 +1,485 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, Button, CheckButton, Entry, EventControllerKey, Grid, Label, Orientation,
+    PasswordEntry, Stack, Window,
+};
+use gtk4_layer_shell::{KeyboardMode, Layer, LayerShell};
+use relm4::prelude::*;
+use std::collections::HashSet;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+
+use crate::core::setup::{self, WIDGETS};
+
+/// The stock theme, written out so it can be edited in place.
+const DEFAULT_STYLE: &str = include_str!("../../style.css");
+
+const PAGES: [&str; 5] = ["welcome", "widgets", "calendar", "summary", "finish"];
+
+/// First-run overlay, shown when there's no `config.toml`: picks bar widgets,
+/// takes the Google OAuth client file and the Cerebras key, writes the config
+/// files and restarts the shell on them. Escape or "Not now" dismisses it for
+/// good; deleting `setup-dismissed` in the data dir brings it back.
+pub struct SetupWizardModel {
+    visible: bool,
+    page: usize,
+    selected: HashSet<&'static str>,
+    credentials: String,
+    api_key: String,
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum SetupWizardInput {
+    Next,
+    Back,
+    Toggle(&'static str, bool),
+    Credentials(String),
+    ApiKey(String),
+    Skip,
+}
+
+pub struct SetupWizardWidgets {
+    overlay: Window,
+    stack: Stack,
+    back: Button,
+    next: Button,
+    finish_summary: Label,
+    error: Label,
+}
+
+impl Component for SetupWizardModel {
+    type Init = Monitor;
+    type Input = SetupWizardInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = SetupWizardWidgets;
+
+    fn init_root() -> Self::Root {
+        // Invisible root — the real UI is the overlay window
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        monitor: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let overlay = Window::new();
+        crate::widgets::a11y::name(&overlay, "jb-shell setup");
+        overlay.set_widget_name("setup-wizard");
+        overlay.init_layer_shell();
+        overlay.set_namespace(Some("jb-shell-setup"));
+        overlay.set_layer(Layer::Overlay);
+        overlay.set_exclusive_zone(-1);
+        overlay.set_keyboard_mode(KeyboardMode::Exclusive);
+        overlay.set_monitor(Some(&monitor));
+
+        let card = GtkBox::new(Orientation::Vertical, 12);
+        card.set_widget_name("setup-card");
+
+        let stack = Stack::new();
+        stack.set_transition_type(gtk4::StackTransitionType::SlideLeftRight);
+        stack.set_vhomogeneous(false);
+        stack.add_named(&welcome_page(), Some(PAGES[0]));
+        stack.add_named(&widgets_page(&sender), Some(PAGES[1]));
+        stack.add_named(&calendar_page(&sender), Some(PAGES[2]));
+        stack.add_named(&summary_page(&sender), Some(PAGES[3]));
+        let (finish_page, finish_summary) = finish_page();
+        stack.add_named(&finish_page, Some(PAGES[4]));
+        card.append(&stack);
+
+        let error = Label::new(None);
+        error.add_css_class("setup-error");
+        error.set_wrap(true);
+        error.set_xalign(0.0);
+        card.append(&error);
+
+        let buttons = GtkBox::new(Orientation::Horizontal, 8);
+        let skip = Button::with_label("Not now");
+        skip.add_css_class("setup-skip");
+        let skip_sender = sender.input_sender().clone();
+        skip.connect_clicked(move |_| skip_sender.emit(SetupWizardInput::Skip));
+        buttons.append(&skip);
+        let spacer = GtkBox::new(Orientation::Horizontal, 0);
+        spacer.set_hexpand(true);
+        buttons.append(&spacer);
+        let back = Button::with_label("Back");
+        let back_sender = sender.input_sender().clone();
+        back.connect_clicked(move |_| back_sender.emit(SetupWizardInput::Back));
+        buttons.append(&back);
+        let next = Button::with_label("Next");
+        next.add_css_class("setup-next");
+        let next_sender = sender.input_sender().clone();
+        next.connect_clicked(move |_| next_sender.emit(SetupWizardInput::Next));
+        buttons.append(&next);
+        card.append(&buttons);
+
+        overlay.set_child(Some(&card));
+
+        let key_ctl = EventControllerKey::new();
+        let key_sender = sender.input_sender().clone();
+        key_ctl.connect_key_pressed(move |_, keyval, _keycode, _state| {
+            if keyval == gdk4::Key::Escape {
+                key_sender.emit(SetupWizardInput::Skip);
+                return glib::Propagation::Stop;
+            }
+            glib::Propagation::Proceed
+        });
+        overlay.add_controller(key_ctl);
+
+        eprintln!("jb-shell: [setup] no config.toml, showing the setup wizard");
+        let model = SetupWizardModel {
+            visible: true,
+            page: 0,
+            selected: WIDGETS
+                .iter()
+                .filter(|w| w.default)
+                .map(|w| w.name)
+                .collect(),
+            credentials: String::new(),
+            api_key: String::new(),
+            error: None,
+        };
+        let mut widgets = SetupWizardWidgets {
+            overlay,
+            stack,
+            back,
+            next,
+            finish_summary,
+            error,
+        };
+        model.update_view(&mut widgets, sender);
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            SetupWizardInput::Next if self.page + 1 < PAGES.len() => {
+                self.error = self.check_page().err();
+                if self.error.is_none() {
+                    self.page += 1;
+                }
+            }
+            // The last page's button writes everything out
+            SetupWizardInput::Next => match self.write() {
+                // Only returns if the restart failed
+                Ok(()) => self.error = Some(restart()),
+                Err(e) => {
+                    eprintln!("jb-shell: [setup] {e}");
+                    self.error = Some(e);
+                }
+            },
+            SetupWizardInput::Back => {
+                self.page = self.page.saturating_sub(1);
+                self.error = None;
+            }
+            SetupWizardInput::Toggle(name, on) => {
+                if on {
+                    self.selected.insert(name);
+                } else {
+                    self.selected.remove(name);
+                }
+                return;
+            }
+            SetupWizardInput::Credentials(path) => {
+                self.credentials = path;
+                return;
+            }
+            SetupWizardInput::ApiKey(key) => {
+                self.api_key = key;
+                return;
+            }
+            SetupWizardInput::Skip => {
+                let marker = dismissed_path();
+                match std::fs::write(&marker, "") {
+                    Ok(()) => eprintln!(
+                        "jb-shell: [setup] skipped; delete {} to see the wizard again",
+                        marker.display()
+                    ),
+                    Err(e) => eprintln!("jb-shell: [setup] failed to remember the skip: {e}"),
+                }
+                self.visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
+        if !self.visible {
+            widgets.overlay.set_visible(false);
+            return;
+        }
+        widgets.stack.set_visible_child_name(PAGES[self.page]);
+        widgets.back.set_sensitive(self.page > 0);
+        let last = self.page + 1 == PAGES.len();
+        widgets.next.set_label(if last {
+            "Write config and restart"
+        } else {
+            "Next"
+        });
+        if last {
+            widgets.finish_summary.set_text(&self.describe());
+        }
+        widgets.error.set_text(self.error.as_deref().unwrap_or(""));
+        widgets.error.set_visible(self.error.is_some());
+        widgets.overlay.set_visible(true);
+    }
+}
+
+impl SetupWizardModel {
+    fn credentials_path(&self) -> Option<PathBuf> {
+        let path = self.credentials.trim();
+        if path.is_empty() {
+            return None;
+        }
+        Some(crate::config::expand_home(path))
+    }
+
+    /// Reject a page's input before moving past it.
+    fn check_page(&self) -> Result<(), String> {
+        match PAGES[self.page] {
+            "widgets" if self.selected.is_empty() => Err("Pick at least one widget".to_string()),
+            "calendar" => match self.credentials_path() {
+                Some(path) if !path.is_file() => Err(format!("No file at {}", path.display())),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// What the finish button is about to write.
+    fn describe(&self) -> String {
+        let dir = crate::config::config_dir();
+        let mut lines = vec![
+            format!(
+                "{} \u{b7} {} bar widgets",
+                dir.join("config.toml").display(),
+                self.selected.len()
+            ),
+            format!("{} \u{b7} the stock theme", dir.join("style.css").display()),
+        ];
+        if self.credentials_path().is_some() {
+            lines.push(format!(
+                "{} \u{b7} sign in from the calendar widget afterwards",
+                crate::google_calendar::credentials_path().display()
+            ));
+        }
+        if !self.api_key.trim().is_empty() {
+            lines.push(format!(
+                "Your keyring, else {} \u{b7} notification summaries",
+                dir.join("cerebras.json").display()
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Writes every file that doesn't exist yet; existing ones are kept.
+    fn write(&self) -> Result<(), String> {
+        let dir = crate::config::config_dir();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("can't create {}: {e}", dir.display()))?;
+
+        let selected: Vec<&str> = self.selected.iter().copied().collect();
+        write_new(
+            &dir.join("config.toml"),
+            &setup::render_config(&selected),
+            0o644,
+        )?;
+        write_new(&dir.join("style.css"), DEFAULT_STYLE, 0o644)?;
+        if let Some(source) = self.credentials_path() {
+            let contents = std::fs::read_to_string(&source)
+                .map_err(|e| format!("can't read {}: {e}", source.display()))?;
+            write_new(
+                &crate::google_calendar::credentials_path(),
+                &contents,
+                0o600,
+            )?;
+        }
+        let api_key = self.api_key.trim();
+        // No unlock prompt: it can't take the keyboard from this overlay
+        let in_keyring = !api_key.is_empty()
+            && crate::secrets::set(crate::secrets::CEREBRAS_API_KEY, api_key, false)
+                .inspect_err(|e| {
+                    eprintln!("jb-shell: [setup] keyring unavailable ({e}), using cerebras.json")
+                })
+                .is_ok();
+        if !api_key.is_empty() && !in_keyring {
+            write_new(
+                &dir.join("cerebras.json"),
+                &setup::render_cerebras(&self.api_key),
+                0o600,
+            )?;
+        }
+        eprintln!("jb-shell: [setup] wrote config to {}", dir.display());
+        Ok(())
+    }
+}
+
+/// Whether to run the wizard: there's no `config.toml` and it hasn't been
+/// skipped before.
+pub fn should_show() -> bool {
+    !crate::config::config_dir().join("config.toml").exists() && !dismissed_path().exists()
+}
+
+fn dismissed_path() -> PathBuf {
+    let data_dir = std::env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".into())).join(".local/share")
+        })
+        .join("jb-shell");
+    std::fs::create_dir_all(&data_dir).ok();
+    data_dir.join("setup-dismissed")
+}
+
+/// Create `path` with `contents`, leaving an existing file alone.
+fn write_new(path: &Path, contents: &str, mode: u32) -> Result<(), String> {
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(path);
+    match file {
+        Ok(mut file) => file
+            .write_all(contents.as_bytes())
+            .map_err(|e| format!("can't write {}: {e}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            eprintln!("jb-shell: [setup] keeping existing {}", path.display());
+            Ok(())
+        }
+        Err(e) => Err(format!("can't create {}: {e}", path.display())),
+    }
+}
+
+/// Config is read once per process, so start over on the new files. Returns
+/// why that failed.
+fn restart() -> String {
+    let err = match std::env::current_exe() {
+        Ok(exe) => {
+            eprintln!("jb-shell: [setup] restarting");
+            std::process::Command::new(exe)
+                .args(std::env::args_os().skip(1))
+                .exec()
+        }
+        Err(e) => e,
+    };
+    eprintln!("jb-shell: [setup] restart failed: {err}");
+    format!("Config written, but the restart failed ({err}); restart jb-shell to load it")
+}
+
+fn page(title: &str, body: &str) -> GtkBox {
+    let page = GtkBox::new(Orientation::Vertical, 8);
+    let title = Label::new(Some(title));
+    title.add_css_class("setup-title");
+    title.set_xalign(0.0);
+    page.append(&title);
+    let body = Label::new(Some(body));
+    body.add_css_class("setup-body");
+    body.set_wrap(true);
+    body.set_max_width_chars(60);
+    body.set_xalign(0.0);
+    page.append(&body);
+    page
+}
+
+fn welcome_page() -> GtkBox {
+    page(
+        "Welcome to jb-shell",
+        "There's no config.toml yet. The next few steps pick what the bar shows, \
+         connect Google Calendar and AI notification summaries, then write \
+         config.toml and style.css and restart the shell on them.\n\n\
+         Everything is optional and can be changed later in the files themselves.",
+    )
+}
+
+fn widgets_page(sender: &ComponentSender<SetupWizardModel>) -> GtkBox {
+    let page = page(
+        "Bar widgets",
+        "Ticked widgets are placed left to right; the layout can be rearranged in \
+         [bar.layout] afterwards.",
+    );
+    let grid = Grid::new();
+    grid.set_column_spacing(16);
+    grid.set_row_spacing(2);
+    let rows = WIDGETS.len().div_ceil(2);
+    for (i, widget) in WIDGETS.iter().enumerate() {
+        let check = CheckButton::with_label(widget.description);
+        check.set_active(widget.default);
+        check.set_tooltip_text(Some(widget.name));
+        let toggle_sender = sender.input_sender().clone();
+        let name = widget.name;
+        check.connect_toggled(move |check| {
+            toggle_sender.emit(SetupWizardInput::Toggle(name, check.is_active()));
+        });
+        grid.attach(&check, (i / rows) as i32, (i % rows) as i32, 1, 1);
+    }
+    page.append(&grid);
+    page
+}
+
+fn calendar_page(sender: &ComponentSender<SetupWizardModel>) -> GtkBox {
+    let page = page(
+        "Google Calendar",
+        "Meeting alerts and the calendar widget need an OAuth client of type \
+         \"Desktop app\" from the Google Cloud console (APIs & Services \u{2192} \
+         Credentials), with the Calendar API enabled. Download its JSON and enter \
+         the path below; you'll sign in from the calendar widget once the shell \
+         restarts. Leave it empty to skip.",
+    );
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("~/Downloads/client_secret_….json"));
+    let entry_sender = sender.input_sender().clone();
+    entry.connect_changed(move |entry| {
+        entry_sender.emit(SetupWizardInput::Credentials(entry.text().to_string()));
+    });
+    let activate_sender = sender.input_sender().clone();
+    entry.connect_activate(move |_| activate_sender.emit(SetupWizardInput::Next));
+    page.append(&entry);
+    page
+}
+
+fn summary_page(sender: &ComponentSender<SetupWizardModel>) -> GtkBox {
+    let page = page(
+        "Notification summaries",
+        "The notification center can summarize what you missed with a Cerebras-hosted \
+         model. Paste an API key from cloud.cerebras.ai to turn it on, or leave it \
+         empty to skip. It's stored in your keyring, or readable only by you \
+         without one.",
+    );
+    let entry = PasswordEntry::new();
+    entry.set_show_peek_icon(true);
+    let entry_sender = sender.input_sender().clone();
+    entry.connect_changed(move |entry| {
+        entry_sender.emit(SetupWizardInput::ApiKey(entry.text().to_string()));
+    });
+    let activate_sender = sender.input_sender().clone();
+    entry.connect_activate(move |_| activate_sender.emit(SetupWizardInput::Next));
+    page.append(&entry);
+    page
+}
+
+fn finish_page() -> (GtkBox, Label) {
+    let page = page(
+        "Ready",
+        "These files will be written; any that already exist are left alone:",
+    );
+    let summary = Label::new(None);
+    summary.add_css_class("setup-files");
+    summary.set_xalign(0.0);
+    summary.set_selectable(true);
+    page.append(&summary);
+    (page, summary)
+}
@@ -6,12 +6,16 @@ use relm4::prelude::*;
 use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::mpsc;
 use std::time::Duration;
 
+use crate::shell::SwitcherHandle;
+use crate::widgets::tooltip::Tooltip;
+
 pub trait SwitcherProvider: 'static {
     const WIDGET_NAME: &'static str;
+    /// What's being switched, as screen readers announce the trigger.
+    const DESCRIPTION: &'static str;
     const TRIGGER_NAME: &'static str;
     const POPUP_NAME: &'static str;
     const MENU_ITEM_NAME: &'static str;
@@ -29,15 +33,45 @@ pub trait SwitcherProvider: 'static {
     fn switch(name: &str);
 }
 
+/// Runs provider `P`'s single poller, however many bars show it: publishes
+/// poll results when they change, and performs switches sent on the returned
+/// channel, re-polling right after so every bar sees the new item at once.
+pub fn spawn_switcher_thread<P: SwitcherProvider>(
+    publish: impl Fn(String, Vec<String>) + Send + 'static,
+) -> mpsc::Sender<String> {
+    let (tx, rx) = mpsc::channel::<String>();
+    std::thread::spawn(move || {
+        let mut last: Option<(String, Vec<String>)> = None;
+        loop {
+            let result = P::poll();
+            if last.as_ref() != Some(&result) {
+                publish(result.0.clone(), result.1.clone());
+                last = Some(result);
+            }
+            match rx.recv_timeout(crate::idle::interval(P::POLL_INTERVAL)) {
+                Ok(name) => P::switch(&name),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+    tx
+}
+
 pub struct SwitcherModel<P: SwitcherProvider> {
     current: String,
     items: Vec<String>,
     popup_visible: bool,
-    alive: Arc<AtomicBool>,
+    switch_tx: mpsc::Sender<String>,
     _phantom: PhantomData<P>,
 }
 
-#[derive(Debug)]
+pub struct SwitcherInit {
+    pub monitor: Monitor,
+    pub handle: SwitcherHandle,
+}
+
+#[derive(Debug, Clone)]
 pub enum SwitcherInput {
     PollResult { current: String, items: Vec<String> },
     SwitchItem(String),
@@ -53,10 +87,11 @@ pub struct SwitcherWidgets {
     popup: Window,
     popup_box: GtkBox,
     close_timer: Rc<RefCell<Option<glib::SourceId>>>,
+    tooltip: Tooltip,
 }
 
 impl<P: SwitcherProvider> Component for SwitcherModel<P> {
-    type Init = Monitor;
+    type Init = SwitcherInit;
     type Input = SwitcherInput;
     type Output = ();
     type CommandOutput = ();
@@ -71,10 +106,11 @@ impl<P: SwitcherProvider> Component for SwitcherModel<P> {
     }
 
     fn init(
-        monitor: Self::Init,
+        init: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
+        let SwitcherInit { monitor, handle } = init;
         // Trigger button
         let trigger_box = GtkBox::new(Orientation::Horizontal, 4);
         let icon_label = Label::new(Some(P::ICON));
@@ -86,6 +122,7 @@ impl<P: SwitcherProvider> Component for SwitcherModel<P> {
 
         let trigger = Button::new();
         trigger.set_widget_name(P::TRIGGER_NAME);
+        crate::widgets::a11y::name(&trigger, P::DESCRIPTION);
         trigger.set_child(Some(&trigger_box));
         root.append(&trigger);
 
@@ -123,35 +160,26 @@ impl<P: SwitcherProvider> Component for SwitcherModel<P> {
         });
         popup.add_controller(focus);
 
-        // Background polling thread with shutdown flag
-        let alive = Arc::new(AtomicBool::new(true));
-        let thread_alive = alive.clone();
-        let input_sender = sender.input_sender().clone();
-        std::thread::spawn(move || {
-            while thread_alive.load(Ordering::Relaxed) {
-                let (current, items) = P::poll();
-                if !thread_alive.load(Ordering::Relaxed) {
-                    break;
-                }
-                input_sender.emit(SwitcherInput::PollResult { current, items });
-                std::thread::sleep(P::POLL_INTERVAL);
-            }
-        });
+        // Shared poller → this bar
+        handle.subscribers.subscribe(sender.input_sender().clone());
 
         let model = SwitcherModel {
             current: String::new(),
             items: Vec::new(),
             popup_visible: false,
-            alive,
+            switch_tx: handle.thread_tx,
             _phantom: PhantomData,
         };
         let close_timer = Rc::new(RefCell::new(None));
+        // Full name on hover, since the label is truncated
+        let tooltip = Tooltip::attach(&trigger);
         let widgets = SwitcherWidgets {
             item_label,
             trigger,
             popup,
             popup_box,
             close_timer,
+            tooltip,
         };
         ComponentParts { model, widgets }
     }
@@ -187,9 +215,7 @@ impl<P: SwitcherProvider> Component for SwitcherModel<P> {
                 SwitcherInput::SwitchItem(name) => {
                     self.current = name.clone();
                     self.popup_visible = false;
-                    std::thread::spawn(move || {
-                        P::switch(&name);
-                    });
+                    let _ = self.switch_tx.send(name);
                 }
                 SwitcherInput::TogglePopup => {
                     self.popup_visible = !self.popup_visible;
@@ -210,8 +236,13 @@ impl<P: SwitcherProvider> Component for SwitcherModel<P> {
         } else {
             truncate_middle(&self.current, P::MAX_LABEL_LEN)
         });
+        widgets
+            .tooltip
+            .set_markup(&glib::markup_escape_text(&self.current));
 
         if self.popup_visible {
+            widgets.tooltip.hide();
+
             // Rebuild menu items
             while let Some(child) = widgets.popup_box.first_child() {
                 widgets.popup_box.remove(&child);
@@ -245,13 +276,7 @@ impl<P: SwitcherProvider> Component for SwitcherModel<P> {
     }
 }
 
-impl<P: SwitcherProvider> Drop for SwitcherModel<P> {
-    fn drop(&mut self) {
-        self.alive.store(false, Ordering::Relaxed);
-    }
-}
-
-fn cancel_close_timer(timer: &Rc<RefCell<Option<glib::SourceId>>>) {
+pub(crate) fn cancel_close_timer(timer: &Rc<RefCell<Option<glib::SourceId>>>) {
     if let Some(id) = timer.borrow_mut().take() {
         id.remove();
     }
@@ -277,22 +302,99 @@ pub fn truncate_middle(name: &str, max_len: usize) -> String {
     format!("{}...{}", &name[..start_end], &name[tail_start..])
 }
 
-fn position_popup(popup: &Window, trigger: &Button) {
+pub(crate) fn position_popup(popup: &Window, trigger: &Button) {
+    position_popup_at_least(popup, trigger, 200);
+}
+
+/// Drop `popup` below `trigger`, kept on screen as if it were at least
+/// `min_width` wide. Margins land on whole device pixels of the popup's
+/// monitor, so fractional scales don't blur it.
+pub(crate) fn position_popup_at_least(popup: &Window, trigger: &Button, min_width: i32) {
     let Some(root) = trigger.root() else {
         popup.set_margin(Edge::Top, 32);
         return;
     };
 
     if let Some(bounds) = trigger.compute_bounds(root.upcast_ref::<gtk4::Widget>()) {
-        popup.set_margin(Edge::Top, (bounds.y() + bounds.height()) as i32);
+        let scale = crate::widgets::scale::window_scale(popup);
+        // A floating bar is inset by its margin; keep the same gap below it
+        let inset = crate::config::get().bar.edge_margin();
+        let top = (bounds.y() + bounds.height()) as f64 + 2.0 * inset as f64;
+        crate::widgets::scale::set_margin(popup, Edge::Top, top, scale);
 
         let screen_w = root.width();
         let (_, popup_natural, _, _) = popup.measure(gtk4::Orientation::Horizontal, -1);
-        let popup_w = popup_natural.max(200);
-        let left = (bounds.x() as i32).min(screen_w - popup_w).max(0);
-        popup.set_margin(Edge::Left, left);
+        let popup_w = popup_natural.max(min_width);
+        let left = (bounds.x() as f64)
+            .min((screen_w - popup_w) as f64)
+            .max(0.0);
+        crate::widgets::scale::set_margin(popup, Edge::Left, left + inset as f64, scale);
     } else {
         popup.set_margin(Edge::Top, 32);
         popup.set_margin(Edge::Left, 0);
     }
 }
+
+/// A bar widget's dropdown: a layer-shell overlay on the bar's monitor that
+/// calls `on_hide` half a second after losing focus, unless focus comes back.
+pub(crate) struct BarPopup {
+    pub window: Window,
+    close_timer: Rc<RefCell<Option<glib::SourceId>>>,
+}
+
+impl BarPopup {
+    pub fn new(
+        name: &str,
+        label: &str,
+        monitor: &Monitor,
+        child: &impl IsA<gtk4::Widget>,
+        on_hide: impl Fn() + 'static,
+    ) -> Self {
+        let window = Window::new();
+        crate::widgets::a11y::name(&window, label);
+        window.set_widget_name(name);
+        window.init_layer_shell();
+        window.set_layer(Layer::Overlay);
+        window.set_exclusive_zone(-1);
+        window.set_anchor(Edge::Top, true);
+        window.set_anchor(Edge::Left, true);
+        window.set_keyboard_mode(KeyboardMode::OnDemand);
+        window.set_monitor(Some(monitor));
+        window.set_child(Some(child));
+        window.set_visible(false);
+
+        let close_timer: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let focus = EventControllerFocus::new();
+        let leave_timer = close_timer.clone();
+        let on_hide = Rc::new(on_hide);
+        focus.connect_leave(move |_| {
+            cancel_close_timer(&leave_timer);
+            let timer_ref = leave_timer.clone();
+            let on_hide = on_hide.clone();
+            let id = glib::timeout_add_local_once(Duration::from_millis(500), move || {
+                *timer_ref.borrow_mut() = None;
+                on_hide();
+            });
+            *leave_timer.borrow_mut() = Some(id);
+        });
+        let enter_timer = close_timer.clone();
+        focus.connect_enter(move |_| cancel_close_timer(&enter_timer));
+        window.add_controller(focus);
+
+        Self {
+            window,
+            close_timer,
+        }
+    }
+
+    /// Drop the popup below `trigger` and show it.
+    pub fn show(&self, trigger: &Button) {
+        position_popup(&self.window, trigger);
+        self.window.set_visible(true);
+    }
+
+    pub fn hide(&self) {
+        cancel_close_timer(&self.close_timer);
+        self.window.set_visible(false);
+    }
+}
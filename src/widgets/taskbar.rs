@@ -0,0 +1,47 @@
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Orientation};
+
+use crate::core::window_focus::FocusQuery;
+
+/// `[taskbar] pinned` apps as icon buttons. A click raises the app's window
+/// through `window_focus`, or launches the app when it has none.
+pub fn build() -> GtkBox {
+    let container = GtkBox::new(Orientation::Horizontal, 2);
+    container.set_widget_name("taskbar");
+    for id in &crate::config::get().taskbar.pinned {
+        let id = id.trim_end_matches(".desktop").to_string();
+        let Some(info) = gio::DesktopAppInfo::new(&format!("{id}.desktop")) else {
+            eprintln!("jb-shell: [taskbar] no desktop entry {id}");
+            continue;
+        };
+        let name = info.display_name().to_string();
+
+        let button = Button::new();
+        button.add_css_class("taskbar-app");
+        match crate::app_icons::image(Some(&id), &name, 18) {
+            Some(image) => button.set_child(Some(&image)),
+            None => button.set_label(&name),
+        }
+        crate::widgets::a11y::name(&button, &name);
+        button.set_tooltip_text(Some(&name));
+        button.connect_clicked(move |_| raise_or_launch(&id, &info));
+        container.append(&button);
+    }
+    container
+}
+
+fn raise_or_launch(id: &str, info: &gio::DesktopAppInfo) {
+    let query = FocusQuery {
+        desktop_entry: Some(id.to_string()),
+        class_hints: vec![info.display_name().to_string()],
+        ..Default::default()
+    };
+    if crate::window_focus::focus(query.clone()) {
+        return;
+    }
+    crate::activation::expect(query);
+    let context = gdk4::Display::default().map(|d| d.app_launch_context());
+    if let Err(e) = info.launch(&[], context.as_ref()) {
+        eprintln!("jb-shell: [taskbar] failed to launch {id}: {e}");
+    }
+}
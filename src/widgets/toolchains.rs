@@ -0,0 +1,163 @@
+use std::process::Command;
+use std::time::Duration;
+
+use super::switcher::{SwitcherModel, SwitcherProvider};
+use crate::config::NodeManager;
+use crate::core::toolchains::{
+    parse_fnm_list, parse_nvm_ls, parse_rustup_toolchains, parse_terraform_workspaces,
+    parse_tfenv_list,
+};
+
+/// Stdout of a successful run, or empty when the tool is missing or fails.
+fn stdout_of(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+/// nvm is a shell function, so each call sources it first.
+fn nvm(command: &str, arg: Option<&str>) -> String {
+    let mut args = vec![
+        "-c",
+        ". \"${NVM_DIR:-$HOME/.nvm}/nvm.sh\" && nvm \"$@\"",
+        "nvm",
+    ];
+    args.extend(command.split(' '));
+    args.extend(arg);
+    stdout_of("bash", &args)
+}
+
+/// Terraform versions installed with tfenv.
+pub struct TfenvProvider;
+
+impl SwitcherProvider for TfenvProvider {
+    const WIDGET_NAME: &'static str = "terraform-version";
+    const DESCRIPTION: &'static str = "Terraform version";
+    const TRIGGER_NAME: &'static str = "terraform-trigger";
+    const POPUP_NAME: &'static str = "terraform-popup";
+    const MENU_ITEM_NAME: &'static str = "toolchain-menu-item";
+    const MENU_BOX_NAME: &'static str = "terraform-menu";
+    const ICON: &'static str = "\u{e69a}";
+    const ICON_CSS_CLASSES: &'static [&'static str] = &["toolchain-icon"];
+    const FALLBACK_LABEL: &'static str = "no terraform";
+    const MAX_LABEL_LEN: usize = 12;
+    const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+    fn poll() -> (String, Vec<String>) {
+        parse_tfenv_list(&stdout_of("tfenv", &["list"]))
+    }
+
+    fn switch(name: &str) {
+        let _ = Command::new("tfenv").args(["use", name]).output();
+    }
+}
+
+pub type TfenvModel = SwitcherModel<TfenvProvider>;
+
+/// Terraform workspace of `[switchers] terraform_dir`.
+pub struct TerraformWorkspaceProvider;
+
+impl TerraformWorkspaceProvider {
+    fn terraform(args: &[&str]) -> Option<String> {
+        let dir = crate::config::get().switchers.terraform_dir.as_deref()?;
+        let output = Command::new("terraform")
+            .args(args)
+            .current_dir(crate::config::expand_home(dir))
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl SwitcherProvider for TerraformWorkspaceProvider {
+    const WIDGET_NAME: &'static str = "terraform-workspace";
+    const DESCRIPTION: &'static str = "Terraform workspace";
+    const TRIGGER_NAME: &'static str = "terraform-workspace-trigger";
+    const POPUP_NAME: &'static str = "terraform-workspace-popup";
+    const MENU_ITEM_NAME: &'static str = "toolchain-menu-item";
+    const MENU_BOX_NAME: &'static str = "terraform-workspace-menu";
+    const ICON: &'static str = "\u{e69a}";
+    const ICON_CSS_CLASSES: &'static [&'static str] = &["toolchain-icon"];
+    const FALLBACK_LABEL: &'static str = "no workspace";
+    const MAX_LABEL_LEN: usize = 16;
+    const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+    fn poll() -> (String, Vec<String>) {
+        parse_terraform_workspaces(&Self::terraform(&["workspace", "list"]).unwrap_or_default())
+    }
+
+    fn switch(name: &str) {
+        Self::terraform(&["workspace", "select", name]);
+    }
+}
+
+pub type TerraformWorkspaceModel = SwitcherModel<TerraformWorkspaceProvider>;
+
+/// Default Node version of fnm or nvm, per `[switchers] node`.
+pub struct NodeProvider;
+
+impl SwitcherProvider for NodeProvider {
+    const WIDGET_NAME: &'static str = "node-version";
+    const DESCRIPTION: &'static str = "Node version";
+    const TRIGGER_NAME: &'static str = "node-trigger";
+    const POPUP_NAME: &'static str = "node-popup";
+    const MENU_ITEM_NAME: &'static str = "toolchain-menu-item";
+    const MENU_BOX_NAME: &'static str = "node-menu";
+    const ICON: &'static str = "\u{e718}";
+    const ICON_CSS_CLASSES: &'static [&'static str] = &["toolchain-icon"];
+    const FALLBACK_LABEL: &'static str = "no node";
+    const MAX_LABEL_LEN: usize = 12;
+    const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+    fn poll() -> (String, Vec<String>) {
+        match crate::config::get().switchers.node {
+            NodeManager::Fnm => parse_fnm_list(&stdout_of("fnm", &["list"])),
+            NodeManager::Nvm => parse_nvm_ls(&nvm("ls --no-colors", None)),
+        }
+    }
+
+    fn switch(name: &str) {
+        match crate::config::get().switchers.node {
+            NodeManager::Fnm => {
+                let _ = Command::new("fnm").args(["default", name]).output();
+            }
+            NodeManager::Nvm => {
+                nvm("alias default", Some(name));
+            }
+        }
+    }
+}
+
+pub type NodeModel = SwitcherModel<NodeProvider>;
+
+/// rustup's default toolchain.
+pub struct RustupProvider;
+
+impl SwitcherProvider for RustupProvider {
+    const WIDGET_NAME: &'static str = "rust-toolchain";
+    const DESCRIPTION: &'static str = "Rust toolchain";
+    const TRIGGER_NAME: &'static str = "rust-trigger";
+    const POPUP_NAME: &'static str = "rust-popup";
+    const MENU_ITEM_NAME: &'static str = "toolchain-menu-item";
+    const MENU_BOX_NAME: &'static str = "rust-menu";
+    const ICON: &'static str = "\u{e7a8}";
+    const ICON_CSS_CLASSES: &'static [&'static str] = &["toolchain-icon"];
+    const FALLBACK_LABEL: &'static str = "no toolchain";
+    const MAX_LABEL_LEN: usize = 20;
+    const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+    fn poll() -> (String, Vec<String>) {
+        parse_rustup_toolchains(&stdout_of("rustup", &["toolchain", "list"]))
+    }
+
+    fn switch(name: &str) {
+        let _ = Command::new("rustup").args(["default", name]).output();
+    }
+}
+
+pub type RustupModel = SwitcherModel<RustupProvider>;
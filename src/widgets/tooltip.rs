@@ -0,0 +1,132 @@
+use gtk4::prelude::*;
+use gtk4::{Label, Widget, Window};
+use gtk4_layer_shell::{Edge, Layer, LayerShell};
+use relm4::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Rich hover tooltip for a bar widget. Shows Pango markup in a layer-shell
+/// popup positioned under the trigger like the bar's other popups, after the
+/// `[tooltips] delay_ms` hover delay.
+#[derive(Clone)]
+pub struct Tooltip {
+    inner: Rc<TooltipInner>,
+}
+
+struct TooltipInner {
+    window: Window,
+    label: Label,
+    trigger: Widget,
+    markup: RefCell<String>,
+    show_timer: RefCell<Option<glib::SourceId>>,
+}
+
+impl Tooltip {
+    pub fn attach(trigger: &impl IsA<Widget>) -> Self {
+        let window = Window::new();
+        window.set_widget_name("bar-tooltip");
+        window.init_layer_shell();
+        window.set_layer(Layer::Overlay);
+        window.set_exclusive_zone(-1);
+        window.set_anchor(Edge::Top, true);
+        window.set_anchor(Edge::Left, true);
+        window.set_can_target(false);
+
+        let label = Label::new(None);
+        label.set_halign(gtk4::Align::Start);
+        label.set_xalign(0.0);
+        window.set_child(Some(&label));
+        window.set_visible(false);
+
+        let tooltip = Tooltip {
+            inner: Rc::new(TooltipInner {
+                window,
+                label,
+                trigger: trigger.clone().upcast(),
+                markup: RefCell::new(String::new()),
+                show_timer: RefCell::new(None),
+            }),
+        };
+
+        let motion = gtk4::EventControllerMotion::new();
+        let enter_ref = tooltip.clone();
+        motion.connect_enter(move |_, _, _| enter_ref.schedule_show());
+        let leave_ref = tooltip.clone();
+        motion.connect_leave(move |_| leave_ref.hide());
+        trigger.add_controller(motion);
+
+        tooltip
+    }
+
+    /// Replace the tooltip content. An empty string disables the tooltip.
+    pub fn set_markup(&self, markup: &str) {
+        if *self.inner.markup.borrow() == markup {
+            return;
+        }
+        *self.inner.markup.borrow_mut() = markup.to_string();
+        // Screen readers never see this popup; they get its text on the trigger
+        if markup.is_empty() {
+            self.hide();
+            crate::widgets::a11y::describe(&self.inner.trigger, "");
+        } else {
+            self.inner.label.set_markup(markup);
+            crate::widgets::a11y::describe(&self.inner.trigger, &self.inner.label.text());
+        }
+    }
+
+    /// Hide immediately, e.g. when the trigger opens its own popup.
+    pub fn hide(&self) {
+        if let Some(id) = self.inner.show_timer.borrow_mut().take() {
+            id.remove();
+        }
+        self.inner.window.set_visible(false);
+    }
+
+    fn schedule_show(&self) {
+        self.hide();
+        let delay = Duration::from_millis(crate::config::get().tooltips.delay_ms);
+        let show_ref = self.clone();
+        let id = glib::timeout_add_local_once(delay, move || {
+            *show_ref.inner.show_timer.borrow_mut() = None;
+            show_ref.show();
+        });
+        *self.inner.show_timer.borrow_mut() = Some(id);
+    }
+
+    fn show(&self) {
+        let inner = &self.inner;
+        if inner.markup.borrow().is_empty() {
+            return;
+        }
+        let Some(root) = inner.trigger.root() else {
+            return;
+        };
+        // Follow the bar the trigger lives on
+        if let Some(monitor) = inner
+            .trigger
+            .native()
+            .and_then(|native| native.surface())
+            .and_then(|surface| surface.display().monitor_at_surface(&surface))
+        {
+            inner.window.set_monitor(Some(&monitor));
+        }
+        if let Some(bounds) = inner
+            .trigger
+            .compute_bounds(root.upcast_ref::<gtk4::Widget>())
+        {
+            let scale = crate::widgets::scale::window_scale(&inner.window);
+            let inset = crate::config::get().bar.edge_margin() as f64;
+            let top = (bounds.y() + bounds.height()) as f64 + 2.0 * inset + 4.0;
+            crate::widgets::scale::set_margin(&inner.window, Edge::Top, top, scale);
+
+            let screen_w = root.width();
+            let (_, natural, _, _) = inner.window.measure(gtk4::Orientation::Horizontal, -1);
+            let left = (bounds.x() as f64)
+                .min((screen_w - natural) as f64)
+                .max(0.0);
+            crate::widgets::scale::set_margin(&inner.window, Edge::Left, left + inset, scale);
+        }
+        inner.window.set_visible(true);
+    }
+}
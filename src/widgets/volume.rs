@@ -1,26 +1,41 @@
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Image, Label, Orientation};
+use gtk4::{
+    Box as GtkBox, EventControllerScroll, EventControllerScrollFlags, Image, Label, Orientation,
+};
 use relm4::prelude::*;
 use std::process::Command;
 use std::time::Duration;
 
+use crate::actions::{Action, Builtin};
+use crate::core::audio::{is_boosted, step_volume_args, ActiveOutput, PortKind};
+use crate::shell::Subscribers;
+use crate::widgets::context_menu::MenuItem;
+use crate::widgets::tooltip::Tooltip;
+
+const SINK: &str = "@DEFAULT_AUDIO_SINK@";
+
 pub struct VolumeModel {
     volume: u32,
     muted: bool,
+    output: Option<ActiveOutput>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum VolumeInput {
     PollResult(u32, bool),
+    /// The default sink's port, from the shell's audio port thread.
+    Output(Option<ActiveOutput>),
 }
 
 pub struct VolumeWidgets {
+    root: GtkBox,
     icon: Image,
     label: Label,
+    tooltip: Tooltip,
 }
 
 impl SimpleComponent for VolumeModel {
-    type Init = ();
+    type Init = Subscribers<VolumeInput>;
     type Input = VolumeInput;
     type Output = ();
     type Root = GtkBox;
@@ -29,11 +44,12 @@ impl SimpleComponent for VolumeModel {
     fn init_root() -> Self::Root {
         let b = GtkBox::new(Orientation::Horizontal, 4);
         b.set_widget_name("volume");
+        crate::widgets::a11y::name(&b, "Volume");
         b
     }
 
     fn init(
-        _init: Self::Init,
+        outputs: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -44,10 +60,47 @@ impl SimpleComponent for VolumeModel {
         root.append(&icon);
         root.append(&label);
 
+        outputs.subscribe(sender.input_sender().clone());
+        crate::widgets::context_menu::attach(&root, || {
+            vec![
+                MenuItem::action("Mute or unmute", || {
+                    crate::actions::run(&Action::Builtin(Builtin::VolumeMute));
+                }),
+                MenuItem::action("Volume mixer", || {
+                    let mixer = &crate::config::get().context_menu.mixer;
+                    crate::actions::run(&Action::Command(mixer.clone()));
+                }),
+            ]
+        });
+
+        // Scrolling steps within `[volume] max_percent`; `[actions.volume]`
+        // scroll actions run first and stop it. DISCRETE has GTK add up
+        // touchpad deltas into whole notches, one step each
+        let scroll = EventControllerScroll::new(
+            EventControllerScrollFlags::VERTICAL | EventControllerScrollFlags::DISCRETE,
+        );
+        let scroll_sender = sender.input_sender().clone();
+        scroll.connect_scroll(move |_, _, dy| {
+            if dy == 0.0 {
+                return glib::Propagation::Proceed;
+            }
+            let up = dy < 0.0;
+            let step = crate::config::get().volume.step;
+            let scroll_sender = scroll_sender.clone();
+            std::thread::spawn(move || {
+                step_volume(step, up);
+                let (volume, muted) = get_volume(SINK);
+                scroll_sender.emit(VolumeInput::PollResult(volume, muted));
+            });
+            glib::Propagation::Stop
+        });
+        root.add_controller(scroll);
+
         // Background polling thread
         let input_sender = sender.input_sender().clone();
         std::thread::spawn(move || loop {
-            let result = get_volume();
+            crate::metrics::wakeup();
+            let result = get_volume(SINK);
             input_sender.emit(VolumeInput::PollResult(result.0, result.1));
             std::thread::sleep(Duration::from_secs(1));
         });
@@ -55,8 +108,15 @@ impl SimpleComponent for VolumeModel {
         let model = VolumeModel {
             volume: 0,
             muted: false,
+            output: None,
+        };
+        let tooltip = Tooltip::attach(&root);
+        let widgets = VolumeWidgets {
+            root: root.clone(),
+            icon,
+            label,
+            tooltip,
         };
-        let widgets = VolumeWidgets { icon, label };
         ComponentParts { model, widgets }
     }
 
@@ -66,6 +126,7 @@ impl SimpleComponent for VolumeModel {
                 self.volume = volume;
                 self.muted = muted;
             }
+            VolumeInput::Output(output) => self.output = output,
         }
     }
 
@@ -81,13 +142,62 @@ impl SimpleComponent for VolumeModel {
         };
         widgets.icon.set_icon_name(Some(icon_name));
         widgets.label.set_label(&format!("{}%", self.volume));
+        if is_boosted(self.volume) {
+            widgets.root.add_css_class("boosted");
+        } else {
+            widgets.root.remove_css_class("boosted");
+        }
+
+        let mut markup = if self.muted {
+            format!("<b>Volume {}% (muted)</b>", self.volume)
+        } else if is_boosted(self.volume) {
+            format!("<b>Volume {}% (boosted)</b>", self.volume)
+        } else {
+            format!("<b>Volume {}%</b>", self.volume)
+        };
+        if let Some(output) = &self.output {
+            markup.push_str(&format!(
+                "\n{} \u{b7} {}",
+                glib::markup_escape_text(&crate::audio_ports::output_name(output)),
+                glib::markup_escape_text(&output.sink_description)
+            ));
+        }
+        widgets.tooltip.set_markup(&markup);
+    }
+}
+
+/// Toast icon for the kind of output now playing.
+pub fn output_icon(kind: PortKind) -> &'static str {
+    match kind {
+        PortKind::Speakers => "audio-speakers-symbolic",
+        PortKind::Headphones => "audio-headphones-symbolic",
+        PortKind::Hdmi => "video-display-symbolic",
+        PortKind::Bluetooth => "bluetooth-symbolic",
+        PortKind::Other => "audio-card-symbolic",
+    }
+}
+
+/// Step the default sink `step` percent up or down, never past
+/// `[volume] max_percent`. Blocks on `wpctl`.
+pub fn step_volume(step: u32, up: bool) {
+    let max = crate::config::get().volume.max_percent;
+    let args = step_volume_args(SINK, step, up, max);
+    match Command::new("wpctl").args(&args).status() {
+        Ok(status) if !status.success() => {
+            eprintln!(
+                "jb-shell: [volume] wpctl {} exited with {status}",
+                args.join(" ")
+            );
+        }
+        Err(e) => eprintln!("jb-shell: [volume] failed to run wpctl: {e}"),
+        _ => {}
     }
 }
 
-fn get_volume() -> (u32, bool) {
-    let output = Command::new("wpctl")
-        .args(["get-volume", "@DEFAULT_AUDIO_SINK@"])
-        .output();
+/// Volume percent and mute state of a PipeWire node, e.g.
+/// `@DEFAULT_AUDIO_SINK@`.
+pub fn get_volume(node: &str) -> (u32, bool) {
+    let output = Command::new("wpctl").args(["get-volume", node]).output();
 
     match output {
         Ok(out) => {
@@ -0,0 +1,291 @@
+use gdk4::Monitor;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Orientation, Picture, Window};
+use gtk4_layer_shell::{Edge, Layer, LayerShell};
+use hyprland::hyprpaper;
+use relm4::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::{expand_home, WallpaperBackend};
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "bmp"];
+
+thread_local! {
+    /// (image, width) -> scaled-down texture for the launcher's `wp` results.
+    static THUMBNAILS: RefCell<HashMap<(PathBuf, i32), gdk4::Texture>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Per-monitor wallpapers via hyprpaper or our own background surfaces, with
+/// an optional slideshow. Choices made from the launcher persist across
+/// restarts in `$XDG_DATA_HOME/jb-shell/wallpaper.json`.
+pub struct WallpaperModel {
+    /// Connector name -> image; the `""` entry covers every other monitor.
+    current: HashMap<String, PathBuf>,
+    monitors: Vec<Monitor>,
+    /// Builtin backend only: one background window per monitor.
+    surfaces: Vec<(Monitor, Window, Picture)>,
+}
+
+#[derive(Debug)]
+pub enum WallpaperInput {
+    /// The set of monitors changed (startup, hotplug).
+    SetMonitors(Vec<Monitor>),
+    /// Set an image on one monitor (by connector) or, with `None`, all of them.
+    Set(Option<String>, PathBuf),
+    /// Slideshow tick: advance every monitor's wallpaper to the next image.
+    Next,
+}
+
+impl SimpleComponent for WallpaperModel {
+    type Init = ();
+    type Input = WallpaperInput;
+    type Output = ();
+    type Root = GtkBox;
+    type Widgets = ();
+
+    fn init_root() -> Self::Root {
+        // Invisible root — the real UI is the background surfaces
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        _init: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let config = &crate::config::get().wallpaper;
+
+        let mut current: HashMap<String, PathBuf> = config
+            .monitors
+            .iter()
+            .map(|(name, path)| (name.clone(), expand_home(path)))
+            .collect();
+        if let Some(path) = &config.path {
+            current.insert(String::new(), expand_home(path));
+        }
+        // Picks from the launcher win over config.toml
+        current.extend(load_state());
+
+        if config.backend != WallpaperBackend::Off && config.slideshow_minutes > 0 {
+            let next_sender = sender.input_sender().clone();
+            glib::timeout_add_local(
+                Duration::from_secs(config.slideshow_minutes as u64 * 60),
+                move || {
+                    next_sender.emit(WallpaperInput::Next);
+                    glib::ControlFlow::Continue
+                },
+            );
+        }
+
+        let model = WallpaperModel {
+            current,
+            monitors: Vec::new(),
+            surfaces: Vec::new(),
+        };
+        ComponentParts { model, widgets: () }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            WallpaperInput::SetMonitors(monitors) => {
+                self.monitors = monitors;
+            }
+            WallpaperInput::Set(monitor, path) => {
+                match monitor {
+                    Some(name) => {
+                        self.current.insert(name, path);
+                    }
+                    None => {
+                        self.current.clear();
+                        self.current.insert(String::new(), path);
+                    }
+                }
+                save_state(&self.current);
+            }
+            WallpaperInput::Next => {
+                let images = list_images();
+                if images.is_empty() {
+                    return;
+                }
+                if self.current.is_empty() {
+                    self.current.insert(String::new(), images[0].clone());
+                } else {
+                    // Each monitor's pick moves on from where it is
+                    for path in self.current.values_mut() {
+                        let next = images
+                            .iter()
+                            .position(|p| p == path)
+                            .map(|i| (i + 1) % images.len())
+                            .unwrap_or(0);
+                        *path = images[next].clone();
+                    }
+                }
+            }
+        }
+        self.apply();
+    }
+}
+
+impl WallpaperModel {
+    fn wallpaper_for(&self, monitor: &Monitor) -> Option<&PathBuf> {
+        monitor
+            .connector()
+            .and_then(|name| self.current.get(name.as_str()))
+            .or_else(|| self.current.get(""))
+    }
+
+    fn apply(&mut self) {
+        match crate::config::get().wallpaper.backend {
+            WallpaperBackend::Off => {}
+            WallpaperBackend::Hyprpaper => {
+                let assignments: Vec<(String, PathBuf)> = self
+                    .monitors
+                    .iter()
+                    .filter_map(|m| {
+                        let name = m.connector()?.to_string();
+                        Some((name, self.wallpaper_for(m)?.clone()))
+                    })
+                    .collect();
+                std::thread::spawn(move || set_hyprpaper(&assignments));
+            }
+            WallpaperBackend::Builtin => self.apply_builtin(),
+        }
+    }
+
+    fn apply_builtin(&mut self) {
+        // Surfaces of monitors that went away
+        let monitors = self.monitors.clone();
+        self.surfaces.retain(|(monitor, window, _)| {
+            let keep = monitors.contains(monitor);
+            if !keep {
+                window.destroy();
+            }
+            keep
+        });
+
+        for monitor in &monitors {
+            let Some(path) = self.wallpaper_for(monitor).cloned() else {
+                continue;
+            };
+            if let Some((_, _, picture)) = self.surfaces.iter().find(|(m, _, _)| m == monitor) {
+                picture.set_filename(Some(&path));
+                continue;
+            }
+            let (window, picture) = build_surface(monitor);
+            picture.set_filename(Some(&path));
+            window.present();
+            self.surfaces.push((monitor.clone(), window, picture));
+        }
+    }
+}
+
+fn build_surface(monitor: &Monitor) -> (Window, Picture) {
+    let window = Window::new();
+    window.set_widget_name("wallpaper");
+    window.init_layer_shell();
+    window.set_namespace(Some("jb-shell-wallpaper"));
+    window.set_layer(Layer::Background);
+    window.set_exclusive_zone(-1);
+    for edge in [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
+        window.set_anchor(edge, true);
+    }
+    window.set_monitor(Some(monitor));
+    window.set_can_target(false);
+
+    let picture = Picture::new();
+    picture.set_content_fit(gtk4::ContentFit::Cover);
+    picture.set_can_shrink(true);
+    window.set_child(Some(&picture));
+    (window, picture)
+}
+
+/// Preload and show each image through hyprpaper's socket. Each request
+/// must answer "ok", so a missing image or a stopped hyprpaper is logged
+/// (`hyprctl hyprpaper` exits 0 either way).
+fn set_hyprpaper(assignments: &[(String, PathBuf)]) {
+    let send = |keyword: hyprpaper::Keyword, what: &str| match hyprpaper::hyprpaper(keyword) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("jb-shell: [wallpaper] hyprpaper {what} failed: {e}");
+            false
+        }
+    };
+    for (monitor, path) in assignments {
+        let path = path.to_string_lossy().into_owned();
+        let preload = hyprpaper::Keyword::Preload(hyprpaper::Preload { path: path.clone() });
+        if !send(preload, &format!("preload {path}")) {
+            continue;
+        }
+        let wallpaper = hyprpaper::Keyword::Wallpaper(hyprpaper::Wallpaper {
+            monitor: Some(hyprpaper::Monitor::Port(monitor.clone())),
+            mode: None,
+            path: path.clone(),
+        });
+        send(wallpaper, &format!("wallpaper {monitor},{path}"));
+    }
+    // The crate has no variant for hyprpaper's `unload unused`
+    let unused = hyprpaper::Keyword::Unload(hyprpaper::Unload::Path("unused".into()));
+    send(unused, "unload unused");
+}
+
+/// Images in `[wallpaper] folder`, sorted by file name.
+pub fn list_images() -> Vec<PathBuf> {
+    let folder = expand_home(&crate::config::get().wallpaper.folder);
+    let Ok(entries) = std::fs::read_dir(&folder) else {
+        return Vec::new();
+    };
+    let mut images: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_image(p))
+        .collect();
+    images.sort();
+    images
+}
+
+/// A `width`-pixel-wide texture of the image, decoded once and cached. GTK main
+/// thread only.
+pub fn thumbnail(path: &Path, width: i32) -> Option<gdk4::Texture> {
+    let key = (path.to_path_buf(), width);
+    if let Some(hit) = THUMBNAILS.with_borrow(|cache| cache.get(&key).cloned()) {
+        return Some(hit);
+    }
+    let pixbuf = gtk4::gdk_pixbuf::Pixbuf::from_file_at_scale(path, width, -1, true).ok()?;
+    let texture = gdk4::Texture::for_pixbuf(&pixbuf);
+    THUMBNAILS.with_borrow_mut(|cache| cache.insert(key, texture.clone()));
+    Some(texture)
+}
+
+pub(crate) fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+fn state_path() -> PathBuf {
+    let data_dir = std::env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".into())).join(".local/share")
+        })
+        .join("jb-shell");
+    std::fs::create_dir_all(&data_dir).ok();
+    data_dir.join("wallpaper.json")
+}
+
+fn load_state() -> HashMap<String, PathBuf> {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(current: &HashMap<String, PathBuf>) {
+    if let Ok(json) = serde_json::to_string_pretty(current) {
+        let _ = std::fs::write(state_path(), json);
+    }
+}
@@ -0,0 +1,391 @@
+use gdk4::{MemoryFormat, MemoryTexture, Monitor};
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, EventControllerKey, GestureClick, Label, Orientation, Picture, Window};
+use gtk4_layer_shell::{KeyboardMode, Layer, LayerShell};
+use hyprland::data::{Clients, Workspace};
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
+use hyprland::shared::{Address, HyprData, HyprDataActive, HyprDataVec};
+use relm4::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use crate::core::preview::{downscale_bilinear, fit_within, Frame};
+use crate::workspace_capture::{CaptureRequest, CaptureResult};
+
+/// Thumbnails are scaled to fit this, in logical pixels.
+const TILE_SIZE: (u32, u32) = (240, 150);
+
+// ── D-Bus activation ─────────────────────────────────────────────────
+
+struct WindowSwitcherDbus {
+    sender: relm4::Sender<WindowSwitcherInput>,
+}
+
+#[zbus::interface(name = "dev.jb.shell.WindowSwitcher")]
+impl WindowSwitcherDbus {
+    /// Open the switcher on the previously focused window, or move to the
+    /// next one while it's open.
+    fn cycle_windows(&self) {
+        self.sender.emit(WindowSwitcherInput::Cycle(1));
+    }
+}
+
+fn spawn_window_switcher_dbus(sender: relm4::Sender<WindowSwitcherInput>) {
+    crate::dbus::serve(
+        "/dev/jb/shell/WindowSwitcher",
+        "dev.jb.shell.WindowSwitcher",
+        WindowSwitcherDbus { sender },
+    );
+}
+
+// ── relm4 Component ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+struct SwitcherWindow {
+    address: Address,
+    class: String,
+    title: String,
+}
+
+/// Alt-tab: the focused workspace's windows, most recently used first, with
+/// thumbnails from `workspace_capture`. Each `CycleWindows` call (bound to
+/// alt+tab) moves the selection; letting go of the modifier focuses it.
+pub struct WindowSwitcherModel {
+    visible: bool,
+    /// Fallback when Hyprland's focused monitor can't be matched.
+    monitor: Monitor,
+    windows: Vec<SwitcherWindow>,
+    selected: usize,
+    thumbnails: HashMap<Address, gdk4::Texture>,
+    capture_tx: mpsc::Sender<CaptureRequest>,
+    capture_supported: Arc<AtomicBool>,
+}
+
+#[derive(Debug)]
+pub enum WindowSwitcherInput {
+    /// Open, or move the selection by this many windows.
+    Cycle(isize),
+    /// Focus the selected window and close.
+    Commit,
+    /// Focus this window and close.
+    Pick(usize),
+    Cancel,
+    Thumbnails(Vec<(Address, gdk4::Texture)>),
+    /// The fallback monitor went away (hotplug) — use another one.
+    SetMonitor(Monitor),
+}
+
+pub struct WindowSwitcherWidgets {
+    overlay: Window,
+    tiles: GtkBox,
+}
+
+impl Component for WindowSwitcherModel {
+    type Init = Monitor;
+    type Input = WindowSwitcherInput;
+    type Output = ();
+    type CommandOutput = ();
+    type Root = GtkBox;
+    type Widgets = WindowSwitcherWidgets;
+
+    fn init_root() -> Self::Root {
+        // Invisible root — the real UI is the overlay window
+        GtkBox::new(Orientation::Horizontal, 0)
+    }
+
+    fn init(
+        monitor: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        // Unanchored, so the compositor centers it
+        let overlay = Window::new();
+        crate::widgets::a11y::name(&overlay, "Window switcher");
+        overlay.set_widget_name("window-switcher");
+        overlay.init_layer_shell();
+        overlay.set_namespace(Some("jb-shell-window-switcher"));
+        overlay.set_layer(Layer::Overlay);
+        overlay.set_exclusive_zone(-1);
+        overlay.set_keyboard_mode(KeyboardMode::Exclusive);
+        overlay.set_monitor(Some(&monitor));
+
+        let tiles = GtkBox::new(Orientation::Horizontal, 8);
+        tiles.set_widget_name("window-switcher-card");
+        overlay.set_child(Some(&tiles));
+        overlay.set_visible(false);
+
+        let key_ctl = EventControllerKey::new();
+        key_ctl.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        let key_sender = sender.input_sender().clone();
+        key_ctl.connect_key_pressed(move |_, keyval, _keycode, state| {
+            let back = state.contains(gdk4::ModifierType::SHIFT_MASK);
+            let message = match keyval {
+                gdk4::Key::Escape => WindowSwitcherInput::Cancel,
+                gdk4::Key::Return | gdk4::Key::KP_Enter => WindowSwitcherInput::Commit,
+                gdk4::Key::Tab => WindowSwitcherInput::Cycle(if back { -1 } else { 1 }),
+                gdk4::Key::ISO_Left_Tab | gdk4::Key::Left => WindowSwitcherInput::Cycle(-1),
+                gdk4::Key::Right => WindowSwitcherInput::Cycle(1),
+                _ => return glib::Propagation::Proceed,
+            };
+            key_sender.emit(message);
+            glib::Propagation::Stop
+        });
+        // Letting go of the alt-tab modifier picks the selection
+        let release_sender = sender.input_sender().clone();
+        key_ctl.connect_key_released(move |_, keyval, _keycode, _state| {
+            if matches!(
+                keyval,
+                gdk4::Key::Alt_L
+                    | gdk4::Key::Alt_R
+                    | gdk4::Key::Super_L
+                    | gdk4::Key::Super_R
+                    | gdk4::Key::Control_L
+                    | gdk4::Key::Control_R
+                    | gdk4::Key::Meta_L
+                    | gdk4::Key::Meta_R
+            ) {
+                release_sender.emit(WindowSwitcherInput::Commit);
+            }
+        });
+        overlay.add_controller(key_ctl);
+
+        // Capture results arrive on the capture thread's channel
+        let (capture_tx, capture_rx, capture_supported) =
+            crate::workspace_capture::spawn_capture_thread();
+        let thumb_sender = sender.input_sender().clone();
+        let scale_ref = overlay.clone();
+        glib::timeout_add_local(Duration::from_millis(32), move || {
+            let mut latest: Option<CaptureResult> = None;
+            while let Ok(result) = capture_rx.try_recv() {
+                latest = Some(result);
+            }
+            if let Some(result) = latest {
+                // The hidden overlay says 1; the monitor's scale is what counts
+                let scale =
+                    crate::core::scale::buffer_scale(result.scale).max(scale_ref.scale_factor());
+                thumb_sender.emit(WindowSwitcherInput::Thumbnails(thumbnail_textures(
+                    &result, scale,
+                )));
+            }
+            glib::ControlFlow::Continue
+        });
+
+        spawn_window_switcher_dbus(sender.input_sender().clone());
+
+        let model = WindowSwitcherModel {
+            visible: false,
+            monitor,
+            windows: Vec::new(),
+            selected: 0,
+            thumbnails: HashMap::new(),
+            capture_tx,
+            capture_supported,
+        };
+        let widgets = WindowSwitcherWidgets { overlay, tiles };
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            WindowSwitcherInput::Cycle(step) if self.visible => {
+                let len = self.windows.len() as isize;
+                self.selected = (self.selected as isize + step).rem_euclid(len) as usize;
+            }
+            WindowSwitcherInput::Cycle(step) => {
+                if !self.open(widgets) {
+                    return;
+                }
+                // The first press lands on the previously focused window
+                let len = self.windows.len() as isize;
+                self.selected = step.rem_euclid(len) as usize;
+            }
+            WindowSwitcherInput::Commit => {
+                if self.visible {
+                    self.focus_selected();
+                }
+            }
+            WindowSwitcherInput::Pick(index) => {
+                self.selected = index;
+                self.focus_selected();
+            }
+            WindowSwitcherInput::Cancel => {
+                self.visible = false;
+            }
+            WindowSwitcherInput::Thumbnails(thumbnails) => {
+                if !self.visible {
+                    return;
+                }
+                self.thumbnails = thumbnails.into_iter().collect();
+            }
+            WindowSwitcherInput::SetMonitor(monitor) => {
+                self.monitor = monitor;
+                self.visible = false;
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
+        if !self.visible {
+            widgets.overlay.set_visible(false);
+            return;
+        }
+        while let Some(child) = widgets.tiles.first_child() {
+            widgets.tiles.remove(&child);
+        }
+        for (index, window) in self.windows.iter().enumerate() {
+            widgets
+                .tiles
+                .append(&self.build_tile(index, window, &sender));
+        }
+        widgets.overlay.set_visible(true);
+    }
+}
+
+impl WindowSwitcherModel {
+    /// Load the focused workspace's windows and show the overlay on its
+    /// monitor. False when there's nothing to switch between.
+    fn open(&mut self, widgets: &WindowSwitcherWidgets) -> bool {
+        let Ok(workspace) = Workspace::get_active() else {
+            return false;
+        };
+        let mut clients: Vec<_> = Clients::get()
+            .map(|c| c.to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| c.workspace.id == workspace.id && c.mapped)
+            .collect();
+        if clients.is_empty() {
+            return false;
+        }
+        clients.sort_by_key(|c| c.focus_history_id);
+        self.windows = clients
+            .into_iter()
+            .map(|c| SwitcherWindow {
+                address: c.address,
+                class: c.class,
+                title: c.title,
+            })
+            .collect();
+        self.thumbnails.clear();
+
+        let monitor =
+            crate::widgets::color_picker::focused_monitor().unwrap_or_else(|| self.monitor.clone());
+        widgets.overlay.set_monitor(Some(&monitor));
+        if !crate::power::is_degraded() && self.capture_supported.load(Ordering::Relaxed) {
+            let _ = self.capture_tx.send(CaptureRequest {
+                ws_id: workspace.id,
+                monitor_name: workspace.monitor,
+            });
+        }
+        self.visible = true;
+        true
+    }
+
+    fn focus_selected(&mut self) {
+        if let Some(window) = self.windows.get(self.selected) {
+            let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
+                window.address.clone(),
+            )));
+        }
+        self.visible = false;
+    }
+
+    /// Thumbnail (or app icon while it's missing), title and class.
+    fn build_tile(
+        &self,
+        index: usize,
+        window: &SwitcherWindow,
+        sender: &ComponentSender<Self>,
+    ) -> GtkBox {
+        let tile = GtkBox::new(Orientation::Vertical, 4);
+        tile.add_css_class("switcher-tile");
+        if index == self.selected {
+            tile.add_css_class("selected");
+        }
+
+        let preview = GtkBox::new(Orientation::Vertical, 0);
+        preview.add_css_class("switcher-preview");
+        preview.set_size_request(TILE_SIZE.0 as i32, TILE_SIZE.1 as i32);
+        match self.thumbnails.get(&window.address) {
+            Some(texture) => {
+                let picture = Picture::for_paintable(texture);
+                picture.set_can_shrink(false);
+                picture.set_valign(gtk4::Align::Center);
+                picture.set_vexpand(true);
+                preview.append(&picture);
+            }
+            None => {
+                let icon = crate::app_icons::image(None, &window.class, 64)
+                    .map(|image| image.upcast::<gtk4::Widget>())
+                    .unwrap_or_else(|| Label::new(Some("\u{f2d0}")).upcast());
+                icon.set_valign(gtk4::Align::Center);
+                icon.set_vexpand(true);
+                preview.append(&icon);
+            }
+        }
+        tile.append(&preview);
+
+        let title = Label::new(Some(&window.title));
+        title.add_css_class("switcher-title");
+        title.set_max_width_chars(28);
+        title.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        tile.append(&title);
+        let class = Label::new(Some(&window.class));
+        class.add_css_class("switcher-class");
+        tile.append(&class);
+
+        let click = GestureClick::new();
+        let pick_sender = sender.input_sender().clone();
+        click.connect_released(move |_, _, _, _| {
+            pick_sender.emit(WindowSwitcherInput::Pick(index));
+        });
+        tile.add_controller(click);
+        tile
+    }
+}
+
+/// Each captured window scaled to fit a tile at the overlay's `scale` factor.
+fn thumbnail_textures(result: &CaptureResult, scale: i32) -> Vec<(Address, gdk4::Texture)> {
+    result
+        .thumbnails
+        .iter()
+        .filter_map(|thumb| {
+            let pixels = thumb.pixels.as_ref()?;
+            let scale = scale.max(1) as u32;
+            let (w, h) = fit_within(
+                pixels.width,
+                pixels.height,
+                TILE_SIZE.0 * scale,
+                TILE_SIZE.1 * scale,
+            );
+            if w == 0 {
+                return None;
+            }
+            let frame = Frame {
+                data: &pixels.data,
+                width: pixels.width,
+                height: pixels.height,
+                stride: pixels.stride,
+            };
+            let texture = MemoryTexture::new(
+                w as i32,
+                h as i32,
+                MemoryFormat::B8g8r8a8Premultiplied,
+                &glib::Bytes::from_owned(downscale_bilinear(&frame, w, h)),
+                w as usize * 4,
+            );
+            Some((thumb.address.clone(), texture.upcast()))
+        })
+        .collect()
+}
@@ -1,34 +1,33 @@
-use gdk4::{MemoryFormat, MemoryTexture, Monitor};
+use gdk4::Monitor;
+use gtk4::graphene::Rect;
 use gtk4::prelude::*;
 use gtk4::{
     Box as GtkBox, Button, EventControllerMotion, EventControllerScroll,
-    EventControllerScrollFlags, GestureClick, Label, Orientation, Picture, Window,
+    EventControllerScrollFlags, GestureClick, Label, Orientation, Window,
 };
 use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
-use hyprland::data::{Clients, Workspace, Workspaces};
+use hyprland::data::{Clients, Monitors, Workspace, Workspaces};
 use hyprland::dispatch::{
-    Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial,
+    Dispatch, DispatchType, MonitorIdentifier, WindowIdentifier, WorkspaceIdentifier,
+    WorkspaceIdentifierWithSpecial,
 };
 use hyprland::shared::{Address, HyprData, HyprDataActive, HyprDataVec};
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::rc::Rc;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 
+use crate::config::PreviewClick;
+use crate::core::preview::Frame;
+use crate::widgets::context_menu::MenuItem;
+use crate::widgets::preview_canvas::{PreviewCanvas, PreviewWindow};
 use crate::workspace_capture::{CaptureRequest, CaptureResult};
 
+/// In logical pixels; rounded up to whole device pixels on scaled monitors.
 const PREVIEW_WIDTH: f64 = 640.0;
 
-/// Hit region for click-to-focus on the composite thumbnail.
-struct ClickRegion {
-    x: f64,
-    y: f64,
-    w: f64,
-    h: f64,
-    address: Address,
-}
-
 pub struct WorkspacesWidget {
     pub container: GtkBox,
     inner: GtkBox,
@@ -37,8 +36,10 @@ pub struct WorkspacesWidget {
     active_id: Rc<RefCell<i32>>,
     popup: Window,
     popup_labels_box: GtkBox,
-    preview_picture: Picture,
+    preview_canvas: PreviewCanvas,
     capture_tx: mpsc::Sender<CaptureRequest>,
+    /// False while the compositor can't export windows; no previews then.
+    capture_supported: Arc<AtomicBool>,
     close_timer: Rc<RefCell<Option<glib::SourceId>>>,
     hovered_ws: Rc<RefCell<Option<i32>>>,
     popup_items: Rc<RefCell<Vec<(Address, Button)>>>,
@@ -57,6 +58,7 @@ impl WorkspacesWidget {
 
         // Popup window — layer shell overlay on same monitor as bar
         let popup = Window::new();
+        crate::widgets::a11y::name(&popup, "Workspace preview");
         popup.set_widget_name("ws-popup");
         popup.init_layer_shell();
         popup.set_layer(Layer::Overlay);
@@ -67,97 +69,93 @@ impl WorkspacesWidget {
         popup.set_monitor(Some(gdk_monitor));
         popup.set_visible(false);
 
-        // Popup layout: single preview picture + text labels
+        // Popup layout: window thumbnails + text labels
         let popup_box = GtkBox::new(Orientation::Vertical, 2);
 
-        let preview_picture = Picture::new();
-        preview_picture.set_widget_name("ws-preview-canvas");
-        preview_picture.set_can_shrink(true);
-        preview_picture.set_visible(false);
+        let preview_canvas = PreviewCanvas::new();
+        preview_canvas.set_widget_name("ws-preview-canvas");
+        preview_canvas.set_visible(false);
 
         let popup_labels_box = GtkBox::new(Orientation::Vertical, 2);
 
-        popup_box.append(&preview_picture);
+        popup_box.append(&preview_canvas);
         popup_box.append(&popup_labels_box);
         popup.set_child(Some(&popup_box));
 
         let close_timer: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
         let hovered_ws: Rc<RefCell<Option<i32>>> = Rc::new(RefCell::new(None));
 
-        // Click regions for thumbnail hit-testing
-        let click_regions: Rc<RefCell<Vec<ClickRegion>>> = Rc::new(RefCell::new(Vec::new()));
-
         // Track popup label buttons by address for hover highlight
         let popup_items: Rc<RefCell<Vec<(Address, Button)>>> = Rc::new(RefCell::new(Vec::new()));
 
-        // Click-to-focus on thumbnail
+        // Thumbnail clicks, per `[workspace_preview]`
         let click = GestureClick::new();
-        let regions_ref = click_regions.clone();
-        let popup_ref = popup.clone();
-        let hovered_ref = hovered_ws.clone();
-        let timer_ref = close_timer.clone();
-        click.connect_released(move |_, _, x, y| {
-            let regions = regions_ref.borrow();
-            for region in regions.iter() {
-                if x >= region.x
-                    && x < region.x + region.w
-                    && y >= region.y
-                    && y < region.y + region.h
-                {
-                    let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
-                        region.address.clone(),
-                    )));
-                    cancel_close_timer(&timer_ref);
-                    popup_ref.set_visible(false);
-                    *hovered_ref.borrow_mut() = None;
-                    break;
+        click.set_button(0);
+        let pending_click: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let ctx = PreviewContext {
+            popup: popup.clone(),
+            labels_box: popup_labels_box.clone(),
+            canvas: preview_canvas.clone(),
+            close_timer: close_timer.clone(),
+            hovered_ws: hovered_ws.clone(),
+            items: popup_items.clone(),
+        };
+        click.connect_released(move |gesture, n_press, x, y| {
+            let bindings = &crate::config::get().workspace_preview;
+            let window = ctx.canvas.window_at(x, y);
+            if let Some(id) = pending_click.borrow_mut().take() {
+                id.remove();
+            }
+            match gesture.current_button() {
+                gdk4::BUTTON_MIDDLE => ctx.run(bindings.middle_click, window),
+                gdk4::BUTTON_PRIMARY if n_press >= 2 => ctx.run(bindings.double_click, window),
+                gdk4::BUTTON_PRIMARY if window.is_none() => ctx.run(bindings.empty_click, None),
+                gdk4::BUTTON_PRIMARY if bindings.double_click != PreviewClick::Nothing => {
+                    // Hold the single click until a second one can't follow
+                    let delay = gtk4::Settings::default()
+                        .map(|s| s.gtk_double_click_time())
+                        .unwrap_or(400);
+                    let ctx = ctx.clone();
+                    let pending = pending_click.clone();
+                    let id = glib::timeout_add_local_once(
+                        Duration::from_millis(delay.max(0) as u64),
+                        move || {
+                            pending.borrow_mut().take();
+                            ctx.run(bindings.click, window);
+                        },
+                    );
+                    *pending_click.borrow_mut() = Some(id);
                 }
+                gdk4::BUTTON_PRIMARY => ctx.run(bindings.click, window),
+                _ => {}
             }
         });
-        preview_picture.add_controller(click);
+        preview_canvas.add_controller(click);
 
-        // Hover highlight: motion over thumbnail highlights corresponding label
+        // Hover highlight: motion over a thumbnail highlights it and its label
         let preview_motion = EventControllerMotion::new();
-        let regions_ref = click_regions.clone();
+        let canvas_ref = preview_canvas.clone();
         let items_ref = popup_items.clone();
         preview_motion.connect_motion(move |_, x, y| {
-            let regions = regions_ref.borrow();
-            let items = items_ref.borrow();
-            let mut matched: Option<&Address> = None;
-            for region in regions.iter() {
-                if x >= region.x
-                    && x < region.x + region.w
-                    && y >= region.y
-                    && y < region.y + region.h
-                {
-                    matched = Some(&region.address);
-                    break;
-                }
-            }
-            for (addr, btn) in items.iter() {
-                if matched.is_some_and(|m| m == addr) {
-                    btn.add_css_class("preview-highlight");
-                } else {
-                    btn.remove_css_class("preview-highlight");
-                }
-            }
+            let matched = canvas_ref.window_at(x, y);
+            canvas_ref.set_hovered(matched.as_ref());
+            highlight_item(&items_ref, matched.as_ref());
         });
+        let canvas_ref = preview_canvas.clone();
         let items_ref = popup_items.clone();
         preview_motion.connect_leave(move |_| {
-            let items = items_ref.borrow();
-            for (_, btn) in items.iter() {
-                btn.remove_css_class("preview-highlight");
-            }
+            canvas_ref.set_hovered(None);
+            highlight_item(&items_ref, None);
         });
-        preview_picture.add_controller(preview_motion);
+        preview_canvas.add_controller(preview_motion);
 
         // Spawn capture thread
-        let (capture_tx, capture_rx) = crate::workspace_capture::spawn_capture_thread();
+        let (capture_tx, capture_rx, capture_supported) =
+            crate::workspace_capture::spawn_capture_thread();
 
         // Poll capture results from the glib main loop
-        let preview_ref = preview_picture.clone();
+        let canvas_ref = preview_canvas.clone();
         let hovered_ref = hovered_ws.clone();
-        let regions_ref = click_regions;
         glib::timeout_add_local(Duration::from_millis(32), move || {
             let mut latest: Option<CaptureResult> = None;
             while let Ok(result) = capture_rx.try_recv() {
@@ -166,7 +164,7 @@ impl WorkspacesWidget {
                 }
             }
             if let Some(result) = latest {
-                apply_capture_result(&preview_ref, &result, &regions_ref);
+                apply_capture_result(&canvas_ref, &result);
             }
             glib::ControlFlow::Continue
         });
@@ -195,8 +193,9 @@ impl WorkspacesWidget {
             active_id,
             popup,
             popup_labels_box,
-            preview_picture,
+            preview_canvas,
             capture_tx,
+            capture_supported,
             close_timer,
             hovered_ws,
             popup_items,
@@ -218,6 +217,7 @@ impl WorkspacesWidget {
         for ws in workspaces.to_vec() {
             if ws.monitor == self.monitor_name {
                 self.add_workspace(ws.id);
+                self.set_name(ws.id, &ws.name);
             }
         }
 
@@ -251,6 +251,7 @@ impl WorkspacesWidget {
 
         let btn = Button::new();
         btn.set_valign(gtk4::Align::Center);
+        crate::widgets::a11y::name(&btn, &format!("Workspace {ws_id}"));
         let label = Label::new(Some(&ws_id.to_string()));
         btn.set_child(Some(&label));
         btn.add_css_class("occupied");
@@ -266,8 +267,9 @@ impl WorkspacesWidget {
         let motion = EventControllerMotion::new();
         let popup_ref = self.popup.clone();
         let labels_ref = self.popup_labels_box.clone();
-        let preview_ref = self.preview_picture.clone();
+        let canvas_ref = self.preview_canvas.clone();
         let capture_tx = self.capture_tx.clone();
+        let capture_supported = self.capture_supported.clone();
         let monitor_name = self.monitor_name.clone();
         let timer_ref = self.close_timer.clone();
         let hovered_ref = self.hovered_ws.clone();
@@ -278,8 +280,9 @@ impl WorkspacesWidget {
                 show_workspace_popup(
                     &popup_ref,
                     &labels_ref,
-                    &preview_ref,
+                    &canvas_ref,
                     &capture_tx,
+                    &capture_supported,
                     &monitor_name,
                     &hovered_ref,
                     &items_ref,
@@ -296,6 +299,9 @@ impl WorkspacesWidget {
         });
         btn.add_controller(motion);
 
+        let monitor_name = self.monitor_name.clone();
+        crate::widgets::context_menu::attach(&btn, move || workspace_menu(ws_id, &monitor_name));
+
         buttons.insert(ws_id, btn);
         drop(buttons);
 
@@ -315,6 +321,28 @@ impl WorkspacesWidget {
         }
     }
 
+    /// Label a workspace by its name, or its number while it has none.
+    pub fn set_name(&self, ws_id: i32, name: &str) {
+        let buttons = self.buttons.borrow();
+        let Some(btn) = buttons.get(&ws_id) else {
+            return;
+        };
+        let text = if name.is_empty() {
+            ws_id.to_string()
+        } else {
+            name.to_string()
+        };
+        if let Some(label) = btn.child().and_downcast::<Label>() {
+            label.set_label(&text);
+        }
+        let accessible = if text == ws_id.to_string() {
+            format!("Workspace {ws_id}")
+        } else {
+            format!("Workspace {ws_id}, {text}")
+        };
+        crate::widgets::a11y::name(btn, &accessible);
+    }
+
     pub fn set_active(&self, ws_id: i32) {
         let buttons = self.buttons.borrow();
         let old_id = *self.active_id.borrow();
@@ -352,6 +380,44 @@ impl WorkspacesWidget {
     }
 }
 
+/// Right-click menu of a workspace button: rename it, or send it to
+/// another monitor.
+fn workspace_menu(ws_id: i32, monitor_name: &str) -> Vec<MenuItem> {
+    let name = Workspaces::get()
+        .ok()
+        .and_then(|all| all.to_vec().into_iter().find(|ws| ws.id == ws_id))
+        .map(|ws| ws.name)
+        .unwrap_or_else(|| ws_id.to_string());
+    let mut items = vec![MenuItem::prompt("Rename", name, move |name| {
+        if let Err(e) = Dispatch::call(DispatchType::RenameWorkspace(ws_id, Some(&name))) {
+            eprintln!("jb-shell: [workspaces] failed to rename {ws_id}: {e}");
+        }
+    })];
+
+    let others: Vec<String> = Monitors::get()
+        .map(|m| m.to_vec())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| m.name)
+        .filter(|name| name != monitor_name)
+        .collect();
+    if !others.is_empty() {
+        items.push(MenuItem::Separator);
+        items.push(MenuItem::Heading("Move to monitor".to_string()));
+        for monitor in others {
+            items.push(MenuItem::action(monitor.clone(), move || {
+                if let Err(e) = Dispatch::call(DispatchType::MoveWorkspaceToMonitor(
+                    WorkspaceIdentifier::Id(ws_id),
+                    MonitorIdentifier::Name(&monitor),
+                )) {
+                    eprintln!("jb-shell: [workspaces] failed to move {ws_id} to {monitor}: {e}");
+                }
+            }));
+        }
+    }
+    items
+}
+
 fn cancel_close_timer(timer: &Rc<RefCell<Option<glib::SourceId>>>) {
     if let Some(id) = timer.borrow_mut().take() {
         id.remove();
@@ -375,85 +441,117 @@ fn start_close_timer(
     *timer.borrow_mut() = Some(id);
 }
 
-/// Composite all thumbnails into a single image buffer and display on a Picture.
-fn apply_capture_result(
-    preview: &Picture,
-    result: &CaptureResult,
-    click_regions: &Rc<RefCell<Vec<ClickRegion>>>,
-) {
-    let scale = PREVIEW_WIDTH / result.monitor_width as f64;
-    let pw = PREVIEW_WIDTH as u32;
-    let ph = ((result.monitor_height as f64 * scale) as u32).max(1);
-    let stride = pw as usize * 4;
-    let mut buf = vec![0u8; stride * ph as usize];
-
-    let mut regions = click_regions.borrow_mut();
-    regions.clear();
-
-    for thumb in &result.thumbnails {
-        let dst_w = ((thumb.win_width as f64 * scale) as u32).max(1);
-        let dst_h = ((thumb.win_height as f64 * scale) as u32).max(1);
-        let (scaled, scaled_stride) = downscale_nearest(
-            &thumb.data,
-            thumb.width,
-            thumb.height,
-            thumb.stride,
-            dst_w,
-            dst_h,
-        );
-
-        let ox = (thumb.x as f64 * scale) as i32;
-        let oy = (thumb.y as f64 * scale) as i32;
-
-        // Blit into composite buffer
-        for row in 0..dst_h as i32 {
-            let by = oy + row;
-            if by < 0 || by >= ph as i32 {
-                continue;
+/// Upload each captured window as its own texture and lay them out on the
+/// preview canvas.
+fn apply_capture_result(canvas: &PreviewCanvas, result: &CaptureResult) {
+    let preview_width = crate::core::scale::snap(PREVIEW_WIDTH, result.scale) as f64;
+    let scale = preview_width / result.monitor_width as f64;
+    let width = preview_width as f32;
+    // The canvas says 1 until it has been shown on the monitor, so go by
+    // the monitor's scale for the first preview too
+    let buffer_scale = crate::core::scale::buffer_scale(result.scale).max(canvas.scale_factor());
+    let height = ((result.monitor_height as f64 * scale) as f32).max(1.0);
+
+    let windows = result
+        .thumbnails
+        .iter()
+        .map(|thumb| {
+            let bounds = Rect::new(
+                (thumb.x as f64 * scale) as f32,
+                (thumb.y as f64 * scale) as f32,
+                ((thumb.win_width as f64 * scale) as f32).max(1.0),
+                ((thumb.win_height as f64 * scale) as f32).max(1.0),
+            );
+            let address = thumb.address.clone();
+            let Some(pixels) = &thumb.pixels else {
+                return PreviewWindow::placeholder(bounds, address, thumb.label.clone());
+            };
+            let frame = Frame {
+                data: &pixels.data,
+                width: pixels.width,
+                height: pixels.height,
+                stride: pixels.stride,
+            };
+            PreviewWindow::from_frame(&frame, bounds, buffer_scale, address)
+        })
+        .collect();
+
+    canvas.set_visible(true);
+    canvas.set_windows(width, height, windows);
+}
+
+/// What the preview's click handler needs to act on the popup.
+#[derive(Clone)]
+struct PreviewContext {
+    popup: Window,
+    labels_box: GtkBox,
+    canvas: PreviewCanvas,
+    close_timer: Rc<RefCell<Option<glib::SourceId>>>,
+    hovered_ws: Rc<RefCell<Option<i32>>>,
+    items: Rc<RefCell<Vec<(Address, Button)>>>,
+}
+
+impl PreviewContext {
+    /// Run a `[workspace_preview]` binding for a click on `window` (`None`
+    /// for empty space).
+    fn run(&self, action: PreviewClick, window: Option<Address>) {
+        let Some(ws_id) = *self.hovered_ws.borrow() else {
+            return;
+        };
+        match (action, window) {
+            (PreviewClick::Focus, Some(address)) => {
+                let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
+                    address,
+                )));
+                self.dismiss();
             }
-            for col in 0..dst_w as i32 {
-                let bx = ox + col;
-                if bx < 0 || bx >= pw as i32 {
-                    continue;
-                }
-                let src_off = row as usize * scaled_stride + col as usize * 4;
-                let dst_off = by as usize * stride + bx as usize * 4;
-                if src_off + 4 <= scaled.len() && dst_off + 4 <= buf.len() {
-                    buf[dst_off..dst_off + 3].copy_from_slice(&scaled[src_off..src_off + 3]);
-                    buf[dst_off + 3] = 0xFF; // force opaque
-                }
+            (PreviewClick::Switch, _) => {
+                let _ = Dispatch::call(DispatchType::Workspace(
+                    WorkspaceIdentifierWithSpecial::Id(ws_id),
+                ));
+                self.dismiss();
             }
+            (PreviewClick::Close, Some(address)) => {
+                let _ = Dispatch::call(DispatchType::CloseWindow(WindowIdentifier::Address(
+                    address.clone(),
+                )));
+                self.canvas.remove_window(&address);
+                self.items.borrow_mut().retain(|(addr, btn)| {
+                    if *addr == address {
+                        self.labels_box.remove(btn);
+                    }
+                    *addr != address
+                });
+            }
+            _ => {}
         }
+    }
 
-        regions.push(ClickRegion {
-            x: ox.max(0) as f64,
-            y: oy.max(0) as f64,
-            w: dst_w as f64,
-            h: dst_h as f64,
-            address: thumb.address.clone(),
-        });
+    fn dismiss(&self) {
+        cancel_close_timer(&self.close_timer);
+        self.popup.set_visible(false);
+        *self.hovered_ws.borrow_mut() = None;
+    }
+}
+
+/// Mark the popup label for `address` (if any) as the hovered window.
+fn highlight_item(items: &Rc<RefCell<Vec<(Address, Button)>>>, address: Option<&Address>) {
+    for (addr, btn) in items.borrow().iter() {
+        if address == Some(addr) {
+            btn.add_css_class("preview-highlight");
+        } else {
+            btn.remove_css_class("preview-highlight");
+        }
     }
-    drop(regions);
-
-    let bytes = glib::Bytes::from(&buf);
-    let texture = MemoryTexture::new(
-        pw as i32,
-        ph as i32,
-        MemoryFormat::B8g8r8a8Premultiplied,
-        &bytes,
-        stride,
-    );
-    preview.set_paintable(Some(&texture));
-    preview.set_size_request(pw as i32, ph as i32);
-    preview.set_visible(true);
 }
 
 #[allow(clippy::too_many_arguments)]
 fn show_workspace_popup(
     popup: &Window,
     popup_labels_box: &GtkBox,
-    preview_picture: &Picture,
+    preview_canvas: &PreviewCanvas,
     capture_tx: &mpsc::Sender<CaptureRequest>,
+    capture_supported: &AtomicBool,
     monitor_name: &str,
     hovered_ws: &Rc<RefCell<Option<i32>>>,
     popup_items: &Rc<RefCell<Vec<(Address, Button)>>>,
@@ -469,8 +567,8 @@ fn show_workspace_popup(
     popup_items.borrow_mut().clear();
 
     // Hide preview (will be populated async by capture thread)
-    preview_picture.set_paintable(None::<&MemoryTexture>);
-    preview_picture.set_visible(false);
+    preview_canvas.clear();
+    preview_canvas.set_visible(false);
 
     // Fetch clients from Hyprland IPC
     let clients = Clients::get().ok();
@@ -507,16 +605,28 @@ fn show_workspace_popup(
                 *hovered_clone.borrow_mut() = None;
             });
 
+            // Hovering a label outlines its thumbnail
+            let motion = EventControllerMotion::new();
+            let canvas_ref = preview_canvas.clone();
+            let address = client.address.clone();
+            motion.connect_enter(move |_, _, _| canvas_ref.set_hovered(Some(&address)));
+            let canvas_ref = preview_canvas.clone();
+            motion.connect_leave(move |_| canvas_ref.set_hovered(None));
+            btn.add_controller(motion);
+
             items.push((client.address.clone(), btn.clone()));
             popup_labels_box.append(&btn);
         }
         drop(items);
 
-        // Request thumbnail capture
-        let _ = capture_tx.send(CaptureRequest {
-            ws_id,
-            monitor_name: monitor_name.to_string(),
-        });
+        // Request thumbnail capture (skipped in battery degraded mode and
+        // without compositor support; the popup is then just the list)
+        if !crate::power::is_degraded() && capture_supported.load(Ordering::Relaxed) {
+            let _ = capture_tx.send(CaptureRequest {
+                ws_id,
+                monitor_name: monitor_name.to_string(),
+            });
+        }
     }
 
     position_ws_popup(popup, trigger);
@@ -553,37 +663,14 @@ fn position_ws_popup(popup: &Window, trigger: &gtk4::Widget) {
     };
 
     if let Some(bounds) = trigger.compute_bounds(root.upcast_ref::<gtk4::Widget>()) {
-        popup.set_margin(Edge::Top, (bounds.y() + bounds.height()) as i32);
-        popup.set_margin(Edge::Left, bounds.x() as i32);
+        let scale = crate::widgets::scale::window_scale(popup);
+        // A floating bar is inset by its margin; keep the same gap below it
+        let inset = crate::config::get().bar.edge_margin() as f64;
+        let top = (bounds.y() + bounds.height()) as f64 + 2.0 * inset;
+        crate::widgets::scale::set_margin(popup, Edge::Top, top, scale);
+        crate::widgets::scale::set_margin(popup, Edge::Left, bounds.x() as f64 + inset, scale);
     } else {
         popup.set_margin(Edge::Top, 32);
         popup.set_margin(Edge::Left, 0);
     }
 }
-
-/// Nearest-neighbor downscale of BGRA pixel data.
-fn downscale_nearest(
-    src: &[u8],
-    src_w: u32,
-    src_h: u32,
-    src_stride: u32,
-    dst_w: u32,
-    dst_h: u32,
-) -> (Vec<u8>, usize) {
-    let dst_stride = dst_w as usize * 4;
-    let mut dst = vec![0u8; dst_stride * dst_h as usize];
-
-    for dy in 0..dst_h {
-        let sy = ((dy as u64 * src_h as u64) / dst_h as u64) as u32;
-        for dx in 0..dst_w {
-            let sx = ((dx as u64 * src_w as u64) / dst_w as u64) as u32;
-            let src_off = (sy * src_stride + sx * 4) as usize;
-            let dst_off = dy as usize * dst_stride + dx as usize * 4;
-            if src_off + 4 <= src.len() {
-                dst[dst_off..dst_off + 4].copy_from_slice(&src[src_off..src_off + 4]);
-            }
-        }
-    }
-
-    (dst, dst_stride)
-}
@@ -0,0 +1,82 @@
+use hyprland::data::{Client, Clients};
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
+use hyprland::shared::{HyprData, HyprDataVec};
+use regex::Regex;
+
+use crate::core::window_focus::{pick_window, FocusQuery, WindowCandidate};
+
+/// Focus the Hyprland window `query` describes, switching workspace if
+/// needed. False if nothing matched.
+pub fn focus(query: FocusQuery) -> bool {
+    let Some(client) = find(query) else {
+        return false;
+    };
+    let _ = Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
+        client.address,
+    )));
+    true
+}
+
+/// The Hyprland window `query` describes. Without a class pattern, one from
+/// `[window_focus] classes` keyed by the desktop entry or a hint is used.
+pub fn find(mut query: FocusQuery) -> Option<Client> {
+    let clients = Clients::get().ok()?.to_vec();
+    if query.class_pattern.is_none() {
+        query.class_pattern = configured_pattern(&query);
+    }
+
+    let windows: Vec<WindowCandidate> = clients
+        .iter()
+        .map(|c| WindowCandidate {
+            class: c.class.clone(),
+            title: c.title.clone(),
+            pid: c.pid,
+        })
+        .collect();
+    let index = pick_window(&windows, &query, parent_pid)?;
+    clients.into_iter().nth(index)
+}
+
+/// Whether `client` is a window of the app `query` describes.
+pub fn is_match(mut query: FocusQuery, client: &Client) -> bool {
+    if query.class_pattern.is_none() {
+        query.class_pattern = configured_pattern(&query);
+    }
+    let window = WindowCandidate {
+        class: client.class.clone(),
+        title: client.title.clone(),
+        pid: client.pid,
+    };
+    pick_window(&[window], &query, parent_pid).is_some()
+}
+
+fn configured_pattern(query: &FocusQuery) -> Option<Regex> {
+    let classes = &crate::config::get().window_focus.classes;
+    let entry = query
+        .desktop_entry
+        .as_deref()
+        .map(|e| e.trim_end_matches(".desktop"));
+    let (key, pattern) = classes.iter().find(|(key, _)| {
+        entry.is_some_and(|e| e.eq_ignore_ascii_case(key))
+            || query
+                .class_hints
+                .iter()
+                .any(|h| h.eq_ignore_ascii_case(key))
+    })?;
+    match Regex::new(pattern) {
+        Ok(re) => Some(re),
+        Err(e) => {
+            eprintln!("jb-shell: [focus] bad class pattern for {key}: {e}");
+            None
+        }
+    }
+}
+
+fn parent_pid(pid: i32) -> Option<i32> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status
+        .lines()
+        .find(|l| l.starts_with("PPid:"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse().ok())
+}
@@ -1,8 +1,12 @@
-use hyprland::data::{Clients, Monitors};
+use hyprland::data::{Client, Clients, CursorPosition, FullscreenMode, Monitors};
 use hyprland::shared::{Address, HyprData, HyprDataVec};
 use std::io::{Read, Seek, SeekFrom};
+use std::net::Shutdown;
 use std::os::fd::AsFd;
-use std::sync::mpsc;
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
 use wayland_client::protocol::{wl_buffer, wl_registry, wl_shm, wl_shm_pool};
 use wayland_client::{Connection, Dispatch, EventQueue, QueueHandle, WEnum};
 use wayland_protocols_hyprland::toplevel_export::v1::client::{
@@ -10,16 +14,37 @@ use wayland_protocols_hyprland::toplevel_export::v1::client::{
     hyprland_toplevel_export_manager_v1::HyprlandToplevelExportManagerV1,
 };
 
+use crate::core::backoff;
+use crate::core::preview::stacking_key;
+
+/// A capture's frames must all arrive within this, or the connection is
+/// dropped (waking the blocked dispatch) and replaced.
+const FRAME_TIMEOUT: Duration = Duration::from_secs(2);
+/// Windows captured at once; each holds a full-size shm buffer until read.
+const MAX_IN_FLIGHT: usize = 4;
+/// Reconnect delays after the compositor goes away or lacks the protocol.
+const RECONNECT_BASE: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
 pub struct CaptureRequest {
     pub ws_id: i32,
     pub monitor_name: String,
 }
 
-pub struct WindowThumbnail {
+/// A captured window's BGRA rows, `stride` bytes apart.
+pub struct ThumbnailPixels {
     pub data: Vec<u8>,
     pub width: u32,
     pub height: u32,
     pub stride: u32,
+}
+
+pub struct WindowThumbnail {
+    /// `None` when the compositor wouldn't export the window (some XWayland
+    /// clients); the preview draws a placeholder tile instead.
+    pub pixels: Option<ThumbnailPixels>,
+    /// Class, with an XWayland note, for the placeholder.
+    pub label: String,
     pub x: i32,
     pub y: i32,
     pub win_width: i32,
@@ -32,18 +57,16 @@ pub struct CaptureResult {
     pub thumbnails: Vec<WindowThumbnail>,
     pub monitor_width: u32,
     pub monitor_height: u32,
+    /// Hyprland's scale for the monitor, possibly fractional.
+    pub scale: f64,
 }
 
 struct CaptureState {
     shm: Option<wl_shm::WlShm>,
     export_manager: Option<HyprlandToplevelExportManagerV1>,
-    frame_format: Option<wl_shm::Format>,
-    frame_width: u32,
-    frame_height: u32,
-    frame_stride: u32,
-    buffer_done: bool,
-    frame_ready: bool,
-    frame_failed: bool,
+    /// One per window of the capture in progress, indexed by the frame's
+    /// user data.
+    frames: Vec<FrameState>,
 }
 
 impl CaptureState {
@@ -51,24 +74,107 @@ impl CaptureState {
         Self {
             shm: None,
             export_manager: None,
-            frame_format: None,
-            frame_width: 0,
-            frame_height: 0,
-            frame_stride: 0,
-            buffer_done: false,
-            frame_ready: false,
-            frame_failed: false,
+            frames: Vec::new(),
         }
     }
+}
 
-    fn reset_frame(&mut self) {
-        self.frame_format = None;
-        self.frame_width = 0;
-        self.frame_height = 0;
-        self.frame_stride = 0;
-        self.buffer_done = false;
-        self.frame_ready = false;
-        self.frame_failed = false;
+#[derive(Default)]
+struct FrameState {
+    format: Option<wl_shm::Format>,
+    width: u32,
+    height: u32,
+    stride: u32,
+    buffer_done: bool,
+    ready: bool,
+    failed: bool,
+}
+
+/// A window's pixels, rows `stride` bytes apart.
+struct Captured {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    stride: u32,
+    format: wl_shm::Format,
+}
+
+/// A frame in flight and, once its buffer parameters arrived, the shm
+/// buffer it's being copied into.
+struct Pending {
+    frame: HyprlandToplevelExportFrameV1,
+    copy: Option<ShmCopy>,
+}
+
+struct ShmCopy {
+    mfd: memfd::Memfd,
+    pool: wl_shm_pool::WlShmPool,
+    buffer: wl_buffer::WlBuffer,
+}
+
+impl Pending {
+    /// Free the protocol objects and, if the copy completed, read the pixels.
+    fn finish(self, state: &FrameState) -> Option<Captured> {
+        self.frame.destroy();
+        let copy = self.copy?;
+        copy.buffer.destroy();
+        copy.pool.destroy();
+        if state.failed || !state.ready {
+            return None;
+        }
+
+        let mut file = copy.mfd.into_file();
+        file.seek(SeekFrom::Start(0)).ok()?;
+        let mut data = vec![0u8; (state.stride * state.height) as usize];
+        file.read_exact(&mut data).ok()?;
+        Some(Captured {
+            data,
+            width: state.width,
+            height: state.height,
+            stride: state.stride,
+            format: state.format?,
+        })
+    }
+}
+
+/// A compositor connection with the export manager bound.
+struct Session {
+    _conn: Connection,
+    event_queue: EventQueue<CaptureState>,
+    qh: QueueHandle<CaptureState>,
+    state: CaptureState,
+    /// The connection's socket, shut down by `Watchdog` when a frame stalls.
+    socket: UnixStream,
+    /// A dispatch failed: the connection is gone and must be replaced.
+    lost: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConnectError {
+    /// No Wayland connection, e.g. while the compositor restarts.
+    Unavailable,
+    /// Connected, but without toplevel export or wl_shm.
+    Unsupported,
+}
+
+/// Shuts the connection's socket down unless dropped within the timeout, so
+/// a frame that never completes fails the blocked dispatch instead of
+/// hanging the thread.
+struct Watchdog {
+    _disarm: mpsc::Sender<()>,
+}
+
+impl Watchdog {
+    fn arm(socket: &UnixStream, timeout: Duration) -> Option<Self> {
+        let socket = socket.try_clone().ok()?;
+        let (tx, rx) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            if rx.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout) {
+                eprintln!("workspace_capture: frame timed out, dropping the connection");
+                let _ = socket.shutdown(Shutdown::Both);
+            }
+        });
+        Some(Watchdog { _disarm: tx })
     }
 }
 
@@ -149,16 +255,20 @@ impl Dispatch<HyprlandToplevelExportManagerV1, ()> for CaptureState {
     }
 }
 
-// Frame events — Buffer, BufferDone, Ready, Failed
-impl Dispatch<HyprlandToplevelExportFrameV1, ()> for CaptureState {
+// Frame events — Buffer, BufferDone, Ready, Failed; user data is the index
+// into `frames`
+impl Dispatch<HyprlandToplevelExportFrameV1, usize> for CaptureState {
     fn event(
         state: &mut Self,
         _proxy: &HyprlandToplevelExportFrameV1,
         event: hyprland_toplevel_export_frame_v1::Event,
-        _data: &(),
+        index: &usize,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
     ) {
+        let Some(frame) = state.frames.get_mut(*index) else {
+            return;
+        };
         match event {
             hyprland_toplevel_export_frame_v1::Event::Buffer {
                 format: WEnum::Value(fmt),
@@ -167,24 +277,24 @@ impl Dispatch<HyprlandToplevelExportFrameV1, ()> for CaptureState {
                 stride,
             } => {
                 // Prefer Argb8888 or Xrgb8888
-                if state.frame_format.is_none()
+                if frame.format.is_none()
                     || fmt == wl_shm::Format::Argb8888
                     || fmt == wl_shm::Format::Xrgb8888
                 {
-                    state.frame_format = Some(fmt);
-                    state.frame_width = width;
-                    state.frame_height = height;
-                    state.frame_stride = stride;
+                    frame.format = Some(fmt);
+                    frame.width = width;
+                    frame.height = height;
+                    frame.stride = stride;
                 }
             }
             hyprland_toplevel_export_frame_v1::Event::BufferDone => {
-                state.buffer_done = true;
+                frame.buffer_done = true;
             }
             hyprland_toplevel_export_frame_v1::Event::Ready { .. } => {
-                state.frame_ready = true;
+                frame.ready = true;
             }
             hyprland_toplevel_export_frame_v1::Event::Failed => {
-                state.frame_failed = true;
+                frame.failed = true;
             }
             _ => {}
         }
@@ -196,37 +306,80 @@ fn parse_window_handle(address: &str) -> Option<u32> {
     u64::from_str_radix(hex, 16).ok().map(|v| v as u32)
 }
 
-fn capture_single_window(
-    state: &mut CaptureState,
-    event_queue: &mut EventQueue<CaptureState>,
-    qh: &QueueHandle<CaptureState>,
-    handle: u32,
-) -> Option<(Vec<u8>, u32, u32, u32)> {
-    let manager = state.export_manager.clone()?;
-    let shm = state.shm.clone()?;
-
-    state.reset_frame();
+/// Capture windows by handle, results in the same order. Up to
+/// `MAX_IN_FLIGHT` frames share the queue at once, each copied as soon as
+/// its buffer parameters arrive, so a busy workspace takes about as long as
+/// its slowest window rather than the sum of them.
+fn capture_windows(session: &mut Session, handles: &[u32]) -> Vec<Option<Captured>> {
+    let mut results: Vec<Option<Captured>> = handles.iter().map(|_| None).collect();
+    let (Some(manager), Some(shm)) = (
+        session.state.export_manager.clone(),
+        session.state.shm.clone(),
+    ) else {
+        return results;
+    };
+    let Some(_watchdog) = Watchdog::arm(&session.socket, FRAME_TIMEOUT) else {
+        return results;
+    };
+
+    session.state.frames = handles.iter().map(|_| FrameState::default()).collect();
+    let mut pending: Vec<Option<Pending>> = handles.iter().map(|_| None).collect();
+    let mut next = 0;
+
+    loop {
+        while next < handles.len() && pending.iter().flatten().count() < MAX_IN_FLIGHT {
+            pending[next] = Some(Pending {
+                frame: manager.capture_toplevel(0, handles[next], &session.qh, next),
+                copy: None,
+            });
+            next += 1;
+        }
 
-    let frame = manager.capture_toplevel(0, handle, qh, ());
+        for (i, slot) in pending.iter_mut().enumerate() {
+            let frame = &mut session.state.frames[i];
+            let Some(p) = slot.as_mut() else {
+                continue;
+            };
+            if frame.buffer_done && p.copy.is_none() && !frame.failed {
+                p.copy = start_copy(&shm, &session.qh, &p.frame, frame);
+                frame.failed = p.copy.is_none();
+            }
+            if frame.ready || frame.failed {
+                results[i] = slot.take().and_then(|p| p.finish(frame));
+            }
+        }
 
-    // Dispatch until BufferDone or Failed
-    while !state.buffer_done && !state.frame_failed {
-        if event_queue.blocking_dispatch(state).is_err() {
-            frame.destroy();
-            return None;
+        if next == handles.len() && pending.iter().all(Option::is_none) {
+            break;
+        }
+        if session
+            .event_queue
+            .blocking_dispatch(&mut session.state)
+            .is_err()
+        {
+            session.lost = true;
+            for (i, slot) in pending.iter_mut().enumerate() {
+                if let Some(p) = slot.take() {
+                    session.state.frames[i].failed = true;
+                    p.finish(&session.state.frames[i]);
+                }
+            }
+            break;
         }
     }
 
-    if state.frame_failed || state.frame_format.is_none() {
-        frame.destroy();
-        return None;
-    }
+    results
+}
 
-    let width = state.frame_width;
-    let height = state.frame_height;
-    let stride = state.frame_stride;
-    let format = state.frame_format.unwrap();
-    let buf_size = (stride * height) as usize;
+/// Allocate a shm buffer matching the frame's parameters and ask for the copy.
+fn start_copy(
+    shm: &wl_shm::WlShm,
+    qh: &QueueHandle<CaptureState>,
+    frame: &HyprlandToplevelExportFrameV1,
+    state: &FrameState,
+) -> Option<ShmCopy> {
+    let format = state.format?;
+    let buf_size = (state.stride * state.height) as usize;
 
     // Allocate shared memory via memfd
     let mfd = memfd::MemfdOptions::default().create("capture").ok()?;
@@ -235,54 +388,20 @@ fn capture_single_window(
     let pool = shm.create_pool(mfd.as_file().as_fd(), buf_size as i32, qh, ());
     let buffer = pool.create_buffer(
         0,
-        width as i32,
-        height as i32,
-        stride as i32,
+        state.width as i32,
+        state.height as i32,
+        state.stride as i32,
         format,
         qh,
         (),
     );
-
-    // Reset ready/failed for the copy phase
-    state.frame_ready = false;
-    state.frame_failed = false;
-
     frame.copy(&buffer, 1);
 
-    // Dispatch until Ready or Failed
-    while !state.frame_ready && !state.frame_failed {
-        if event_queue.blocking_dispatch(state).is_err() {
-            frame.destroy();
-            buffer.destroy();
-            pool.destroy();
-            return None;
-        }
-    }
-
-    frame.destroy();
-
-    if state.frame_failed {
-        buffer.destroy();
-        pool.destroy();
-        return None;
-    }
-
-    // Read pixels from memfd
-    let mut file = mfd.into_file();
-    file.seek(SeekFrom::Start(0)).ok()?;
-    let mut data = vec![0u8; buf_size];
-    file.read_exact(&mut data).ok()?;
-
-    buffer.destroy();
-    pool.destroy();
-
-    Some((data, width, height, stride))
+    Some(ShmCopy { mfd, pool, buffer })
 }
 
 fn capture_workspace(
-    state: &mut CaptureState,
-    event_queue: &mut EventQueue<CaptureState>,
-    qh: &QueueHandle<CaptureState>,
+    session: &mut Session,
     ws_id: i32,
     monitor_name: &str,
 ) -> Option<CaptureResult> {
@@ -296,43 +415,85 @@ fn capture_workspace(
 
     let mon_x = monitor.x;
     let mon_y = monitor.y;
-    let scale_factor = monitor.scale as f64;
-    let monitor_width = (monitor.width as f64 / scale_factor) as u32;
-    let monitor_height = (monitor.height as f64 / scale_factor) as u32;
-
-    let ws_clients: Vec<_> = clients
+    let scale = monitor.scale as f64;
+    let monitor_width = crate::core::scale::logical_size(monitor.width as u32, scale);
+    let monitor_height = crate::core::scale::logical_size(monitor.height as u32, scale);
+
+    // Pinned windows follow the active workspace, so they belong in every
+    // preview of the monitor they're on
+    let on_monitor = |c: &Client| {
+        let (x, y) = (c.at.0 as i32, c.at.1 as i32);
+        x < mon_x + monitor_width as i32
+            && y < mon_y + monitor_height as i32
+            && x + c.size.0 as i32 > mon_x
+            && y + c.size.1 as i32 > mon_y
+    };
+    let mut ws_clients: Vec<_> = clients
         .to_vec()
         .into_iter()
-        .filter(|c| c.workspace.id == ws_id && c.mapped && c.size.0 > 0 && c.size.1 > 0)
+        .filter(|c| c.mapped && c.size.0 > 0 && c.size.1 > 0)
+        .filter(|c| c.workspace.id == ws_id || (c.pinned && on_monitor(c)))
         .collect();
 
     if ws_clients.is_empty() {
         return None;
     }
+    ws_clients.sort_by_key(|c| {
+        stacking_key(
+            c.floating || c.pinned,
+            is_fullscreen(c),
+            c.focus_history_id as i32,
+        )
+    });
 
-    let mut thumbnails = Vec::new();
+    let handles: Vec<Option<u32>> = ws_clients
+        .iter()
+        .map(|c| parse_window_handle(&c.address.to_string()))
+        .collect();
+    let valid: Vec<u32> = handles.iter().flatten().copied().collect();
+    let mut captures = capture_windows(session, &valid).into_iter();
+    if session.lost {
+        return None;
+    }
 
-    for client in &ws_clients {
-        let handle = match parse_window_handle(&client.address.to_string()) {
-            Some(h) => h,
-            None => continue,
+    let mut thumbnails = Vec::new();
+    for (client, handle) in ws_clients.iter().zip(&handles) {
+        let capture = match handle {
+            Some(_) => captures.next().flatten(),
+            None => None,
         };
-
-        if let Some((data, width, height, stride)) =
-            capture_single_window(state, event_queue, qh, handle)
-        {
-            thumbnails.push(WindowThumbnail {
-                data,
-                width,
-                height,
-                stride,
-                x: client.at.0 as i32 - mon_x,
-                y: client.at.1 as i32 - mon_y,
-                win_width: client.size.0 as i32,
-                win_height: client.size.1 as i32,
-                address: client.address.clone(),
-            });
-        }
+        // The internal state decides: a fullscreen window covers the
+        // monitor whatever its tiled geometry; `fullscreen_client` alone
+        // (fake fullscreen) stays in its tile
+        let (x, y, win_width, win_height) = if is_fullscreen(client) {
+            (0, 0, monitor_width as i32, monitor_height as i32)
+        } else {
+            (
+                client.at.0 as i32 - mon_x,
+                client.at.1 as i32 - mon_y,
+                client.size.0 as i32,
+                client.size.1 as i32,
+            )
+        };
+        let label = if client.xwayland {
+            format!("{} (XWayland)", client.class)
+        } else {
+            client.class.clone()
+        };
+        thumbnails.push(WindowThumbnail {
+            pixels: capture.map(|c| ThumbnailPixels {
+                data: c.data,
+                width: c.width,
+                height: c.height,
+                stride: c.stride,
+            }),
+            label,
+            x,
+            y,
+            win_width,
+            win_height,
+            address: client.address.clone(),
+        });
     }
 
     if thumbnails.is_empty() {
@@ -344,48 +505,186 @@ fn capture_workspace(
         thumbnails,
         monitor_width,
         monitor_height,
+        scale,
     })
 }
 
-pub fn spawn_capture_thread() -> (mpsc::Sender<CaptureRequest>, mpsc::Receiver<CaptureResult>) {
-    let (req_tx, req_rx) = mpsc::channel::<CaptureRequest>();
-    let (res_tx, res_rx) = mpsc::channel::<CaptureResult>();
+fn is_fullscreen(client: &Client) -> bool {
+    matches!(
+        client.fullscreen,
+        FullscreenMode::Fullscreen | FullscreenMode::MaximizedFullscreen
+    )
+}
 
-    std::thread::spawn(move || {
-        let conn = match Connection::connect_to_env() {
-            Ok(c) => c,
-            Err(e) => {
-                eprintln!("workspace_capture: failed to connect to wayland: {e}");
-                return;
-            }
-        };
+/// Capture one window as BGRA `(data, width, height, stride)`. Blocking —
+/// call off the GTK thread.
+pub fn capture_window(address: &Address) -> Option<(Vec<u8>, u32, u32, u32)> {
+    let handle = parse_window_handle(&address.to_string())?;
+    let capture = capture_windows(&mut connect().ok()?, &[handle]).pop()??;
+    Some((capture.data, capture.width, capture.height, capture.stride))
+}
 
-        let display = conn.display();
-        let mut event_queue = conn.new_event_queue::<CaptureState>();
-        let qh = event_queue.handle();
-        let mut state = CaptureState::new();
+/// Sample the on-screen color under the pointer by capturing the topmost
+/// window there. Blocking — call off the GTK thread. `None` over bare desktop
+/// (there is no window to export) or when capture isn't available.
+pub fn sample_cursor_color() -> Option<[u8; 3]> {
+    let cursor = CursorPosition::get().ok()?;
+    let (x, y) = (cursor.x, cursor.y);
 
-        display.get_registry(&qh, ());
+    let visible_workspaces: Vec<i32> = Monitors::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .flat_map(|m| [m.active_workspace.id, m.special_workspace.id])
+        .collect();
+    let client = Clients::get()
+        .ok()?
+        .to_vec()
+        .into_iter()
+        .filter(|c| c.mapped && visible_workspaces.contains(&c.workspace.id))
+        .filter(|c| {
+            let (cx, cy) = (c.at.0 as i64, c.at.1 as i64);
+            x >= cx && y >= cy && x < cx + c.size.0 as i64 && y < cy + c.size.1 as i64
+        })
+        // Floating windows sit above tiled ones; otherwise most recently focused
+        .min_by_key(|c| (!c.floating, c.focus_history_id))?;
+    let handle = parse_window_handle(&client.address.to_string())?;
+
+    let Captured {
+        data,
+        width,
+        height,
+        stride,
+        format,
+    } = capture_windows(&mut connect().ok()?, &[handle]).pop()??;
 
-        if event_queue.roundtrip(&mut state).is_err() {
-            eprintln!("workspace_capture: roundtrip failed");
-            return;
+    // The buffer is in physical pixels; the window geometry is logical
+    let px = ((x - client.at.0 as i64) * width as i64 / client.size.0 as i64) as usize;
+    let py = ((y - client.at.1 as i64) * height as i64 / client.size.1 as i64) as usize;
+    if px >= width as usize || py >= height as usize {
+        return None;
+    }
+    let offset = py * stride as usize + px * 4;
+    let pixel = data.get(offset..offset + 4)?;
+    match format {
+        // Little-endian 32-bit formats: bytes are B, G, R, A
+        wl_shm::Format::Argb8888 | wl_shm::Format::Xrgb8888 => Some([pixel[2], pixel[1], pixel[0]]),
+        wl_shm::Format::Abgr8888 | wl_shm::Format::Xbgr8888 => Some([pixel[0], pixel[1], pixel[2]]),
+        other => {
+            eprintln!("workspace_capture: unsupported pixel format {other:?}");
+            None
         }
+    }
+}
 
-        if state.export_manager.is_none() {
-            eprintln!("workspace_capture: hyprland_toplevel_export_manager_v1 not available");
-            return;
-        }
-        if state.shm.is_none() {
-            eprintln!("workspace_capture: wl_shm not available");
-            return;
+/// Bind wl_shm and the toplevel export manager on a fresh connection.
+fn connect() -> Result<Session, ConnectError> {
+    let conn = match Connection::connect_to_env() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("workspace_capture: failed to connect to wayland: {e}");
+            return Err(ConnectError::Unavailable);
         }
+    };
+
+    let display = conn.display();
+    let mut event_queue = conn.new_event_queue::<CaptureState>();
+    let qh = event_queue.handle();
+    let mut state = CaptureState::new();
+
+    display.get_registry(&qh, ());
+
+    if event_queue.roundtrip(&mut state).is_err() {
+        eprintln!("workspace_capture: roundtrip failed");
+        return Err(ConnectError::Unavailable);
+    }
+
+    if state.export_manager.is_none() {
+        eprintln!("workspace_capture: hyprland_toplevel_export_manager_v1 not available");
+        return Err(ConnectError::Unsupported);
+    }
+    if state.shm.is_none() {
+        eprintln!("workspace_capture: wl_shm not available");
+        return Err(ConnectError::Unsupported);
+    }
+
+    let socket = conn
+        .backend()
+        .poll_fd()
+        .try_clone_to_owned()
+        .map(UnixStream::from)
+        .map_err(|e| {
+            eprintln!("workspace_capture: failed to clone the wayland socket: {e}");
+            ConnectError::Unavailable
+        })?;
+
+    Ok(Session {
+        _conn: conn,
+        event_queue,
+        qh,
+        state,
+        socket,
+        lost: false,
+    })
+}
+
+/// Runs workspace captures off the GTK thread. The flag reads false while the
+/// compositor lacks toplevel export, so the bar can skip asking for previews.
+/// A lost or unavailable connection is retried with backoff.
+pub fn spawn_capture_thread() -> (
+    mpsc::Sender<CaptureRequest>,
+    mpsc::Receiver<CaptureResult>,
+    Arc<AtomicBool>,
+) {
+    let (req_tx, req_rx) = mpsc::channel::<CaptureRequest>();
+    let (res_tx, res_rx) = mpsc::channel::<CaptureResult>();
+    let supported = Arc::new(AtomicBool::new(true));
+    let supported_flag = supported.clone();
+
+    std::thread::spawn(move || {
+        let mut session: Option<Session> = None;
+        let mut attempt = 0;
 
         loop {
+            if session.is_none() {
+                match connect() {
+                    Ok(s) => {
+                        if attempt > 0 {
+                            eprintln!("workspace_capture: reconnected");
+                        }
+                        session = Some(s);
+                        attempt = 0;
+                        supported_flag.store(true, Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        if e == ConnectError::Unsupported {
+                            supported_flag.store(false, Ordering::Relaxed);
+                        }
+                        // Wait out the backoff; a request meanwhile retries
+                        // right away and is otherwise dropped
+                        let wait = backoff::delay(
+                            attempt,
+                            RECONNECT_BASE,
+                            RECONNECT_MAX,
+                            backoff::clock_jitter(),
+                        );
+                        attempt += 1;
+                        match req_rx.recv_timeout(wait) {
+                            Ok(_) | Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+                }
+            }
+            let Some(current) = session.as_mut() else {
+                continue;
+            };
+
             let req = match req_rx.recv() {
                 Ok(r) => r,
                 Err(_) => return,
             };
+            crate::metrics::wakeup();
 
             // Drain to latest request
             let mut latest = req;
@@ -393,17 +692,16 @@ pub fn spawn_capture_thread() -> (mpsc::Sender<CaptureRequest>, mpsc::Receiver<C
                 latest = newer;
             }
 
-            if let Some(result) = capture_workspace(
-                &mut state,
-                &mut event_queue,
-                &qh,
-                latest.ws_id,
-                &latest.monitor_name,
-            ) {
+            let result = capture_workspace(current, latest.ws_id, &latest.monitor_name);
+            if current.lost {
+                eprintln!("workspace_capture: lost the compositor connection, reconnecting");
+                session = None;
+            }
+            if let Some(result) = result {
                 let _ = res_tx.send(result);
             }
         }
     });
 
-    (req_tx, res_rx)
+    (req_tx, res_rx, supported)
 }